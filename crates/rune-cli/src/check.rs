@@ -0,0 +1,25 @@
+//! Type checking of scripts in the cli.
+
+use crate::{load_path, walk_paths, Args, ExitCode};
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Compile the given paths with type checking enabled, without running them.
+pub(crate) fn run(args: &Args, options: &rune::Options, paths: Vec<PathBuf>) -> Result<ExitCode> {
+    let mut options = *options;
+    options.type_check(true);
+    // NB: checking neither uses nor produces cached bytecode.
+    options.bytecode(false);
+
+    let mut status = ExitCode::Success;
+
+    for path in walk_paths(args.recursive, paths) {
+        let path = path?;
+
+        if load_path(args, &options, &path)?.is_none() {
+            status = ExitCode::Failure;
+        }
+    }
+
+    Ok(status)
+}
@@ -0,0 +1,221 @@
+//! The interactive debugger of the cli.
+
+use crate::{load_path, Args, ExitCode};
+use anyhow::Result;
+use rune::termcolor::{ColorChoice, StandardStream};
+use rune::EmitDiagnostics as _;
+use runestick::{DebugFrame, DebugHalt, Debugger, StepMode, VmExecution};
+use std::io::{self, BufRead as _, Write as _};
+use std::path::Path;
+
+const HELP: &str = "\
+Commands:
+  b, break <line>   - Set a breakpoint on the given line.
+  d, delete <id>    - Delete the breakpoint with the given id.
+  c, continue       - Continue until the next breakpoint.
+  s, step           - Step to the next line, entering function calls.
+  n, next           - Step to the next line, stepping over function calls.
+  f, finish         - Continue until the current function returns.
+  l, locals         - Print local variables in the current frame.
+  p, print <name>   - Print the local variable with the given name.
+  bt, backtrace     - Print the call stack.
+  frame <n>         - Select the frame with the given index.
+  q, quit           - Stop debugging.
+  h, help           - Print this help.";
+
+/// Run the given path in the interactive debugger.
+pub(crate) async fn run(args: &Args, options: &rune::Options, path: &Path) -> Result<ExitCode> {
+    let (context, unit, sources) = match load_path(args, options, path)? {
+        Some(loaded) => loaded,
+        None => return Ok(ExitCode::Failure),
+    };
+
+    if unit.debug_info().is_none() {
+        println!("The unit doesn't have any debug info (compile with -O debug-info=true)");
+        return Ok(ExitCode::Failure);
+    }
+
+    let mut debugger = Debugger::new(unit.clone(), |source_id| {
+        sources.get(source_id).map(|s| &**s)
+    });
+
+    let vm = runestick::Vm::new(context, unit);
+    let mut execution: VmExecution = vm.execute(&["main"], ())?;
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut line = String::new();
+    let mut frame = 0;
+
+    println!(
+        "Debugging {} (type `help` for a list of commands)",
+        path.display()
+    );
+
+    loop {
+        print!("(rune) ");
+        io::stdout().flush()?;
+
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(ExitCode::Success);
+        }
+
+        let mut it = line.split_whitespace();
+
+        let command = match it.next() {
+            Some(command) => command,
+            None => continue,
+        };
+
+        let argument = it.next();
+
+        let step = match command {
+            "b" | "break" => {
+                let requested = match argument.and_then(|a| a.parse::<usize>().ok()) {
+                    Some(line) if line > 0 => line,
+                    _ => {
+                        println!("expected a line number");
+                        continue;
+                    }
+                };
+
+                match debugger.add_breakpoint(0, requested - 1) {
+                    Some(breakpoint) => println!(
+                        "breakpoint {} at line {}",
+                        breakpoint.id(),
+                        breakpoint.location().line + 1
+                    ),
+                    None => println!("no code at or after line {}", requested),
+                }
+
+                continue;
+            }
+            "d" | "delete" => {
+                match argument.and_then(|a| a.parse::<usize>().ok()) {
+                    Some(id) if debugger.remove_breakpoint(id) => {
+                        println!("deleted breakpoint {}", id)
+                    }
+                    _ => println!("no such breakpoint"),
+                }
+
+                continue;
+            }
+            "c" | "continue" => None,
+            "s" | "step" => Some(StepMode::In),
+            "n" | "next" => Some(StepMode::Over),
+            "f" | "finish" => Some(StepMode::Out),
+            "l" | "locals" => {
+                if let Some(frame) = debugger.frames(&execution).get(frame) {
+                    for local in debugger.locals(&execution, frame) {
+                        println!("{} = {:?}", local.name, local.value);
+                    }
+                }
+
+                continue;
+            }
+            "p" | "print" => {
+                let name = match argument {
+                    Some(name) => name,
+                    None => {
+                        println!("expected a variable name");
+                        continue;
+                    }
+                };
+
+                let value = debugger
+                    .frames(&execution)
+                    .get(frame)
+                    .and_then(|frame| debugger.local(&execution, frame, name));
+
+                match value {
+                    Some(value) => println!("{} = {:?}", name, value),
+                    None => println!("no local variable named `{}`", name),
+                }
+
+                continue;
+            }
+            "bt" | "backtrace" => {
+                for (n, f) in debugger.frames(&execution).iter().enumerate() {
+                    let marker = if n == frame { "*" } else { " " };
+                    println!("{} #{} {}", marker, n, describe_frame(f, &sources));
+                }
+
+                continue;
+            }
+            "frame" => {
+                let frames = debugger.frames(&execution);
+
+                match argument.and_then(|a| a.parse::<usize>().ok()) {
+                    Some(n) if n < frames.len() => {
+                        frame = n;
+                        println!("#{} {}", n, describe_frame(&frames[n], &sources));
+                    }
+                    _ => println!("no such frame"),
+                }
+
+                continue;
+            }
+            "q" | "quit" => return Ok(ExitCode::Success),
+            "h" | "help" => {
+                println!("{}", HELP);
+                continue;
+            }
+            other => {
+                println!(
+                    "unknown command `{}` (type `help` for a list of commands)",
+                    other
+                );
+                continue;
+            }
+        };
+
+        if let Some(mode) = step {
+            debugger.step(&execution, mode)?;
+        }
+
+        frame = 0;
+
+        match debugger.async_resume(&mut execution).await {
+            Ok(DebugHalt::Exited(value)) => {
+                println!("== {:?}", value);
+                return Ok(ExitCode::Success);
+            }
+            Ok(halt) => {
+                if let DebugHalt::Breakpoint(id) = halt {
+                    println!("hit breakpoint {}", id);
+                }
+
+                if let Some(f) = debugger.frames(&execution).first() {
+                    println!("{}", describe_frame(f, &sources));
+                }
+            }
+            Err(error) => {
+                println!("== ! ({})", error);
+                let mut writer = StandardStream::stderr(ColorChoice::Always);
+                error.emit_diagnostics(&mut writer, &sources)?;
+                return Ok(ExitCode::VmError);
+            }
+        }
+    }
+}
+
+/// Describe the given frame with its function and current source line.
+fn describe_frame(frame: &DebugFrame, sources: &rune::Sources) -> String {
+    let function = match frame.function() {
+        Some(function) => function.to_string(),
+        None => String::from("?"),
+    };
+
+    let location = frame.location().and_then(|location| {
+        let source = sources.get(location.source_id)?;
+        let line = source.as_str().lines().nth(location.line)?;
+        Some((source.name(), location.line, line))
+    });
+
+    match location {
+        Some((name, n, line)) => format!("{} at {}:{} - {}", function, name, n + 1, line.trim()),
+        None => format!("{} at ip {:04}", function, frame.ip()),
+    }
+}
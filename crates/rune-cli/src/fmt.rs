@@ -0,0 +1,53 @@
+//! Formatting of source files in the cli.
+
+use crate::{walk_paths, ExitCode};
+use anyhow::{Context as _, Result};
+use rune::termcolor::{ColorChoice, StandardStream};
+use rune::EmitDiagnostics as _;
+use std::fs;
+use std::path::PathBuf;
+
+/// Format the given paths in place.
+///
+/// If `check` is set, the files are left untouched and the paths of the files
+/// which are not formatted are printed instead.
+pub(crate) fn run(recursive: bool, paths: Vec<PathBuf>, check: bool) -> Result<ExitCode> {
+    let mut status = ExitCode::Success;
+
+    for path in walk_paths(recursive, paths) {
+        let path = path?;
+
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read: {}", path.display()))?;
+
+        let formatted = match rune::format(&source) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                let mut sources = rune::Sources::new();
+                let name = path.display().to_string();
+                let source_id = sources.insert(runestick::Source::new(name, source));
+                let error = rune::LoadError::new(source_id, error);
+
+                let mut out = StandardStream::stderr(ColorChoice::Auto);
+                error.emit_diagnostics(&mut out, &sources)?;
+                status = ExitCode::Failure;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("{}: not formatted", path.display());
+            status = ExitCode::Failure;
+            continue;
+        }
+
+        fs::write(&path, formatted)
+            .with_context(|| format!("failed to write: {}", path.display()))?;
+    }
+
+    Ok(status)
+}
@@ -60,6 +60,10 @@ use structopt::StructOpt;
 
 use runestick::{Unit, Value, VmExecution};
 
+mod check;
+mod debug;
+mod fmt;
+
 pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/version.txt"));
 
 #[derive(Default, Debug, Clone, StructOpt)]
@@ -122,8 +126,39 @@ struct Args {
     /// macros[=<true/false>] - Enable or disable macros (experimental).
     ///
     /// bytecode[=<true/false>] - Enable or disable bytecode caching (experimental).
+    ///
+    /// type-check[=<true/false>] - Check type annotations before compiling.
     #[structopt(name = "option", short = "O", number_of_values = 1)]
     compiler_options: Vec<String>,
+    /// Alternative modes of operation.
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
+}
+
+#[derive(Debug, Clone, StructOpt)]
+enum Command {
+    /// Check scripts for type errors without running them.
+    Check {
+        /// Rune scripts to check.
+        #[structopt(parse(from_os_str))]
+        paths: Vec<PathBuf>,
+    },
+    /// Run a script in an interactive debugger.
+    Debug {
+        /// Rune script to debug.
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Format Rune scripts in place.
+    Fmt {
+        /// Only check that the scripts are formatted, and exit with an error
+        /// listing the ones which aren't.
+        #[structopt(long)]
+        check: bool,
+        /// Rune scripts to format.
+        #[structopt(parse(from_os_str))]
+        paths: Vec<PathBuf>,
+    },
 }
 
 async fn try_main() -> Result<ExitCode> {
@@ -160,6 +195,19 @@ async fn try_main() -> Result<ExitCode> {
         options.parse_option(opt)?;
     }
 
+    match &args.cmd {
+        Some(Command::Check { paths }) => {
+            return check::run(&args, &options, paths.clone());
+        }
+        Some(Command::Debug { path }) => {
+            return debug::run(&args, &options, path).await;
+        }
+        Some(Command::Fmt { check, paths }) => {
+            return fmt::run(args.recursive, paths.clone(), *check);
+        }
+        None => (),
+    }
+
     if args.paths.is_empty() {
         println!("Invalid usage: Missing Input Paths (at least one file required)");
         return Ok(ExitCode::Failure);
@@ -224,13 +272,15 @@ fn walk_paths(recursive: bool, paths: Vec<PathBuf>) -> impl Iterator<Item = io::
     })
 }
 
-/// Run a single path.
-async fn run_path(args: &Args, options: &rune::Options, path: &Path) -> Result<ExitCode> {
-    if args.test {
-        println!("testing: {}", path.display());
-    }
-
-    let bytecode_path = path.with_extension("rnc");
+/// Load the context and compile the unit for a single path.
+///
+/// Returns `None` if the path failed to compile, in which case diagnostics have
+/// already been emitted.
+fn load_path(
+    args: &Args,
+    options: &rune::Options,
+    path: &Path,
+) -> Result<Option<(Arc<runestick::Context>, Arc<Unit>, rune::Sources)>> {
     let mut context = rune::default_context()?;
 
     if args.experimental {
@@ -245,59 +295,45 @@ async fn run_path(args: &Args, options: &rune::Options, path: &Path) -> Result<E
 
     sources.insert(source);
 
-    let use_cache = options.bytecode && should_cache_be_used(&path, &bytecode_path)?;
-    let maybe_unit = if use_cache {
-        let f = fs::File::open(&bytecode_path)?;
-        match bincode::deserialize_from::<_, Unit>(f) {
-            Ok(unit) => {
-                log::trace!("using cache: {}", bytecode_path.display());
-                Some(Arc::new(unit))
-            }
-            Err(e) => {
-                log::error!("failed to deserialize: {}: {}", bytecode_path.display(), e);
-                None
-            }
+    log::trace!("building file: {}", path.display());
+
+    let mut errors = rune::Errors::new();
+    let mut warnings = rune::Warnings::new();
+
+    let unit = match rune::load_sources(
+        &*context,
+        &options,
+        &mut sources,
+        &mut errors,
+        &mut warnings,
+    ) {
+        Ok(unit) => unit,
+        Err(rune::LoadSourcesError) => {
+            let mut writer = StandardStream::stderr(ColorChoice::Always);
+            errors.emit_diagnostics(&mut writer, &sources)?;
+            return Ok(None);
         }
-    } else {
-        None
     };
 
-    let unit = match maybe_unit {
-        Some(unit) => unit,
-        None => {
-            log::trace!("building file: {}", path.display());
-
-            let mut errors = rune::Errors::new();
-            let mut warnings = rune::Warnings::new();
-
-            let unit = match rune::load_sources(
-                &*context,
-                &options,
-                &mut sources,
-                &mut errors,
-                &mut warnings,
-            ) {
-                Ok(unit) => unit,
-                Err(rune::LoadSourcesError) => {
-                    let mut writer = StandardStream::stderr(ColorChoice::Always);
-                    errors.emit_diagnostics(&mut writer, &sources)?;
-                    return Ok(ExitCode::Failure);
-                }
-            };
+    if !warnings.is_empty() {
+        let mut writer = StandardStream::stderr(ColorChoice::Always);
+        warnings.emit_diagnostics(&mut writer, &sources)?;
+    }
 
-            if options.bytecode {
-                log::trace!("serializing cache: {}", bytecode_path.display());
-                let f = fs::File::create(&bytecode_path)?;
-                bincode::serialize_into(f, &unit)?;
-            }
+    let unit = Arc::new(unit);
 
-            if !warnings.is_empty() {
-                let mut writer = StandardStream::stderr(ColorChoice::Always);
-                warnings.emit_diagnostics(&mut writer, &sources)?;
-            }
+    Ok(Some((context, unit, sources)))
+}
 
-            Arc::new(unit)
-        }
+/// Run a single path.
+async fn run_path(args: &Args, options: &rune::Options, path: &Path) -> Result<ExitCode> {
+    if args.test {
+        println!("testing: {}", path.display());
+    }
+
+    let (context, unit, sources) = match load_path(args, options, path)? {
+        Some(loaded) => loaded,
+        None => return Ok(ExitCode::Failure),
     };
 
     let vm = runestick::Vm::new(context.clone(), unit.clone());
@@ -643,16 +679,3 @@ async fn do_trace(
         }
     }
 }
-
-/// Test if path `a` is newer than path `b`.
-fn should_cache_be_used(source: &Path, cached: &Path) -> io::Result<bool> {
-    let source = fs::metadata(source)?;
-
-    let cached = match fs::metadata(cached) {
-        Ok(cached) => cached,
-        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
-        Err(error) => return Err(error),
-    };
-
-    Ok(source.modified()? < cached.modified()?)
-}
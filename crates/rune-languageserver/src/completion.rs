@@ -0,0 +1,389 @@
+//! Completions and signature help.
+//!
+//! These operate on the text preceding the cursor, since the source being
+//! edited is usually in a state which doesn't compile. Items are looked up in
+//! the context and in the last unit which was successfully built.
+
+use hashbrown::HashSet;
+use runestick::debug::{DebugArgs, DebugSignature, DebugVariable};
+use runestick::{Component, ContextSignature, Hash, Item, Span, Unit};
+
+/// The path being typed right before the cursor.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Prefix<'a> {
+    /// If the path is preceded by a `.`, which means that it's an instance
+    /// function being typed.
+    pub(crate) instance: bool,
+    /// Complete path components preceding the partial component.
+    pub(crate) path: Vec<&'a str>,
+    /// The partial component being typed.
+    pub(crate) partial: &'a str,
+}
+
+impl<'a> Prefix<'a> {
+    /// Parse the path being typed at the end of the given text.
+    pub(crate) fn parse(text: &'a str) -> Self {
+        let (mut rest, partial) = split_ident(text);
+        let mut prefix = Prefix {
+            partial,
+            ..Prefix::default()
+        };
+
+        loop {
+            if let Some(r) = rest.strip_suffix("::") {
+                let (r, ident) = split_ident(r);
+
+                if ident.is_empty() {
+                    break;
+                }
+
+                prefix.path.insert(0, ident);
+                rest = r;
+                continue;
+            }
+
+            prefix.instance = prefix.path.is_empty() && rest.ends_with('.');
+            break;
+        }
+
+        prefix
+    }
+}
+
+/// Collect completions for the given prefix.
+pub(crate) fn complete(
+    context: &runestick::Context,
+    unit: Option<&Unit>,
+    locals: &[&DebugVariable],
+    prefix: &Prefix<'_>,
+) -> Vec<lsp::CompletionItem> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+
+    if prefix.instance {
+        for (_, signature) in context.iter_functions() {
+            if let ContextSignature::Instance { name, .. } = signature {
+                if name.starts_with(prefix.partial) && seen.insert(name.clone()) {
+                    out.push(item(name, lsp::CompletionItemKind::Method, signature));
+                }
+            }
+        }
+
+        return out;
+    }
+
+    if prefix.path.is_empty() {
+        for local in locals {
+            if local.name.starts_with(prefix.partial) && seen.insert(local.name.clone()) {
+                out.push(lsp::CompletionItem {
+                    label: local.name.clone(),
+                    kind: Some(lsp::CompletionItemKind::Variable),
+                    ..lsp::CompletionItem::default()
+                });
+            }
+        }
+    }
+
+    let mut functions = Vec::new();
+
+    if let Some(debug) = unit.and_then(Unit::debug_info) {
+        for signature in debug.functions.values() {
+            functions.push((&signature.path, signature.to_string()));
+        }
+    }
+
+    for (_, signature) in context.iter_functions() {
+        if let ContextSignature::Function { item, .. } = signature {
+            functions.push((item, signature.to_string()));
+        }
+    }
+
+    for (path, detail) in functions {
+        let components = match string_components(path) {
+            Some(components) => components,
+            None => continue,
+        };
+
+        let rest = match strip_path(&components, &prefix.path) {
+            Some(rest) => rest,
+            None => continue,
+        };
+
+        // NB: the functions which are imported by the prelude live directly
+        // in `std`, so they are offered without a path as well.
+        let rest = match rest {
+            [_, _] if prefix.path.is_empty() && components[0] == "std" => &rest[1..],
+            rest => rest,
+        };
+
+        let (name, kind, detail) = match rest {
+            [name] => (name, lsp::CompletionItemKind::Function, Some(detail)),
+            [name, ..] => (name, lsp::CompletionItemKind::Module, None),
+            [] => continue,
+        };
+
+        if name.starts_with(prefix.partial) && seen.insert(name.to_string()) {
+            out.push(lsp::CompletionItem {
+                label: name.to_string(),
+                kind: Some(kind),
+                detail,
+                ..lsp::CompletionItem::default()
+            });
+        }
+    }
+
+    out
+}
+
+/// Find the local variables which are visible at the given offset, by looking
+/// at the functions whose instructions surround it.
+pub(crate) fn locals_at(unit: &Unit, source_id: usize, offset: usize) -> Vec<&DebugVariable> {
+    let debug = match unit.debug_info() {
+        Some(debug) => debug,
+        None => return Vec::new(),
+    };
+
+    let mut starts = debug.functions_rev.keys().copied().collect::<Vec<_>>();
+    starts.sort();
+
+    let mut out = Vec::new();
+
+    for (n, start) in starts.iter().copied().enumerate() {
+        let end = starts
+            .get(n + 1)
+            .copied()
+            .unwrap_or(debug.instructions.len());
+
+        let extent = debug.instructions[start..end]
+            .iter()
+            .filter(|inst| inst.source_id == source_id)
+            .map(|inst| inst.span)
+            .fold(None::<Span>, |extent, span| match extent {
+                Some(extent) => Some(Span::new(
+                    usize::min(extent.start, span.start),
+                    usize::max(extent.end, span.end),
+                )),
+                None => Some(span),
+            });
+
+        match extent {
+            Some(extent) if extent.start <= offset && offset <= extent.end => (),
+            _ => continue,
+        }
+
+        if let Some(variables) = debug.variables.get(&start) {
+            out.extend(
+                variables
+                    .iter()
+                    .filter(|v| v.span.end <= offset && offset <= v.scope_end),
+            );
+        }
+    }
+
+    // Later declarations shadow earlier ones.
+    out.sort_by_key(|v| std::cmp::Reverse(v.span.start));
+    out
+}
+
+/// Information on the call surrounding the cursor.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Call<'a> {
+    /// The offset at the end of the path being called.
+    pub(crate) end: usize,
+    /// The prefix of the function being called.
+    pub(crate) prefix: Prefix<'a>,
+    /// The index of the argument the cursor is at.
+    pub(crate) argument: usize,
+}
+
+/// Find the call surrounding the end of the given text.
+pub(crate) fn call_at(text: &str) -> Option<Call<'_>> {
+    let mut depth = 0usize;
+    let mut argument = 0;
+
+    for (n, c) in text.char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' if depth == 0 => {
+                let callee = text[..n].trim_end();
+                let prefix = Prefix::parse(callee);
+
+                if prefix.partial.is_empty() {
+                    return None;
+                }
+
+                return Some(Call {
+                    end: callee.len(),
+                    prefix,
+                    argument,
+                });
+            }
+            '[' | '{' if depth == 0 => return None,
+            '(' | '[' | '{' => depth -= 1,
+            ',' if depth == 0 => argument += 1,
+            ';' if depth == 0 => return None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Build signature information from a debug signature of a script function.
+pub(crate) fn debug_signature(signature: &DebugSignature) -> lsp::SignatureInformation {
+    let parameters = match &signature.args {
+        DebugArgs::Named(args) => args.clone(),
+        DebugArgs::TupleArgs(args) => (0..*args).map(|n| n.to_string()).collect(),
+    };
+
+    signature_information(signature.to_string(), parameters)
+}
+
+/// Build signature information from a native function signature.
+pub(crate) fn context_signature(signature: &ContextSignature) -> lsp::SignatureInformation {
+    let args = match signature {
+        ContextSignature::Function { args, .. } => args,
+        ContextSignature::Instance { args, .. } => args,
+    };
+
+    let parameters = match args {
+        Some(args) => (0..*args).map(|n| format!("#{}", n)).collect(),
+        None => Vec::new(),
+    };
+
+    signature_information(signature.to_string(), parameters)
+}
+
+/// Find signatures of instance functions with the given name.
+pub(crate) fn instance_signatures<'a>(
+    context: &'a runestick::Context,
+    name: &'a str,
+) -> impl Iterator<Item = &'a ContextSignature> + 'a {
+    context
+        .iter_functions()
+        .filter_map(move |(_, signature)| match signature {
+            ContextSignature::Instance { name: n, .. } if n == name => Some(signature),
+            _ => None,
+        })
+}
+
+/// Lookup the signature of the given item, either among script functions in
+/// the unit or among native functions in the context.
+pub(crate) fn lookup_signature(
+    context: &runestick::Context,
+    unit: Option<&Unit>,
+    item: &Item,
+) -> Option<lsp::SignatureInformation> {
+    let hash = Hash::type_hash(item);
+
+    if let Some(signature) = unit
+        .and_then(Unit::debug_info)
+        .and_then(|debug| debug.functions.get(&hash))
+    {
+        return Some(debug_signature(signature));
+    }
+
+    context.lookup_signature(hash).map(context_signature)
+}
+
+fn signature_information(label: String, parameters: Vec<String>) -> lsp::SignatureInformation {
+    let parameters = parameters
+        .into_iter()
+        .map(|p| lsp::ParameterInformation {
+            label: lsp::ParameterLabel::Simple(p),
+            documentation: None,
+        })
+        .collect();
+
+    lsp::SignatureInformation {
+        label,
+        documentation: None,
+        parameters: Some(parameters),
+    }
+}
+
+fn item(
+    name: &str,
+    kind: lsp::CompletionItemKind,
+    signature: &ContextSignature,
+) -> lsp::CompletionItem {
+    lsp::CompletionItem {
+        label: name.to_owned(),
+        kind: Some(kind),
+        detail: Some(signature.to_string()),
+        ..lsp::CompletionItem::default()
+    }
+}
+
+/// Get the string components of the item, or `None` if it contains other
+/// kinds of components.
+fn string_components(item: &Item) -> Option<Vec<String>> {
+    item.iter()
+        .map(|c| match c {
+            Component::String(s) => Some(s.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Strip the given path from the components.
+fn strip_path<'a>(components: &'a [String], path: &[&str]) -> Option<&'a [String]> {
+    if components.len() < path.len() {
+        return None;
+    }
+
+    let (head, rest) = components.split_at(path.len());
+
+    if head.iter().zip(path).all(|(a, b)| a == b) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// Split off the identifier at the end of the given string.
+fn split_ident(text: &str) -> (&str, &str) {
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map(|(n, _)| n)
+        .unwrap_or_else(|| text.len());
+
+    text.split_at(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{call_at, Prefix};
+
+    #[test]
+    fn test_prefix() {
+        let prefix = Prefix::parse("let a = std::str::fr");
+        assert!(!prefix.instance);
+        assert_eq!(prefix.path, vec!["std", "str"]);
+        assert_eq!(prefix.partial, "fr");
+
+        let prefix = Prefix::parse("foo.ba");
+        assert!(prefix.instance);
+        assert!(prefix.path.is_empty());
+        assert_eq!(prefix.partial, "ba");
+
+        let prefix = Prefix::parse("http::");
+        assert_eq!(prefix.path, vec!["http"]);
+        assert_eq!(prefix.partial, "");
+    }
+
+    #[test]
+    fn test_call_at() {
+        let call = call_at("let a = foo::bar(1, (2, 3), ").unwrap();
+        assert_eq!(call.prefix.path, vec!["foo"]);
+        assert_eq!(call.prefix.partial, "bar");
+        assert_eq!(call.argument, 2);
+        assert_eq!(call.end, "let a = foo::bar".len());
+
+        assert!(call_at("foo(1); bar").is_none());
+        assert!(call_at("let a = [1, ").is_none());
+    }
+}
@@ -4,7 +4,8 @@ use std::fmt;
 use std::sync::Arc;
 use tokio::io;
 use tokio::io::{
-    AsyncBufRead, AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _, BufReader,
+    AsyncBufRead, AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite,
+    AsyncWriteExt as _, BufReader,
 };
 use tokio::sync::Mutex;
 
@@ -17,7 +18,7 @@ pub struct Frame<'a> {
 /// Input connection.
 pub struct Input {
     buf: Vec<u8>,
-    stdin: BufReader<io::Stdin>,
+    stdin: BufReader<Box<dyn AsyncRead + Unpin + Send>>,
 }
 
 impl Input {
@@ -47,7 +48,7 @@ impl Input {
 /// Output connection.
 #[derive(Clone)]
 pub struct Output {
-    stdout: Arc<Mutex<io::Stdout>>,
+    stdout: Arc<Mutex<Box<dyn AsyncWrite + Unpin + Send>>>,
 }
 
 impl Output {
@@ -126,6 +127,16 @@ impl Output {
         Ok(())
     }
 
+    /// Send a raw message, which is serialized as-is.
+    pub async fn message<M>(&self, message: &M) -> Result<()>
+    where
+        M: serde::Serialize,
+    {
+        let mut bytes = serde_json::to_vec(message)?;
+        self.write_response(&mut bytes).await?;
+        Ok(())
+    }
+
     /// Write the given response body.
     async fn write_response(&self, bytes: &mut Vec<u8>) -> Result<()> {
         use std::io::Write as _;
@@ -145,19 +156,25 @@ impl Output {
 
 /// Setup a stdin/stdout connection.
 pub fn stdio() -> Result<(Input, Output)> {
-    let stdin = io::stdin();
-    let stdout = io::stdout();
+    Ok(connect(io::stdin(), io::stdout()))
+}
 
+/// Setup a connection which reads from and writes to the given streams.
+pub fn connect<R, W>(reader: R, writer: W) -> (Input, Output)
+where
+    R: 'static + AsyncRead + Unpin + Send,
+    W: 'static + AsyncWrite + Unpin + Send,
+{
     let input = Input {
         buf: Vec::new(),
-        stdin: BufReader::new(stdin),
+        stdin: BufReader::new(Box::new(reader)),
     };
 
     let output = Output {
-        stdout: Arc::new(Mutex::new(stdout)),
+        stdout: Arc::new(Mutex::new(Box::new(writer))),
     };
 
-    Ok((input, output))
+    (input, output)
 }
 
 #[derive(Debug)]
@@ -0,0 +1,198 @@
+//! Types of the debug adapter protocol.
+//!
+//! Only the subset of the protocol used by the adapter is modelled.
+
+use serde::{Deserialize, Serialize};
+
+/// An incoming request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Request {
+    /// Sequence number of the request.
+    pub seq: u64,
+    /// The command to execute.
+    pub command: String,
+    /// Arguments of the command.
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+/// A response to a request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response<T> {
+    pub seq: u64,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    // NB: unlike other fields, this is snake case in the protocol.
+    #[serde(rename = "request_seq")]
+    pub request_seq: u64,
+    pub success: bool,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<T>,
+}
+
+/// An event sent to the client.
+#[derive(Debug, Clone, Serialize)]
+pub struct Event<T> {
+    pub seq: u64,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<T>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeArguments {
+    #[serde(default)]
+    pub lines_start_at1: Option<bool>,
+    #[serde(default)]
+    pub columns_start_at1: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub supports_configuration_done_request: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchArguments {
+    pub program: String,
+    #[serde(default)]
+    pub stop_on_entry: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetBreakpointsArguments {
+    pub source: Source,
+    #[serde(default)]
+    pub breakpoints: Vec<SourceBreakpoint>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Source {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceBreakpoint {
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetBreakpointsResponse {
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Breakpoint {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<usize>,
+    pub verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadsResponse {
+    pub threads: Vec<Thread>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Thread {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTraceResponse {
+    pub stack_frames: Vec<StackFrame>,
+    pub total_frames: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StackFrame {
+    pub id: usize,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopesArguments {
+    pub frame_id: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopesResponse {
+    pub scopes: Vec<Scope>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scope {
+    pub name: String,
+    pub variables_reference: usize,
+    pub expensive: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablesArguments {
+    pub variables_reference: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VariablesResponse {
+    pub variables: Vec<Variable>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    pub variables_reference: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoppedEvent {
+    pub reason: &'static str,
+    pub thread_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_breakpoint_ids: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputEvent {
+    pub category: &'static str,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitedEvent {
+    pub exit_code: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinueResponse {
+    pub all_threads_continued: bool,
+}
@@ -0,0 +1,618 @@
+//! A server for the [Debug Adapter Protocol], which allows editors to launch
+//! and debug Rune scripts.
+//!
+//! The adapter runs a single script with a single thread of execution. Script
+//! execution happens on the same task as the protocol, so the adapter can't
+//! process requests while the script is running (e.g. `pause`).
+//!
+//! [Debug Adapter Protocol]: https://microsoft.github.io/debug-adapter-protocol/
+
+mod core;
+pub mod envelope;
+
+use crate::{Input, Output};
+use anyhow::{anyhow, Result};
+use hashbrown::HashMap;
+use rune::termcolor::NoColor;
+use rune::EmitDiagnostics as _;
+use runestick::{DebugHalt, Debugger, Source, SourceId, StepMode, Vm, VmExecution};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The identifier of the only thread being debugged.
+const THREAD_ID: u64 = 1;
+
+/// Run the debug adapter over the given connection until it's disconnected.
+///
+/// This has to be run on a single thread, like on a current-thread runtime,
+/// since the output of the script is captured in a thread-local buffer.
+pub async fn run(mut input: Input, output: Output) -> Result<()> {
+    let mut context = runestick::Context::with_config(false)?;
+    context.install(&self::core::module()?)?;
+    context.install(&rune_modules::http::module()?)?;
+    context.install(&rune_modules::json::module()?)?;
+    context.install(&rune_modules::toml::module()?)?;
+    context.install(&rune_modules::time::module()?)?;
+    context.install(&rune_modules::process::module()?)?;
+    context.install(&rune_modules::fs::module()?)?;
+    context.install(&rune_modules::signal::module()?)?;
+    context.install(&rune_experimental::module()?)?;
+
+    let mut options = rune::Options::default();
+    options.macros(true);
+
+    let mut adapter = Adapter {
+        output,
+        context: Arc::new(context),
+        options,
+        seq: 0,
+        lines_start_at1: true,
+        columns_start_at1: true,
+        pending_breakpoints: HashMap::new(),
+        configured: false,
+        stop_on_entry: false,
+        session: None,
+    };
+
+    while let Some(frame) = input.next().await? {
+        let request: envelope::Request = serde_json::from_slice(frame.content)?;
+        log::info!("dap request: {}", request.command);
+
+        if !adapter.process(request).await? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// A script which has been launched.
+struct Session {
+    sources: rune::Sources,
+    debugger: Debugger,
+    execution: VmExecution,
+    /// Breakpoints registered in the debugger, by source.
+    breakpoints: HashMap<SourceId, Vec<usize>>,
+    /// If the execution has been started.
+    started: bool,
+}
+
+impl Session {
+    /// Find the source corresponding to the given path.
+    fn source_id_by_path(&self, path: &Path) -> Option<SourceId> {
+        let path = canonicalize(path);
+
+        (0..)
+            .map(|id| (id, self.sources.get(id)))
+            .take_while(|(_, source)| source.is_some())
+            .find_map(|(id, source)| match source?.path() {
+                Some(p) if canonicalize(p) == path => Some(id),
+                _ => None,
+            })
+    }
+
+    /// Replace all breakpoints in the given source, returning the lines they
+    /// were resolved to.
+    fn set_breakpoints(
+        &mut self,
+        source_id: SourceId,
+        lines: &[usize],
+    ) -> Vec<Option<(usize, usize)>> {
+        for id in self.breakpoints.remove(&source_id).unwrap_or_default() {
+            self.debugger.remove_breakpoint(id);
+        }
+
+        let mut resolved = Vec::new();
+        let mut ids = Vec::new();
+
+        for &line in lines {
+            match self.debugger.add_breakpoint(source_id, line) {
+                Some(breakpoint) => {
+                    ids.push(breakpoint.id());
+                    resolved.push(Some((breakpoint.id(), breakpoint.location().line)));
+                }
+                None => resolved.push(None),
+            }
+        }
+
+        self.breakpoints.insert(source_id, ids);
+        resolved
+    }
+}
+
+struct Adapter {
+    output: Output,
+    context: Arc<runestick::Context>,
+    options: rune::Options,
+    /// Sequence number of the last message sent.
+    seq: u64,
+    lines_start_at1: bool,
+    columns_start_at1: bool,
+    /// Breakpoints set before the script was launched, by path.
+    pending_breakpoints: HashMap<PathBuf, Vec<usize>>,
+    /// If the client is done configuring the session.
+    configured: bool,
+    /// If the script should stop at the first instruction.
+    stop_on_entry: bool,
+    session: Option<Session>,
+}
+
+impl Adapter {
+    /// Process a single request, returning `false` if the adapter should shut
+    /// down.
+    async fn process(&mut self, request: envelope::Request) -> Result<bool> {
+        let result = match request.command.as_str() {
+            "initialize" => self.initialize(&request).await,
+            "launch" => self.launch(&request).await,
+            "setBreakpoints" => self.set_breakpoints(&request).await,
+            "setExceptionBreakpoints" => self.respond(&request, None::<()>).await,
+            "configurationDone" => self.configuration_done(&request).await,
+            "threads" => self.threads(&request).await,
+            "stackTrace" => self.stack_trace(&request).await,
+            "scopes" => self.scopes(&request).await,
+            "variables" => self.variables(&request).await,
+            "continue" => self.resume(&request, None).await,
+            "next" => self.resume(&request, Some(StepMode::Over)).await,
+            "stepIn" => self.resume(&request, Some(StepMode::In)).await,
+            "stepOut" => self.resume(&request, Some(StepMode::Out)).await,
+            "disconnect" | "terminate" => {
+                self.respond(&request, None::<()>).await?;
+                return Ok(false);
+            }
+            command => Err(anyhow!("unsupported command `{}`", command)),
+        };
+
+        if let Err(error) = result {
+            self.fail(&request, error).await?;
+        }
+
+        Ok(true)
+    }
+
+    async fn initialize(&mut self, request: &envelope::Request) -> Result<()> {
+        let args: envelope::InitializeArguments =
+            serde_json::from_value(request.arguments.clone())?;
+
+        self.lines_start_at1 = args.lines_start_at1.unwrap_or(true);
+        self.columns_start_at1 = args.columns_start_at1.unwrap_or(true);
+
+        let capabilities = envelope::Capabilities {
+            supports_configuration_done_request: true,
+        };
+
+        self.respond(request, Some(capabilities)).await?;
+        self.event("initialized", None::<()>).await?;
+        Ok(())
+    }
+
+    async fn launch(&mut self, request: &envelope::Request) -> Result<()> {
+        let args: envelope::LaunchArguments = serde_json::from_value(request.arguments.clone())?;
+        let path = PathBuf::from(&args.program);
+
+        let mut sources = rune::Sources::new();
+        sources.insert(Source::from_path(&path)?);
+
+        let mut errors = rune::Errors::new();
+        let mut warnings = rune::Warnings::new();
+
+        let unit = match rune::load_sources(
+            &*self.context,
+            &self.options,
+            &mut sources,
+            &mut errors,
+            &mut warnings,
+        ) {
+            Ok(unit) => unit,
+            Err(rune::LoadSourcesError) => {
+                let mut out = NoColor::new(Vec::new());
+                errors.emit_diagnostics(&mut out, &sources)?;
+                let out = String::from_utf8_lossy(&out.into_inner()).into_owned();
+                self.output_event("stderr", out).await?;
+                return Err(anyhow!("failed to compile `{}`", path.display()));
+            }
+        };
+
+        if !warnings.is_empty() {
+            let mut out = NoColor::new(Vec::new());
+            warnings.emit_diagnostics(&mut out, &sources)?;
+            let out = String::from_utf8_lossy(&out.into_inner()).into_owned();
+            self.output_event("console", out).await?;
+        }
+
+        let unit = Arc::new(unit);
+
+        let debugger = Debugger::new(unit.clone(), |source_id| {
+            sources.get(source_id).map(|s| &**s)
+        });
+
+        let execution = Vm::new(self.context.clone(), unit).execute(&["main"], ())?;
+
+        let mut session = Session {
+            sources,
+            debugger,
+            execution,
+            breakpoints: HashMap::new(),
+            started: false,
+        };
+
+        for (path, lines) in self.pending_breakpoints.drain() {
+            if let Some(source_id) = session.source_id_by_path(&path) {
+                session.set_breakpoints(source_id, &lines);
+            }
+        }
+
+        self.stop_on_entry = args.stop_on_entry;
+        self.session = Some(session);
+        self.respond(request, None::<()>).await?;
+
+        if self.configured {
+            self.start().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn set_breakpoints(&mut self, request: &envelope::Request) -> Result<()> {
+        let args: envelope::SetBreakpointsArguments =
+            serde_json::from_value(request.arguments.clone())?;
+
+        let path = match &args.source.path {
+            Some(path) => PathBuf::from(path),
+            None => return Err(anyhow!("breakpoints are only supported for files")),
+        };
+
+        let lines = args
+            .breakpoints
+            .iter()
+            .map(|b| self.zero_based_line(b.line))
+            .collect::<Vec<_>>();
+
+        let breakpoints = match &mut self.session {
+            Some(session) => {
+                let resolved = match session.source_id_by_path(&path) {
+                    Some(source_id) => session.set_breakpoints(source_id, &lines),
+                    None => vec![None; lines.len()],
+                };
+
+                resolved
+                    .into_iter()
+                    .map(|resolved| match resolved {
+                        Some((id, line)) => envelope::Breakpoint {
+                            id: Some(id),
+                            verified: true,
+                            line: Some(self.to_client_line(line)),
+                            message: None,
+                        },
+                        None => envelope::Breakpoint {
+                            id: None,
+                            verified: false,
+                            line: None,
+                            message: Some(String::from("no code at or after line")),
+                        },
+                    })
+                    .collect()
+            }
+            None => {
+                let breakpoints = args
+                    .breakpoints
+                    .iter()
+                    .map(|b| envelope::Breakpoint {
+                        id: None,
+                        verified: false,
+                        line: Some(b.line),
+                        message: None,
+                    })
+                    .collect();
+
+                self.pending_breakpoints.insert(path, lines);
+                breakpoints
+            }
+        };
+
+        self.respond(
+            request,
+            Some(envelope::SetBreakpointsResponse { breakpoints }),
+        )
+        .await
+    }
+
+    async fn configuration_done(&mut self, request: &envelope::Request) -> Result<()> {
+        self.configured = true;
+        self.respond(request, None::<()>).await?;
+
+        if self.session.is_some() {
+            self.start().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn threads(&mut self, request: &envelope::Request) -> Result<()> {
+        let threads = vec![envelope::Thread {
+            id: THREAD_ID,
+            name: String::from("main"),
+        }];
+
+        self.respond(request, Some(envelope::ThreadsResponse { threads }))
+            .await
+    }
+
+    async fn stack_trace(&mut self, request: &envelope::Request) -> Result<()> {
+        let session = self.session()?;
+        let mut stack_frames = Vec::new();
+
+        for (id, frame) in session
+            .debugger
+            .frames(&session.execution)
+            .iter()
+            .enumerate()
+        {
+            let name = match frame.function() {
+                Some(item) => item.to_string(),
+                None => String::from("<unknown>"),
+            };
+
+            let mut stack_frame = envelope::StackFrame {
+                id,
+                name,
+                source: None,
+                line: 0,
+                column: 0,
+            };
+
+            if let (Some(location), Some(span)) = (frame.location(), frame.span()) {
+                if let Some(source) = session.sources.get(location.source_id) {
+                    let (line, column) = source.position_to_unicode_line_char(span.start);
+
+                    stack_frame.source = Some(envelope::Source {
+                        name: Some(source.name().to_owned()),
+                        path: source.path().map(|p| p.display().to_string()),
+                    });
+
+                    stack_frame.line = self.to_client_line(line);
+                    stack_frame.column = self.to_client_column(column);
+                }
+            }
+
+            stack_frames.push(stack_frame);
+        }
+
+        let total_frames = stack_frames.len();
+
+        self.respond(
+            request,
+            Some(envelope::StackTraceResponse {
+                stack_frames,
+                total_frames,
+            }),
+        )
+        .await
+    }
+
+    async fn scopes(&mut self, request: &envelope::Request) -> Result<()> {
+        let args: envelope::ScopesArguments = serde_json::from_value(request.arguments.clone())?;
+
+        // NB: variable references must be non-zero, so they are offset by one
+        // from the frame identifier.
+        let scopes = vec![envelope::Scope {
+            name: String::from("Locals"),
+            variables_reference: args.frame_id + 1,
+            expensive: false,
+        }];
+
+        self.respond(request, Some(envelope::ScopesResponse { scopes }))
+            .await
+    }
+
+    async fn variables(&mut self, request: &envelope::Request) -> Result<()> {
+        let args: envelope::VariablesArguments = serde_json::from_value(request.arguments.clone())?;
+
+        let session = self.session()?;
+        let frames = session.debugger.frames(&session.execution);
+
+        let variables = match args
+            .variables_reference
+            .checked_sub(1)
+            .and_then(|id| frames.get(id))
+        {
+            Some(frame) => session
+                .debugger
+                .locals(&session.execution, frame)
+                .into_iter()
+                .map(|local| envelope::Variable {
+                    name: local.name,
+                    value: format!("{:?}", local.value),
+                    variables_reference: 0,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        self.respond(request, Some(envelope::VariablesResponse { variables }))
+            .await
+    }
+
+    /// Resume execution after responding to the given request.
+    async fn resume(&mut self, request: &envelope::Request, step: Option<StepMode>) -> Result<()> {
+        let session = self.session_mut()?;
+
+        if let Some(mode) = step {
+            session.debugger.step(&session.execution, mode)?;
+        }
+
+        if step.is_none() {
+            self.respond(
+                request,
+                Some(envelope::ContinueResponse {
+                    all_threads_continued: true,
+                }),
+            )
+            .await?;
+        } else {
+            self.respond(request, None::<()>).await?;
+        }
+
+        self.run_until_halt().await
+    }
+
+    /// Start the execution once it's both launched and configured.
+    async fn start(&mut self) -> Result<()> {
+        let stop_on_entry = self.stop_on_entry;
+        let session = self.session_mut()?;
+
+        if session.started {
+            return Ok(());
+        }
+
+        session.started = true;
+
+        if stop_on_entry {
+            return self.stopped("entry", None).await;
+        }
+
+        self.run_until_halt().await
+    }
+
+    /// Run the execution until it halts, and report why it halted.
+    async fn run_until_halt(&mut self) -> Result<()> {
+        let session = self.session_mut()?;
+        let result = session.debugger.async_resume(&mut session.execution).await;
+
+        let output = self::core::drain_output();
+
+        if !output.is_empty() {
+            self.output_event("stdout", output).await?;
+        }
+
+        match result {
+            Ok(DebugHalt::Breakpoint(id)) => self.stopped("breakpoint", Some(vec![id])).await,
+            Ok(DebugHalt::Step) => self.stopped("step", None).await,
+            Ok(DebugHalt::Exited(value)) => {
+                self.output_event("console", format!("== {:?}\n", value))
+                    .await?;
+                self.exited(0).await
+            }
+            Err(error) => {
+                self.output_event("stderr", format!("error: {}\n", error))
+                    .await?;
+                self.exited(1).await
+            }
+        }
+    }
+
+    async fn stopped(&mut self, reason: &'static str, hit: Option<Vec<usize>>) -> Result<()> {
+        let event = envelope::StoppedEvent {
+            reason,
+            thread_id: THREAD_ID,
+            hit_breakpoint_ids: hit,
+        };
+
+        self.event("stopped", Some(event)).await
+    }
+
+    async fn exited(&mut self, exit_code: i64) -> Result<()> {
+        self.session = None;
+        self.event("exited", Some(envelope::ExitedEvent { exit_code }))
+            .await?;
+        self.event("terminated", None::<()>).await
+    }
+
+    fn session(&self) -> Result<&Session> {
+        self.session
+            .as_ref()
+            .ok_or_else(|| anyhow!("no script is running"))
+    }
+
+    fn session_mut(&mut self) -> Result<&mut Session> {
+        self.session
+            .as_mut()
+            .ok_or_else(|| anyhow!("no script is running"))
+    }
+
+    /// Convert a line from the client into a zero-based line.
+    fn zero_based_line(&self, line: usize) -> usize {
+        if self.lines_start_at1 {
+            line.saturating_sub(1)
+        } else {
+            line
+        }
+    }
+
+    /// Convert a zero-based line into a line for the client.
+    fn to_client_line(&self, line: usize) -> usize {
+        if self.lines_start_at1 {
+            line + 1
+        } else {
+            line
+        }
+    }
+
+    /// Convert a zero-based column into a column for the client.
+    fn to_client_column(&self, column: usize) -> usize {
+        if self.columns_start_at1 {
+            column + 1
+        } else {
+            column
+        }
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        self.seq += 1;
+        self.seq
+    }
+
+    async fn respond<T>(&mut self, request: &envelope::Request, body: Option<T>) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let response = envelope::Response {
+            seq: self.next_seq(),
+            ty: "response",
+            request_seq: request.seq,
+            success: true,
+            command: request.command.clone(),
+            message: None,
+            body,
+        };
+
+        self.output.message(&response).await
+    }
+
+    async fn fail(&mut self, request: &envelope::Request, error: anyhow::Error) -> Result<()> {
+        let response = envelope::Response {
+            seq: self.next_seq(),
+            ty: "response",
+            request_seq: request.seq,
+            success: false,
+            command: request.command.clone(),
+            message: Some(error.to_string()),
+            body: None::<()>,
+        };
+
+        self.output.message(&response).await
+    }
+
+    async fn event<T>(&mut self, event: &'static str, body: Option<T>) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let event = envelope::Event {
+            seq: self.next_seq(),
+            ty: "event",
+            event,
+            body,
+        };
+
+        self.output.message(&event).await
+    }
+
+    async fn output_event(&mut self, category: &'static str, output: String) -> Result<()> {
+        self.event("output", Some(envelope::OutputEvent { category, output }))
+            .await
+    }
+}
+
+/// Canonicalize the given path, falling back to the path as-is.
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
@@ -44,14 +44,17 @@
 //!
 //! [Rune Language]: https://rune-rs.github.io
 
+mod completion;
 mod connection;
+pub mod dap;
 pub mod envelope;
 mod server;
 mod state;
+mod symbols;
 
 pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/version.txt"));
 
-pub use crate::connection::stdio;
+pub use crate::connection::{connect, stdio};
 pub use crate::connection::{Input, Output};
 pub use crate::server::Server;
 pub use crate::state::State;
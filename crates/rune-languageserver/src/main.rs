@@ -73,25 +73,54 @@ fn setup_logging() -> Result<()> {
     Ok(())
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     setup_logging()?;
 
     let mut it = env::args();
     it.next();
 
+    let mut dap = false;
+
     while let Some(arg) = it.next() {
         match arg.as_str() {
             "--version" => {
                 println!("Rune language server {}", rune_languageserver::VERSION);
                 return Ok(());
             }
+            "--dap" => {
+                dap = true;
+            }
             other => {
                 bail!("Unsupported option: {}", other);
             }
         }
     }
 
+    if dap {
+        // NB: the debug adapter has to run on a single thread, see
+        // `rune_languageserver::dap::run`.
+        let mut runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()?;
+
+        return runtime.block_on(async {
+            let (input, output) = rune_languageserver::stdio()?;
+            log::info!("Starting debug adapter");
+            rune_languageserver::dap::run(input, output).await
+        });
+    }
+
+    let mut runtime = tokio::runtime::Builder::new()
+        .threaded_scheduler()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(server())
+}
+
+/// Run the language server over stdio.
+async fn server() -> Result<()> {
     let mut context = rune::default_context()?;
     context.install(&rune_experimental::module()?)?;
 
@@ -107,6 +136,14 @@ async fn main() -> Result<()> {
     server.request_handler::<lsp::request::Initialize, _, _>(initialize);
 
     server.request_handler::<lsp::request::GotoDefinition, _, _>(goto_definition);
+    server.request_handler::<lsp::request::HoverRequest, _, _>(hover);
+    server.request_handler::<lsp::request::Completion, _, _>(completion);
+    server.request_handler::<lsp::request::SignatureHelpRequest, _, _>(signature_help);
+    server.request_handler::<lsp::request::References, _, _>(references);
+    server.request_handler::<lsp::request::Rename, _, _>(rename);
+    server.request_handler::<lsp::request::DocumentSymbolRequest, _, _>(document_symbol);
+    server.request_handler::<lsp::request::Formatting, _, _>(formatting);
+    server.request_handler::<lsp::request::WorkspaceSymbol, _, _>(workspace_symbol);
 
     server.notification_handler::<lsp::notification::DidOpenTextDocument, _, _>(
         did_open_text_document,
@@ -163,6 +200,23 @@ async fn initialize(
     ));
 
     capabilities.definition_provider = Some(true);
+    capabilities.hover_provider = Some(lsp::HoverProviderCapability::Simple(true));
+
+    capabilities.completion_provider = Some(lsp::CompletionOptions {
+        trigger_characters: Some(vec![String::from("."), String::from(":")]),
+        ..lsp::CompletionOptions::default()
+    });
+
+    capabilities.references_provider = Some(true);
+    capabilities.rename_provider = Some(lsp::RenameProviderCapability::Simple(true));
+    capabilities.document_symbol_provider = Some(true);
+    capabilities.document_formatting_provider = Some(true);
+    capabilities.workspace_symbol_provider = Some(true);
+
+    capabilities.signature_help_provider = Some(lsp::SignatureHelpOptions {
+        trigger_characters: Some(vec![String::from("("), String::from(",")]),
+        ..lsp::SignatureHelpOptions::default()
+    });
 
     let server_info = lsp::ServerInfo {
         name: String::from("Rune Language Server"),
@@ -197,6 +251,104 @@ async fn goto_definition(
     Ok(position.map(lsp::GotoDefinitionResponse::Scalar))
 }
 
+/// Handle hover requests.
+async fn hover(state: State, _: Output, params: lsp::HoverParams) -> Result<Option<lsp::Hover>> {
+    Ok(state
+        .hover(
+            &params.text_document_position_params.text_document.uri,
+            params.text_document_position_params.position,
+        )
+        .await)
+}
+
+/// Handle completion requests.
+async fn completion(
+    state: State,
+    _: Output,
+    params: lsp::CompletionParams,
+) -> Result<Option<lsp::CompletionResponse>> {
+    let items = state
+        .complete(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+        )
+        .await;
+
+    Ok(items.map(lsp::CompletionResponse::Array))
+}
+
+/// Handle signature help requests.
+async fn signature_help(
+    state: State,
+    _: Output,
+    params: lsp::SignatureHelpParams,
+) -> Result<Option<lsp::SignatureHelp>> {
+    Ok(state
+        .signature_help(
+            &params.text_document_position_params.text_document.uri,
+            params.text_document_position_params.position,
+        )
+        .await)
+}
+
+/// Handle find references requests.
+async fn references(
+    state: State,
+    _: Output,
+    params: lsp::ReferenceParams,
+) -> Result<Option<Vec<lsp::Location>>> {
+    Ok(state
+        .references(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            params.context.include_declaration,
+        )
+        .await)
+}
+
+/// Handle rename requests.
+async fn rename(
+    state: State,
+    _: Output,
+    params: lsp::RenameParams,
+) -> Result<Option<lsp::WorkspaceEdit>> {
+    Ok(state
+        .rename(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            &params.new_name,
+        )
+        .await)
+}
+
+/// Handle document symbol requests.
+async fn document_symbol(
+    state: State,
+    _: Output,
+    params: lsp::DocumentSymbolParams,
+) -> Result<Option<lsp::DocumentSymbolResponse>> {
+    let symbols = state.document_symbols(&params.text_document.uri).await;
+    Ok(symbols.map(lsp::DocumentSymbolResponse::Nested))
+}
+
+/// Handle formatting requests.
+async fn formatting(
+    state: State,
+    _: Output,
+    params: lsp::DocumentFormattingParams,
+) -> Result<Option<Vec<lsp::TextEdit>>> {
+    Ok(state.format(&params.text_document.uri).await)
+}
+
+/// Handle workspace symbol requests.
+async fn workspace_symbol(
+    state: State,
+    _: Output,
+    params: lsp::WorkspaceSymbolParams,
+) -> Result<Option<Vec<lsp::SymbolInformation>>> {
+    Ok(Some(state.workspace_symbols(&params.query).await))
+}
+
 /// Handle open text document.
 async fn did_open_text_document(
     state: State,
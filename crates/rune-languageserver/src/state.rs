@@ -1,6 +1,8 @@
+use crate::completion::{self, Prefix};
+use crate::symbols::{self, Symbol};
 use crate::Output;
 use anyhow::{anyhow, Result};
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use lsp::Url;
 use ropey::Rope;
 use rune::Spanned as _;
@@ -73,23 +75,24 @@ impl State {
         let sources = self.inner.sources.read().await;
 
         let source = sources.get(uri)?;
-        let offset = source.lsp_position_to_offset(position);
+        let offset = source.lsp_position_to_offset(position)?;
         let def = source.find_definition_at(Span::point(offset))?;
+        let def_source = def.source.as_ref()?;
 
-        let url = match def.source.path.as_ref() {
+        let url = match def_source.path.as_ref() {
             Some(path) => Url::from_file_path(path).ok()?,
             None => uri.clone(),
         };
 
-        let source = source.build_sources.as_ref()?.get(def.source.source_id)?;
+        let source = source.build_sources.as_ref()?.get(def_source.source_id)?;
 
-        let (l, c) = source.position_to_utf16cu_line_char(def.source.span.start)?;
+        let (l, c) = source.position_to_utf16cu_line_char(def_source.span.start)?;
         let start = lsp::Position {
             line: l as u64,
             character: c as u64,
         };
 
-        let (l, c) = source.position_to_utf16cu_line_char(def.source.span.end)?;
+        let (l, c) = source.position_to_utf16cu_line_char(def_source.span.end)?;
         let end = lsp::Position {
             line: l as u64,
             character: c as u64,
@@ -106,6 +109,246 @@ impl State {
         Some(location)
     }
 
+    /// Describe the definition at the given uri and LSP position.
+    pub async fn hover(&self, uri: &Url, position: lsp::Position) -> Option<lsp::Hover> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position)?;
+        let (span, def) = source.definition_at(Span::point(offset))?;
+
+        let description = match (def.kind, &def.item) {
+            (DefinitionKind::Local, _) => {
+                let def_source = def.source.as_ref()?;
+                let build_source = source.build_sources.as_ref()?.get(def_source.source_id)?;
+                format!("let {}", build_source.source(def_source.span)?)
+            }
+            (DefinitionKind::Function, Some(item)) => {
+                match completion::lookup_signature(&self.inner.context, source.unit.as_ref(), item)
+                {
+                    Some(signature) => format!("fn {}", signature.label),
+                    None => format!("fn {}", item),
+                }
+            }
+            (DefinitionKind::Tuple, Some(item)) | (DefinitionKind::Struct, Some(item)) => {
+                format!("struct {}", item)
+            }
+            (DefinitionKind::TupleVariant, Some(item))
+            | (DefinitionKind::ObjectVariant, Some(item)) => format!("variant {}", item),
+            (DefinitionKind::Enum, Some(item)) => format!("enum {}", item),
+            _ => return None,
+        };
+
+        let contents = lsp::HoverContents::Markup(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value: format!("```rune\n{}\n```", description),
+        });
+
+        Some(lsp::Hover {
+            contents,
+            range: Some(source.span_to_lsp_range(span)),
+        })
+    }
+
+    /// Collect completions at the given uri and LSP position.
+    pub async fn complete(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<Vec<lsp::CompletionItem>> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position)?;
+        let text = source.text_before(offset);
+        let prefix = Prefix::parse(&text);
+
+        let unit = source.unit.as_ref();
+
+        // NB: the unit might have been built before the document was last
+        // modified, so only locals from the source matching it are relevant.
+        let locals = match (unit, source.build_source_id(uri)) {
+            (Some(unit), Some(source_id)) => completion::locals_at(unit, source_id, offset),
+            _ => Vec::new(),
+        };
+
+        Some(completion::complete(
+            &self.inner.context,
+            unit,
+            &locals,
+            &prefix,
+        ))
+    }
+
+    /// Find signatures of the call surrounding the given uri and LSP position.
+    pub async fn signature_help(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<lsp::SignatureHelp> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position)?;
+        let text = source.text_before(offset);
+        let call = completion::call_at(&text)?;
+
+        let context = &self.inner.context;
+        let unit = source.unit.as_ref();
+
+        let signatures = if call.prefix.instance {
+            completion::instance_signatures(context, call.prefix.partial)
+                .map(completion::context_signature)
+                .collect::<Vec<_>>()
+        } else {
+            // NB: prefer the item that the compiler resolved the call to, and
+            // fall back to the path as written.
+            let item = match source.find_definition_at(Span::point(call.end - 1)) {
+                Some(Definition {
+                    kind: DefinitionKind::Function,
+                    item: Some(item),
+                    ..
+                }) => item.clone(),
+                _ => {
+                    let mut path = call.prefix.path.clone();
+                    path.push(call.prefix.partial);
+                    Item::of(path)
+                }
+            };
+
+            completion::lookup_signature(context, unit, &item)
+                .into_iter()
+                .collect()
+        };
+
+        if signatures.is_empty() {
+            return None;
+        }
+
+        Some(lsp::SignatureHelp {
+            signatures,
+            active_signature: Some(0),
+            active_parameter: Some(call.argument as i64),
+        })
+    }
+
+    /// Find all references to the declaration at the given uri and LSP
+    /// position, in all open sources.
+    pub async fn references(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        include_declaration: bool,
+    ) -> Option<Vec<lsp::Location>> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position)?;
+
+        let locations = sources
+            .references_at(uri, offset)?
+            .into_iter()
+            .filter(|reference| include_declaration || !reference.is_declaration)
+            .map(|reference| reference.location.clone())
+            .collect();
+
+        Some(locations)
+    }
+
+    /// Rename the declaration at the given uri and LSP position, and all
+    /// references to it in all open sources.
+    pub async fn rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        new_name: &str,
+    ) -> Option<lsp::WorkspaceEdit> {
+        if !is_ident(new_name) {
+            return None;
+        }
+
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position)?;
+
+        let mut changes = std::collections::HashMap::<Url, Vec<lsp::TextEdit>>::new();
+
+        for reference in sources.references_at(uri, offset)? {
+            changes
+                .entry(reference.location.uri.clone())
+                .or_default()
+                .push(lsp::TextEdit {
+                    range: reference.location.range,
+                    new_text: new_name.to_owned(),
+                });
+        }
+
+        Some(lsp::WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+        })
+    }
+
+    /// List the symbols declared in the given uri.
+    pub async fn document_symbols(&self, uri: &Url) -> Option<Vec<lsp::DocumentSymbol>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+        Some(source.document_symbols(&source.index.symbols))
+    }
+
+    /// Find the symbols matching the given query in all open sources.
+    pub async fn workspace_symbols(&self, query: &str) -> Vec<lsp::SymbolInformation> {
+        let sources = self.inner.sources.read().await;
+        let query = query.to_lowercase();
+
+        let mut out = Vec::new();
+
+        for (url, source) in &sources.sources {
+            for (parent, symbol) in symbols::flatten(&source.index.symbols) {
+                if !symbol.name.to_lowercase().contains(&query) {
+                    continue;
+                }
+
+                #[allow(deprecated)]
+                out.push(lsp::SymbolInformation {
+                    name: symbol.name.clone(),
+                    kind: symbol.kind,
+                    deprecated: None,
+                    location: lsp::Location {
+                        uri: url.clone(),
+                        range: source.span_to_lsp_range(symbol.name_span),
+                    },
+                    container_name: parent.map(|p| p.name.clone()),
+                });
+            }
+        }
+
+        out
+    }
+
+    /// Format the given uri, returning an edit which replaces the whole
+    /// document.
+    ///
+    /// Returns `None` if the document doesn't parse.
+    pub async fn format(&self, uri: &Url) -> Option<Vec<lsp::TextEdit>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+        let text = source.to_string();
+        let formatted = rune::format(&text).ok()?;
+
+        if formatted == text {
+            return Some(Vec::new());
+        }
+
+        let range = lsp::Range {
+            start: lsp::Position::new(0, 0),
+            end: source.offset_to_lsp_position(text.len()),
+        };
+
+        Some(vec![lsp::TextEdit::new(range, formatted)])
+    }
+
     /// Rebuild the current project.
     pub async fn rebuild(&self, output: &Output) -> Result<()> {
         let mut inner = self.inner.sources.write().await;
@@ -122,7 +365,10 @@ impl State {
             log::trace!("build: {}", url);
 
             by_url.insert(url.clone(), Default::default());
-            let mut index = Index::default();
+            let mut index = Index {
+                symbols: symbols::collect(&source.to_string()).unwrap_or_default(),
+                ..Index::default()
+            };
 
             let mut sources = rune::Sources::new();
 
@@ -136,7 +382,7 @@ impl State {
             let mut visitor = Visitor::new(&mut index);
             let mut source_loader = SourceLoader::new(&inner.sources);
 
-            let result = rune::load_sources_with_visitor(
+            let unit = rune::load_sources_with_visitor(
                 &self.inner.context,
                 &self.inner.options,
                 &mut sources,
@@ -146,7 +392,10 @@ impl State {
                 &mut source_loader,
             );
 
-            if let Err(rune::LoadSourcesError) = result {
+            let uses = std::mem::take(&mut visitor.uses);
+            index.references = resolve_references(&sources, uses);
+
+            if let Err(rune::LoadSourcesError) = &unit {
                 for error in errors {
                     let source_id = error.source_id();
 
@@ -207,13 +456,19 @@ impl State {
                 );
             }
 
-            builds.push((url.clone(), sources, index));
+            builds.push((url.clone(), sources, index, unit.ok()));
         }
 
-        for (url, build_sources, index) in builds {
+        for (url, build_sources, index, unit) in builds {
             if let Some(source) = inner.sources.get_mut(&url) {
                 source.index = index;
                 source.build_sources = Some(build_sources);
+
+                // NB: keep the last unit which built successfully around, since
+                // it's used to look up signatures and locals while editing.
+                if let Some(unit) = unit {
+                    source.unit = Some(unit);
+                }
             }
         }
 
@@ -263,6 +518,7 @@ impl Sources {
             content: Rope::from(text),
             index: Default::default(),
             build_sources: None,
+            unit: None,
         };
 
         self.sources.insert(url, source)
@@ -278,6 +534,35 @@ impl Sources {
         self.sources.get_mut(url)
     }
 
+    /// Find all references to the declaration which is declared or referenced
+    /// at the given offset in the given url.
+    ///
+    /// References are deduplicated, since sources which are loaded as modules
+    /// are indexed both by themselves and by the sources loading them.
+    fn references_at(&self, url: &Url, offset: usize) -> Option<Vec<&Reference>> {
+        let references = self
+            .sources
+            .values()
+            .flat_map(|source| source.index.references.iter());
+
+        let declaration = &references
+            .clone()
+            .find(|r| r.location.uri == *url && r.span.start <= offset && offset <= r.span.end)?
+            .declaration;
+
+        let mut seen = HashSet::new();
+
+        let mut out = references
+            .filter(|r| r.declaration == *declaration)
+            .filter(|r| seen.insert((&r.location.uri, r.span)))
+            .collect::<Vec<_>>();
+
+        out.sort_by(|a, b| {
+            (a.location.uri.as_str(), a.span).cmp(&(b.location.uri.as_str(), b.span))
+        });
+        Some(out)
+    }
+
     /// Remove the given url as a source.
     pub fn remove(&mut self, url: &Url) {
         if let Some(source) = self.sources.remove(url) {
@@ -295,21 +580,71 @@ pub struct Source {
     /// Loaded Rune sources for this source file. Will be present after the
     /// source file has been built.
     build_sources: Option<rune::Sources>,
+    /// The last unit which was successfully built from this source file.
+    unit: Option<runestick::Unit>,
 }
 
 impl Source {
     /// Find the definition at the given span.
     pub fn find_definition_at(&self, span: Span) -> Option<&Definition> {
-        let (found_span, definition) = self.index.definitions.range(..=span).rev().next()?;
+        Some(self.definition_at(span)?.1)
+    }
+
+    /// Find the definition at the given span, together with the span it's
+    /// referenced at.
+    fn definition_at(&self, span: Span) -> Option<(Span, &Definition)> {
+        let (found_span, definition) = self
+            .index
+            .definitions
+            .range(..=Span::new(span.start, usize::MAX))
+            .rev()
+            .find(|(found, _)| span.start >= found.start && span.end <= found.end)?;
+
+        log::trace!("found {:?}", definition);
+        Some((*found_span, definition))
+    }
+
+    /// Find the id of the build source corresponding to the given url.
+    fn build_source_id(&self, url: &Url) -> Option<SourceId> {
+        let sources = self.build_sources.as_ref()?;
+        let mut source_id = 0;
+
+        while let Some(source) = sources.get(source_id) {
+            if source_url(source).as_ref() == Some(url) {
+                return Some(source_id);
+            }
 
-        if span.start >= found_span.start && span.end <= found_span.end {
-            log::trace!("found {:?}", definition);
-            return Some(definition);
+            source_id += 1;
         }
 
         None
     }
 
+    /// Convert symbols into document symbols.
+    fn document_symbols(&self, symbols: &[Symbol]) -> Vec<lsp::DocumentSymbol> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                let children = self.document_symbols(&symbol.children);
+
+                #[allow(deprecated)]
+                lsp::DocumentSymbol {
+                    name: symbol.name.clone(),
+                    detail: None,
+                    kind: symbol.kind,
+                    deprecated: None,
+                    range: self.span_to_lsp_range(symbol.span),
+                    selection_range: self.span_to_lsp_range(symbol.name_span),
+                    children: if children.is_empty() {
+                        None
+                    } else {
+                        Some(children)
+                    },
+                }
+            })
+            .collect()
+    }
+
     /// Modify the given lsp range in the file.
     pub fn modify_lsp_range(&mut self, range: lsp::Range, content: &str) -> Result<()> {
         let start = rope_utf16_position(&self.content, range.start)?;
@@ -346,11 +681,23 @@ impl Source {
         lsp::Position::new(line as u64, col_char as u64)
     }
 
-    /// Offset in the rope to lsp position.
-    fn lsp_position_to_offset(&self, position: lsp::Position) -> usize {
-        let line = self.content.line_to_char(position.line as usize);
-        self.content
-            .utf16_cu_to_char(line + position.character as usize)
+    /// Lsp position to offset in the rope.
+    ///
+    /// Returns `None` if the position is out of bounds.
+    fn lsp_position_to_offset(&self, position: lsp::Position) -> Option<usize> {
+        let line = self.content.try_line_to_char(position.line as usize).ok()?;
+        let line = self.content.char_to_utf16_cu(line);
+        let offset = self
+            .content
+            .try_utf16_cu_to_char(line + position.character as usize)
+            .ok()?;
+        Some(self.content.char_to_byte(offset))
+    }
+
+    /// Get the text preceding the given offset.
+    fn text_before(&self, offset: usize) -> String {
+        let offset = self.content.byte_to_char(offset);
+        self.content.slice(..offset).to_string()
     }
 
     /// Iterate over the text chunks in the source.
@@ -365,6 +712,14 @@ impl fmt::Display for Source {
     }
 }
 
+/// Get the url of the given build source.
+fn source_url(source: &runestick::Source) -> Option<Url> {
+    match source.path() {
+        Some(path) => Url::from_file_path(path).ok(),
+        None => Url::parse(source.name()).ok(),
+    }
+}
+
 /// Conver the given span into an lsp range.
 fn span_to_lsp_range(source: &runestick::Source, span: Span) -> Option<lsp::Range> {
     let (line, character) = source.position_to_utf16cu_line_char(span.start)?;
@@ -403,6 +758,18 @@ fn rope_utf16_position(rope: &Rope, position: lsp::Position) -> Result<usize> {
     Ok(rope.line_to_char(position.line as usize) + char_offset)
 }
 
+/// Test if the given string is a valid identifier.
+fn is_ident(s: &str) -> bool {
+    let mut it = s.chars();
+
+    match it.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => (),
+        _ => return false,
+    }
+
+    it.all(|c| c.is_alphanumeric() || c == '_')
+}
+
 /// Convert the given span and error into an error diagnostic.
 fn report<E, R>(
     sources: &rune::Sources,
@@ -473,18 +840,154 @@ where
     }
 }
 
+/// Resolve the uses visited while building into references.
+fn resolve_references(sources: &rune::Sources, uses: Vec<Use>) -> Vec<Reference> {
+    let mut symbols = HashMap::<SourceId, Vec<Symbol>>::new();
+    let mut out = Vec::new();
+
+    for Use {
+        source_id,
+        span: use_span,
+        declaration: compile_source,
+        name,
+    } in uses
+    {
+        let source = match sources.get(compile_source.source_id) {
+            Some(source) => source,
+            None => continue,
+        };
+
+        let url = match source_url(source) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        let (name, name_span) = match name {
+            Some(name) => {
+                let symbols = symbols
+                    .entry(compile_source.source_id)
+                    .or_insert_with(|| symbols::collect(source.as_str()).unwrap_or_default());
+
+                let name_span = symbols::flatten(symbols)
+                    .into_iter()
+                    .find(|(_, s)| s.span == compile_source.span && s.name == name)
+                    .map(|(_, s)| s.name_span);
+
+                (name, name_span)
+            }
+            // NB: local variables are declared at their name.
+            None => match source.source(compile_source.span) {
+                Some(name) => (name.to_owned(), Some(compile_source.span)),
+                None => continue,
+            },
+        };
+
+        let declaration = Declaration {
+            url,
+            span: compile_source.span,
+        };
+
+        if let Some(name_span) = name_span {
+            if let Some(range) = span_to_lsp_range(source, name_span) {
+                out.push(Reference {
+                    location: lsp::Location::new(declaration.url.clone(), range),
+                    span: name_span,
+                    declaration: declaration.clone(),
+                    is_declaration: true,
+                });
+            }
+        }
+
+        // NB: unused items are visited at their declaration.
+        if source_id == compile_source.source_id && use_span == compile_source.span {
+            continue;
+        }
+
+        let source = match sources.get(source_id) {
+            Some(source) => source,
+            None => continue,
+        };
+
+        let url = match source_url(source) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        // NB: items are referenced by paths, where the last component is the
+        // name of the item.
+        let span = Span::new(use_span.end.saturating_sub(name.len()), use_span.end);
+
+        if source.source(span) != Some(name.as_str()) {
+            continue;
+        }
+
+        if let Some(range) = span_to_lsp_range(source, span) {
+            out.push(Reference {
+                location: lsp::Location::new(url, range),
+                span,
+                declaration,
+                is_declaration: false,
+            });
+        }
+    }
+
+    out
+}
+
+/// A declaration which can be referenced.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Declaration {
+    /// The url the declaration is in.
+    url: Url,
+    /// The span of the whole declaration.
+    span: Span,
+}
+
+/// The name of a declaration, or a name referencing it.
+#[derive(Debug, Clone)]
+struct Reference {
+    /// The location of the name.
+    location: lsp::Location,
+    /// The span of the name.
+    span: Span,
+    /// The declaration being referenced.
+    declaration: Declaration,
+    /// If this is the name of the declaration itself.
+    is_declaration: bool,
+}
+
+/// A use of a declaration visited while building.
+struct Use {
+    /// The source the use is in.
+    source_id: SourceId,
+    /// The span of the use.
+    span: Span,
+    /// Where the used declaration is declared.
+    declaration: CompileSource,
+    /// The name of the declaration, or `None` if it's a local variable, in
+    /// which case the declaration is the name.
+    name: Option<String>,
+}
+
 #[derive(Default)]
 pub struct Index {
     /// Spans mapping to their corresponding definitions.
     definitions: BTreeMap<Span, Definition>,
+    /// Symbols declared in the source.
+    symbols: Vec<Symbol>,
+    /// Names declared or referenced in any of the built sources.
+    references: Vec<Reference>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Definition {
     /// The kind of the definition.
     pub(crate) kind: DefinitionKind,
-    /// The id of the source id the definition corresponds to.
-    pub(crate) source: CompileSource,
+    /// The id of the source id the definition corresponds to, if it's
+    /// defined in a source.
+    pub(crate) source: Option<CompileSource>,
+    /// The item of the definition, if it has one.
+    pub(crate) item: Option<Item>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -509,26 +1012,35 @@ pub enum DefinitionKind {
 
 struct Visitor<'a> {
     index: &'a mut Index,
+    /// Uses visited in any source.
+    uses: Vec<Use>,
 }
 
 impl<'a> Visitor<'a> {
     /// Construct a new visitor.
     pub fn new(index: &'a mut Index) -> Self {
-        Self { index }
+        Self {
+            index,
+            uses: Vec::new(),
+        }
     }
 }
 
 impl rune::CompileVisitor for Visitor<'_> {
     fn visit_meta(&mut self, source_id: SourceId, meta: &CompileMeta, span: Span) {
+        if let (Some(source), Some(Component::String(name))) = (&meta.source, meta.item().last()) {
+            self.uses.push(Use {
+                source_id,
+                span,
+                declaration: source.clone(),
+                name: Some(name.to_string()),
+            });
+        }
+
         if source_id != 0 {
             return;
         }
 
-        let source = match meta.source.as_ref() {
-            Some(source) => source,
-            None => return,
-        };
-
         let kind = match &meta.kind {
             CompileMetaKind::Tuple { .. } => DefinitionKind::Tuple,
             CompileMetaKind::TupleVariant { .. } => DefinitionKind::TupleVariant,
@@ -541,7 +1053,8 @@ impl rune::CompileVisitor for Visitor<'_> {
 
         let definition = Definition {
             kind,
-            source: source.clone(),
+            source: meta.source.clone(),
+            item: Some(meta.item().clone()),
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -550,17 +1063,27 @@ impl rune::CompileVisitor for Visitor<'_> {
     }
 
     fn visit_variable_use(&mut self, source_id: SourceId, var: &rune::Var, span: Span) {
+        let declaration = CompileSource {
+            span: var.span(),
+            path: None,
+            source_id,
+        };
+
+        self.uses.push(Use {
+            source_id,
+            span,
+            declaration: declaration.clone(),
+            name: None,
+        });
+
         if source_id != 0 {
             return;
         }
 
         let definition = Definition {
             kind: DefinitionKind::Local,
-            source: CompileSource {
-                span: var.span(),
-                path: None,
-                source_id,
-            },
+            source: Some(declaration),
+            item: None,
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -575,11 +1098,12 @@ impl rune::CompileVisitor for Visitor<'_> {
 
         let definition = Definition {
             kind: DefinitionKind::Module,
-            source: CompileSource {
+            source: Some(CompileSource {
                 span: Span::empty(),
                 path: None,
                 source_id,
-            },
+            }),
+            item: None,
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -604,7 +1128,8 @@ impl<'a> SourceLoader<'a> {
 
     /// Generate a collection of URl candidates.
     fn candidates(root: &Path, item: &Item) -> Option<[Url; 2]> {
-        let mut base = root.to_owned();
+        // NB: the root is the path of the source declaring the module.
+        let mut base = root.parent()?.to_owned();
 
         let mut it = item.iter();
 
@@ -649,7 +1174,9 @@ impl rune::SourceLoader for SourceLoader<'_> {
         if let Some(candidates) = Self::candidates(root, item) {
             for url in candidates.iter() {
                 if let Some(s) = self.sources.get(url) {
-                    return Ok(runestick::Source::new(url.to_string(), s.to_string()));
+                    let mut source = runestick::Source::new(url.to_string(), s.to_string());
+                    *source.path_mut() = url.to_file_path().ok();
+                    return Ok(source);
                 }
             }
         }
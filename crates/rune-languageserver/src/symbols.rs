@@ -0,0 +1,140 @@
+//! Symbols declared in a source file.
+
+use rune::ast;
+use rune::Spanned as _;
+use runestick::Span;
+
+/// A symbol declared in a source.
+#[derive(Debug, Clone)]
+pub(crate) struct Symbol {
+    /// The name of the symbol.
+    pub(crate) name: String,
+    /// The kind of the symbol.
+    pub(crate) kind: lsp::SymbolKind,
+    /// The span of the whole declaration.
+    pub(crate) span: Span,
+    /// The span of the name of the declaration.
+    pub(crate) name_span: Span,
+    /// Symbols declared inside of this symbol.
+    pub(crate) children: Vec<Symbol>,
+}
+
+/// Flatten the given symbols, pairing each symbol with its parent.
+pub(crate) fn flatten(symbols: &[Symbol]) -> Vec<(Option<&Symbol>, &Symbol)> {
+    let mut out = Vec::new();
+    let mut queue = symbols.iter().rev().map(|s| (None, s)).collect::<Vec<_>>();
+
+    while let Some((parent, symbol)) = queue.pop() {
+        out.push((parent, symbol));
+        queue.extend(symbol.children.iter().rev().map(|c| (Some(symbol), c)));
+    }
+
+    out
+}
+
+/// Collect all symbols declared in the given source.
+///
+/// Returns `None` if the source doesn't parse.
+pub(crate) fn collect(source: &str) -> Option<Vec<Symbol>> {
+    let file = rune::parse_all::<ast::File>(source).ok()?;
+    let mut out = Vec::new();
+    file_symbols(source, &file, &mut out);
+    Some(out)
+}
+
+fn file_symbols(source: &str, file: &ast::File, out: &mut Vec<Symbol>) {
+    for (item, _) in &file.items {
+        match item {
+            ast::Item::ItemFn(item_fn) => {
+                out.push(fn_symbol(source, item_fn, lsp::SymbolKind::Function));
+            }
+            ast::Item::ItemStruct(item_struct) => {
+                out.push(symbol(
+                    source,
+                    lsp::SymbolKind::Struct,
+                    item_struct.span(),
+                    item_struct.ident.span(),
+                ));
+            }
+            ast::Item::ItemEnum(item_enum) => {
+                let mut symbol = symbol(
+                    source,
+                    lsp::SymbolKind::Enum,
+                    item_enum.span(),
+                    item_enum.name.span(),
+                );
+
+                for variant in &item_enum.variants {
+                    // NB: variants are declared at the span of their name.
+                    let span = variant.name.span();
+                    let child = self::symbol(source, lsp::SymbolKind::EnumMember, span, span);
+                    symbol.children.push(child);
+                }
+
+                out.push(symbol);
+            }
+            ast::Item::ItemConst(item_const) => {
+                out.push(symbol(
+                    source,
+                    lsp::SymbolKind::Constant,
+                    item_const.span(),
+                    item_const.name.span(),
+                ));
+            }
+            ast::Item::ItemImpl(item_impl) => {
+                let mut symbol = symbol(
+                    source,
+                    lsp::SymbolKind::Object,
+                    item_impl.span(),
+                    item_impl.path.span(),
+                );
+
+                for item_fn in &item_impl.functions {
+                    let kind = if item_fn.is_instance() {
+                        lsp::SymbolKind::Method
+                    } else {
+                        lsp::SymbolKind::Function
+                    };
+
+                    symbol.children.push(fn_symbol(source, item_fn, kind));
+                }
+
+                out.push(symbol);
+            }
+            ast::Item::ItemMod(item_mod) => {
+                let mut symbol = symbol(
+                    source,
+                    lsp::SymbolKind::Module,
+                    item_mod.span(),
+                    item_mod.name.span(),
+                );
+
+                if let ast::ItemModBody::InlineBody(body) = &item_mod.body {
+                    file_symbols(source, &body.file, &mut symbol.children);
+                }
+
+                out.push(symbol);
+            }
+            _ => (),
+        }
+    }
+}
+
+fn fn_symbol(source: &str, item_fn: &ast::ItemFn, kind: lsp::SymbolKind) -> Symbol {
+    symbol(source, kind, item_fn.span(), item_fn.name.span())
+}
+
+fn symbol(source: &str, kind: lsp::SymbolKind, span: Span, name_span: Span) -> Symbol {
+    let name = source
+        .get(name_span.start..name_span.end)
+        .unwrap_or_default()
+        .to_owned();
+
+    Symbol {
+        name,
+        kind,
+        span,
+        name_span,
+        children: Vec::new(),
+    }
+}
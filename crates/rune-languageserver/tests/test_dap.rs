@@ -0,0 +1,209 @@
+use rune_languageserver::{connect, dap};
+use serde_json::{json, Value};
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+const SCRIPT: &str = r#"fn add(a, b) {
+    a + b
+}
+
+fn main() {
+    let a = 1;
+    let b = add(a, 2);
+    b
+}
+"#;
+
+/// Writer which captures everything written to it.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl AsyncWrite for Capture {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Write the test script to a temporary file with the given name.
+fn script(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rune-dap-{}-{}.rn", name, std::process::id()));
+    std::fs::write(&path, SCRIPT).unwrap();
+    path
+}
+
+/// Run the debug adapter over the given requests, and collect all messages
+/// it sent back.
+async fn session(requests: Vec<(&str, Value)>) -> Vec<Value> {
+    let mut input = Vec::new();
+
+    for (seq, (command, arguments)) in requests.into_iter().enumerate() {
+        let request = json!({
+            "seq": seq + 1,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        });
+
+        let request = serde_json::to_vec(&request).unwrap();
+        input.extend(format!("Content-Length: {}\r\n\r\n", request.len()).into_bytes());
+        input.extend(request);
+    }
+
+    let capture = Capture::default();
+    let (input, output) = connect(io::Cursor::new(input), capture.clone());
+    dap::run(input, output).await.unwrap();
+
+    let data = capture.0.lock().unwrap().clone();
+    let mut data = &data[..];
+    let mut messages = Vec::new();
+
+    while !data.is_empty() {
+        let end = data.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let header = std::str::from_utf8(&data[..end]).unwrap();
+        let length = header["Content-Length: ".len()..].parse::<usize>().unwrap();
+        data = &data[end + 4..];
+        messages.push(serde_json::from_slice(&data[..length]).unwrap());
+        data = &data[length..];
+    }
+
+    messages
+}
+
+/// Find the response to the request with the given sequence number.
+fn response(messages: &[Value], request_seq: u64) -> &Value {
+    messages
+        .iter()
+        .find(|m| m["type"] == "response" && m["request_seq"] == request_seq)
+        .expect("missing response")
+}
+
+/// Get all events of the given kind.
+fn events<'a>(messages: &'a [Value], event: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+    messages
+        .iter()
+        .filter(move |m| m["type"] == "event" && m["event"] == event)
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let messages = session(vec![
+        ("initialize", json!({"linesStartAt1": true})),
+        ("disconnect", json!({})),
+    ])
+    .await;
+
+    let initialize = response(&messages, 1);
+    assert_eq!(initialize["success"], true);
+    assert_eq!(initialize["command"], "initialize");
+    assert_eq!(initialize["body"]["supportsConfigurationDoneRequest"], true);
+    assert_eq!(events(&messages, "initialized").count(), 1);
+    assert_eq!(response(&messages, 2)["success"], true);
+}
+
+#[tokio::test]
+async fn test_set_breakpoints() {
+    let path = script("breakpoints");
+    let program = path.display().to_string();
+
+    let messages = session(vec![
+        ("initialize", json!({})),
+        (
+            "setBreakpoints",
+            json!({"source": {"path": program}, "breakpoints": [{"line": 6}]}),
+        ),
+        ("launch", json!({"program": program})),
+        (
+            "setBreakpoints",
+            json!({"source": {"path": program}, "breakpoints": [{"line": 7}, {"line": 100}]}),
+        ),
+        ("disconnect", json!({})),
+    ])
+    .await;
+
+    std::fs::remove_file(&path).unwrap();
+
+    // NB: breakpoints set before launching can't be verified yet.
+    let pending = &response(&messages, 2)["body"]["breakpoints"];
+    assert_eq!(pending[0]["verified"], false);
+    assert_eq!(pending[0]["line"], 6);
+
+    assert_eq!(response(&messages, 3)["success"], true);
+
+    let breakpoints = &response(&messages, 4)["body"]["breakpoints"];
+    assert_eq!(breakpoints[0]["verified"], true);
+    assert_eq!(breakpoints[0]["line"], 7);
+    assert_eq!(breakpoints[1]["verified"], false);
+}
+
+#[tokio::test]
+async fn test_continue_and_step() {
+    let path = script("step");
+    let program = path.display().to_string();
+
+    let messages = session(vec![
+        ("initialize", json!({})),
+        ("launch", json!({"program": program})),
+        (
+            "setBreakpoints",
+            json!({"source": {"path": program}, "breakpoints": [{"line": 7}]}),
+        ),
+        ("configurationDone", json!({})),
+        ("stackTrace", json!({"threadId": 1})),
+        ("scopes", json!({"frameId": 0})),
+        ("variables", json!({"variablesReference": 1})),
+        ("stepIn", json!({"threadId": 1})),
+        ("stackTrace", json!({"threadId": 1})),
+        ("continue", json!({"threadId": 1})),
+        ("disconnect", json!({})),
+    ])
+    .await;
+
+    std::fs::remove_file(&path).unwrap();
+
+    let stopped = events(&messages, "stopped").collect::<Vec<_>>();
+    assert_eq!(stopped.len(), 2);
+    assert_eq!(stopped[0]["body"]["reason"], "breakpoint");
+    assert_eq!(stopped[1]["body"]["reason"], "step");
+
+    let frames = &response(&messages, 5)["body"]["stackFrames"];
+    assert_eq!(response(&messages, 5)["body"]["totalFrames"], 1);
+    assert_eq!(frames[0]["name"], "main");
+    assert_eq!(frames[0]["line"], 7);
+    assert_eq!(frames[0]["source"]["path"], program.as_str());
+
+    let variables = &response(&messages, 7)["body"]["variables"];
+    assert!(variables
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|v| v["name"] == "a"));
+
+    // NB: stepping in enters `add`.
+    let frames = &response(&messages, 9)["body"]["stackFrames"];
+    assert_eq!(response(&messages, 9)["body"]["totalFrames"], 2);
+    assert_eq!(frames[0]["name"], "add");
+    assert_eq!(frames[0]["line"], 2);
+
+    assert_eq!(response(&messages, 10)["body"]["allThreadsContinued"], true);
+
+    let exited = events(&messages, "exited").collect::<Vec<_>>();
+    assert_eq!(exited.len(), 1);
+    assert_eq!(exited[0]["body"]["exitCode"], 0);
+}
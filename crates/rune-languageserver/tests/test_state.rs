@@ -0,0 +1,163 @@
+use lsp::Url;
+use rune_languageserver::{connect, State};
+use tokio::sync::mpsc;
+
+/// Construct a state with the given documents open and built.
+async fn state(documents: &[(&Url, &str)]) -> State {
+    let (rebuild_tx, _rebuild_rx) = mpsc::channel(1);
+    let context = rune::default_context().unwrap();
+    let state = State::new(rebuild_tx, context, rune::Options::default());
+
+    {
+        let mut sources = state.sources_mut().await;
+
+        for (url, text) in documents {
+            sources.insert_text((*url).clone(), (*text).to_owned());
+        }
+    }
+
+    let (_, output) = connect(tokio::io::empty(), tokio::io::sink());
+    state.rebuild(&output).await.unwrap();
+    state
+}
+
+/// Construct a file url to a document which doesn't need to exist.
+fn url(name: &str) -> Url {
+    Url::from_file_path(std::env::temp_dir().join("rune-state").join(name)).unwrap()
+}
+
+#[tokio::test]
+async fn test_complete_locals() {
+    let foo = url("foo.rn");
+    let main = url("main.rn");
+
+    let state = state(&[
+        (
+            &main,
+            "mod foo;\n\nfn main() {\n    let number = 1;\n    number\n}\n",
+        ),
+        (&foo, "fn bar() {\n    let numeral = 2;\n    numeral\n}\n"),
+    ])
+    .await;
+
+    let labels = |items: Vec<lsp::CompletionItem>| {
+        items
+            .into_iter()
+            .map(|item| item.label)
+            .filter(|label| label.starts_with("num"))
+            .collect::<Vec<_>>()
+    };
+
+    let items = state
+        .complete(&main, lsp::Position::new(4, 7))
+        .await
+        .unwrap();
+    assert_eq!(labels(items), vec![String::from("number")]);
+
+    let items = state
+        .complete(&foo, lsp::Position::new(2, 7))
+        .await
+        .unwrap();
+    assert_eq!(labels(items), vec![String::from("numeral")]);
+}
+
+/// Collect the edits of the given workspace edit as sorted tuples of url,
+/// line, character, and new text.
+fn edits(edit: lsp::WorkspaceEdit) -> Vec<(Url, u64, u64, String)> {
+    let mut out = Vec::new();
+
+    for (url, edits) in edit.changes.unwrap() {
+        for edit in edits {
+            let start = edit.range.start;
+            out.push((url.clone(), start.line, start.character, edit.new_text));
+        }
+    }
+
+    out.sort();
+    out
+}
+
+#[tokio::test]
+async fn test_rename_same_file() {
+    let main = url("same.rn");
+
+    let state = state(&[(
+        &main,
+        "fn add(a, b) {\n    a + b\n}\n\nfn main() {\n    let a = add(1, 2);\n    add(a, 3)\n}\n",
+    )])
+    .await;
+
+    let edit = state
+        .rename(&main, lsp::Position::new(6, 5), "sum")
+        .await
+        .unwrap();
+
+    let sum = String::from("sum");
+
+    assert_eq!(
+        edits(edit),
+        vec![
+            (main.clone(), 0, 3, sum.clone()),
+            (main.clone(), 5, 12, sum.clone()),
+            (main.clone(), 6, 4, sum.clone()),
+        ]
+    );
+
+    // NB: the local `a` in `main` is distinct from the argument `a` in `add`.
+    let edit = state
+        .rename(&main, lsp::Position::new(5, 8), "b")
+        .await
+        .unwrap();
+
+    let b = String::from("b");
+    assert_eq!(
+        edits(edit),
+        vec![
+            (main.clone(), 5, 8, b.clone()),
+            (main.clone(), 6, 8, b.clone())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_rename_cross_file() {
+    let main = url("main.rn");
+    let foo = url("foo.rn");
+
+    let state = state(&[
+        (
+            &main,
+            "mod foo;\n\nfn main() {\n    foo::bar(1) + foo::bar(2)\n}\n",
+        ),
+        (&foo, "pub fn bar(n) {\n    n * 2\n}\n"),
+    ])
+    .await;
+
+    let baz = String::from("baz");
+
+    let expected = vec![
+        (foo.clone(), 0, 7, baz.clone()),
+        (main.clone(), 3, 9, baz.clone()),
+        (main.clone(), 3, 23, baz.clone()),
+    ];
+
+    // Renaming works both from a reference and from the declaration.
+    let edit = state
+        .rename(&main, lsp::Position::new(3, 10), "baz")
+        .await
+        .unwrap();
+    assert_eq!(edits(edit), expected);
+
+    let edit = state
+        .rename(&foo, lsp::Position::new(0, 8), "baz")
+        .await
+        .unwrap();
+    assert_eq!(edits(edit), expected);
+
+    let references = state
+        .references(&foo, lsp::Position::new(0, 8), false)
+        .await
+        .unwrap();
+    assert_eq!(references.len(), 2);
+    assert!(references.iter().all(|location| location.uri == main));
+}
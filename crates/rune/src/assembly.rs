@@ -2,6 +2,7 @@
 
 use crate::collections::HashMap;
 use crate::unit_builder::UnitBuilderError;
+use runestick::debug::DebugVariable;
 use runestick::{Hash, Inst, Label, Span};
 
 #[derive(Debug, Clone)]
@@ -33,6 +34,8 @@ pub struct Assembly {
     pub(crate) label_count: usize,
     /// The collection of functions required by this assembly.
     pub(crate) required_functions: HashMap<Hash, Vec<(Span, usize)>>,
+    /// Local variables declared in the assembly.
+    pub(crate) variables: Vec<DebugVariable>,
 }
 
 impl Assembly {
@@ -46,6 +49,7 @@ impl Assembly {
             comments: Default::default(),
             label_count,
             required_functions: Default::default(),
+            variables: Default::default(),
         }
     }
 
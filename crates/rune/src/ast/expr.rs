@@ -3,6 +3,10 @@ use crate::{Parse, ParseError, ParseErrorKind, Parser, Peek, Spanned, ToTokens};
 use std::mem::take;
 use std::ops;
 
+/// The precedence of range expressions like `a..b`. Ranges bind more tightly
+/// than assignments, but less tightly than any other binary operator.
+const RANGE_PRECEDENCE: usize = 2;
+
 /// Indicator that an expression should be parsed with an eager brace.
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct EagerBrace(pub(crate) bool);
@@ -65,10 +69,14 @@ pub enum Expr {
     ExprBinary(ast::ExprBinary),
     /// A unary expression.
     ExprUnary(ast::ExprUnary),
+    /// A range expression.
+    ExprRange(ast::ExprRange),
     /// An index set operation.
     ExprIndexGet(ast::ExprIndexGet),
     /// A break expression.
     ExprBreak(ast::ExprBreak),
+    /// A continue expression.
+    ExprContinue(ast::ExprContinue),
     /// A yield expression.
     ExprYield(ast::ExprYield),
     /// A block as an expression.
@@ -116,6 +124,7 @@ impl Expr {
             Self::ExprIf(expr_if) => expr_if.produces_nothing(),
             Self::ExprGroup(expr_group) => expr_group.produces_nothing(),
             Self::ExprBreak(..) => true,
+            Self::ExprContinue(..) => true,
             Self::ExprBinary(expr_binary) => expr_binary.produces_nothing(),
             Self::ExprBlock(expr_block) => expr_block.produces_nothing(),
             Self::ExprAsync(..) => false,
@@ -315,8 +324,12 @@ impl Expr {
                     Self::parse_ident_start(parser, eager_brace, &mut attributes)?
                 }
                 ast::Kind::Break => Self::ExprBreak(parser.parse()?),
+                ast::Kind::Continue => Self::ExprContinue(parser.parse()?),
                 ast::Kind::Yield => Self::ExprYield(parser.parse()?),
                 ast::Kind::Return => Self::ExprReturn(parser.parse()?),
+                ast::Kind::DotDot | ast::Kind::DotDotEq => {
+                    Self::parse_range(parser, None, eager_brace)?
+                }
                 _ => {
                     return Err(ParseError::new(
                         token,
@@ -465,7 +478,24 @@ impl Expr {
 
             let (op, t1, t2) = match lookahead {
                 Some((op, t1, t2)) if op.precedence() >= min_precedence => (op, t1, t2),
-                _ => break,
+                _ => {
+                    if min_precedence <= RANGE_PRECEDENCE
+                        && parser.peek::<ast::ExprRangeLimits>()?
+                    {
+                        if let Self::ExprRange(..) = lhs {
+                            return Err(ParseError::new(
+                                lhs.span(),
+                                ParseErrorKind::PrecedenceGroupRequired,
+                            ));
+                        }
+
+                        lhs = Self::parse_range(parser, Some(lhs), eager_brace)?;
+                        lookahead_tok = parser.token_peek_pair()?;
+                        continue;
+                    }
+
+                    break;
+                }
             };
 
             for _ in 0..op.advance() {
@@ -477,6 +507,12 @@ impl Expr {
             lookahead_tok = parser.token_peek_pair()?;
 
             loop {
+                if op.precedence() < RANGE_PRECEDENCE && parser.peek::<ast::ExprRangeLimits>()? {
+                    rhs = Self::parse_range(parser, Some(rhs), eager_brace)?;
+                    lookahead_tok = parser.token_peek_pair()?;
+                    continue;
+                }
+
                 let lh = match lookahead_tok.and_then(ast::BinOp::from_token) {
                     Some((lh, _, _)) if lh.precedence() > op.precedence() => lh,
                     Some((lh, _, _)) if lh.precedence() == op.precedence() && !op.is_assoc() => {
@@ -503,6 +539,39 @@ impl Expr {
 
         Ok(lhs)
     }
+
+    /// Parse a range expression, starting with its limits `..` or `..=`.
+    ///
+    /// The end of a half-open range is optional, so it's only parsed if
+    /// something that can start an expression follows.
+    fn parse_range(
+        parser: &mut Parser<'_>,
+        from: Option<Self>,
+        eager_brace: EagerBrace,
+    ) -> Result<Self, ParseError> {
+        let limits = parser.parse::<ast::ExprRangeLimits>()?;
+
+        let has_end = match limits {
+            ast::ExprRangeLimits::Closed(..) => true,
+            ast::ExprRangeLimits::HalfOpen(..) => {
+                parser.peek::<Self>()? && (*eager_brace || !parser.peek::<ast::OpenBrace>()?)
+            }
+        };
+
+        let to = if has_end {
+            let rhs = Self::parse_primary(parser, eager_brace, ExprChain(true))?;
+            let rhs = Self::parse_expr_binary(parser, rhs, RANGE_PRECEDENCE + 1, eager_brace)?;
+            Some(Box::new(rhs))
+        } else {
+            None
+        };
+
+        Ok(Self::ExprRange(ast::ExprRange {
+            from: from.map(Box::new),
+            limits,
+            to,
+        }))
+    }
 }
 
 /// Parsing a block expression.
@@ -582,7 +651,9 @@ impl Peek for Expr {
             ast::Kind::True | ast::Kind::False => true,
             ast::Kind::Ident(..) => true,
             ast::Kind::Break => true,
+            ast::Kind::Continue => true,
             ast::Kind::Return => true,
+            ast::Kind::DotDot | ast::Kind::DotDotEq => true,
             _ => false,
         }
     }
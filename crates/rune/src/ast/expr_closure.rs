@@ -90,12 +90,15 @@ impl Parse for ExprClosure {
     }
 }
 
+/// The arguments of a closure.
 #[derive(Debug, Clone, ToTokens)]
 pub enum ExprClosureArgs {
+    /// A closure without arguments, like `|| 42`.
     Empty {
         /// The `||` token.
         token: ast::Or,
     },
+    /// A closure with a list of arguments, like `|a, b| a + b`.
     List {
         /// The opening pipe for the argument group.
         open: ast::Pipe,
@@ -0,0 +1,12 @@
+use crate::ast;
+use crate::{Parse, Spanned, ToTokens};
+
+/// A continue statement `continue [label]`.
+#[derive(Debug, Clone, ToTokens, Parse, Spanned)]
+pub struct ExprContinue {
+    /// The continue token.
+    pub continue_: ast::Continue,
+    /// An optional label to continue to.
+    #[rune(iter)]
+    pub label: Option<ast::Label>,
+}
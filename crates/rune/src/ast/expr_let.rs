@@ -8,6 +8,9 @@ pub struct ExprLet {
     pub let_: ast::Let,
     /// The name of the binding.
     pub pat: ast::Pat,
+    /// The optional type annotation of the binding.
+    #[rune(iter)]
+    pub ty: Option<ast::TypeAnnotation>,
     /// The equality keyword.
     pub eq: ast::Eq,
     /// The expression the binding is assigned to.
@@ -20,6 +23,7 @@ impl ExprLet {
         Ok(Self {
             let_: parser.parse()?,
             pat: parser.parse()?,
+            ty: parser.parse()?,
             eq: parser.parse()?,
             expr: Box::new(ast::Expr::parse_without_eager_brace(parser)?),
         })
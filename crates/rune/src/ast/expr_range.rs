@@ -0,0 +1,67 @@
+use crate::ast;
+use crate::{Parse, ParseError, ParseErrorKind, Parser, Peek, Spanned, ToTokens};
+
+/// A range expression `a..b`, `a..=b`, `a..`, `..b` or `..`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::Expr>("0..10").unwrap();
+/// parse_all::<ast::Expr>("0..=10").unwrap();
+/// parse_all::<ast::Expr>("0..").unwrap();
+/// parse_all::<ast::Expr>("..10").unwrap();
+/// parse_all::<ast::Expr>("..=10").unwrap();
+/// parse_all::<ast::Expr>("..").unwrap();
+/// ```
+#[derive(Debug, Clone, ToTokens, Spanned)]
+pub struct ExprRange {
+    /// The start of the range.
+    #[rune(iter)]
+    pub from: Option<Box<ast::Expr>>,
+    /// The limits of the range.
+    pub limits: ExprRangeLimits,
+    /// The end of the range.
+    #[rune(iter)]
+    pub to: Option<Box<ast::Expr>>,
+}
+
+impl ExprRange {
+    /// Test if the range is closed, like `a..=b`.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.limits, ExprRangeLimits::Closed(..))
+    }
+}
+
+/// The limits of the specified range.
+#[derive(Debug, Clone, ToTokens, Spanned)]
+pub enum ExprRangeLimits {
+    /// Half-open range expression `..`.
+    HalfOpen(ast::DotDot),
+    /// Closed expression `..=`.
+    Closed(ast::DotDotEq),
+}
+
+impl Parse for ExprRangeLimits {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let token = parser.token_peek_eof()?;
+
+        Ok(match token.kind {
+            ast::Kind::DotDot => Self::HalfOpen(parser.parse()?),
+            ast::Kind::DotDotEq => Self::Closed(parser.parse()?),
+            actual => {
+                return Err(ParseError::new(
+                    token,
+                    ParseErrorKind::ExpectedRangeLimits { actual },
+                ));
+            }
+        })
+    }
+}
+
+impl Peek for ExprRangeLimits {
+    fn peek(t1: Option<ast::Token>, _: Option<ast::Token>) -> bool {
+        matches!(peek!(t1).kind, ast::Kind::DotDot | ast::Kind::DotDotEq)
+    }
+}
@@ -11,6 +11,9 @@ use crate::{Parse, ParseError, ParseErrorKind, Parser, Spanned, ToTokens};
 /// parse_all::<ast::FnArg>("self").unwrap();
 /// parse_all::<ast::FnArg>("_").unwrap();
 /// parse_all::<ast::FnArg>("abc").unwrap();
+///
+/// let arg = parse_all::<ast::FnArg>("abc: int").unwrap();
+/// assert!(matches!(arg, ast::FnArg::Ident(_, Some(..))));
 /// ```
 #[derive(Debug, Clone, ToTokens, Spanned)]
 pub enum FnArg {
@@ -18,8 +21,8 @@ pub enum FnArg {
     Self_(ast::Self_),
     /// Ignoring the argument with `_`.
     Ignore(ast::Underscore),
-    /// Binding the argument to an ident.
-    Ident(ast::Ident),
+    /// Binding the argument to an ident, with an optional type annotation.
+    Ident(ast::Ident, #[rune(iter)] Option<ast::TypeAnnotation>),
 }
 
 impl Parse for FnArg {
@@ -29,7 +32,7 @@ impl Parse for FnArg {
         Ok(match token.kind {
             ast::Kind::Self_ => Self::Self_(parser.parse()?),
             ast::Kind::Underscore => Self::Ignore(parser.parse()?),
-            ast::Kind::Ident(..) => Self::Ident(parser.parse()?),
+            ast::Kind::Ident(..) => Self::Ident(parser.parse()?, parser.parse()?),
             _ => {
                 return Err(ParseError::new(
                     token,
@@ -1,5 +1,7 @@
 use crate::ast;
-use crate::{Parse, ParseError, ParseErrorKind, Parser, Peek, Spanned, ToTokens};
+use crate::{
+    OptionSpanned as _, Parse, ParseError, ParseErrorKind, Parser, Peek, Spanned, ToTokens,
+};
 
 /// A declaration.
 #[derive(Debug, Clone, ToTokens, Spanned)]
@@ -15,6 +17,8 @@ pub enum Item {
     ItemStruct(ast::ItemStruct),
     /// An impl declaration.
     ItemImpl(ast::ItemImpl),
+    /// A trait declaration.
+    ItemTrait(ast::ItemTrait),
     /// A module declaration.
     ItemMod(ast::ItemMod),
     /// A const declaration.
@@ -29,6 +33,19 @@ impl Item {
         matches!(self, Self::MacroCall(..))
     }
 
+    /// Error if a visibility was specified for an item which doesn't support
+    /// it.
+    fn no_visibility(visibility: &ast::Visibility) -> Result<(), ParseError> {
+        if let Some(span) = visibility.option_span() {
+            return Err(ParseError::new(
+                span,
+                ParseErrorKind::VisibilityNotSupported,
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Test if declaration is suitable inside of a block.
     pub fn peek_as_stmt(parser: &mut Parser<'_>) -> Result<bool, ParseError> {
         let t1 = parser.token_peek_pair()?;
@@ -39,10 +56,12 @@ impl Item {
             ast::Kind::Enum => true,
             ast::Kind::Struct => true,
             ast::Kind::Impl => true,
+            ast::Kind::Trait => true,
             ast::Kind::Async => matches!(peek!(t2, Ok(false)).kind, ast::Kind::Fn),
             ast::Kind::Fn => true,
             ast::Kind::Mod => true,
             ast::Kind::Const => true,
+            ast::Kind::Pub => true,
             _ => false,
         })
     }
@@ -50,31 +69,39 @@ impl Item {
     /// Parse an item within a nested block
     pub fn parse_in_nested_block(parser: &mut Parser) -> Result<Self, ParseError> {
         let attributes: Vec<ast::Attribute> = parser.parse()?;
+        let visibility: ast::Visibility = parser.parse()?;
         let t = parser.token_peek_eof()?;
 
         Ok(match t.kind {
-            ast::Kind::Use => {
-                Self::ItemUse(ast::ItemUse::parse_with_attributes(parser, attributes)?)
-            }
-            ast::Kind::Enum => {
-                Self::ItemEnum(ast::ItemEnum::parse_with_attributes(parser, attributes)?)
-            }
-            ast::Kind::Struct => {
-                Self::ItemStruct(ast::ItemStruct::parse_with_attributes(parser, attributes)?)
-            }
+            ast::Kind::Use => Self::ItemUse(ast::ItemUse::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Enum => Self::ItemEnum(ast::ItemEnum::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Struct => Self::ItemStruct(ast::ItemStruct::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
             ast::Kind::Impl => {
+                Self::no_visibility(&visibility)?;
                 Self::ItemImpl(ast::ItemImpl::parse_with_attributes(parser, attributes)?)
             }
+            ast::Kind::Trait => Self::ItemTrait(ast::ItemTrait::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
             ast::Kind::Async | ast::Kind::Fn => Self::ItemFn(Box::new(
-                ast::ItemFn::parse_with_attributes(parser, attributes)?,
+                ast::ItemFn::parse_with_meta(parser, attributes, visibility)?,
             )),
-            ast::Kind::Mod => {
-                Self::ItemMod(ast::ItemMod::parse_with_attributes(parser, attributes)?)
-            }
-            ast::Kind::Const => {
-                Self::ItemConst(ast::ItemConst::parse_with_attributes(parser, attributes)?)
+            ast::Kind::Mod => Self::ItemMod(ast::ItemMod::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Const => Self::ItemConst(ast::ItemConst::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Ident(..) => {
+                Self::no_visibility(&visibility)?;
+                Self::MacroCall(parser.parse()?)
             }
-            ast::Kind::Ident(..) => Self::MacroCall(parser.parse()?),
             _ => {
                 return Err(ParseError::new(
                     t,
@@ -92,11 +119,13 @@ impl Peek for Item {
             ast::Kind::Enum => true,
             ast::Kind::Struct => true,
             ast::Kind::Impl => true,
+            ast::Kind::Trait => true,
             ast::Kind::Async => matches!(peek!(t2).kind, ast::Kind::Fn),
             ast::Kind::Fn => true,
             ast::Kind::Mod => true,
             ast::Kind::Const => true,
             ast::Kind::Ident(..) => true,
+            ast::Kind::Pub => true,
             _ => ast::Attribute::peek(t1, t2),
         }
     }
@@ -105,31 +134,39 @@ impl Peek for Item {
 impl Parse for Item {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
         let attributes: Vec<ast::Attribute> = parser.parse()?;
+        let visibility: ast::Visibility = parser.parse()?;
         let t = parser.token_peek_eof()?;
 
         Ok(match t.kind {
-            ast::Kind::Use => {
-                Self::ItemUse(ast::ItemUse::parse_with_attributes(parser, attributes)?)
-            }
-            ast::Kind::Enum => {
-                Self::ItemEnum(ast::ItemEnum::parse_with_attributes(parser, attributes)?)
-            }
-            ast::Kind::Struct => {
-                Self::ItemStruct(ast::ItemStruct::parse_with_attributes(parser, attributes)?)
-            }
+            ast::Kind::Use => Self::ItemUse(ast::ItemUse::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Enum => Self::ItemEnum(ast::ItemEnum::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Struct => Self::ItemStruct(ast::ItemStruct::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
             ast::Kind::Impl => {
+                Self::no_visibility(&visibility)?;
                 Self::ItemImpl(ast::ItemImpl::parse_with_attributes(parser, attributes)?)
             }
+            ast::Kind::Trait => Self::ItemTrait(ast::ItemTrait::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
             ast::Kind::Async | ast::Kind::Fn => Self::ItemFn(Box::new(
-                ast::ItemFn::parse_with_attributes(parser, attributes)?,
+                ast::ItemFn::parse_with_meta(parser, attributes, visibility)?,
             )),
-            ast::Kind::Mod => {
-                Self::ItemMod(ast::ItemMod::parse_with_attributes(parser, attributes)?)
-            }
-            ast::Kind::Const => {
-                Self::ItemConst(ast::ItemConst::parse_with_attributes(parser, attributes)?)
+            ast::Kind::Mod => Self::ItemMod(ast::ItemMod::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Const => Self::ItemConst(ast::ItemConst::parse_with_meta(
+                parser, attributes, visibility,
+            )?),
+            ast::Kind::Ident(..) => {
+                Self::no_visibility(&visibility)?;
+                Self::MacroCall(parser.parse()?)
             }
-            ast::Kind::Ident(..) => Self::MacroCall(parser.parse()?),
             _ => {
                 return Err(ParseError::new(
                     t,
@@ -7,6 +7,9 @@ pub struct ItemConst {
     /// The *inner* attributes that are applied to the const declaration.
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the constant.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// The `const` keyword.
     pub const_token: ast::Const,
     /// The name of the constant.
@@ -20,13 +23,15 @@ pub struct ItemConst {
 }
 
 impl ItemConst {
-    /// Parse a `mod` item with the given attributes
-    pub fn parse_with_attributes(
+    /// Parse a `mod` item with the given attributes and visibility
+    pub fn parse_with_meta(
         parser: &mut Parser<'_>,
         attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
     ) -> Result<Self, ParseError> {
         Ok(Self {
             attributes,
+            visibility,
             const_token: parser.parse()?,
             name: parser.parse()?,
             eq: parser.parse()?,
@@ -48,6 +53,7 @@ impl ItemConst {
 impl Parse for ItemConst {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
         let attributes = parser.parse()?;
-        Self::parse_with_attributes(parser, attributes)
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
     }
 }
@@ -1,5 +1,5 @@
 use crate::ast;
-use crate::{OptionSpanned, Parse, ParseError, Parser, Spanned, ToTokens};
+use crate::{OptionSpanned, Parse, ParseError, ParseErrorKind, Parser, Spanned, ToTokens};
 
 /// An enum declaration.
 #[derive(Debug, Clone, ToTokens, Spanned)]
@@ -7,6 +7,9 @@ pub struct ItemEnum {
     /// The attributes for the enum block
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the enum.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// The `enum` token.
     pub enum_: ast::Enum,
     /// The name of the enum.
@@ -20,10 +23,11 @@ pub struct ItemEnum {
 }
 
 impl ItemEnum {
-    /// Parse a `enum` item with the given attributes
-    pub fn parse_with_attributes(
+    /// Parse a `enum` item with the given attributes and visibility
+    pub fn parse_with_meta(
         parser: &mut Parser<'_>,
         attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
     ) -> Result<Self, ParseError> {
         let enum_ = parser.parse()?;
         let name = parser.parse()?;
@@ -39,6 +43,15 @@ impl ItemEnum {
                 comma: parser.parse()?,
             };
 
+            for field in variant.body.fields() {
+                if let Some(span) = field.visibility.option_span() {
+                    return Err(ParseError::new(
+                        span,
+                        ParseErrorKind::VisibilityNotSupported,
+                    ));
+                }
+            }
+
             let done = variant.comma.is_none();
             variants.push(variant);
 
@@ -51,6 +64,7 @@ impl ItemEnum {
 
         Ok(Self {
             attributes,
+            visibility,
             enum_,
             name,
             open,
@@ -70,11 +84,13 @@ impl ItemEnum {
 /// parse_all::<ast::ItemEnum>("enum Foo { Bar(a), Baz(b), Empty() }").unwrap();
 /// parse_all::<ast::ItemEnum>("enum Foo { Bar(a), Baz(b), #[default_value = \"zombie\"] Empty() }").unwrap();
 /// parse_all::<ast::ItemEnum>("#[repr(Rune)] enum Foo { Bar(a), Baz(b), #[default_value = \"zombie\"] Empty() }").unwrap();
+/// assert!(parse_all::<ast::ItemEnum>("enum Foo { Bar { pub a } }").is_err());
 /// ```
 impl Parse for ItemEnum {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let attributes = parser.parse()?;
-        Self::parse_with_attributes(parser, attributes)
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
     }
 }
 
@@ -8,6 +8,9 @@ pub struct ItemFn {
     /// The attributes for the fn
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the function.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// The optional `async` keyword.
     #[rune(iter)]
     pub async_: Option<ast::Async>,
@@ -17,6 +20,9 @@ pub struct ItemFn {
     pub name: ast::Ident,
     /// The arguments of the function.
     pub args: ast::Parenthesized<ast::FnArg, ast::Comma>,
+    /// The optional return type of the function.
+    #[rune(iter)]
+    pub output: Option<ast::ReturnType>,
     /// The body of the function.
     pub body: ast::Block,
 }
@@ -36,17 +42,20 @@ impl ItemFn {
         matches!(self.args.items.first(), Some((ast::FnArg::Self_(..), _)))
     }
 
-    /// Parse a `fn` item with the given attributes
-    pub fn parse_with_attributes(
+    /// Parse a `fn` item with the given attributes and visibility
+    pub fn parse_with_meta(
         parser: &mut Parser<'_>,
         attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
     ) -> Result<Self, ParseError> {
         Ok(Self {
             attributes,
+            visibility,
             async_: parser.parse()?,
             fn_: parser.parse()?,
             name: parser.parse()?,
             args: parser.parse()?,
+            output: parser.parse()?,
             body: parser.parse()?,
         })
     }
@@ -78,10 +87,13 @@ impl Peek for ItemFn {
 /// assert_eq!(item.args.items.len(), 2);
 /// assert_eq!(item.attributes.len(), 1);
 ///
+/// let item = parse_all::<ast::ItemFn>("fn hello(foo: int) -> String {}").unwrap();
+/// assert!(item.output.is_some());
 /// ```
 impl Parse for ItemFn {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let attributes = parser.parse()?;
-        Self::parse_with_attributes(parser, attributes)
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
     }
 }
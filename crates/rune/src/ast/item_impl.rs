@@ -1,5 +1,5 @@
 use crate::ast;
-use crate::{Parse, ParseError, Parser, Spanned, ToTokens};
+use crate::{OptionSpanned as _, Parse, ParseError, ParseErrorKind, Parser, Spanned, ToTokens};
 
 /// An impl declaration.
 #[derive(Debug, Clone, ToTokens, Spanned)]
@@ -9,6 +9,10 @@ pub struct ItemImpl {
     pub attributes: Vec<ast::Attribute>,
     /// The `impl` keyword.
     pub impl_: ast::Impl,
+    /// The path of the trait being implemented, followed by the `for`
+    /// keyword, as in `impl Trait for Type`.
+    #[rune(iter)]
+    pub trait_: Option<(ast::Path, ast::For)>,
     /// Path of the implementation.
     pub path: ast::Path,
     /// The open brace.
@@ -26,14 +30,35 @@ impl ItemImpl {
         attributes: Vec<ast::Attribute>,
     ) -> Result<Self, ParseError> {
         let impl_ = parser.parse()?;
-        let path = parser.parse()?;
+        let mut path = parser.parse()?;
+
+        let trait_ = if parser.peek::<ast::For>()? {
+            let for_ = parser.parse()?;
+            Some((std::mem::replace(&mut path, parser.parse()?), for_))
+        } else {
+            None
+        };
+
         let open = parser.parse()?;
 
         let mut functions = vec![];
 
         while !parser.peek::<ast::CloseBrace>()? {
             let attributes = parser.parse()?;
-            functions.push(ast::ItemFn::parse_with_attributes(parser, attributes)?);
+            let visibility: ast::Visibility = parser.parse()?;
+
+            // NB: functions in trait implementations are as visible as the
+            // trait is.
+            if let (Some(span), Some(..)) = (visibility.option_span(), &trait_) {
+                return Err(ParseError::new(
+                    span,
+                    ParseErrorKind::VisibilityNotSupported,
+                ));
+            }
+
+            functions.push(ast::ItemFn::parse_with_meta(
+                parser, attributes, visibility,
+            )?);
         }
 
         let close = parser.parse()?;
@@ -41,6 +66,7 @@ impl ItemImpl {
         Ok(Self {
             attributes,
             impl_,
+            trait_,
             path,
             open,
             functions,
@@ -58,6 +84,11 @@ impl ItemImpl {
 ///
 /// parse_all::<ast::ItemImpl>("impl Foo {}").unwrap();
 /// parse_all::<ast::ItemImpl>("impl Foo { fn test(self) { } }").unwrap();
+/// parse_all::<ast::ItemImpl>("impl Foo { pub fn test(self) { } }").unwrap();
+///
+/// let item = parse_all::<ast::ItemImpl>("impl Bar for Foo { fn test(self) { } }").unwrap();
+/// assert!(item.trait_.is_some());
+/// assert!(parse_all::<ast::ItemImpl>("impl Bar for Foo { pub fn test(self) { } }").is_err());
 /// parse_all::<ast::ItemImpl>("#[variant(enum_= \"SuperHero\", x = \"1\")] impl Foo { fn test(self) { } }").unwrap();
 /// parse_all::<ast::ItemImpl>("#[xyz] impl Foo { #[jit] fn test(self) { } }").unwrap();
 /// ```
@@ -7,6 +7,9 @@ pub struct ItemMod {
     /// The *inner* attributes are applied to the module  `#[cfg(test)] mod tests {  }`
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the module.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// The `mod` keyword.
     pub mod_: ast::Mod,
     /// The name of the mod.
@@ -16,13 +19,15 @@ pub struct ItemMod {
 }
 
 impl ItemMod {
-    /// Parse a `mod` item with the given attributes
-    pub fn parse_with_attributes(
+    /// Parse a `mod` item with the given attributes and visibility
+    pub fn parse_with_meta(
         parser: &mut Parser<'_>,
         attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
     ) -> Result<Self, ParseError> {
         Ok(Self {
             attributes,
+            visibility,
             mod_: parser.parse()?,
             name: parser.parse()?,
             body: parser.parse()?,
@@ -55,7 +60,8 @@ impl ItemMod {
 impl Parse for ItemMod {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
         let attributes = parser.parse()?;
-        Self::parse_with_attributes(parser, attributes)
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
     }
 }
 
@@ -7,6 +7,9 @@ pub struct ItemStruct {
     /// The attributes for the struct
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the struct.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// The `struct` keyword.
     pub struct_: ast::Struct,
     /// The identifier of the struct declaration.
@@ -16,13 +19,15 @@ pub struct ItemStruct {
 }
 
 impl ItemStruct {
-    /// Parse a `struct` item with the given attributes
-    pub fn parse_with_attributes(
+    /// Parse a `struct` item with the given attributes and visibility
+    pub fn parse_with_meta(
         parser: &mut Parser,
         attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
     ) -> Result<Self, ParseError> {
         Ok(Self {
             attributes,
+            visibility,
             struct_: parser.parse()?,
             ident: parser.parse()?,
             body: parser.parse()?,
@@ -46,7 +51,8 @@ impl ItemStruct {
 impl Parse for ItemStruct {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let attributes = parser.parse()?;
-        Self::parse_with_attributes(parser, attributes)
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
     }
 }
 
@@ -201,14 +207,24 @@ impl Parse for StructBody {
 ///
 /// parse_all::<ast::Field>("a").unwrap();
 /// parse_all::<ast::Field>("#[x] a").unwrap();
+/// parse_all::<ast::Field>("a: int").unwrap();
+///
+/// let field = parse_all::<ast::Field>("pub(crate) a").unwrap();
+/// assert!(matches!(field.visibility, ast::Visibility::Crate(..)));
 /// ```
 #[derive(Debug, Clone, ToTokens, Parse, Spanned)]
 pub struct Field {
     /// Attributes associated with field.
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the field.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// Name of the field.
     pub name: ast::Ident,
+    /// The optional type annotation of the field.
+    #[rune(iter)]
+    pub ty: Option<ast::TypeAnnotation>,
     /// Trailing comma of the field.
     #[rune(iter)]
     pub comma: Option<ast::Comma>,
@@ -0,0 +1,166 @@
+use crate::ast;
+use crate::{Parse, ParseError, Parser, Spanned, ToTokens};
+use runestick::Span;
+
+/// A trait declaration.
+#[derive(Debug, Clone, ToTokens, Spanned)]
+pub struct ItemTrait {
+    /// The attributes of the `trait` item.
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the trait.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
+    /// The `trait` keyword.
+    pub trait_: ast::Trait,
+    /// The name of the trait.
+    pub name: ast::Ident,
+    /// The open brace.
+    pub open: ast::OpenBrace,
+    /// The functions declared in the trait.
+    pub functions: Vec<ItemTraitFn>,
+    /// The close brace.
+    pub close: ast::CloseBrace,
+}
+
+impl ItemTrait {
+    /// Parse a `trait` item with the given attributes and visibility
+    pub fn parse_with_meta(
+        parser: &mut Parser<'_>,
+        attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
+    ) -> Result<Self, ParseError> {
+        let trait_ = parser.parse()?;
+        let name = parser.parse()?;
+        let open = parser.parse()?;
+
+        let mut functions = vec![];
+
+        while !parser.peek::<ast::CloseBrace>()? {
+            let attributes = parser.parse()?;
+            functions.push(ItemTraitFn::parse_with_attributes(parser, attributes)?);
+        }
+
+        let close = parser.parse()?;
+
+        Ok(Self {
+            attributes,
+            visibility,
+            trait_,
+            name,
+            open,
+            functions,
+            close,
+        })
+    }
+}
+
+/// Parse implementation for a trait.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ItemTrait>("trait Foo {}").unwrap();
+///
+/// let item = parse_all::<ast::ItemTrait>("trait Foo { fn a(self); fn b(self) { 1 } }").unwrap();
+/// assert!(item.functions[0].body.is_none());
+/// assert!(item.functions[1].body.is_some());
+/// ```
+impl Parse for ItemTrait {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let attributes = parser.parse()?;
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
+    }
+}
+
+/// A function declared in a trait, which might have a default implementation.
+#[derive(Debug, Clone, ToTokens, Spanned)]
+pub struct ItemTraitFn {
+    /// The attributes for the fn
+    #[rune(iter)]
+    pub attributes: Vec<ast::Attribute>,
+    /// The optional `async` keyword.
+    #[rune(iter)]
+    pub async_: Option<ast::Async>,
+    /// The `fn` token.
+    pub fn_: ast::Fn,
+    /// The name of the function.
+    pub name: ast::Ident,
+    /// The arguments of the function.
+    pub args: ast::Parenthesized<ast::FnArg, ast::Comma>,
+    /// The optional return type of the function.
+    #[rune(iter)]
+    pub output: Option<ast::ReturnType>,
+    /// The default implementation of the function, if present.
+    #[rune(iter)]
+    pub body: Option<ast::Block>,
+    /// The terminating semi-colon of a function without a default
+    /// implementation.
+    #[rune(iter)]
+    pub semi: Option<ast::SemiColon>,
+}
+
+impl ItemTraitFn {
+    /// Get the identifying span for this function.
+    pub fn item_span(&self) -> Span {
+        if let Some(async_) = &self.async_ {
+            async_.span().join(self.args.span())
+        } else {
+            self.fn_.span().join(self.args.span())
+        }
+    }
+
+    /// Test if function is an instance fn.
+    pub fn is_instance(&self) -> bool {
+        matches!(self.args.items.first(), Some((ast::FnArg::Self_(..), _)))
+    }
+
+    /// Get the default implementation of this function as a regular function
+    /// item, if it has one.
+    pub fn default_fn(&self) -> Option<ast::ItemFn> {
+        let body = self.body.clone()?;
+
+        Some(ast::ItemFn {
+            attributes: self.attributes.clone(),
+            visibility: ast::Visibility::Inherited,
+            async_: self.async_,
+            fn_: self.fn_,
+            name: self.name,
+            args: self.args.clone(),
+            output: self.output.clone(),
+            body,
+        })
+    }
+
+    /// Parse a trait `fn` item with the given attributes
+    pub fn parse_with_attributes(
+        parser: &mut Parser<'_>,
+        attributes: Vec<ast::Attribute>,
+    ) -> Result<Self, ParseError> {
+        let async_ = parser.parse()?;
+        let fn_ = parser.parse()?;
+        let name = parser.parse()?;
+        let args = parser.parse()?;
+        let output = parser.parse()?;
+
+        let (body, semi) = if parser.peek::<ast::SemiColon>()? {
+            (None, Some(parser.parse()?))
+        } else {
+            (Some(parser.parse()?), None)
+        };
+
+        Ok(Self {
+            attributes,
+            async_,
+            fn_,
+            name,
+            args,
+            output,
+            body,
+            semi,
+        })
+    }
+}
@@ -7,6 +7,9 @@ pub struct ItemUse {
     /// The attributes on use item
     #[rune(iter)]
     pub attributes: Vec<ast::Attribute>,
+    /// The visibility of the use item.
+    #[rune(optional)]
+    pub visibility: ast::Visibility,
     /// The use token.
     pub use_: ast::Use,
     /// First component in use.
@@ -18,13 +21,15 @@ pub struct ItemUse {
 }
 
 impl ItemUse {
-    /// Parse a `use` item with the given attributes
-    pub fn parse_with_attributes(
+    /// Parse a `use` item with the given attributes and visibility
+    pub fn parse_with_meta(
         parser: &mut Parser,
         attributes: Vec<ast::Attribute>,
+        visibility: ast::Visibility,
     ) -> Result<Self, ParseError> {
         Ok(Self {
             attributes,
+            visibility,
             use_: parser.parse()?,
             first: parser.parse()?,
             rest: parser.parse()?,
@@ -47,7 +52,8 @@ impl ItemUse {
 impl Parse for ItemUse {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
         let attributes = parser.parse()?;
-        Self::parse_with_attributes(parser, attributes)
+        let visibility = parser.parse()?;
+        Self::parse_with_meta(parser, attributes, visibility)
     }
 }
 
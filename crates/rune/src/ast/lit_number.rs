@@ -75,6 +75,7 @@ impl<'a> Resolve<'a> for LitNumber {
 
         if text.is_fractional {
             let number = f64::from_str(string).map_err(err_span(span))?;
+            let number = if text.is_negative { -number } else { number };
             return Ok(ast::Number::Float(number));
         }
 
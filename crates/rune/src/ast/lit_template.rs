@@ -1,6 +1,9 @@
 use crate::ast;
-use crate::{Parse, ParseError, ParseErrorKind, Parser, Resolve, Spanned, Storage, ToTokens};
-use runestick::{Source, Span};
+use crate::{
+    Lexer, Parse, ParseError, ParseErrorKind, Parser, Resolve, Spanned, Storage, ToTokens,
+    TokenStream,
+};
+use runestick::{FormatSpec, Source, Span};
 use std::borrow::Cow;
 
 /// A string literal.
@@ -20,6 +23,9 @@ pub enum TemplateComponent {
     String(String),
     /// An expression inside of the template. Like `{1 + 2}`.
     Expr(Box<ast::Expr>),
+    /// An expression with a format specification inside of the template.
+    /// Like `{value:>8.2}`.
+    Format(Box<ast::Expr>, FormatSpec),
 }
 
 /// A resolved and parsed string template.
@@ -90,11 +96,7 @@ impl<'a> Resolve<'a> for LitTemplate {
                     }
 
                     let span = ast::utils::template_expr(span, &mut it)?;
-                    let source = &source.as_str()[..span.end];
-
-                    let mut parser = Parser::new_with_start(source, span.start);
-                    let expr = ast::Expr::parse(&mut parser)?;
-                    components.push(TemplateComponent::Expr(Box::new(expr)));
+                    components.push(self.component(storage, source, &text, span)?);
                     has_expansions = true;
                 }
                 c => {
@@ -117,6 +119,150 @@ impl<'a> Resolve<'a> for LitTemplate {
     }
 }
 
+impl LitTemplate {
+    /// Parse the expression at the given span of the template, and the
+    /// format specification which follows its first top-level `:`, if any.
+    fn component(
+        &self,
+        storage: &Storage,
+        source: &Source,
+        text: &str,
+        span: Span,
+    ) -> Result<TemplateComponent, ParseError> {
+        // NB: the expressions of synthetic templates are lexed from the
+        // synthetic string, while spans of other templates are relative to
+        // the source.
+        let (full, synthetic) = match self.source {
+            ast::LitStrSource::Text(..) => (source.as_str(), None),
+            ast::LitStrSource::Synthetic(..) => (text, Some(Source::new("template", text))),
+        };
+
+        let mut lexer = Lexer::new_with_start(&full[..span.end], span.start);
+        let mut tokens = Vec::new();
+        let mut level = 0usize;
+        let mut spec = None;
+
+        while let Some(token) = lexer.next()? {
+            match token.kind {
+                ast::Kind::Open(..) => level += 1,
+                ast::Kind::Close(..) => level = level.saturating_sub(1),
+                ast::Kind::Colon if level == 0 => {
+                    spec = Some(Span::new(token.span.end, span.end));
+                    break;
+                }
+                _ => (),
+            }
+
+            tokens.push(match &synthetic {
+                Some(synthetic) => into_synthetic(storage, synthetic, token, self.token.span)?,
+                None => token,
+            });
+        }
+
+        let end = match spec {
+            Some(spec) => Span::point(spec.start),
+            None => Span::point(span.end),
+        };
+
+        let stream = TokenStream::new(tokens, end);
+        let mut parser = Parser::from_token_stream(&stream);
+        let expr = Box::new(parser.parse::<ast::Expr>()?);
+        parser.parse_eof()?;
+
+        let spec = match spec {
+            Some(spec) => {
+                let error_span = if synthetic.is_some() {
+                    self.token.span
+                } else {
+                    spec
+                };
+
+                full[spec.start..spec.end]
+                    .parse::<FormatSpec>()
+                    .map_err(|error| {
+                        ParseError::new(error_span, ParseErrorKind::BadFormatSpec { error })
+                    })?
+            }
+            None => FormatSpec::default(),
+        };
+
+        if spec.is_default() {
+            Ok(TemplateComponent::Expr(expr))
+        } else {
+            Ok(TemplateComponent::Format(expr, spec))
+        }
+    }
+}
+
+/// Convert a token lexed from a synthetic template into a synthetic token,
+/// since there is no source to resolve it against once it's been parsed.
+fn into_synthetic(
+    storage: &Storage,
+    source: &Source,
+    token: ast::Token,
+    span: Span,
+) -> Result<ast::Token, ParseError> {
+    let kind = match token.kind {
+        ast::Kind::Ident(ast::StringSource::Text) => {
+            let ident = resolve::<ast::Ident>(storage, source, token)?;
+            ast::Kind::Ident(ast::StringSource::Synthetic(storage.insert_string(&ident)))
+        }
+        ast::Kind::Label(ast::StringSource::Text) => {
+            let label = resolve::<ast::Label>(storage, source, token)?;
+            ast::Kind::Label(ast::StringSource::Synthetic(storage.insert_string(&label)))
+        }
+        ast::Kind::LitNumber(ast::NumberSource::Text(..)) => {
+            storage.insert_number(resolve::<ast::LitNumber>(storage, source, token)?)
+        }
+        ast::Kind::LitStr(ast::LitStrSource::Text(..)) => {
+            let string = resolve::<ast::LitStr>(storage, source, token)?;
+            ast::Kind::LitStr(ast::LitStrSource::Synthetic(storage.insert_string(&string)))
+        }
+        ast::Kind::LitByteStr(ast::LitByteStrSource::Text(..)) => {
+            let bytes = resolve::<ast::LitByteStr>(storage, source, token)?;
+            let id = storage.insert_byte_string(&bytes);
+            ast::Kind::LitByteStr(ast::LitByteStrSource::Synthetic(id))
+        }
+        ast::Kind::LitChar(ast::CopySource::Text) => {
+            let c = resolve::<ast::LitChar>(storage, source, token)?;
+            ast::Kind::LitChar(ast::CopySource::Inline(c))
+        }
+        ast::Kind::LitByte(ast::CopySource::Text) => {
+            let b = resolve::<ast::LitByte>(storage, source, token)?;
+            ast::Kind::LitByte(ast::CopySource::Inline(b))
+        }
+        ast::Kind::LitTemplate(ast::LitStrSource::Text(..)) => {
+            let span = token.span.narrow(1);
+
+            let template = source
+                .source(span)
+                .ok_or_else(|| ParseError::new(span, ParseErrorKind::BadSlice))?;
+
+            ast::Kind::LitTemplate(ast::LitStrSource::Synthetic(
+                storage.insert_string(template),
+            ))
+        }
+        kind => kind,
+    };
+
+    Ok(ast::Token { span, kind })
+}
+
+/// Parse and resolve a single token.
+fn resolve<'a, T>(
+    storage: &Storage,
+    source: &'a Source,
+    token: ast::Token,
+) -> Result<T::Output, ParseError>
+where
+    T: Parse + Resolve<'a>,
+{
+    let stream = TokenStream::new(vec![token], Span::point(token.span.end));
+    Parser::from_token_stream(&stream)
+        .parse::<T>()?
+        .resolve(storage, source)
+}
+
 /// Parse a string literal.
 ///
 /// # Examples
@@ -14,6 +14,7 @@ mod expr_block;
 mod expr_break;
 mod expr_call;
 mod expr_closure;
+mod expr_continue;
 mod expr_else;
 mod expr_else_if;
 mod expr_field_access;
@@ -28,6 +29,7 @@ mod expr_let;
 mod expr_lit;
 mod expr_loop;
 mod expr_match;
+mod expr_range;
 mod expr_return;
 mod expr_select;
 mod expr_try;
@@ -44,6 +46,7 @@ mod item_fn;
 mod item_impl;
 mod item_mod;
 mod item_struct;
+mod item_trait;
 mod item_use;
 mod label;
 mod lit;
@@ -63,14 +66,18 @@ mod parenthesized;
 mod pat;
 mod pat_object;
 mod pat_path;
+mod pat_range;
 mod pat_tuple;
 mod pat_vec;
 mod path;
+mod return_type;
 mod stmt;
 mod token;
+mod type_annotation;
 pub(super) mod utils;
+mod visibility;
 
-pub use self::attribute::Attribute;
+pub use self::attribute::{AttrStyle, Attribute};
 pub use self::block::Block;
 pub use self::condition::Condition;
 pub use self::expr::Expr;
@@ -80,7 +87,8 @@ pub use self::expr_binary::{BinOp, ExprBinary};
 pub use self::expr_block::ExprBlock;
 pub use self::expr_break::{ExprBreak, ExprBreakValue};
 pub use self::expr_call::ExprCall;
-pub use self::expr_closure::ExprClosure;
+pub use self::expr_closure::{ExprClosure, ExprClosureArgs};
+pub use self::expr_continue::ExprContinue;
 pub use self::expr_else::ExprElse;
 pub use self::expr_else_if::ExprElseIf;
 pub use self::expr_field_access::{ExprField, ExprFieldAccess};
@@ -95,6 +103,7 @@ pub use self::expr_let::ExprLet;
 pub use self::expr_lit::ExprLit;
 pub use self::expr_loop::ExprLoop;
 pub use self::expr_match::{ExprMatch, ExprMatchBranch};
+pub use self::expr_range::{ExprRange, ExprRangeLimits};
 pub use self::expr_return::ExprReturn;
 pub use self::expr_select::ExprSelect;
 pub use self::expr_try::ExprTry;
@@ -111,6 +120,7 @@ pub use self::item_fn::ItemFn;
 pub use self::item_impl::ItemImpl;
 pub use self::item_mod::{ItemMod, ItemModBody};
 pub use self::item_struct::{Field, ItemStruct, ItemStructBody, StructBody, TupleBody};
+pub use self::item_trait::{ItemTrait, ItemTraitFn};
 pub use self::item_use::{ItemUse, ItemUseComponent};
 pub use self::label::Label;
 pub use self::lit::Lit;
@@ -132,14 +142,18 @@ pub use self::parenthesized::Parenthesized;
 pub use self::pat::Pat;
 pub use self::pat_object::{PatObject, PatObjectItem};
 pub use self::pat_path::PatPath;
+pub use self::pat_range::PatRange;
 pub use self::pat_tuple::PatTuple;
 pub use self::pat_vec::PatVec;
 pub use self::path::Path;
+pub use self::return_type::ReturnType;
 pub use self::stmt::Stmt;
 pub use self::token::{
     CopySource, Delimiter, Kind, LitByteStrSource, LitByteStrSourceText, LitStrSource,
     LitStrSourceText, Number, NumberBase, NumberSource, NumberSourceText, StringSource, Token,
 };
+pub use self::type_annotation::TypeAnnotation;
+pub use self::visibility::Visibility;
 
 macro_rules! decl_tokens {
     ($(($parser:ident, $doc:expr, $($kind:tt)*),)*) => {
@@ -222,11 +236,14 @@ decl_tokens! {
     (For, "The `for` keyword.", Kind::For),
     (In, "The `in` keyword.", Kind::In),
     (Break, "The `break` keyword.", Kind::Break),
+    (Continue, "The `continue` keyword.", Kind::Continue),
     (Yield, "The `yield` keyword.", Kind::Yield),
     (Return, "The `return` keyword.", Kind::Return),
     (Rocket, "The rocket `=>`.", Kind::Rocket),
+    (Arrow, "The arrow `->`.", Kind::Arrow),
     (Hash, "The hash `#`.", Kind::Pound),
     (DotDot, "Two dots `..`.", Kind::DotDot),
+    (DotDotEq, "Two dots and an equals `..=`.", Kind::DotDotEq),
     (Await, "The `await` keyword.", Kind::Await),
     (Async, "The `async` keyword.", Kind::Async),
     (Select, "The `select` keyword.", Kind::Select),
@@ -236,9 +253,13 @@ decl_tokens! {
     (And, "And `&&` operator.", Kind::AmpAmp),
     (Or, "Or `||` operator.", Kind::PipePipe),
     (Impl, "The `impl` keyword", Kind::Impl),
+    (Trait, "The `trait` keyword.", Kind::Trait),
     (Mul, "Multiply `*` operator.", Kind::Star),
     (Mod, "The `mod` keyword.", Kind::Mod),
     (Bang, "The `!` operator.", Kind::Bang),
+    (Pub, "The `pub` keyword.", Kind::Pub),
+    (Crate, "The `crate` keyword.", Kind::Crate),
+    (Super, "The `super` keyword.", Kind::Super),
 }
 
 #[cfg(test)]
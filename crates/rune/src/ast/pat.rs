@@ -24,6 +24,8 @@ pub enum Pat {
     PatTuple(ast::PatTuple),
     /// An object pattern.
     PatObject(ast::PatObject),
+    /// A range pattern.
+    PatRange(ast::PatRange),
 }
 
 impl Pat {
@@ -64,6 +66,9 @@ impl Pat {
 /// parse_all::<ast::Pat>("var").unwrap();
 /// parse_all::<ast::Pat>("_").unwrap();
 /// parse_all::<ast::Pat>("Foo(n)").unwrap();
+/// parse_all::<ast::Pat>("1..=5").unwrap();
+/// parse_all::<ast::Pat>("'a'..'z'").unwrap();
+/// parse_all::<ast::Pat>("10..").unwrap();
 /// ```
 impl Parse for Pat {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -79,9 +84,15 @@ impl Parse for Pat {
             }
             ast::Kind::Open(ast::Delimiter::Bracket) => Self::PatVec(parser.parse()?),
             ast::Kind::Pound => Self::PatObject(parser.parse()?),
-            ast::Kind::LitByte { .. } => Self::PatByte(parser.parse()?),
-            ast::Kind::LitChar { .. } => Self::PatChar(parser.parse()?),
-            ast::Kind::LitNumber { .. } => Self::PatNumber(parser.parse()?),
+            ast::Kind::LitByte { .. } | ast::Kind::LitChar { .. } | ast::Kind::LitNumber { .. } => {
+                let start = ast::PatRange::parse_bound(parser)?;
+
+                if parser.peek::<ast::ExprRangeLimits>()? {
+                    Self::PatRange(ast::PatRange::parse_with_start(parser, start)?)
+                } else {
+                    start
+                }
+            }
             ast::Kind::LitStr { .. } => Self::PatString(parser.parse()?),
             ast::Kind::Underscore => Self::PatIgnore(parser.parse()?),
             ast::Kind::Ident(..) => Self::parse_ident(parser)?,
@@ -0,0 +1,68 @@
+use crate::ast;
+use crate::{ParseError, ParseErrorKind, Parser, Spanned, ToTokens};
+
+/// A range pattern over literals, like `1..=5` or `'a'..'z'`.
+#[derive(Debug, Clone, ToTokens, Spanned)]
+pub struct PatRange {
+    /// The start of the range.
+    pub start: Box<ast::Pat>,
+    /// The limits of the range.
+    pub limits: ast::ExprRangeLimits,
+    /// The end of the range, if the range is bounded.
+    #[rune(iter)]
+    pub end: Option<Box<ast::Pat>>,
+}
+
+impl PatRange {
+    /// Parse a range pattern with the already parsed start of the range.
+    pub fn parse_with_start(parser: &mut Parser<'_>, start: ast::Pat) -> Result<Self, ParseError> {
+        let limits = parser.parse::<ast::ExprRangeLimits>()?;
+
+        let end = match limits {
+            ast::ExprRangeLimits::Closed(..) => Some(Box::new(Self::parse_bound(parser)?)),
+            ast::ExprRangeLimits::HalfOpen(..) if Self::peek_bound(parser)? => {
+                Some(Box::new(Self::parse_bound(parser)?))
+            }
+            ast::ExprRangeLimits::HalfOpen(..) => None,
+        };
+
+        Ok(Self {
+            start: Box::new(start),
+            limits,
+            end,
+        })
+    }
+
+    /// Test if the range is closed, like `a..=b`.
+    pub fn is_closed(&self) -> bool {
+        matches!(self.limits, ast::ExprRangeLimits::Closed(..))
+    }
+
+    /// Test if the next token is a literal which can be used as the bound of
+    /// a range pattern.
+    fn peek_bound(parser: &mut Parser<'_>) -> Result<bool, ParseError> {
+        Ok(matches!(
+            parser.token_peek()?.map(|t| t.kind),
+            Some(ast::Kind::LitByte { .. })
+                | Some(ast::Kind::LitChar { .. })
+                | Some(ast::Kind::LitNumber { .. })
+        ))
+    }
+
+    /// Parse a literal which is used as the bound of a range pattern.
+    pub(super) fn parse_bound(parser: &mut Parser<'_>) -> Result<ast::Pat, ParseError> {
+        let token = parser.token_peek_eof()?;
+
+        Ok(match token.kind {
+            ast::Kind::LitByte { .. } => ast::Pat::PatByte(parser.parse()?),
+            ast::Kind::LitChar { .. } => ast::Pat::PatChar(parser.parse()?),
+            ast::Kind::LitNumber { .. } => ast::Pat::PatNumber(parser.parse()?),
+            actual => {
+                return Err(ParseError::new(
+                    token,
+                    ParseErrorKind::ExpectedPatError { actual },
+                ));
+            }
+        })
+    }
+}
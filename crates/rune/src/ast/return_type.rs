@@ -0,0 +1,26 @@
+use crate::ast;
+use crate::{Parse, Peek, Spanned, ToTokens};
+
+/// The return type of a function, like the `-> int` in `fn foo() -> int`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::ReturnType>("-> int").unwrap();
+/// parse_all::<ast::ReturnType>("-> std::string::String").unwrap();
+/// ```
+#[derive(Debug, Clone, ToTokens, Parse, Spanned)]
+pub struct ReturnType {
+    /// The arrow preceding the type.
+    pub arrow: ast::Arrow,
+    /// The path to the type.
+    pub path: ast::Path,
+}
+
+impl Peek for ReturnType {
+    fn peek(t1: Option<ast::Token>, _: Option<ast::Token>) -> bool {
+        matches!(peek!(t1).kind, ast::Kind::Arrow)
+    }
+}
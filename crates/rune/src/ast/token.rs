@@ -227,6 +227,7 @@ kinds! {
     ColonColon, "`::`.",
     Comma, "`,`.",
     Const, "The `const` keyword.",
+    Continue, "The `continue` keyword.",
     Crate, "The `crate` keyword.",
     Dash, "`-`.",
     DashEq, "`-=`.",
@@ -236,6 +237,7 @@ kinds! {
     Dollar, "`$`.",
     Dot, "`.`.",
     DotDot, "`..`.",
+    DotDotEq, "`..=`.",
     Else, "The `else` keyword.",
     Enum, "The `enum` keyword.",
     Eq, "`=`.",
@@ -285,6 +287,7 @@ kinds! {
     Pound, "`#`.",
     Priv, "The `priv` keyword.",
     Proc, "The `proc` keyword.",
+    Pub, "The `pub` keyword.",
     Pure, "The `pure` keyword.",
     QuestionMark, "`?`.",
     Ref, "The `ref` keyword.",
@@ -301,6 +304,7 @@ kinds! {
     Struct, "The `struct` keyword.",
     Super, "The `super` keyword.",
     Tilde, "`~`.",
+    Trait, "The `trait` keyword.",
     True, "The `true` keyword.",
     TypeOf, "The `typeof` keyword.",
     Underscore, "`_`.",
@@ -323,6 +327,7 @@ impl Kind {
             "become" => Self::Become,
             "break" => Self::Break,
             "const" => Self::Const,
+            "continue" => Self::Continue,
             "crate" => Self::Crate,
             "default" => Self::Default,
             "do" => Self::Do,
@@ -348,6 +353,7 @@ impl Kind {
             "override" => Self::Override,
             "priv" => Self::Priv,
             "proc" => Self::Proc,
+            "pub" => Self::Pub,
             "pure" => Self::Pure,
             "ref" => Self::Ref,
             "return" => Self::Return,
@@ -357,6 +363,7 @@ impl Kind {
             "static" => Self::Static,
             "struct" => Self::Struct,
             "super" => Self::Super,
+            "trait" => Self::Trait,
             "true" => Self::True,
             "typeof" => Self::TypeOf,
             "unsafe" => Self::Unsafe,
@@ -393,6 +400,7 @@ impl fmt::Display for Kind {
             Self::ColonColon => write!(f, "::")?,
             Self::Comma => write!(f, ",")?,
             Self::Const => write!(f, "const")?,
+            Self::Continue => write!(f, "continue")?,
             Self::Crate => write!(f, "crate")?,
             Self::Dash => write!(f, "-")?,
             Self::DashEq => write!(f, "-=")?,
@@ -402,6 +410,7 @@ impl fmt::Display for Kind {
             Self::Dollar => write!(f, "$")?,
             Self::Dot => write!(f, ".")?,
             Self::DotDot => write!(f, "..")?,
+            Self::DotDotEq => write!(f, "..=")?,
             Self::Else => write!(f, "else")?,
             Self::Enum => write!(f, "enum")?,
             Self::Eq => write!(f, "=")?,
@@ -451,6 +460,7 @@ impl fmt::Display for Kind {
             Self::Pound => write!(f, "#")?,
             Self::Priv => write!(f, "priv")?,
             Self::Proc => write!(f, "proc")?,
+            Self::Pub => write!(f, "pub")?,
             Self::Pure => write!(f, "pure")?,
             Self::QuestionMark => write!(f, "?")?,
             Self::Ref => write!(f, "ref")?,
@@ -467,6 +477,7 @@ impl fmt::Display for Kind {
             Self::Struct => write!(f, "struct")?,
             Self::Super => write!(f, "super")?,
             Self::Tilde => write!(f, "~")?,
+            Self::Trait => write!(f, "trait")?,
             Self::True => write!(f, "true")?,
             Self::TypeOf => write!(f, "typeof")?,
             Self::Underscore => write!(f, "_")?,
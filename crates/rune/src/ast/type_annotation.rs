@@ -0,0 +1,26 @@
+use crate::ast;
+use crate::{Parse, Peek, Spanned, ToTokens};
+
+/// A type annotation, like the `: int` in `let a: int = 1`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::TypeAnnotation>(": int").unwrap();
+/// parse_all::<ast::TypeAnnotation>(": std::string::String").unwrap();
+/// ```
+#[derive(Debug, Clone, ToTokens, Parse, Spanned)]
+pub struct TypeAnnotation {
+    /// The colon separating the annotated item from its type.
+    pub colon: ast::Colon,
+    /// The path to the type.
+    pub path: ast::Path,
+}
+
+impl Peek for TypeAnnotation {
+    fn peek(t1: Option<ast::Token>, _: Option<ast::Token>) -> bool {
+        matches!(peek!(t1).kind, ast::Kind::Colon)
+    }
+}
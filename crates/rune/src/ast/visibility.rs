@@ -0,0 +1,80 @@
+use crate::ast;
+use crate::{OptionSpanned, Parse, ParseError, Parser, Spanned as _, ToTokens};
+use runestick::Span;
+
+/// The visibility of an item or a field.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// let vis = parse_all::<ast::Visibility>("pub").unwrap();
+/// assert!(matches!(vis, ast::Visibility::Public(..)));
+///
+/// let vis = parse_all::<ast::Visibility>("pub(crate)").unwrap();
+/// assert!(matches!(vis, ast::Visibility::Crate(..)));
+///
+/// let vis = parse_all::<ast::Visibility>("pub(super)").unwrap();
+/// assert!(matches!(vis, ast::Visibility::Super(..)));
+///
+/// let vis = parse_all::<ast::Visibility>("").unwrap();
+/// assert!(matches!(vis, ast::Visibility::Inherited));
+/// ```
+#[derive(Debug, Clone, ToTokens)]
+pub enum Visibility {
+    /// No visibility was specified, so the item is private to the module it's
+    /// declared in.
+    Inherited,
+    /// Public visibility, as in `pub`.
+    Public(ast::Pub),
+    /// Crate visibility, as in `pub(crate)`.
+    Crate(ast::Pub, ast::OpenParen, ast::Crate, ast::CloseParen),
+    /// Visibility to the parent module, as in `pub(super)`.
+    Super(ast::Pub, ast::OpenParen, ast::Super, ast::CloseParen),
+}
+
+impl Visibility {
+    /// Test if the visibility is inherited, which means it wasn't specified.
+    pub fn is_inherited(&self) -> bool {
+        matches!(self, Self::Inherited)
+    }
+}
+
+impl OptionSpanned for Visibility {
+    fn option_span(&self) -> Option<Span> {
+        match self {
+            Self::Inherited => None,
+            Self::Public(pub_) => Some(pub_.span()),
+            Self::Crate(pub_, _, _, close) => Some(pub_.span().join(close.span())),
+            Self::Super(pub_, _, _, close) => Some(pub_.span().join(close.span())),
+        }
+    }
+}
+
+impl Parse for Visibility {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        if !parser.peek::<ast::Pub>()? {
+            return Ok(Self::Inherited);
+        }
+
+        let pub_ = parser.parse()?;
+
+        let t = parser.token_peek_pair()?;
+        let (t1, t2) = peek!(t, Ok(Self::Public(pub_)));
+
+        if !matches!(t1.kind, ast::Kind::Open(ast::Delimiter::Parenthesis)) {
+            return Ok(Self::Public(pub_));
+        }
+
+        Ok(match peek!(t2, Ok(Self::Public(pub_))).kind {
+            ast::Kind::Crate => {
+                Self::Crate(pub_, parser.parse()?, parser.parse()?, parser.parse()?)
+            }
+            ast::Kind::Super => {
+                Self::Super(pub_, parser.parse()?, parser.parse()?, parser.parse()?)
+            }
+            _ => Self::Public(pub_),
+        })
+    }
+}
@@ -0,0 +1,229 @@
+//! On-disk caching of compiled units, enabled through the `bytecode` option.
+//!
+//! A unit compiled from sources where the first source was loaded from a path
+//! is stored next to it, with the `rnc` extension. The cached unit is only used
+//! if it was stored in the same [FORMAT] by the same version of the compiler,
+//! for an identical context, with the same options and from sources with
+//! identical contents, and if it passes [Unit::verify].
+
+use crate::{Options, Sources};
+use runestick::{Context, Source, Unit};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::hash::{Hash as _, Hasher as _};
+use std::io;
+use std::io::{Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use twox_hash::XxHash64;
+
+/// The bytes every cached unit starts with.
+pub(crate) const MAGIC: [u8; 4] = *b"RNC\0";
+
+/// The version of the format of cached units, which follows [MAGIC].
+///
+/// This must be bumped whenever the serialized representation of a [CacheKey]
+/// or a [Unit] changes, like when an instruction is added or changed, since
+/// the crate version isn't necessarily bumped along with it.
+pub(crate) const FORMAT: u32 = 2;
+
+/// The key of a cached unit, which determines if it can be used.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CacheKey {
+    /// The version of the compiler which produced the unit.
+    version: String,
+    /// A fingerprint of the context the unit was compiled for.
+    context: u64,
+    /// A fingerprint of the options the unit was compiled with.
+    options: u64,
+    /// The sources the unit was compiled from, in the order of their source
+    /// ids.
+    sources: Vec<CachedSource>,
+}
+
+/// A source a cached unit was compiled from.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CachedSource {
+    /// The name of the source.
+    name: String,
+    /// The path the source was loaded from.
+    path: Option<PathBuf>,
+    /// A hash of the contents of the source.
+    hash: u64,
+}
+
+impl CachedSource {
+    fn new(source: &Source) -> Self {
+        Self {
+            name: source.name().to_owned(),
+            path: source.path().map(ToOwned::to_owned),
+            hash: hash(source.as_str()),
+        }
+    }
+}
+
+impl CacheKey {
+    fn new<'a, I>(context: &Context, options: &Options, sources: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Source>,
+    {
+        Self {
+            version: String::from(env!("CARGO_PKG_VERSION")),
+            context: context_fingerprint(context),
+            options: hash(options),
+            sources: sources.into_iter().map(CachedSource::new).collect(),
+        }
+    }
+}
+
+/// Get the path of the cached unit for the given sources, if they can be
+/// cached.
+pub(crate) fn cache_path(sources: &Sources) -> Option<PathBuf> {
+    let path = sources.get(0)?.path()?;
+    Some(path.with_extension("rnc"))
+}
+
+/// Load a cached unit from the given path, if it's valid for the given
+/// sources.
+///
+/// Sources which were loaded while compiling the cached unit, like modules,
+/// are added to `sources`.
+pub(crate) fn load(
+    path: &Path,
+    context: &Context,
+    options: &Options,
+    sources: &mut Sources,
+) -> io::Result<Option<Unit>> {
+    let f = match fs::File::open(path) {
+        Ok(f) => f,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    let mut f = io::BufReader::new(f);
+    let mut header = [0u8; 8];
+
+    match f.read_exact(&mut header) {
+        Ok(()) => (),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+            log::warn!("ignoring corrupt cache: {}: {}", path.display(), error);
+            return Ok(None);
+        }
+        Err(error) => return Err(error),
+    }
+
+    if header[..4] != MAGIC || header[4..] != FORMAT.to_le_bytes() {
+        log::trace!("cache has a different format: {}", path.display());
+        return Ok(None);
+    }
+
+    let cached = match bincode::deserialize_from::<_, CacheKey>(&mut f) {
+        Ok(key) => key,
+        Err(error) => {
+            log::warn!("ignoring corrupt cache: {}: {}", path.display(), error);
+            return Ok(None);
+        }
+    };
+
+    let mut loaded = Vec::new();
+
+    for cached in cached.sources.iter().skip(sources.iter().count()) {
+        let path = match &cached.path {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        let mut source = Source::new(&cached.name, contents);
+        *source.path_mut() = Some(path.clone());
+        loaded.push(source);
+    }
+
+    let key = CacheKey::new(context, options, sources.iter().chain(&loaded));
+
+    if key != cached {
+        log::trace!("cache is stale: {}", path.display());
+        return Ok(None);
+    }
+
+    let unit = match bincode::deserialize_from::<_, Unit>(&mut f) {
+        Ok(unit) => unit,
+        Err(error) => {
+            log::warn!("ignoring corrupt cache: {}: {}", path.display(), error);
+            return Ok(None);
+        }
+    };
+
+    if let Err(error) = unit.verify(context) {
+        log::warn!("ignoring invalid cache: {}: {}", path.display(), error);
+        return Ok(None);
+    }
+
+    for source in loaded {
+        sources.insert(source);
+    }
+
+    log::trace!("using cache: {}", path.display());
+    Ok(Some(unit))
+}
+
+/// Store a unit compiled from the given sources in the cache.
+pub(crate) fn store(
+    path: &Path,
+    context: &Context,
+    options: &Options,
+    sources: &Sources,
+    unit: &Unit,
+) -> bincode::Result<()> {
+    log::trace!("storing cache: {}", path.display());
+
+    let key = CacheKey::new(context, options, sources.iter());
+    let mut f = io::BufWriter::new(fs::File::create(path)?);
+    f.write_all(&MAGIC)?;
+    f.write_all(&FORMAT.to_le_bytes())?;
+    bincode::serialize_into(&mut f, &key)?;
+    bincode::serialize_into(&mut f, unit)?;
+    Ok(())
+}
+
+/// Calculate a fingerprint of everything a compiled unit might depend on in
+/// a context.
+fn context_fingerprint(context: &Context) -> u64 {
+    let mut functions = context
+        .iter_functions()
+        .map(|(hash, signature)| (hash, signature.to_string()))
+        .collect::<Vec<_>>();
+
+    functions.sort();
+
+    let mut types = context
+        .iter_types()
+        .map(|(hash, ty)| (hash, ty.item.to_string(), ty.type_of))
+        .collect::<Vec<_>>();
+
+    types.sort_by_key(|(hash, ..)| *hash);
+
+    let mut hasher = XxHash64::default();
+    context.has_default_modules().hash(&mut hasher);
+    functions.hash(&mut hasher);
+    types.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash the given value.
+///
+/// Hashes are stored in cached units, so they are calculated with a fixed
+/// algorithm instead of the `DefaultHasher`, which might change between
+/// releases of Rust.
+fn hash<T>(value: T) -> u64
+where
+    T: std::hash::Hash,
+{
+    let mut hasher = XxHash64::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
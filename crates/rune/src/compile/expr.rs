@@ -40,6 +40,9 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
             ast::Expr::ExprBinary(expr_binary) => {
                 self.compile((expr_binary, needs))?;
             }
+            ast::Expr::ExprRange(expr_range) => {
+                self.compile((expr_range, needs))?;
+            }
             ast::Expr::ExprIf(expr_if) => {
                 self.compile((expr_if, needs))?;
             }
@@ -52,6 +55,9 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
             ast::Expr::ExprBreak(expr_break) => {
                 self.compile(expr_break)?;
             }
+            ast::Expr::ExprContinue(expr_continue) => {
+                self.compile(expr_continue)?;
+            }
             ast::Expr::ExprYield(expr_yield) => {
                 self.compile((expr_yield, needs))?;
             }
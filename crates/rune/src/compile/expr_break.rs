@@ -30,9 +30,10 @@ impl Compile<&ast::ExprBreak> for Compiler<'_> {
                     (current_loop, current_loop.drop.into_iter().collect(), true)
                 }
                 ast::ExprBreakValue::Label(label) => {
-                    let (last_loop, to_drop) =
+                    let (last_loop, mut to_drop) =
                         self.loops
                             .walk_until_label(self.storage, &*self.source, *label)?;
+                    to_drop.extend(last_loop.drop);
                     (last_loop, to_drop, false)
                 }
             }
@@ -113,6 +113,15 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
                     ));
                 }
 
+                for n in 0..tuple.args {
+                    self.query.check_field_visibility(
+                        &self.items.item(),
+                        &tuple.item,
+                        &n.to_string(),
+                        path.span(),
+                    )?;
+                }
+
                 if tuple.args == 0 {
                     let tuple = path.span();
                     self.warnings.remove_tuple_call_parens(
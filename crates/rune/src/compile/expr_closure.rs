@@ -22,7 +22,7 @@ impl Compile<(ast::ExprClosure, &[CompileMetaCapture])> for Compiler<'_> {
                     ast::FnArg::Self_(s) => {
                         return Err(CompileError::new(s, CompileErrorKind::UnsupportedSelf))
                     }
-                    ast::FnArg::Ident(ident) => {
+                    ast::FnArg::Ident(ident, _) => {
                         let ident = ident.resolve(&self.storage, &*self.source)?;
                         self.scopes.new_var(ident.as_ref(), span)?;
                     }
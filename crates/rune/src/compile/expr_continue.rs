@@ -0,0 +1,47 @@
+use crate::ast;
+use crate::compiler::Compiler;
+use crate::traits::Compile;
+use crate::CompileResult;
+use crate::{CompileError, CompileErrorKind, Spanned as _};
+use runestick::Inst;
+
+/// Compile a continue expression.
+impl Compile<&ast::ExprContinue> for Compiler<'_> {
+    fn compile(&mut self, expr_continue: &ast::ExprContinue) -> CompileResult<()> {
+        let span = expr_continue.span();
+        log::trace!("ExprContinue => {:?}", self.source.source(span));
+
+        let current_loop = match self.loops.last() {
+            Some(current_loop) => current_loop,
+            None => {
+                return Err(CompileError::new(
+                    span,
+                    CompileErrorKind::ContinueOutsideOfLoop,
+                ));
+            }
+        };
+
+        let (last_loop, to_drop) = if let Some(label) = expr_continue.label {
+            self.loops
+                .walk_until_label(self.storage, &*self.source, label)?
+        } else {
+            (current_loop, Vec::new())
+        };
+
+        // Drop the temporaries of nested loops. The temporaries of the loop
+        // being continued are still in use.
+        for offset in to_drop {
+            self.asm.push(Inst::Drop { offset }, span);
+        }
+
+        let vars = self
+            .scopes
+            .total_var_count(span)?
+            .checked_sub(last_loop.continue_var_count)
+            .ok_or_else(|| CompileError::internal(&span, "var count should be larger"))?;
+
+        self.locals_pop(vars, span);
+        self.asm.jump(last_loop.continue_label, span);
+        Ok(())
+    }
+}
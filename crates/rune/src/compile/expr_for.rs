@@ -35,14 +35,6 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
             (iter_offset, loop_scope_expected)
         };
 
-        let _guard = self.loops.push(Loop {
-            label: expr_for.label.map(|(label, _)| label),
-            break_label,
-            total_var_count,
-            needs,
-            drop: Some(iter_offset),
-        });
-
         // Declare named loop variable.
         let binding_offset = {
             self.asm.push(Inst::unit(), expr_for.iter.span());
@@ -78,6 +70,16 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
             None
         };
 
+        let _guard = self.loops.push(Loop {
+            label: expr_for.label.map(|(label, _)| label),
+            break_label,
+            continue_label: start_label,
+            continue_var_count: self.scopes.total_var_count(span)?,
+            total_var_count,
+            needs,
+            drop: Some(iter_offset),
+        });
+
         self.asm.label(start_label)?;
 
         // Use the memoized loop variable.
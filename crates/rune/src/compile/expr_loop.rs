@@ -14,10 +14,14 @@ impl Compile<(&ast::ExprLoop, Needs)> for Compiler<'_> {
         let start_label = self.asm.new_label("loop_start");
         let end_label = self.asm.new_label("loop_end");
 
+        let total_var_count = self.scopes.total_var_count(span)?;
+
         let _guard = self.loops.push(Loop {
             label: expr_loop.label.map(|(label, _)| label),
             break_label: end_label,
-            total_var_count: self.scopes.total_var_count(span)?,
+            continue_label: start_label,
+            continue_var_count: total_var_count,
+            total_var_count,
             needs,
             drop: None,
         });
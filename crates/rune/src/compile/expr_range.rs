@@ -0,0 +1,44 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::traits::Compile;
+use crate::CompileResult;
+use crate::Spanned as _;
+use runestick::{Inst, InstRange};
+
+/// Compile a range expression.
+impl Compile<(&ast::ExprRange, Needs)> for Compiler<'_> {
+    fn compile(&mut self, (expr_range, needs): (&ast::ExprRange, Needs)) -> CompileResult<()> {
+        let span = expr_range.span();
+        log::trace!("ExprRange => {:?}", self.source.source(span));
+
+        let guard = self.scopes.push_child(span)?;
+        let mut count = 0;
+
+        for expr in expr_range.from.iter().chain(expr_range.to.iter()) {
+            self.compile((&**expr, Needs::Value))?;
+            self.scopes.decl_anon(expr.span())?;
+            count += 1;
+        }
+
+        let range = match (&expr_range.from, &expr_range.to, expr_range.is_closed()) {
+            (None, None, _) => InstRange::RangeFull,
+            (Some(..), None, _) => InstRange::RangeFrom,
+            (None, Some(..), false) => InstRange::RangeTo,
+            (None, Some(..), true) => InstRange::RangeToInclusive,
+            (Some(..), Some(..), false) => InstRange::Range,
+            (Some(..), Some(..), true) => InstRange::RangeInclusive,
+        };
+
+        self.asm.push(Inst::Range { range }, span);
+        self.scopes.undecl_anon(count, span)?;
+
+        // NB: we still need to evaluate the bounds since they might have side
+        // effects, but pop the range in case a value is not needed.
+        if !needs.value() {
+            self.asm.push(Inst::Pop, span);
+        }
+
+        self.scopes.pop(guard, span)?;
+        Ok(())
+    }
+}
@@ -17,10 +17,14 @@ impl Compile<(&ast::ExprWhile, Needs)> for Compiler<'_> {
         let end_label = self.asm.new_label("while_end");
         let break_label = self.asm.new_label("while_break");
 
+        let total_var_count = self.scopes.total_var_count(span)?;
+
         let _guard = self.loops.push(Loop {
             label: expr_while.label.map(|(label, _)| label),
             break_label,
-            total_var_count: self.scopes.total_var_count(span)?,
+            continue_label: start_label,
+            continue_var_count: total_var_count,
+            total_var_count,
             needs,
             drop: None,
         });
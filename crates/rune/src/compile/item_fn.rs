@@ -24,7 +24,7 @@ impl Compile<(ast::ItemFn, bool)> for Compiler<'_> {
                     let span = s.span();
                     self.scopes.new_var("self", span)?;
                 }
-                ast::FnArg::Ident(ident) => {
+                ast::FnArg::Ident(ident, _) => {
                     let span = ident.span();
                     let name = ident.resolve(&self.storage, &*self.source)?;
                     self.scopes.new_var(name.as_ref(), span)?;
@@ -87,6 +87,15 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
 
                 match &meta.kind {
                     CompileMetaKind::Struct { object, .. } => {
+                        for (key, span) in &check_keys {
+                            self.query.check_field_visibility(
+                                &self.items.item(),
+                                &object.item,
+                                key,
+                                *span,
+                            )?;
+                        }
+
                         check_object_fields(
                             object.fields.as_ref(),
                             check_keys,
@@ -37,6 +37,11 @@ impl Compile<(&ast::LitTemplate, Needs)> for Compiler<'_> {
                     self.compile((&**expr, Needs::Value))?;
                     self.scopes.decl_anon(span)?;
                 }
+                ast::TemplateComponent::Format(expr, spec) => {
+                    self.compile((&**expr, Needs::Value))?;
+                    self.asm.push(Inst::Format { spec: *spec }, span);
+                    self.scopes.decl_anon(span)?;
+                }
             }
         }
 
@@ -244,6 +244,25 @@ pub enum CompileErrorKind {
         /// The name of the missing module.
         item: Item,
     },
+    /// Tried to access an item which isn't visible from where it's used.
+    #[error("`{item}` is private")]
+    NotVisible {
+        /// The item which isn't visible.
+        item: Item,
+        /// The item it was accessed from.
+        from: Item,
+    },
+    /// Tried to access a struct field which isn't visible from where it's
+    /// used.
+    #[error("field `{field}` of `{item}` is private")]
+    NotVisibleField {
+        /// The struct the field belongs to.
+        item: Item,
+        /// The name of the field.
+        field: String,
+        /// The item it was accessed from.
+        from: Item,
+    },
     /// A specific label is missing.
     #[error("label not found in scope")]
     MissingLabel,
@@ -265,6 +284,10 @@ pub enum CompileErrorKind {
     /// Unsupported wildcard component in use.
     #[error("wildcard support not supported in this position")]
     UnsupportedWildcard,
+    /// Tried to add a visibility to a use, which would re-export the items
+    /// it imports.
+    #[error("re-exporting items with `pub use` is not supported")]
+    UnsupportedUseVisibility,
     /// Tried to use a meta as an async block for which it is not supported.
     #[error("`{meta}` is not a supported async block")]
     UnsupportedAsyncBlock {
@@ -380,6 +403,9 @@ pub enum CompileErrorKind {
     /// Error raised when trying to use a break outside of a loop.
     #[error("break expressions cannot be used as a value")]
     BreakOutsideOfLoop,
+    /// Error raised when trying to use a continue outside of a loop.
+    #[error("continue expressions can only be used inside of a loop")]
+    ContinueOutsideOfLoop,
     /// Attempting to use a float in a match pattern.
     #[error("floating point numbers cannot be used in patterns")]
     MatchFloatInPattern,
@@ -425,4 +451,86 @@ pub enum CompileErrorKind {
     /// Trying to process a cycle of constants.
     #[error("constant cycle detected")]
     ConstCycle,
+    /// Tried to implement a trait which doesn't exist.
+    #[error("no such trait `{item}`")]
+    MissingTrait {
+        /// The item of the missing trait.
+        item: Item,
+    },
+    /// Tried to implement something which is not a trait.
+    #[error("expected a trait, but got `{meta}`")]
+    ExpectedTrait {
+        /// The meta of the item which is not a trait.
+        meta: CompileMeta,
+    },
+    /// A function required by a trait was not implemented.
+    #[error("missing function `{name}` required by trait `{item}`")]
+    MissingTraitFunction {
+        /// The name of the missing function.
+        name: String,
+        /// The item of the trait.
+        item: Item,
+    },
+    /// A function in a trait implementation is not declared in the trait.
+    #[error("function `{name}` is not a member of trait `{item}`")]
+    UnsupportedTraitFunction {
+        /// The name of the function.
+        name: String,
+        /// The item of the trait.
+        item: Item,
+    },
+    /// A function in a trait implementation takes a different number of
+    /// arguments than the function declared in the trait.
+    #[error("function `{name}` takes {actual} arguments, but the trait `{item}` declares it with {expected}")]
+    TraitFunctionArgumentMismatch {
+        /// The name of the function.
+        name: String,
+        /// The item of the trait.
+        item: Item,
+        /// The number of arguments declared in the trait.
+        expected: usize,
+        /// The number of arguments in the implementation.
+        actual: usize,
+    },
+    /// A default implementation of a trait function doesn't take `self`.
+    #[error("default implementation of trait function `{name}` must take `self`")]
+    TraitDefaultFunctionWithoutSelf {
+        /// The name of the function.
+        name: String,
+    },
+    /// The `#[protocol]` attribute was used on a function which doesn't
+    /// implement a protocol.
+    #[error("`{name}` is not a protocol which can be implemented by an instance function")]
+    UnsupportedProtocol {
+        /// The name of the function.
+        name: String,
+    },
+    /// A value has a different type than the one it was annotated with.
+    #[error("mismatched types, expected `{expected}` but got `{actual}`")]
+    TypeMismatch {
+        /// The expected type.
+        expected: String,
+        /// The actual type.
+        actual: String,
+    },
+    /// A function was called with the wrong number of arguments.
+    #[error("function `{item}` takes {expected} arguments, but {actual} were given")]
+    ArgumentCountMismatch {
+        /// The item of the function being called.
+        item: Item,
+        /// The number of arguments the function takes.
+        expected: usize,
+        /// The number of arguments given.
+        actual: usize,
+    },
+    /// A binary operation is not supported for the given types.
+    #[error("the operation `{lhs} {op} {rhs}` is not supported")]
+    UnsupportedOperation {
+        /// The operation.
+        op: ast::BinOp,
+        /// The type of the left hand side.
+        lhs: String,
+        /// The type of the right hand side.
+        rhs: String,
+    },
 }
@@ -7,6 +7,7 @@ use crate::loops::Loops;
 use crate::query::{Build, BuildEntry, Query};
 use crate::scopes::{Scope, ScopeGuard, Scopes};
 use crate::traits::Compile as _;
+use crate::type_check::{self, TypeChecker};
 use crate::worker::{Expanded, LoadFileKind, Task, Worker};
 use crate::CompileResult;
 use crate::{
@@ -14,8 +15,8 @@ use crate::{
     Options, Resolve as _, SourceLoader, Sources, Spanned as _, Storage, UnitBuilder, Warnings,
 };
 use runestick::{
-    CompileMeta, CompileMetaKind, ConstValue, Context, Inst, InstValue, Item, Label, Source, Span,
-    TypeCheck,
+    CompileMeta, CompileMetaKind, ConstValue, Context, Hash, Inst, InstRange, InstValue, Item,
+    Label, Source, Span, Type, TypeCheck,
 };
 use std::cell::RefCell;
 use std::collections::VecDeque;
@@ -112,7 +113,11 @@ pub fn compile_with_options(
         return Err(());
     }
 
-    verify_imports(worker.errors, context, &mut *unit.borrow_mut())?;
+    verify_imports(worker.errors, context, &worker.query, &*unit.borrow())?;
+
+    if options.type_check {
+        type_check::check_annotations(context, &storage, &mut worker.query, worker.errors);
+    }
 
     loop {
         while let Some(entry) = worker.query.queue.pop_front() {
@@ -134,6 +139,14 @@ pub fn compile_with_options(
             }
         }
 
+        for impl_trait in std::mem::take(&mut worker.query.impl_traits) {
+            let source_id = impl_trait.source_id;
+
+            if let Err(error) = worker.query.build_impl_trait(impl_trait) {
+                worker.errors.push(LoadError::new(source_id, error));
+            }
+        }
+
         match worker.query.queue_unused_entries(worker.visitor) {
             Ok(true) => (),
             Ok(false) => break,
@@ -212,8 +225,21 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
 
             let span = f.ast.span();
             let count = f.ast.args.items.len();
+
+            if options.type_check {
+                TypeChecker::new(
+                    context,
+                    storage,
+                    compiler.query,
+                    source.clone(),
+                    item.clone(),
+                )
+                .check_fn(&f.ast, None)?;
+            }
+
             compiler.contexts.push(span);
             compiler.compile((f.ast, false))?;
+            compiler.asm.variables = compiler.scopes.take_variables();
 
             if used.is_unused() {
                 compiler.warnings.not_used(source_id, span, None);
@@ -243,14 +269,31 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
                     )
                 })?;
 
-            let type_of = meta.type_of().ok_or_else(|| {
-                CompileError::new(
-                    span,
-                    CompileErrorKind::UnsupportedInstanceFunction { meta: meta.clone() },
+            // NB: default implementations in traits are declared as instance
+            // functions of the trait itself.
+            let type_of = match &meta.kind {
+                CompileMetaKind::Trait { item, .. } => Type::from(Hash::type_hash(item)),
+                _ => meta.type_of().ok_or_else(|| {
+                    CompileError::new(
+                        span,
+                        CompileErrorKind::UnsupportedInstanceFunction { meta: meta.clone() },
+                    )
+                })?,
+            };
+
+            if options.type_check {
+                TypeChecker::new(
+                    context,
+                    storage,
+                    compiler.query,
+                    source.clone(),
+                    item.clone(),
                 )
-            })?;
+                .check_fn(&f.ast, Some(&meta))?;
+            }
 
             compiler.compile((f.ast, true))?;
+            compiler.asm.variables = compiler.scopes.take_variables();
 
             if used.is_unused() {
                 compiler.warnings.not_used(source_id, span, None);
@@ -264,6 +307,7 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
                     asm,
                     f.call,
                     args,
+                    f.protocol,
                 )?;
             }
         }
@@ -278,6 +322,7 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
             let count = c.ast.args.len();
             compiler.contexts.push(span);
             compiler.compile((c.ast, &c.captures[..]))?;
+            compiler.asm.variables = compiler.scopes.take_variables();
 
             if used.is_unused() {
                 compiler.warnings.not_used(source_id, span, None);
@@ -291,6 +336,7 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
             let args = async_block.captures.len();
             compiler.contexts.push(span);
             compiler.compile((&async_block.ast, &async_block.captures[..]))?;
+            compiler.asm.variables = compiler.scopes.take_variables();
 
             if used.is_unused() {
                 compiler.warnings.not_used(source_id, span, None);
@@ -331,7 +377,7 @@ where
             ast::FnArg::Ignore(..) => {
                 args.push(String::from("_"));
             }
-            ast::FnArg::Ident(ident) => {
+            ast::FnArg::Ident(ident, _) => {
                 args.push(ident.resolve(storage, source)?.to_string());
             }
         }
@@ -343,10 +389,18 @@ where
 fn verify_imports(
     errors: &mut Errors,
     context: &Context,
-    unit: &mut UnitBuilder,
+    query: &Query,
+    unit: &UnitBuilder,
 ) -> Result<(), ()> {
-    for (_, entry) in unit.iter_imports() {
+    for (key, entry) in unit.iter_imports() {
         if context.contains_prefix(&entry.item) || unit.contains_prefix(&entry.item) {
+            if let Some((span, source_id)) = entry.span {
+                if let Err(error) = query.check_visibility(&key.item, &entry.item, span) {
+                    errors.push(LoadError::new(source_id, error));
+                    return Err(());
+                }
+            }
+
             continue;
         }
 
@@ -435,6 +489,8 @@ impl<'a> Compiler<'a> {
 
             if let Some(meta) = self.query.query_meta(&current)? {
                 log::trace!("found in query: {:?}", meta);
+                self.query
+                    .check_visibility(&self.items.item(), &current, span)?;
                 self.visitor.visit_meta(self.source_id, &meta, span);
                 return Ok(Some(meta));
             }
@@ -718,6 +774,15 @@ impl<'a> Compiler<'a> {
                     ));
                 };
 
+            for n in 0..tuple.args {
+                self.query.check_field_visibility(
+                    &self.items.item(),
+                    &tuple.item,
+                    &n.to_string(),
+                    path.span(),
+                )?;
+            }
+
             let count = pat_tuple.items.len();
             let is_open = pat_tuple.open_pattern.is_some();
 
@@ -871,6 +936,13 @@ impl<'a> Compiler<'a> {
                             },
                         ));
                     }
+
+                    self.query.check_field_visibility(
+                        &self.items.item(),
+                        &object.item,
+                        &*key,
+                        span,
+                    )?;
                 }
 
                 type_check
@@ -1066,6 +1138,47 @@ impl<'a> Compiler<'a> {
                 self.compile_pat_object(object, false_label, &load)?;
                 return Ok(true);
             }
+            ast::Pat::PatRange(pat_range) => {
+                let span = pat_range.span();
+                let start = self.compile_pat_range_bound(&pat_range.start)?;
+
+                let end = match &pat_range.end {
+                    Some(end) => {
+                        let end = self.compile_pat_range_bound(end)?;
+
+                        match (start, end) {
+                            (InstValue::Byte(..), InstValue::Byte(..))
+                            | (InstValue::Char(..), InstValue::Char(..))
+                            | (InstValue::Integer(..), InstValue::Integer(..)) => (),
+                            _ => {
+                                return Err(CompileError::new(
+                                    span,
+                                    CompileErrorKind::UnsupportedPattern,
+                                ));
+                            }
+                        }
+
+                        Some(end)
+                    }
+                    None => None,
+                };
+
+                let range = match (end.is_some(), pat_range.is_closed()) {
+                    (false, _) => InstRange::RangeFrom,
+                    (true, false) => InstRange::Range,
+                    (true, true) => InstRange::RangeInclusive,
+                };
+
+                load(self, Needs::Value)?;
+                self.asm
+                    .push(Inst::Push { value: start }, pat_range.start.span());
+
+                if let (Some(value), Some(end)) = (end, &pat_range.end) {
+                    self.asm.push(Inst::Push { value }, end.span());
+                }
+
+                self.asm.push(Inst::MatchRange { range }, span);
+            }
         }
 
         self.asm
@@ -1073,6 +1186,37 @@ impl<'a> Compiler<'a> {
         Ok(true)
     }
 
+    /// Compile the literal bound of a range pattern into a value.
+    fn compile_pat_range_bound(&mut self, pat: &ast::Pat) -> CompileResult<InstValue> {
+        let span = pat.span();
+
+        Ok(match pat {
+            ast::Pat::PatByte(lit_byte) => {
+                InstValue::Byte(lit_byte.resolve(&self.storage, &*self.source)?)
+            }
+            ast::Pat::PatChar(lit_char) => {
+                InstValue::Char(lit_char.resolve(&self.storage, &*self.source)?)
+            }
+            ast::Pat::PatNumber(lit_number) => {
+                match lit_number.resolve(&self.storage, &*self.source)? {
+                    ast::Number::Integer(integer) => InstValue::Integer(integer),
+                    ast::Number::Float(..) => {
+                        return Err(CompileError::new(
+                            span,
+                            CompileErrorKind::MatchFloatInPattern,
+                        ));
+                    }
+                }
+            }
+            _ => {
+                return Err(CompileError::new(
+                    span,
+                    CompileErrorKind::UnsupportedPattern,
+                ));
+            }
+        })
+    }
+
     /// Clean the last scope.
     pub(crate) fn clean_last_scope(
         &mut self,
@@ -1,4 +1,5 @@
 use crate::eval::prelude::*;
+use runestick::FormatType;
 use std::fmt::Write as _;
 
 impl Eval<&ast::LitTemplate> for ConstCompiler<'_> {
@@ -42,6 +43,41 @@ impl Eval<&ast::LitTemplate> for ConstCompiler<'_> {
                         }
                     }
                 }
+                ast::TemplateComponent::Format(expr, spec) => {
+                    let span = expr.span();
+                    let const_value = self
+                        .eval(&*expr, used)?
+                        .ok_or_else(|| CompileError::not_const(span))?;
+
+                    match (spec.format_type, const_value) {
+                        (_, ConstValue::Integer(integer)) => {
+                            spec.format_integer(integer, &mut buf);
+                        }
+                        (FormatType::Display, ConstValue::Float(float))
+                        | (FormatType::Debug, ConstValue::Float(float)) => {
+                            spec.format_float(float, &mut buf);
+                        }
+                        (FormatType::Display, ConstValue::String(s))
+                        | (FormatType::Debug, ConstValue::String(s)) => {
+                            spec.format_str(&s, &mut buf);
+                        }
+                        (FormatType::Display, ConstValue::Bool(b)) => {
+                            spec.format_str(if b { "true" } else { "false" }, &mut buf);
+                        }
+                        (FormatType::Debug, ConstValue::Bool(b)) => {
+                            spec.pad(if b { "true" } else { "false" }, &mut buf);
+                        }
+                        (FormatType::Debug, ConstValue::Unit) => {
+                            spec.pad("()", &mut buf);
+                        }
+                        _ => {
+                            return Err(CompileError::const_error(
+                                span,
+                                "unsupported format for constant value",
+                            ));
+                        }
+                    }
+                }
             }
         }
 
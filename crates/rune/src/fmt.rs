@@ -0,0 +1,1317 @@
+//! Formatting of Rune source code.
+//!
+//! The formatter pretty-prints the parsed [File][ast::File] in a canonical
+//! style. Comments are retained by the [Lexer] and re-inserted in front of the
+//! first token which follows them, or at the end of the line if they trail
+//! something on the same line.
+//!
+//! Some layout decisions are taken from the source being formatted. Lists
+//! which start on a new line after their opening delimiter are laid out one
+//! item per line, and a single empty line between items and statements is
+//! preserved.
+
+use crate::ast;
+use crate::{Lexer, ParseError, Spanned as _};
+use runestick::Span;
+use std::iter::Peekable;
+use std::vec;
+
+/// The string used for each level of indentation.
+const INDENT: &str = "    ";
+
+/// Format the given source code.
+///
+/// # Errors
+///
+/// Errors if the source doesn't parse.
+///
+/// # Examples
+///
+/// ```rust
+/// let source = "fn  main( ){let a=#{\"a\":1};// answer\nfoo(a,[1,2])}";
+///
+/// assert_eq! {
+///     rune::format(source).unwrap(),
+///     "fn main() {\n    let a = #{\"a\": 1}; // answer\n    foo(a, [1, 2])\n}\n",
+/// };
+/// ```
+pub fn format(source: &str) -> Result<String, ParseError> {
+    let file = crate::parse_all::<ast::File>(source)?;
+
+    let mut lexer = Lexer::new(source).retain_comments();
+    while lexer.next()?.is_some() {}
+
+    let mut printer = Printer {
+        source,
+        comments: lexer.take_comments().into_iter().peekable(),
+        out: String::new(),
+        indent: 0,
+        newlines: 0,
+        space: false,
+        continued: false,
+        last: 0,
+    };
+
+    printer.file(&file);
+    Ok(printer.finish())
+}
+
+/// How the items of a delimited list are separated from its delimiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delim {
+    /// No padding, like `[a, b]`.
+    Tight,
+    /// Padded with spaces, like `Foo { a, b }`.
+    Padded,
+    /// A tuple, which requires a trailing comma if it has a single item.
+    Tuple,
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    /// Comments which have not been written yet.
+    comments: Peekable<vec::IntoIter<Span>>,
+    out: String,
+    /// The current level of indentation.
+    indent: usize,
+    /// The number of newlines to write before the next token.
+    newlines: usize,
+    /// If a space should be written before the next token.
+    space: bool,
+    /// If the next line continues an expression which was broken by a
+    /// comment, in which case it's indented one more level.
+    continued: bool,
+    /// The end of the last piece of the source which was written.
+    last: usize,
+}
+
+impl Printer<'_> {
+    /// Finish the output, writing any remaining comments.
+    fn finish(mut self) -> String {
+        self.newline();
+        self.flush_comments(usize::MAX);
+
+        let len = self.out.trim_end().len();
+        self.out.truncate(len);
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+
+        self.out
+    }
+
+    /// Write the source text at the given span.
+    fn text(&mut self, span: Span) {
+        self.flush_comments(span.start);
+        self.write_pending();
+        self.continued = false;
+        self.out.push_str(&self.source[span.start..span.end]);
+        self.last = span.end;
+    }
+
+    /// Write the source text at the given span as-is, including any comments
+    /// inside of it.
+    fn verbatim(&mut self, span: Span) {
+        self.text(span);
+
+        while self.comments.peek().map(|c| c.start < span.end) == Some(true) {
+            self.comments.next();
+        }
+    }
+
+    /// Write a string which is not part of the source, like an inserted comma.
+    fn write(&mut self, s: &str) {
+        self.write_pending();
+        self.continued = false;
+        self.out.push_str(s);
+    }
+
+    fn space(&mut self) {
+        self.space = true;
+    }
+
+    fn newline(&mut self) {
+        self.newlines = usize::max(self.newlines, 1);
+        self.continued = false;
+    }
+
+    /// Start a new line for an item in a sequence of items which begins at the
+    /// given offset, preserving an empty line before it.
+    fn line(&mut self, start: usize) {
+        self.newline();
+        self.flush_comments(start);
+
+        if self.blank_before(start) && !self.after_open() {
+            self.newlines = 2;
+        }
+    }
+
+    /// Test if the output is at the start of the file or right after an
+    /// opening delimiter, where empty lines are not preserved.
+    fn after_open(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with(&['{', '(', '['][..])
+    }
+
+    fn write_pending(&mut self) {
+        if self.newlines > 0 {
+            if !self.out.is_empty() {
+                for _ in 0..self.newlines {
+                    self.out.push('\n');
+                }
+            }
+
+            for _ in 0..self.indent + self.continued as usize {
+                self.out.push_str(INDENT);
+            }
+        } else if self.space {
+            self.out.push(' ');
+        }
+
+        self.newlines = 0;
+        self.space = false;
+    }
+
+    /// Write all comments which start before the given offset.
+    ///
+    /// If no new line was started before the comments, they break the line in
+    /// the middle of an expression, so what follows them is continued.
+    fn flush_comments(&mut self, offset: usize) {
+        let continued = !self.out.is_empty() && self.newlines == 0;
+
+        while let Some(comment) = self.comments.peek().copied() {
+            if comment.start >= offset {
+                break;
+            }
+
+            self.comments.next();
+            self.continued |= continued;
+            let text = &self.source[comment.start..comment.end];
+
+            if !self.out.is_empty() && !self.source[self.last..comment.start].contains('\n') {
+                // NB: the comment trails something on the same line.
+                self.out.push(' ');
+                self.out.push_str(text);
+            } else {
+                self.newlines = usize::max(self.newlines, 1);
+
+                if self.blank_before(comment.start) && !self.after_open() {
+                    self.newlines = 2;
+                }
+
+                self.write_pending();
+                self.out.push_str(text);
+            }
+
+            self.last = comment.end;
+            self.newlines = usize::max(self.newlines, 1);
+        }
+    }
+
+    /// Test if there's a comment before the given offset.
+    fn has_comment_before(&mut self, offset: usize) -> bool {
+        self.comments.peek().map(|c| c.start < offset) == Some(true)
+    }
+
+    /// Test if the line preceding the given offset is empty.
+    fn blank_before(&self, offset: usize) -> bool {
+        let is_space = |c: char| c == ' ' || c == '\t' || c == '\r';
+        let before = self.source[..offset].trim_end_matches(is_space);
+
+        match before.strip_suffix('\n') {
+            Some(before) => before.trim_end_matches(is_space).ends_with('\n'),
+            None => false,
+        }
+    }
+
+    /// Test if the source breaks the line right after the given opening
+    /// delimiter.
+    fn breaks_after(&self, open: Span) -> bool {
+        let after = self.source[open.end..].trim_start_matches(&[' ', '\t'][..]);
+        after.starts_with(&['\r', '\n'][..]) || after.starts_with("//")
+    }
+
+    /// Write the dot of a field access or an await in a chain of calls.
+    ///
+    /// If the source puts the dot on a new line, so does the output, in which
+    /// case the indentation is increased and `true` is returned. The caller is
+    /// then responsible for decreasing it again.
+    fn chain_dot(&mut self, dot: Span) -> bool {
+        let broken = self.source[self.last..dot.start].contains('\n');
+
+        if broken {
+            self.indent += 1;
+            self.newline();
+        }
+
+        self.text(dot);
+        broken
+    }
+
+    /// Write a comma-separated list of items surrounded by the given
+    /// delimiters, with an optional rest pattern `..` at the end.
+    fn list<T>(
+        &mut self,
+        open: Span,
+        items: &[T],
+        rest: Option<Span>,
+        close: Span,
+        delim: Delim,
+        mut f: impl FnMut(&mut Self, &T),
+    ) {
+        self.text(open);
+
+        if items.is_empty() && rest.is_none() && !self.has_comment_before(close.start) {
+            self.text(close);
+            return;
+        }
+
+        if self.breaks_after(open) || self.has_comment_before(close.start) {
+            self.indent += 1;
+
+            for item in items {
+                self.newline();
+                f(self, item);
+                self.write(",");
+            }
+
+            if let Some(rest) = rest {
+                self.newline();
+                self.text(rest);
+            }
+
+            self.newline();
+            self.flush_comments(close.start);
+            self.indent -= 1;
+            self.newline();
+            self.text(close);
+            return;
+        }
+
+        if delim == Delim::Padded {
+            self.space();
+        }
+
+        for (n, item) in items.iter().enumerate() {
+            if n > 0 {
+                self.write(",");
+                self.space();
+            }
+
+            f(self, item);
+        }
+
+        if let Some(rest) = rest {
+            if !items.is_empty() {
+                self.write(",");
+                self.space();
+            }
+
+            self.text(rest);
+        } else if delim == Delim::Tuple && items.len() == 1 {
+            self.write(",");
+        }
+
+        if delim == Delim::Padded {
+            self.space();
+        }
+
+        self.text(close);
+    }
+
+    /// Write the contents of a file or an inline module.
+    fn file(&mut self, file: &ast::File) {
+        for attribute in &file.attributes {
+            self.verbatim(attribute.span());
+            self.newline();
+        }
+
+        for (item, semi) in &file.items {
+            self.line(item.span().start);
+            self.item(item);
+
+            if let Some(semi) = semi {
+                self.text(semi.span());
+            }
+        }
+    }
+
+    /// Write attributes which are each on a line of their own.
+    fn outer_attributes(&mut self, attributes: &[ast::Attribute]) {
+        for attribute in attributes {
+            self.verbatim(attribute.span());
+            self.newline();
+        }
+    }
+
+    /// Write attributes which precede something on the same line.
+    fn inline_attributes(&mut self, attributes: &[ast::Attribute]) {
+        for attribute in attributes {
+            self.verbatim(attribute.span());
+            self.space();
+        }
+    }
+
+    /// Write the visibility of an item, followed by a space if it has one.
+    fn visibility(&mut self, visibility: &ast::Visibility) {
+        match visibility {
+            ast::Visibility::Inherited => return,
+            ast::Visibility::Public(pub_) => {
+                self.text(pub_.span());
+            }
+            ast::Visibility::Crate(pub_, open, crate_, close) => {
+                self.text(pub_.span());
+                self.text(open.span());
+                self.text(crate_.span());
+                self.text(close.span());
+            }
+            ast::Visibility::Super(pub_, open, super_, close) => {
+                self.text(pub_.span());
+                self.text(open.span());
+                self.text(super_.span());
+                self.text(close.span());
+            }
+        }
+
+        self.space();
+    }
+
+    fn item(&mut self, item: &ast::Item) {
+        match item {
+            ast::Item::ItemUse(item_use) => {
+                self.outer_attributes(&item_use.attributes);
+                self.visibility(&item_use.visibility);
+                self.text(item_use.use_.span());
+                self.space();
+                self.text(item_use.first.span());
+
+                for (scope, component) in &item_use.rest {
+                    self.text(scope.span());
+
+                    match component {
+                        ast::ItemUseComponent::Ident(ident) => self.text(ident.span()),
+                        ast::ItemUseComponent::Wildcard(wildcard) => self.text(wildcard.span()),
+                    }
+                }
+
+                self.text(item_use.semi.span());
+            }
+            ast::Item::ItemFn(item_fn) => {
+                self.item_fn(item_fn);
+            }
+            ast::Item::ItemEnum(item_enum) => {
+                self.outer_attributes(&item_enum.attributes);
+                self.visibility(&item_enum.visibility);
+                self.text(item_enum.enum_.span());
+                self.space();
+                self.text(item_enum.name.span());
+                self.space();
+                self.text(item_enum.open.span());
+
+                let close = item_enum.close.span();
+
+                if !item_enum.variants.is_empty() || self.has_comment_before(close.start) {
+                    self.indent += 1;
+
+                    for variant in &item_enum.variants {
+                        self.line(variant.span().start);
+                        self.outer_attributes(&variant.attributes);
+                        self.text(variant.name.span());
+
+                        match &variant.body {
+                            ast::ItemVariantBody::EmptyBody => (),
+                            ast::ItemVariantBody::TupleBody(body) => {
+                                self.tuple_body(body);
+                            }
+                            ast::ItemVariantBody::StructBody(body) => {
+                                self.space();
+                                self.struct_body(body);
+                            }
+                        }
+
+                        self.write(",");
+                    }
+
+                    self.newline();
+                    self.flush_comments(close.start);
+                    self.indent -= 1;
+                    self.newline();
+                }
+
+                self.text(close);
+            }
+            ast::Item::ItemStruct(item_struct) => {
+                self.outer_attributes(&item_struct.attributes);
+                self.visibility(&item_struct.visibility);
+                self.text(item_struct.struct_.span());
+                self.space();
+                self.text(item_struct.ident.span());
+
+                match &item_struct.body {
+                    ast::ItemStructBody::EmptyBody(semi) => {
+                        self.text(semi.span());
+                    }
+                    ast::ItemStructBody::TupleBody(body, semi) => {
+                        self.tuple_body(body);
+                        self.text(semi.span());
+                    }
+                    ast::ItemStructBody::StructBody(body) => {
+                        self.space();
+                        self.struct_body(body);
+                    }
+                }
+            }
+            ast::Item::ItemImpl(item_impl) => {
+                self.outer_attributes(&item_impl.attributes);
+                self.text(item_impl.impl_.span());
+                self.space();
+
+                if let Some((path, for_)) = &item_impl.trait_ {
+                    self.path(path);
+                    self.space();
+                    self.text(for_.span());
+                    self.space();
+                }
+
+                self.path(&item_impl.path);
+                self.space();
+                self.text(item_impl.open.span());
+
+                let close = item_impl.close.span();
+
+                if !item_impl.functions.is_empty() || self.has_comment_before(close.start) {
+                    self.indent += 1;
+
+                    for item_fn in &item_impl.functions {
+                        self.line(item_fn.span().start);
+                        self.item_fn(item_fn);
+                    }
+
+                    self.newline();
+                    self.flush_comments(close.start);
+                    self.indent -= 1;
+                    self.newline();
+                }
+
+                self.text(close);
+            }
+            ast::Item::ItemTrait(item_trait) => {
+                self.outer_attributes(&item_trait.attributes);
+                self.visibility(&item_trait.visibility);
+                self.text(item_trait.trait_.span());
+                self.space();
+                self.text(item_trait.name.span());
+                self.space();
+                self.text(item_trait.open.span());
+
+                let close = item_trait.close.span();
+
+                if !item_trait.functions.is_empty() || self.has_comment_before(close.start) {
+                    self.indent += 1;
+
+                    for trait_fn in &item_trait.functions {
+                        self.line(trait_fn.span().start);
+                        self.item_trait_fn(trait_fn);
+                    }
+
+                    self.newline();
+                    self.flush_comments(close.start);
+                    self.indent -= 1;
+                    self.newline();
+                }
+
+                self.text(close);
+            }
+            ast::Item::ItemMod(item_mod) => {
+                self.outer_attributes(&item_mod.attributes);
+                self.visibility(&item_mod.visibility);
+                self.text(item_mod.mod_.span());
+                self.space();
+                self.text(item_mod.name.span());
+
+                match &item_mod.body {
+                    ast::ItemModBody::EmptyBody(semi) => {
+                        self.text(semi.span());
+                    }
+                    ast::ItemModBody::InlineBody(body) => {
+                        self.space();
+                        self.text(body.open.span());
+
+                        let close = body.close.span();
+
+                        if !body.file.attributes.is_empty()
+                            || !body.file.items.is_empty()
+                            || self.has_comment_before(close.start)
+                        {
+                            self.indent += 1;
+                            self.newline();
+                            self.file(&body.file);
+                            self.newline();
+                            self.flush_comments(close.start);
+                            self.indent -= 1;
+                            self.newline();
+                        }
+
+                        self.text(close);
+                    }
+                }
+            }
+            ast::Item::ItemConst(item_const) => {
+                self.outer_attributes(&item_const.attributes);
+                self.visibility(&item_const.visibility);
+                self.text(item_const.const_token.span());
+                self.space();
+                self.text(item_const.name.span());
+                self.space();
+                self.text(item_const.eq.span());
+                self.space();
+                self.expr(&item_const.expr);
+                self.text(item_const.semi.span());
+            }
+            ast::Item::MacroCall(macro_call) => {
+                self.verbatim(macro_call.span());
+            }
+        }
+    }
+
+    fn item_fn(&mut self, item_fn: &ast::ItemFn) {
+        self.outer_attributes(&item_fn.attributes);
+        self.visibility(&item_fn.visibility);
+        self.fn_signature(
+            item_fn.async_.as_ref(),
+            &item_fn.fn_,
+            &item_fn.name,
+            &item_fn.args,
+            item_fn.output.as_ref(),
+        );
+        self.space();
+        self.block(&item_fn.body);
+    }
+
+    fn item_trait_fn(&mut self, trait_fn: &ast::ItemTraitFn) {
+        self.outer_attributes(&trait_fn.attributes);
+        self.fn_signature(
+            trait_fn.async_.as_ref(),
+            &trait_fn.fn_,
+            &trait_fn.name,
+            &trait_fn.args,
+            trait_fn.output.as_ref(),
+        );
+
+        if let Some(body) = &trait_fn.body {
+            self.space();
+            self.block(body);
+        } else if let Some(semi) = &trait_fn.semi {
+            self.text(semi.span());
+        }
+    }
+
+    /// Write everything in a function declaration up until its body.
+    fn fn_signature(
+        &mut self,
+        async_: Option<&ast::Async>,
+        fn_: &ast::Fn,
+        name: &ast::Ident,
+        args: &ast::Parenthesized<ast::FnArg, ast::Comma>,
+        output: Option<&ast::ReturnType>,
+    ) {
+        if let Some(async_) = async_ {
+            self.text(async_.span());
+            self.space();
+        }
+
+        self.text(fn_.span());
+        self.space();
+        self.text(name.span());
+
+        self.list(
+            args.open.span(),
+            &args.items,
+            None,
+            args.close.span(),
+            Delim::Tight,
+            |p, (arg, _)| p.fn_arg(arg),
+        );
+
+        if let Some(output) = output {
+            self.space();
+            self.text(output.arrow.span());
+            self.space();
+            self.path(&output.path);
+        }
+    }
+
+    fn fn_arg(&mut self, arg: &ast::FnArg) {
+        match arg {
+            ast::FnArg::Ident(ident, ty) => {
+                self.text(ident.span());
+                self.type_annotation(ty.as_ref());
+            }
+            arg => self.text(arg.span()),
+        }
+    }
+
+    fn type_annotation(&mut self, ty: Option<&ast::TypeAnnotation>) {
+        if let Some(ty) = ty {
+            self.text(ty.colon.span());
+            self.space();
+            self.path(&ty.path);
+        }
+    }
+
+    fn tuple_body(&mut self, body: &ast::TupleBody) {
+        self.list(
+            body.open.span(),
+            &body.fields,
+            None,
+            body.close.span(),
+            Delim::Tight,
+            Self::field,
+        );
+    }
+
+    fn struct_body(&mut self, body: &ast::StructBody) {
+        self.list(
+            body.open.span(),
+            &body.fields,
+            None,
+            body.close.span(),
+            Delim::Padded,
+            Self::field,
+        );
+    }
+
+    fn field(&mut self, field: &ast::Field) {
+        self.inline_attributes(&field.attributes);
+        self.visibility(&field.visibility);
+        self.text(field.name.span());
+        self.type_annotation(field.ty.as_ref());
+    }
+
+    fn path(&mut self, path: &ast::Path) {
+        self.text(path.first.span());
+
+        for (scope, ident) in &path.rest {
+            self.text(scope.span());
+            self.text(ident.span());
+        }
+
+        if let Some(trailing) = &path.trailing {
+            self.text(trailing.span());
+        }
+    }
+
+    fn block(&mut self, block: &ast::Block) {
+        self.text(block.open.span());
+
+        let close = block.close.span();
+
+        if !block.statements.is_empty() || self.has_comment_before(close.start) {
+            self.indent += 1;
+
+            for stmt in &block.statements {
+                self.line(stmt.span().start);
+
+                match stmt {
+                    ast::Stmt::Item(item) => {
+                        self.item(item);
+                    }
+                    ast::Stmt::Expr(expr) => {
+                        self.expr(expr);
+                    }
+                    ast::Stmt::Semi(expr, semi) => {
+                        self.expr(expr);
+                        self.text(semi.span());
+                    }
+                }
+            }
+
+            self.newline();
+            self.flush_comments(close.start);
+            self.indent -= 1;
+            self.newline();
+        }
+
+        self.text(close);
+    }
+
+    fn expr_block(&mut self, expr_block: &ast::ExprBlock) {
+        self.inline_attributes(&expr_block.attributes);
+        self.block(&expr_block.block);
+    }
+
+    fn label(&mut self, label: &Option<(ast::Label, ast::Colon)>) {
+        if let Some((label, colon)) = label {
+            self.text(label.span());
+            self.text(colon.span());
+            self.space();
+        }
+    }
+
+    fn condition(&mut self, condition: &ast::Condition) {
+        match condition {
+            ast::Condition::Expr(expr) => self.expr(expr),
+            ast::Condition::ExprLet(expr_let) => self.expr_let(expr_let),
+        }
+    }
+
+    fn expr_let(&mut self, expr_let: &ast::ExprLet) {
+        self.text(expr_let.let_.span());
+        self.space();
+        self.pat(&expr_let.pat);
+        self.type_annotation(expr_let.ty.as_ref());
+        self.space();
+        self.text(expr_let.eq.span());
+        self.space();
+        self.expr(&expr_let.expr);
+    }
+
+    /// Write the body of a match or select branch, followed by a comma unless
+    /// the body is a block.
+    fn branch_body(&mut self, rocket: &ast::Rocket, body: &ast::Expr) {
+        self.space();
+        self.text(rocket.span());
+        self.space();
+        self.expr(body);
+
+        if !matches!(body, ast::Expr::ExprBlock(..)) {
+            self.write(",");
+        }
+    }
+
+    fn expr(&mut self, expr: &ast::Expr) {
+        match expr {
+            ast::Expr::Self_(self_) => {
+                self.text(self_.span());
+            }
+            ast::Expr::Path(path) => {
+                self.path(path);
+            }
+            ast::Expr::Item(item) => {
+                self.item(item);
+            }
+            ast::Expr::ExprWhile(expr_while) => {
+                self.label(&expr_while.label);
+                self.text(expr_while.while_.span());
+                self.space();
+                self.condition(&expr_while.condition);
+                self.space();
+                self.expr_block(&expr_while.body);
+            }
+            ast::Expr::ExprLoop(expr_loop) => {
+                self.label(&expr_loop.label);
+                self.text(expr_loop.loop_.span());
+                self.space();
+                self.expr_block(&expr_loop.body);
+            }
+            ast::Expr::ExprFor(expr_for) => {
+                self.label(&expr_for.label);
+                self.text(expr_for.for_.span());
+                self.space();
+                self.text(expr_for.var.span());
+                self.space();
+                self.text(expr_for.in_.span());
+                self.space();
+                self.expr(&expr_for.iter);
+                self.space();
+                self.expr_block(&expr_for.body);
+            }
+            ast::Expr::ExprLet(expr_let) => {
+                self.expr_let(expr_let);
+            }
+            ast::Expr::ExprIndexSet(expr) => {
+                self.expr(&expr.target);
+                self.text(expr.open.span());
+                self.expr(&expr.index);
+                self.text(expr.close.span());
+                self.space();
+                self.text(expr.eq.span());
+                self.space();
+                self.expr(&expr.value);
+            }
+            ast::Expr::ExprIf(expr_if) => {
+                self.text(expr_if.if_.span());
+                self.space();
+                self.condition(&expr_if.condition);
+                self.space();
+                self.expr_block(&expr_if.block);
+
+                for expr_else_if in &expr_if.expr_else_ifs {
+                    self.space();
+                    self.text(expr_else_if.else_.span());
+                    self.space();
+                    self.text(expr_else_if.if_.span());
+                    self.space();
+                    self.condition(&expr_else_if.condition);
+                    self.space();
+                    self.expr_block(&expr_else_if.block);
+                }
+
+                if let Some(expr_else) = &expr_if.expr_else {
+                    self.space();
+                    self.text(expr_else.else_.span());
+                    self.space();
+                    self.expr_block(&expr_else.block);
+                }
+            }
+            ast::Expr::ExprMatch(expr_match) => {
+                self.inline_attributes(&expr_match.attributes);
+                self.text(expr_match.match_.span());
+                self.space();
+                self.expr(&expr_match.expr);
+                self.space();
+                self.text(expr_match.open.span());
+
+                let close = expr_match.close.span();
+
+                if !expr_match.branches.is_empty() || self.has_comment_before(close.start) {
+                    self.indent += 1;
+
+                    for (branch, _) in &expr_match.branches {
+                        self.line(branch.span().start);
+                        self.pat(&branch.pat);
+
+                        if let Some((if_, condition)) = &branch.condition {
+                            self.space();
+                            self.text(if_.span());
+                            self.space();
+                            self.expr(condition);
+                        }
+
+                        self.branch_body(&branch.rocket, &branch.body);
+                    }
+
+                    self.newline();
+                    self.flush_comments(close.start);
+                    self.indent -= 1;
+                    self.newline();
+                }
+
+                self.text(close);
+            }
+            ast::Expr::ExprCall(expr_call) => {
+                self.expr(&expr_call.expr);
+
+                let args = &expr_call.args;
+
+                self.list(
+                    args.open.span(),
+                    &args.items,
+                    None,
+                    args.close.span(),
+                    Delim::Tight,
+                    |p, (arg, _)| p.expr(arg),
+                );
+            }
+            ast::Expr::MacroCall(macro_call) => {
+                self.verbatim(macro_call.span());
+            }
+            ast::Expr::ExprFieldAccess(expr) => {
+                self.expr(&expr.expr);
+                let broken = self.chain_dot(expr.dot.span());
+
+                match &expr.expr_field {
+                    ast::ExprField::Ident(ident) => self.text(ident.span()),
+                    ast::ExprField::LitNumber(number) => self.text(number.span()),
+                }
+
+                if broken {
+                    self.indent -= 1;
+                }
+            }
+            ast::Expr::ExprGroup(expr_group) => {
+                self.text(expr_group.open.span());
+                self.expr(&expr_group.expr);
+                self.text(expr_group.close.span());
+            }
+            ast::Expr::ExprBinary(expr_binary) => {
+                self.expr(&expr_binary.lhs);
+                self.space();
+                self.text(expr_binary.t1.span());
+
+                if let Some(t2) = &expr_binary.t2 {
+                    self.space();
+                    self.text(t2.span());
+                }
+
+                self.space();
+                self.expr(&expr_binary.rhs);
+            }
+            ast::Expr::ExprUnary(expr_unary) => {
+                self.text(expr_unary.token.span());
+                self.expr(&expr_unary.expr);
+            }
+            ast::Expr::ExprRange(expr_range) => {
+                if let Some(from) = &expr_range.from {
+                    self.expr(from);
+                }
+
+                self.text(expr_range.limits.span());
+
+                if let Some(to) = &expr_range.to {
+                    self.expr(to);
+                }
+            }
+            ast::Expr::ExprIndexGet(expr) => {
+                self.expr(&expr.target);
+                self.text(expr.open.span());
+                self.expr(&expr.index);
+                self.text(expr.close.span());
+            }
+            ast::Expr::ExprBreak(expr_break) => {
+                self.text(expr_break.break_.span());
+
+                match &expr_break.expr {
+                    Some(ast::ExprBreakValue::Expr(expr)) => {
+                        self.space();
+                        self.expr(expr);
+                    }
+                    Some(ast::ExprBreakValue::Label(label)) => {
+                        self.space();
+                        self.text(label.span());
+                    }
+                    None => (),
+                }
+            }
+            ast::Expr::ExprContinue(expr_continue) => {
+                self.text(expr_continue.continue_.span());
+
+                if let Some(label) = &expr_continue.label {
+                    self.space();
+                    self.text(label.span());
+                }
+            }
+            ast::Expr::ExprYield(expr_yield) => {
+                self.text(expr_yield.yield_.span());
+
+                if let Some(expr) = &expr_yield.expr {
+                    self.space();
+                    self.expr(expr);
+                }
+            }
+            ast::Expr::ExprBlock(expr_block) => {
+                self.expr_block(expr_block);
+            }
+            ast::Expr::ExprAsync(expr_async) => {
+                self.inline_attributes(&expr_async.attributes);
+                self.text(expr_async.async_.span());
+                self.space();
+                self.block(&expr_async.block);
+            }
+            ast::Expr::ExprReturn(expr_return) => {
+                self.text(expr_return.return_.span());
+
+                if let Some(expr) = &expr_return.expr {
+                    self.space();
+                    self.expr(expr);
+                }
+            }
+            ast::Expr::ExprAwait(expr_await) => {
+                self.expr(&expr_await.expr);
+                let broken = self.chain_dot(expr_await.dot.span());
+                self.text(expr_await.await_.span());
+
+                if broken {
+                    self.indent -= 1;
+                }
+            }
+            ast::Expr::ExprTry(expr_try) => {
+                self.expr(&expr_try.expr);
+                self.text(expr_try.try_.span());
+            }
+            ast::Expr::ExprSelect(expr_select) => {
+                self.text(expr_select.select.span());
+                self.space();
+                self.text(expr_select.open.span());
+                self.indent += 1;
+
+                for (branch, _) in &expr_select.branches {
+                    self.line(branch.span().start);
+                    self.pat(&branch.pat);
+                    self.space();
+                    self.text(branch.eq.span());
+                    self.space();
+                    self.expr(&branch.expr);
+                    self.branch_body(&branch.rocket, &branch.body);
+                }
+
+                if let Some((branch, _)) = &expr_select.default_branch {
+                    self.line(branch.span().start);
+                    self.text(branch.default.span());
+                    self.branch_body(&branch.rocket, &branch.body);
+                }
+
+                let close = expr_select.close.span();
+                self.newline();
+                self.flush_comments(close.start);
+                self.indent -= 1;
+                self.newline();
+                self.text(close);
+            }
+            ast::Expr::ExprClosure(expr_closure) => {
+                self.inline_attributes(&expr_closure.attributes);
+
+                if let Some(async_) = &expr_closure.async_ {
+                    self.text(async_.span());
+                    self.space();
+                }
+
+                match &expr_closure.args {
+                    ast::ExprClosureArgs::Empty { token } => {
+                        self.text(token.span());
+                    }
+                    ast::ExprClosureArgs::List { open, args, close } => {
+                        self.text(open.span());
+
+                        for (n, (arg, _)) in args.iter().enumerate() {
+                            if n > 0 {
+                                self.write(",");
+                                self.space();
+                            }
+
+                            self.fn_arg(arg);
+                        }
+
+                        self.text(close.span());
+                    }
+                }
+
+                self.space();
+                self.expr(&expr_closure.body);
+            }
+            ast::Expr::ExprLit(expr_lit) => {
+                self.inline_attributes(&expr_lit.attributes);
+                self.lit(&expr_lit.lit);
+            }
+        }
+    }
+
+    fn lit(&mut self, lit: &ast::Lit) {
+        match lit {
+            ast::Lit::Unit(unit) => {
+                self.text(unit.open.span());
+                self.text(unit.close.span());
+            }
+            ast::Lit::Object(object) => {
+                let delim = self.object_ident(&object.ident);
+
+                self.list(
+                    object.open.span(),
+                    &object.assignments,
+                    None,
+                    object.close.span(),
+                    delim,
+                    |p, assign| {
+                        p.text(assign.key.span());
+
+                        if let Some((colon, expr)) = &assign.assign {
+                            p.text(colon.span());
+                            p.space();
+                            p.expr(expr);
+                        }
+                    },
+                );
+            }
+            ast::Lit::Tuple(tuple) => {
+                self.list(
+                    tuple.open.span(),
+                    &tuple.items,
+                    None,
+                    tuple.close.span(),
+                    Delim::Tuple,
+                    |p, (expr, _)| p.expr(expr),
+                );
+            }
+            ast::Lit::Vec(vec) => {
+                self.list(
+                    vec.open.span(),
+                    &vec.items,
+                    None,
+                    vec.close.span(),
+                    Delim::Tight,
+                    Self::expr,
+                );
+            }
+            lit => {
+                self.text(lit.span());
+            }
+        }
+    }
+
+    /// Write the identifier of an object, returning the delimiter used for
+    /// its fields.
+    fn object_ident(&mut self, ident: &ast::LitObjectIdent) -> Delim {
+        match ident {
+            ast::LitObjectIdent::Anonymous(hash) => {
+                self.text(hash.span());
+                Delim::Tight
+            }
+            ast::LitObjectIdent::Named(path) => {
+                self.path(path);
+                self.space();
+                Delim::Padded
+            }
+        }
+    }
+
+    fn pat(&mut self, pat: &ast::Pat) {
+        match pat {
+            ast::Pat::PatPath(pat_path) => {
+                self.path(&pat_path.path);
+            }
+            ast::Pat::PatUnit(unit) => {
+                self.text(unit.open.span());
+                self.text(unit.close.span());
+            }
+            ast::Pat::PatVec(pat_vec) => {
+                self.list(
+                    pat_vec.open.span(),
+                    &pat_vec.items,
+                    pat_vec.open_pattern.as_ref().map(|t| t.span()),
+                    pat_vec.close.span(),
+                    Delim::Tight,
+                    |p, (pat, _)| p.pat(pat),
+                );
+            }
+            ast::Pat::PatTuple(pat_tuple) => {
+                let delim = match &pat_tuple.path {
+                    Some(path) => {
+                        self.path(path);
+                        Delim::Tight
+                    }
+                    None => Delim::Tuple,
+                };
+
+                self.list(
+                    pat_tuple.open.span(),
+                    &pat_tuple.items,
+                    pat_tuple.open_pattern.as_ref().map(|t| t.span()),
+                    pat_tuple.close.span(),
+                    delim,
+                    |p, (pat, _)| p.pat(pat),
+                );
+            }
+            ast::Pat::PatObject(pat_object) => {
+                let delim = self.object_ident(&pat_object.ident);
+
+                self.list(
+                    pat_object.open.span(),
+                    &pat_object.fields,
+                    pat_object.open_pattern.as_ref().map(|t| t.span()),
+                    pat_object.close.span(),
+                    delim,
+                    |p, (item, _)| {
+                        p.text(item.key.span());
+
+                        if let Some((colon, pat)) = &item.binding {
+                            p.text(colon.span());
+                            p.space();
+                            p.pat(pat);
+                        }
+                    },
+                );
+            }
+            ast::Pat::PatRange(pat_range) => {
+                self.pat(&pat_range.start);
+                self.text(pat_range.limits.span());
+
+                if let Some(end) = &pat_range.end {
+                    self.pat(end);
+                }
+            }
+            pat => {
+                self.text(pat.span());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format;
+
+    macro_rules! assert_format {
+        ($source:expr, $expected:expr) => {{
+            let formatted = format($source).unwrap();
+            assert_eq!(formatted, $expected);
+            assert_eq!(format(&formatted).unwrap(), formatted, "not idempotent");
+        }};
+    }
+
+    #[test]
+    fn test_items() {
+        assert_format!(
+            "use std::collections::*;struct A;struct B(a,b);struct C{a,b}enum D{E,F(a),G{b}}",
+            "use std::collections::*;\nstruct A;\nstruct B(a, b);\nstruct C { a, b }\nenum D {\n    E,\n    F(a),\n    G { b },\n}\n"
+        );
+
+        assert_format!(
+            "impl Foo{fn a(self){}\n\n\n\nfn b(){}}mod m{const A=1;}",
+            "impl Foo {\n    fn a(self) {}\n\n    fn b() {}\n}\nmod m {\n    const A = 1;\n}\n"
+        );
+
+        assert_format!(
+            "trait T{fn a(self);fn b(self){}}impl T for Foo{fn a(self){}}",
+            "trait T {\n    fn a(self);\n    fn b(self) {}\n}\nimpl T for Foo {\n    fn a(self) {}\n}\n"
+        );
+
+        assert_format!(
+            "struct A{a:int,b}fn f(a:int,b)->std::string::String{let c:int=a;let d=|e:float|e;}",
+            "struct A { a: int, b }\nfn f(a: int, b) -> std::string::String {\n    let c: int = a;\n    let d = |e: float| e;\n}\n"
+        );
+
+        assert_format!(
+            "pub mod m{pub(crate) struct A{pub a,pub(super) b,c}impl A{pub fn f(){}}}pub(crate) const B=1;",
+            "pub mod m {\n    pub(crate) struct A { pub a, pub(super) b, c }\n    impl A {\n        pub fn f() {}\n    }\n}\npub(crate) const B = 1;\n"
+        );
+    }
+
+    #[test]
+    fn test_expressions() {
+        assert_format!(
+            "fn f(){if a{b}else if c{d}else{e}match x{1=>a,_ if b=>{c}}}",
+            "fn f() {\n    if a {\n        b\n    } else if c {\n        d\n    } else {\n        e\n    }\n    match x {\n        1 => a,\n        _ if b => {\n            c\n        }\n    }\n}\n"
+        );
+
+        assert_format!(
+            "fn f(){let (a,)=(1,);let #{b,..}=#{b:2};let c=|x,y|x+y;'l: loop{continue 'l;break 'l;}}",
+            "fn f() {\n    let (a,) = (1,);\n    let #{b, ..} = #{b: 2};\n    let c = |x, y| x + y;\n    'l: loop {\n        continue 'l;\n        break 'l;\n    }\n}\n"
+        );
+
+        assert_format!(
+            "fn f(){for i in 0 .. n{a[.. 2];}match c{'a' ..= 'z'=>1,10 .. =>2,_=>3}}",
+            "fn f() {\n    for i in 0..n {\n        a[..2];\n    }\n    match c {\n        'a'..='z' => 1,\n        10.. => 2,\n        _ => 3,\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_broken_lists() {
+        assert_format!(
+            "fn f(){foo(\na,b)}",
+            "fn f() {\n    foo(\n        a,\n        b,\n    )\n}\n"
+        );
+
+        assert_format!(
+            "fn f(){a\n.b()\n.await}",
+            "fn f() {\n    a\n        .b()\n        .await\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        assert_format!(
+            "// head\n\nfn f(){ // open\n// own\nlet a=1;// trailing\n\n// before\nb\n// last\n}\n// end",
+            "// head\n\nfn f() { // open\n    // own\n    let a = 1; // trailing\n\n    // before\n    b\n    // last\n}\n// end\n"
+        );
+
+        assert_format!(
+            "fn f(){[1,// one\n2]}",
+            "fn f() {\n    [\n        1, // one\n        2,\n    ]\n}\n"
+        );
+
+        assert_format!(
+            "fn f(){let a=1// one\n+2;let b=3\n// two\n*4;c}",
+            "fn f() {\n    let a = 1 // one\n        + 2;\n    let b = 3\n        // two\n        * 4;\n    c\n}\n"
+        );
+    }
+}
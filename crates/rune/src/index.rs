@@ -3,15 +3,20 @@ use crate::collections::HashMap;
 use crate::eval::Used;
 use crate::index_scopes::IndexScopes;
 use crate::items::Items;
-use crate::query::{Build, BuildEntry, Function, Indexed, IndexedEntry, InstanceFunction, Query};
+use crate::query::{
+    Build, BuildEntry, Function, ImplTrait, Indexed, IndexedEntry, InstanceFunction, Query,
+    TypeAnnotations, Visibility,
+};
+use crate::unit_builder::script_protocol;
 use crate::worker::{Import, LoadFileKind, Macro, MacroKind, Task};
 use crate::CompileResult;
 use crate::{
-    CompileError, CompileErrorKind, CompileVisitor, Resolve as _, SourceLoader, Sources,
-    Spanned as _, Storage, Warnings,
+    CompileError, CompileErrorKind, CompileVisitor, OptionSpanned as _, Resolve as _, SourceLoader,
+    Sources, Spanned as _, Storage, Warnings,
 };
 use runestick::{
-    Call, CompileMeta, CompileMetaKind, CompileSource, Hash, Item, Source, SourceId, Span, Type,
+    Call, CompileMeta, CompileMetaKind, CompileSource, Hash, Item, Protocol, Source, SourceId,
+    Span, Type,
 };
 use std::collections::VecDeque;
 use std::path::PathBuf;
@@ -56,6 +61,43 @@ impl<'a> Indexer<'a> {
         }
     }
 
+    /// Get the protocol which the given instance function opts in to
+    /// implementing with the `#[protocol]` attribute, if any.
+    fn protocol(&self, decl_fn: &ast::ItemFn, name: &str) -> CompileResult<Option<Protocol>> {
+        let mut protocol = None;
+
+        for attribute in &decl_fn.attributes {
+            let is_protocol = match (&attribute.style, attribute.path.try_as_ident()) {
+                (ast::AttrStyle::Inner, Some(ident)) => {
+                    ident.resolve(&self.storage, &*self.source)?.as_ref() == "protocol"
+                        && attribute.input.iter().next().is_none()
+                }
+                _ => false,
+            };
+
+            if !is_protocol || protocol.is_some() {
+                return Err(CompileError::internal(
+                    attribute,
+                    "function attributes are not supported",
+                ));
+            }
+
+            protocol = match script_protocol(name) {
+                Some(protocol) if decl_fn.is_instance() => Some(protocol),
+                _ => {
+                    return Err(CompileError::new(
+                        attribute,
+                        CompileErrorKind::UnsupportedProtocol {
+                            name: name.to_owned(),
+                        },
+                    ))
+                }
+            };
+        }
+
+        Ok(protocol)
+    }
+
     /// Handle a filesystem module.
     pub(crate) fn handle_file_mod(&mut self, item_mod: &ast::ItemMod) -> CompileResult<()> {
         let span = item_mod.span();
@@ -74,6 +116,8 @@ impl<'a> Indexer<'a> {
 
         let item = self.items.item();
         let source = self.source_loader.load(root, &item, span)?;
+        self.query
+            .index_mod(item.clone(), (&item_mod.visibility).into());
 
         if let Some(existing) = self.loaded.insert(item.clone(), (self.source_id, span)) {
             return Err(CompileError::new(
@@ -133,27 +177,46 @@ impl Index<ast::ItemFn> for Indexer<'_> {
 
         let is_toplevel = self.items.is_empty();
         let name = decl_fn.name.resolve(&self.storage, &*self.source)?;
+        let protocol = self.protocol(decl_fn, name.as_ref())?;
         let _guard = self.items.push_name(name.as_ref());
 
         let item = self.items.item();
 
         let guard = self.scopes.push_function(decl_fn.async_.is_some());
 
+        let mut fields = Vec::new();
+
         for (arg, _) in &decl_fn.args.items {
             match arg {
                 ast::FnArg::Self_(s) => {
                     let span = s.span();
                     self.scopes.declare("self", span)?;
+                    fields.push((String::from("self"), None));
                 }
-                ast::FnArg::Ident(ident) => {
+                ast::FnArg::Ident(ident, ty) => {
                     let span = ident.span();
                     let ident = ident.resolve(&self.storage, &*self.source)?;
                     self.scopes.declare(ident.as_ref(), span)?;
+                    let ty = ty.as_ref().map(|ty| ty.path.clone());
+                    fields.push((ident.as_ref().to_owned(), ty));
+                }
+                ast::FnArg::Ignore(..) => {
+                    fields.push((String::from("_"), None));
                 }
-                _ => (),
             }
         }
 
+        self.query.index_annotations(
+            item.clone(),
+            TypeAnnotations {
+                base: item.clone(),
+                source: self.source.clone(),
+                source_id: self.source_id,
+                fields,
+                output: decl_fn.output.as_ref().map(|output| output.path.clone()),
+            },
+        );
+
         self.index(&decl_fn.body)?;
 
         let f = guard.into_function(span)?;
@@ -174,6 +237,7 @@ impl Index<ast::ItemFn> for Indexer<'_> {
                 impl_item: impl_item.clone(),
                 instance_span: span,
                 call: fun.call,
+                protocol,
             };
 
             // NB: all instance functions must be pre-emptively built,
@@ -366,6 +430,7 @@ impl Index<ast::Pat> for Indexer<'_> {
             ast::Pat::PatString(..) => (),
             ast::Pat::PatUnit(..) => (),
             ast::Pat::PatChar(..) => (),
+            ast::Pat::PatRange(..) => (),
         }
 
         Ok(())
@@ -477,12 +542,17 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::ExprUnary(expr_unary) => {
                 self.index(expr_unary)?;
             }
+            ast::Expr::ExprRange(expr_range) => {
+                self.index(expr_range)?;
+            }
             ast::Expr::ExprIndexGet(expr_index_get) => {
                 self.index(expr_index_get)?;
             }
             ast::Expr::ExprBreak(expr_break) => {
                 self.index(expr_break)?;
             }
+            // NB: nothing to index in a continue.
+            ast::Expr::ExprContinue(..) => (),
             ast::Expr::ExprYield(expr_yield) => {
                 self.index(expr_yield)?;
             }
@@ -612,6 +682,13 @@ impl Index<ast::Item> for Indexer<'_> {
                     ));
                 }
 
+                if let Some(span) = import.visibility.option_span() {
+                    return Err(CompileError::new(
+                        span,
+                        CompileErrorKind::UnsupportedUseVisibility,
+                    ));
+                }
+
                 self.queue.push_back(Task::Import(Import {
                     item: self.items.item(),
                     ast: import.clone(),
@@ -633,6 +710,9 @@ impl Index<ast::Item> for Indexer<'_> {
                 let span = item_enum.span();
                 let enum_item = self.items.item();
 
+                self.query
+                    .index_visibility(enum_item.clone(), (&item_enum.visibility).into());
+
                 self.query.index_enum(
                     enum_item.clone(),
                     self.source.clone(),
@@ -696,6 +776,27 @@ impl Index<ast::Item> for Indexer<'_> {
 
                 let ident = item_struct.ident.resolve(&self.storage, &*self.source)?;
                 let _guard = self.items.push_name(ident.as_ref());
+                let item = self.items.item();
+
+                let mut fields = HashMap::new();
+
+                // NB: the fields of tuple structs are identified by their
+                // position.
+                for (n, field) in item_struct.body.fields().enumerate() {
+                    let name = match &item_struct.body {
+                        ast::ItemStructBody::TupleBody(..) => n.to_string(),
+                        _ => field
+                            .name
+                            .resolve(&self.storage, &*self.source)?
+                            .to_string(),
+                    };
+
+                    fields.insert(name, Visibility::from(&field.visibility));
+                }
+
+                self.query
+                    .index_visibility(item.clone(), (&item_struct.visibility).into());
+                self.query.field_visibility.insert(item, fields);
 
                 self.query.index_struct(
                     self.items.item(),
@@ -705,14 +806,12 @@ impl Index<ast::Item> for Indexer<'_> {
                 )?;
             }
             ast::Item::ItemFn(item_fn) => {
-                if let Some(first) = item_fn.attributes.first() {
-                    return Err(CompileError::internal(
-                        first,
-                        "function attributes are not supported",
-                    ));
-                }
-
                 self.index(&**item_fn)?;
+
+                let name = item_fn.name.resolve(&self.storage, &*self.source)?;
+                let item = self.items.item().extended(name.as_ref());
+                self.query
+                    .index_visibility(item, (&item_fn.visibility).into());
             }
             ast::Item::ItemImpl(item_impl) => {
                 if let Some(first) = item_impl.attributes.first() {
@@ -722,6 +821,8 @@ impl Index<ast::Item> for Indexer<'_> {
                     ));
                 }
 
+                let base = self.items.item();
+
                 let mut guards = Vec::new();
 
                 for ident in item_impl.path.into_components() {
@@ -729,10 +830,97 @@ impl Index<ast::Item> for Indexer<'_> {
                     guards.push(self.items.push_name(ident.as_ref()));
                 }
 
-                self.impl_items.push(self.items.item());
+                let impl_item = self.items.item();
+                self.impl_items.push(impl_item.clone());
 
                 for item_fn in &item_impl.functions {
                     self.index(item_fn)?;
+
+                    // NB: functions in trait implementations are as visible as
+                    // the trait.
+                    if item_impl.trait_.is_none() {
+                        let name = item_fn.name.resolve(&self.storage, &*self.source)?;
+                        let item = impl_item.extended(name.as_ref());
+                        self.query
+                            .index_visibility(item, (&item_fn.visibility).into());
+                    }
+                }
+
+                self.impl_items.pop();
+
+                if let Some((trait_path, _)) = &item_impl.trait_ {
+                    let mut functions = Vec::new();
+
+                    for item_fn in &item_impl.functions {
+                        let name = item_fn.name.resolve(&self.storage, &*self.source)?;
+                        let args = item_fn.args.items.len();
+                        functions.push((name.to_string(), args, item_fn.item_span()));
+                    }
+
+                    self.query.impl_traits.push(ImplTrait {
+                        trait_path: trait_path.clone(),
+                        base,
+                        source: self.source.clone(),
+                        impl_item,
+                        functions,
+                        source_id: self.source_id,
+                    });
+                }
+            }
+            ast::Item::ItemTrait(item_trait) => {
+                if let Some(first) = item_trait.attributes.first() {
+                    return Err(CompileError::internal(
+                        first,
+                        "trait attributes are not supported",
+                    ));
+                }
+
+                let name = item_trait.name.resolve(&self.storage, &*self.source)?;
+                let _guard = self.items.push_name(name.as_ref());
+                let trait_item = self.items.item();
+
+                self.query
+                    .index_visibility(trait_item.clone(), (&item_trait.visibility).into());
+
+                self.query.index_trait(
+                    trait_item.clone(),
+                    item_trait.clone(),
+                    self.source.clone(),
+                    self.source_id,
+                )?;
+
+                // NB: default implementations are built as instance functions
+                // of the trait, and are made available to the types
+                // implementing the trait once everything has been built.
+                self.impl_items.push(trait_item);
+
+                for trait_fn in &item_trait.functions {
+                    let item_fn = match trait_fn.default_fn() {
+                        Some(item_fn) => item_fn,
+                        None => {
+                            if let Some(first) = trait_fn.attributes.first() {
+                                return Err(CompileError::internal(
+                                    first,
+                                    "function attributes are not supported",
+                                ));
+                            }
+
+                            continue;
+                        }
+                    };
+
+                    if !item_fn.is_instance() {
+                        let name = trait_fn.name.resolve(&self.storage, &*self.source)?;
+
+                        return Err(CompileError::new(
+                            trait_fn.item_span(),
+                            CompileErrorKind::TraitDefaultFunctionWithoutSelf {
+                                name: name.to_string(),
+                            },
+                        ));
+                    }
+
+                    self.index(&item_fn)?;
                 }
 
                 self.impl_items.pop();
@@ -752,6 +940,8 @@ impl Index<ast::Item> for Indexer<'_> {
                     ast::ItemModBody::InlineBody(body) => {
                         let name = item_mod.name.resolve(&self.storage, &*self.source)?;
                         let _guard = self.items.push_name(name.as_ref());
+                        self.query
+                            .index_mod(self.items.item(), (&item_mod.visibility).into());
                         self.index(&*body.file)?;
                     }
                 }
@@ -768,6 +958,9 @@ impl Index<ast::Item> for Indexer<'_> {
                 let name = item_const.name.resolve(&self.storage, &*self.source)?;
                 let _guard = self.items.push_name(name.as_ref());
 
+                self.query
+                    .index_visibility(self.items.item(), (&item_const.visibility).into());
+
                 self.query.index_const(
                     self.items.item(),
                     self.source.clone(),
@@ -862,7 +1055,7 @@ impl Index<ast::ExprClosure> for Indexer<'_> {
                 ast::FnArg::Self_(s) => {
                     return Err(CompileError::new(s, CompileErrorKind::UnsupportedSelf));
                 }
-                ast::FnArg::Ident(ident) => {
+                ast::FnArg::Ident(ident, _) => {
                     let ident = ident.resolve(&self.storage, &*self.source)?;
                     self.scopes.declare(ident.as_ref(), span)?;
                 }
@@ -933,6 +1126,23 @@ impl Index<ast::ExprIndexGet> for Indexer<'_> {
     }
 }
 
+impl Index<ast::ExprRange> for Indexer<'_> {
+    fn index(&mut self, expr_range: &ast::ExprRange) -> CompileResult<()> {
+        let span = expr_range.span();
+        log::trace!("ExprRange => {:?}", self.source.source(span));
+
+        if let Some(from) = &expr_range.from {
+            self.index(&**from)?;
+        }
+
+        if let Some(to) = &expr_range.to {
+            self.index(&**to)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Index<ast::ExprBreak> for Indexer<'_> {
     fn index(&mut self, expr_break: &ast::ExprBreak) -> CompileResult<()> {
         let span = expr_break.span();
@@ -1032,11 +1242,28 @@ impl Index<ast::ExprCall> for Indexer<'_> {
         let span = expr_call.span();
         log::trace!("ExprCall => {:?}", self.source.source(span));
 
+        // NB: instance function calls compile their target before their
+        // arguments, and closures are named in the order they're indexed.
+        let target_first = matches!(
+            &*expr_call.expr,
+            ast::Expr::ExprFieldAccess(ast::ExprFieldAccess {
+                expr_field: ast::ExprField::Ident(..),
+                ..
+            })
+        );
+
+        if target_first {
+            self.index(&*expr_call.expr)?;
+        }
+
         for (expr, _) in expr_call.args.items.iter() {
             self.index(expr)?;
         }
 
-        self.index(&*expr_call.expr)?;
+        if !target_first {
+            self.index(&*expr_call.expr)?;
+        }
+
         Ok(())
     }
 }
@@ -1087,7 +1314,7 @@ impl Index<ast::LitTemplate> for Indexer<'_> {
 
         for c in &template.components {
             match c {
-                ast::TemplateComponent::Expr(expr) => {
+                ast::TemplateComponent::Expr(expr) | ast::TemplateComponent::Format(expr, _) => {
                     self.index(&**expr)?;
                 }
                 ast::TemplateComponent::String(..) => (),
@@ -8,6 +8,8 @@ use runestick::Span;
 pub struct Lexer<'a> {
     cursor: usize,
     source: &'a str,
+    /// Spans of the comments encountered, if they are being retained.
+    comments: Option<Vec<Span>>,
 }
 
 impl<'a> Lexer<'a> {
@@ -37,7 +39,7 @@ impl<'a> Lexer<'a> {
     /// };
     /// ```
     pub fn new(source: &'a str) -> Self {
-        Self { cursor: 0, source }
+        Self::new_with_start(source, 0)
     }
 
     /// Construct a new lexer with the given start.
@@ -45,6 +47,40 @@ impl<'a> Lexer<'a> {
         Self {
             cursor: start,
             source,
+            comments: None,
+        }
+    }
+
+    /// Retain the spans of comments that are skipped over, so that they can be
+    /// accessed through [take_comments][Self::take_comments].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rune::Lexer;
+    /// use runestick::Span;
+    ///
+    /// let mut lexer = Lexer::new("a // first\n// second\nb").retain_comments();
+    /// while lexer.next().unwrap().is_some() {}
+    ///
+    /// assert_eq! {
+    ///     lexer.take_comments(),
+    ///     vec![Span::new(2, 10), Span::new(11, 20)],
+    /// };
+    /// ```
+    pub fn retain_comments(mut self) -> Self {
+        self.comments = Some(Vec::new());
+        self
+    }
+
+    /// Take the spans of the comments retained so far.
+    ///
+    /// This is always empty unless the lexer was configured with
+    /// [retain_comments][Self::retain_comments].
+    pub fn take_comments(&mut self) -> Vec<Span> {
+        match &mut self.comments {
+            Some(comments) => std::mem::take(comments),
+            None => Vec::new(),
         }
     }
 
@@ -134,12 +170,14 @@ impl<'a> Lexer<'a> {
             match c {
                 c if char::is_alphanumeric(c) => (),
                 '.' if !is_fractional => {
-                    is_fractional = true;
-
-                    // char immediately following a dot should be numerical.
+                    // char immediately following a dot should be numerical,
+                    // otherwise the dot belongs to something else like a range
+                    // `1..2` or a field access `1.foo`.
                     if !it.next().map(|(_, c)| c.is_numeric()).unwrap_or_default() {
                         break self.cursor + n;
                     }
+
+                    is_fractional = true;
                 }
                 _ => break self.cursor + n,
             }
@@ -467,14 +505,15 @@ impl<'a> Lexer<'a> {
         }))
     }
 
-    /// Consume the entire line.
-    fn consume_line<I>(&mut self, it: &mut I)
+    /// Consume the entire line, returning the end of its content.
+    fn consume_line<I>(&mut self, it: &mut I) -> usize
     where
         I: Clone + Iterator<Item = (usize, char)>,
     {
         loop {
             match it.next() {
-                Some((_, '\n')) | None => break,
+                Some((n, '\n')) => break self.cursor + n,
+                None => break self.source.len(),
                 _ => (),
             }
         }
@@ -530,7 +569,13 @@ impl<'a> Lexer<'a> {
                             break ast::Kind::PipeEq;
                         }
                         ('/', '/') => {
-                            self.consume_line(&mut it);
+                            let end = self.consume_line(&mut it);
+
+                            if let Some(comments) = &mut self.comments {
+                                let end = self.source[start..end].trim_end().len() + start;
+                                comments.push(Span::new(start, end));
+                            }
+
                             continue 'outer;
                         }
                         (':', ':') => {
@@ -564,7 +609,7 @@ impl<'a> Lexer<'a> {
                         ('<', '<') => {
                             it.next();
 
-                            break if matches!(it.next(), Some((_, '='))) {
+                            break if matches!(it.clone().next(), Some((_, '='))) {
                                 it.next();
                                 ast::Kind::LtLtEq
                             } else {
@@ -574,7 +619,7 @@ impl<'a> Lexer<'a> {
                         ('>', '>') => {
                             it.next();
 
-                            break if matches!(it.next(), Some((_, '='))) {
+                            break if matches!(it.clone().next(), Some((_, '='))) {
                                 it.next();
                                 ast::Kind::GtGtEq
                             } else {
@@ -583,7 +628,13 @@ impl<'a> Lexer<'a> {
                         }
                         ('.', '.') => {
                             it.next();
-                            break ast::Kind::DotDot;
+
+                            break if matches!(it.clone().next(), Some((_, '='))) {
+                                it.next();
+                                ast::Kind::DotDotEq
+                            } else {
+                                ast::Kind::DotDot
+                            };
                         }
                         ('=', '>') => {
                             it.next();
@@ -773,6 +824,95 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_shift_operators() {
+        test_lexer! {
+            "<< <<= >>=>>",
+            ast::Token {
+                span: Span::new(0, 2),
+                kind: ast::Kind::LtLt,
+            },
+            ast::Token {
+                span: Span::new(3, 6),
+                kind: ast::Kind::LtLtEq,
+            },
+            ast::Token {
+                span: Span::new(7, 10),
+                kind: ast::Kind::GtGtEq,
+            },
+            ast::Token {
+                span: Span::new(10, 12),
+                kind: ast::Kind::GtGt,
+            }
+        };
+
+        test_lexer! {
+            ">>= <<==",
+            ast::Token {
+                span: Span::new(0, 3),
+                kind: ast::Kind::GtGtEq,
+            },
+            ast::Token {
+                span: Span::new(4, 7),
+                kind: ast::Kind::LtLtEq,
+            },
+            ast::Token {
+                span: Span::new(7, 8),
+                kind: ast::Kind::Eq,
+            }
+        };
+    }
+
+    #[test]
+    fn test_ranges() {
+        let number = ast::Kind::LitNumber(ast::NumberSource::Text(ast::NumberSourceText {
+            is_fractional: false,
+            is_negative: false,
+            base: ast::NumberBase::Decimal,
+        }));
+
+        test_lexer! {
+            "1..2 ..=3",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: number,
+            },
+            ast::Token {
+                span: Span::new(1, 3),
+                kind: ast::Kind::DotDot,
+            },
+            ast::Token {
+                span: Span::new(3, 4),
+                kind: number,
+            },
+            ast::Token {
+                span: Span::new(5, 8),
+                kind: ast::Kind::DotDotEq,
+            },
+            ast::Token {
+                span: Span::new(8, 9),
+                kind: number,
+            }
+        };
+    }
+
+    #[test]
+    fn test_comments() {
+        let mut lexer = Lexer::new("a // one \n  // two\nb //").retain_comments();
+
+        assert_eq!(lexer.next().unwrap().map(|t| t.span), Some(Span::new(0, 1)));
+        assert_eq!(
+            lexer.next().unwrap().map(|t| t.span),
+            Some(Span::new(19, 20))
+        );
+        assert_eq!(lexer.next().unwrap(), None);
+
+        assert_eq! {
+            lexer.take_comments(),
+            vec![Span::new(2, 8), Span::new(12, 18), Span::new(21, 23)],
+        };
+    }
+
     #[test]
     fn test_idents() {
         test_lexer! {
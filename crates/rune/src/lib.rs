@@ -173,6 +173,7 @@
 mod util_macros;
 mod assembly;
 pub mod ast;
+mod bytecode_cache;
 mod compile;
 mod compile_error;
 mod compile_visitor;
@@ -182,6 +183,7 @@ mod const_compiler;
 pub mod diagnostics;
 mod errors;
 mod eval;
+mod fmt;
 mod index;
 mod index_scopes;
 mod items;
@@ -191,6 +193,7 @@ mod load_error;
 mod loops;
 mod macro_context;
 mod macros;
+pub mod modules;
 mod options;
 mod parse_error;
 mod parser;
@@ -202,6 +205,7 @@ mod sources;
 mod storage;
 mod token_stream;
 mod traits;
+mod type_check;
 mod unit_builder;
 mod warning;
 mod worker;
@@ -224,6 +228,7 @@ pub use crate::assembly::Assembly;
 pub use crate::compile_error::{CompileError, CompileErrorKind, CompileResult};
 pub use crate::compile_visitor::{CompileVisitor, NoopCompileVisitor};
 pub use crate::errors::Errors;
+pub use crate::fmt::format;
 pub use crate::lexer::Lexer;
 pub use crate::load::{load_sources, load_sources_with_visitor, LoadSourcesError};
 pub use crate::load_error::{LoadError, LoadErrorKind};
@@ -253,8 +258,8 @@ pub use diagnostics::{termcolor, DiagnosticsError, EmitDiagnostics};
 ///
 /// See [load_sources](crate::load_sources) for how to use.
 pub fn default_context() -> Result<runestick::Context, runestick::ContextError> {
-    #[allow(unused_mut)]
     let mut context = runestick::Context::with_default_modules()?;
+    context.install(&crate::modules::core::module()?)?;
 
     #[cfg(feature = "modules")]
     {
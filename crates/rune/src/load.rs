@@ -1,5 +1,5 @@
 use crate::unit_builder::UnitBuilder;
-use crate::{compiler, CompileVisitor};
+use crate::{bytecode_cache, compiler, CompileVisitor};
 use crate::{
     Errors, FileSourceLoader, LoadError, NoopCompileVisitor, Options, SourceLoader, Sources,
     Warnings,
@@ -20,6 +20,11 @@ pub struct LoadSourcesError;
 ///
 /// Uses the [Source::name] when generating diagnostics to reference the file.
 ///
+/// If the `bytecode` option is enabled and the first source
+/// was loaded from a path, the compiled unit is cached on disk next to it and
+/// reused as long as it's up to date. No warnings are reported when a cached
+/// unit is used.
+///
 /// # Examples
 ///
 /// Note: these must be built with the `diagnostics` feature enabled to give
@@ -74,10 +79,26 @@ pub fn load_sources(
     errors: &mut Errors,
     warnings: &mut Warnings,
 ) -> Result<Unit, LoadSourcesError> {
+    let cache_path = if options.bytecode {
+        bytecode_cache::cache_path(sources)
+    } else {
+        None
+    };
+
+    if let Some(cache_path) = &cache_path {
+        match bytecode_cache::load(cache_path, context, options, sources) {
+            Ok(Some(unit)) => return Ok(unit),
+            Ok(None) => (),
+            Err(error) => {
+                log::warn!("failed to load cache: {}: {}", cache_path.display(), error);
+            }
+        }
+    }
+
     let mut visitor = NoopCompileVisitor::new();
     let mut source_loader = FileSourceLoader::new();
 
-    load_sources_with_visitor(
+    let unit = load_sources_with_visitor(
         context,
         options,
         sources,
@@ -85,7 +106,15 @@ pub fn load_sources(
         warnings,
         &mut visitor,
         &mut source_loader,
-    )
+    )?;
+
+    if let Some(cache_path) = &cache_path {
+        if let Err(error) = bytecode_cache::store(cache_path, context, options, sources, &unit) {
+            log::warn!("failed to store cache: {}: {}", cache_path.display(), error);
+        }
+    }
+
+    Ok(unit)
 }
 
 /// Load the specified sources with a visitor.
@@ -23,6 +23,10 @@ pub(crate) struct Loop {
     pub(crate) label: Option<ast::Label>,
     /// The end label of the loop.
     pub(crate) break_label: Label,
+    /// The label to jump to when continuing the loop.
+    pub(crate) continue_label: Label,
+    /// The number of variables observed at the continue label.
+    pub(crate) continue_var_count: usize,
     /// The number of variables observed at the start of the loop.
     pub(crate) total_var_count: usize,
     /// If the loop needs a value.
@@ -57,7 +61,8 @@ impl Loops {
         }
     }
 
-    /// Find the loop with the matching label.
+    /// Find the loop with the matching label, and collect the locals that
+    /// need to be dropped by the loops nested inside of it.
     pub(crate) fn walk_until_label(
         &self,
         storage: &Storage,
@@ -71,20 +76,15 @@ impl Loops {
         let mut to_drop = Vec::new();
 
         for l in self.loops.borrow().iter().rev() {
-            to_drop.extend(l.drop);
+            if let Some(label) = l.label {
+                let label = label.resolve(storage, source)?;
 
-            let label = match l.label {
-                Some(label) => label,
-                None => {
-                    continue;
+                if expected == label {
+                    return Ok((*l, to_drop));
                 }
-            };
-
-            let label = label.resolve(storage, source)?;
-
-            if expected == label {
-                return Ok((*l, to_drop));
             }
+
+            to_drop.extend(l.drop);
         }
 
         Err(CompileError::new(span, CompileErrorKind::MissingLabel))
@@ -3,7 +3,7 @@
 use crate::CompileResult;
 use crate::{
     ast, CompileError, CompileErrorKind, MacroContext, Options, Parse, ParseError, Parser,
-    Spanned as _, Storage, TokenStream, UnitBuilder,
+    Resolve as _, Spanned as _, Storage, TokenStream, UnitBuilder,
 };
 use runestick::{Context, Hash, Item, Source, Span};
 use std::cell::RefCell;
@@ -41,9 +41,20 @@ impl MacroCompiler<'_> {
             &self.storage,
             &*self.source,
         )?;
-        let hash = Hash::type_hash(&item);
+        let mut handler = self.context.lookup_macro(Hash::type_hash(&item));
 
-        let handler = match self.context.lookup_macro(hash) {
+        // NB: macros in `std`, like `format!`, can be used by name anywhere
+        // unless something else by that name is in scope.
+        if handler.is_none() {
+            if let Some(ident) = expr_call_macro.path.try_as_ident() {
+                let ident = ident.resolve(&self.storage, &*self.source)?;
+                handler = self
+                    .context
+                    .lookup_macro(Hash::type_hash(["std", ident.as_ref()]));
+            }
+        }
+
+        let handler = match handler {
             Some(handler) => handler,
             None => {
                 return Err(CompileError::new(
@@ -0,0 +1,240 @@
+//! Macros for the core `std` module, which are implemented on top of the
+//! compiler.
+
+use crate::ast;
+use crate::{quote, MacroContext, Parser, Resolve as _, TokenStream};
+use runestick::{ContextError, Module};
+
+/// Construct the macros of the `std` module.
+///
+/// This provides `format!`, `print!` and `println!`, which are used like their
+/// Rust counterparts. Like other macros they have to be enabled with the
+/// `macros` compiler option.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std"]);
+    module.macro_(&["format"], format_macro)?;
+    module.macro_(&["print"], print_macro)?;
+    module.macro_(&["println"], println_macro)?;
+    Ok(module)
+}
+
+/// Implementation of the `format!` macro.
+fn format_macro(ctx: &mut MacroContext, stream: &TokenStream) -> runestick::Result<TokenStream> {
+    FormatArgs::parse(ctx, stream)?.expand(ctx)
+}
+
+/// Implementation of the `print!` macro.
+fn print_macro(ctx: &mut MacroContext, stream: &TokenStream) -> runestick::Result<TokenStream> {
+    let expanded = FormatArgs::parse(ctx, stream)?.expand(ctx)?;
+    Ok(quote!(ctx => std::print(#expanded)))
+}
+
+/// Implementation of the `println!` macro.
+fn println_macro(ctx: &mut MacroContext, stream: &TokenStream) -> runestick::Result<TokenStream> {
+    let expanded = FormatArgs::parse(ctx, stream)?.expand(ctx)?;
+    Ok(quote!(ctx => std::println(#expanded)))
+}
+
+/// The arguments to a formatting macro, like `"{} {name:>4}", a, name = b`.
+struct FormatArgs {
+    /// The resolved format string.
+    format: String,
+    /// Positional arguments.
+    positional: Vec<ast::Expr>,
+    /// Named arguments.
+    named: Vec<(String, ast::Expr)>,
+}
+
+impl FormatArgs {
+    fn parse(ctx: &MacroContext, stream: &TokenStream) -> runestick::Result<Self> {
+        let mut parser = Parser::from_token_stream(stream);
+
+        let format = parser
+            .parse::<ast::LitStr>()?
+            .macro_resolve(ctx)?
+            .into_owned();
+
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+
+        while parser.parse::<Option<ast::Comma>>()?.is_some() {
+            if parser.is_eof()? {
+                break;
+            }
+
+            if parser.peek::<ast::Ident>()? && parser.peek2::<ast::Eq>()? {
+                let name = parser
+                    .parse::<ast::Ident>()?
+                    .macro_resolve(ctx)?
+                    .into_owned();
+                parser.parse::<ast::Eq>()?;
+                named.push((name, parser.parse::<ast::Expr>()?));
+                continue;
+            }
+
+            if !named.is_empty() {
+                return Err(runestick::Error::msg(
+                    "positional arguments must come before named arguments",
+                ));
+            }
+
+            positional.push(parser.parse::<ast::Expr>()?);
+        }
+
+        parser.parse_eof()?;
+
+        Ok(Self {
+            format,
+            positional,
+            named,
+        })
+    }
+
+    /// Expand the arguments into a template string.
+    ///
+    /// Arguments are bound to variables in a block before the template, so
+    /// that each argument is evaluated exactly once and in order. Names in the
+    /// format string which aren't named arguments refer to variables in
+    /// scope, like `{value}` does in a template string.
+    fn expand(self, ctx: &mut MacroContext) -> runestick::Result<TokenStream> {
+        let mut template = String::new();
+        let mut has_expansions = false;
+        let mut next = 0;
+        let mut used_positional = vec![false; self.positional.len()];
+        let mut used_named = vec![false; self.named.len()];
+
+        let mut it = self.format.chars().peekable();
+
+        while let Some(c) = it.next() {
+            match c {
+                '{' if it.peek() == Some(&'{') => {
+                    it.next();
+                    template.push_str("\\{");
+                }
+                '}' if it.peek() == Some(&'}') => {
+                    it.next();
+                    template.push_str("\\}");
+                }
+                '}' => {
+                    return Err(runestick::Error::msg(
+                        "unmatched `}` in format string, closing braces are escaped with `}}`",
+                    ));
+                }
+                '{' => {
+                    let mut argument = String::new();
+
+                    loop {
+                        match it.next() {
+                            Some('}') => break,
+                            Some(c) => argument.push(c),
+                            None => {
+                                return Err(runestick::Error::msg(
+                                    "unterminated `{` in format string, opening braces are escaped with `{{`",
+                                ));
+                            }
+                        }
+                    }
+
+                    let (name, spec) = match argument.find(':') {
+                        Some(n) => (&argument[..n], Some(&argument[n + 1..])),
+                        None => (argument.as_str(), None),
+                    };
+
+                    let variable = if name.is_empty() {
+                        let index = next;
+                        next += 1;
+                        positional(&mut used_positional, index)?
+                    } else if let Ok(index) = name.parse::<usize>() {
+                        positional(&mut used_positional, index)?
+                    } else if let Some(index) = self.named.iter().position(|(n, _)| n == name) {
+                        used_named[index] = true;
+                        format!("format_arg_{}", name)
+                    } else if is_ident(name) {
+                        name.to_owned()
+                    } else {
+                        return Err(runestick::Error::msg(format!(
+                            "invalid argument `{}` in format string",
+                            name
+                        )));
+                    };
+
+                    template.push('{');
+                    template.push_str(&variable);
+
+                    if let Some(spec) = spec {
+                        template.push(':');
+                        template.push_str(spec);
+                    }
+
+                    template.push('}');
+                    has_expansions = true;
+                }
+                '\\' => template.push_str("\\\\"),
+                c => template.push(c),
+            }
+        }
+
+        if used_positional.iter().any(|used| !used) {
+            return Err(runestick::Error::msg(
+                "positional argument never used in format string",
+            ));
+        }
+
+        if let Some(index) = used_named.iter().position(|used| !used) {
+            return Err(runestick::Error::msg(format!(
+                "named argument `{}` never used in format string",
+                self.named[index].0
+            )));
+        }
+
+        if !has_expansions {
+            // NB: the format string can only contain escaped braces here.
+            let format = self.format.replace("{{", "{").replace("}}", "}");
+            let lit = ctx.lit(format.as_str());
+            return Ok(quote!(ctx => #lit));
+        }
+
+        let mut bindings = Vec::new();
+
+        for (index, expr) in self.positional.into_iter().enumerate() {
+            let ident = ctx.ident(&format!("format_arg_{}", index));
+            bindings.push(quote!(ctx => let #ident = #expr;));
+        }
+
+        for (name, expr) in self.named {
+            let ident = ctx.ident(&format!("format_arg_{}", name));
+            bindings.push(quote!(ctx => let #ident = #expr;));
+        }
+
+        let template = ctx.template_string(&template);
+        Ok(quote!(ctx => { #(bindings)* #template }))
+    }
+}
+
+/// Mark the positional argument at the given index as used, and get the name
+/// of the variable it's bound to.
+fn positional(used: &mut [bool], index: usize) -> runestick::Result<String> {
+    match used.get_mut(index) {
+        Some(used) => {
+            *used = true;
+            Ok(format!("format_arg_{}", index))
+        }
+        None => Err(runestick::Error::msg(format!(
+            "invalid reference to positional argument {} in format string, there are {} arguments",
+            index,
+            used.len()
+        ))),
+    }
+}
+
+/// Test if the given string is an identifier, which refers to a variable.
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => (),
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_alphanumeric() || c == '_')
+}
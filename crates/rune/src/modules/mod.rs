@@ -0,0 +1,3 @@
+//! Native modules which are implemented on top of the compiler, like macros.
+
+pub mod core;
@@ -11,7 +11,7 @@ pub enum ConfigurationError {
 }
 
 /// Compiler options.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Options {
     /// Perform link-time checks.
     pub(crate) link_checks: bool,
@@ -23,6 +23,8 @@ pub struct Options {
     pub(crate) macros: bool,
     /// Support (experimental) bytecode caching.
     pub bytecode: bool,
+    /// Check type annotations before compiling.
+    pub(crate) type_check: bool,
 }
 
 impl Options {
@@ -51,6 +53,9 @@ impl Options {
             Some("bytecode") => {
                 self.bytecode = it.next() != Some("false");
             }
+            Some("type-check") => {
+                self.type_check = it.next() != Some("false");
+            }
             _ => {
                 return Err(ConfigurationError::UnsupportedOptimizationOption {
                     option: option.to_owned(),
@@ -79,10 +84,21 @@ impl Options {
     }
 
     /// Set if bytecode caching is enabled or not. Defaults to `false`.
+    ///
+    /// When enabled, [load_sources][crate::load_sources] stores compiled units
+    /// next to the first source with the `rnc` extension, and reuses them as
+    /// long as the sources, options, context and compiler version are
+    /// unchanged.
     pub fn bytecode(&mut self, enabled: bool) {
         self.bytecode = enabled;
     }
 
+    /// Set if type annotations should be checked before compiling. Defaults to
+    /// `false`.
+    pub fn type_check(&mut self, enabled: bool) {
+        self.type_check = enabled;
+    }
+
     /// Memoize the instance function in a loop. Defaults to `false`.
     pub fn memoize_instance_fn(&mut self, enabled: bool) {
         self.memoize_instance_fn = enabled;
@@ -97,6 +113,7 @@ impl Default for Options {
             debug_info: true,
             macros: false,
             bytecode: false,
+            type_check: false,
         }
     }
 }
@@ -121,6 +121,12 @@ pub enum ParseErrorKind {
         /// The kind of the actual token we saw.
         actual: ast::Kind,
     },
+    /// Expected the limits of a range, like `..` or `..=`.
+    #[error("expected range limits `..` or `..=` but got `{actual}`")]
+    ExpectedRangeLimits {
+        /// The kind of the actual token we saw.
+        actual: ast::Kind,
+    },
     /// When we expect to see a loop (typically after a label).
     #[error("expected loop but got `{actual}")]
     ExpectedLoop {
@@ -235,6 +241,13 @@ pub enum ParseErrorKind {
     /// When we encounter an unescaped closing brace `}`.
     #[error("closing braces must be escaped inside of templates with `\\}}`")]
     UnexpectedCloseBrace,
+    /// When we encounter a bad format specification in a template, like
+    /// `{value:y}`.
+    #[error("bad format specification: {error}")]
+    BadFormatSpec {
+        /// The source error.
+        error: runestick::FormatSpecError,
+    },
     /// When we encounter an expression that cannot be used in a chained manner.
     #[error("unsupported field access")]
     UnsupportedFieldAccess,
@@ -267,6 +280,9 @@ pub enum ParseErrorKind {
     /// Encountered a position with attributes for which it is not supported.
     #[error("attributes not supported in this position")]
     AttributesNotSupported,
+    /// Encountered a position with a visibility for which it is not supported.
+    #[error("visibility not supported in this position")]
+    VisibilityNotSupported,
     /// Encountered when we expect inner attributes.
     #[error("expected inner attribute")]
     ExpectedInnerAttribute,
@@ -9,8 +9,8 @@ use crate::{
     Storage, UnitBuilder,
 };
 use runestick::{
-    Call, CompileMeta, CompileMetaCapture, CompileMetaKind, CompileMetaStruct, CompileMetaTuple,
-    CompileSource, Hash, Item, Source, SourceId, Span, Type,
+    Call, CompileMeta, CompileMetaCapture, CompileMetaKind, CompileMetaStruct, CompileMetaTraitFn,
+    CompileMetaTuple, CompileSource, Hash, Item, Protocol, Source, SourceId, Span, Type,
 };
 use std::cell::RefCell;
 use std::collections::VecDeque;
@@ -25,6 +25,7 @@ pub(crate) enum Indexed {
     Closure(Closure),
     AsyncBlock(AsyncBlock),
     Const(Const),
+    Trait(Trait),
 }
 
 pub struct Struct {
@@ -53,6 +54,18 @@ impl Variant {
     }
 }
 
+pub struct Trait {
+    /// The ast of the trait.
+    ast: ast::ItemTrait,
+}
+
+impl Trait {
+    /// Construct a new trait entry.
+    pub fn new(ast: ast::ItemTrait) -> Self {
+        Self { ast }
+    }
+}
+
 pub(crate) struct Function {
     /// Ast for declaration.
     pub(crate) ast: ast::ItemFn,
@@ -67,6 +80,9 @@ pub(crate) struct InstanceFunction {
     /// The span of the instance function.
     pub(crate) instance_span: Span,
     pub(crate) call: Call,
+    /// The protocol implemented by the instance function, if it opted in to
+    /// one with the `#[protocol]` attribute.
+    pub(crate) protocol: Option<Protocol>,
 }
 
 pub(crate) struct Closure {
@@ -92,6 +108,65 @@ pub(crate) struct Const {
     pub(crate) expr: ast::Expr,
 }
 
+/// An implementation of a trait for a type, which is checked and completed
+/// with the default functions of the trait once everything has been built.
+pub(crate) struct ImplTrait {
+    /// The path to the trait being implemented.
+    pub(crate) trait_path: ast::Path,
+    /// The item that the path to the trait is resolved relative to.
+    pub(crate) base: Item,
+    /// The source the implementation is declared in.
+    pub(crate) source: Arc<Source>,
+    /// The item of the type the trait is implemented for.
+    pub(crate) impl_item: Item,
+    /// The name, number of arguments and span of the functions in the
+    /// implementation.
+    pub(crate) functions: Vec<(String, usize, Span)>,
+    /// The source id of the implementation.
+    pub(crate) source_id: SourceId,
+}
+
+/// The type annotations of a function or a struct, which are used by the
+/// optional type checking pass.
+pub(crate) struct TypeAnnotations {
+    /// The item that paths in the annotations are resolved relative to.
+    pub(crate) base: Item,
+    /// The source the annotations are declared in.
+    pub(crate) source: Arc<Source>,
+    /// The source id the annotations are declared in.
+    pub(crate) source_id: SourceId,
+    /// The arguments of a function or the fields of a struct, in declaration
+    /// order, together with their annotated type if any.
+    pub(crate) fields: Vec<(String, Option<ast::Path>)>,
+    /// The annotated return type of a function.
+    pub(crate) output: Option<ast::Path>,
+}
+
+/// The visibility of an indexed item or struct field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Visibility {
+    /// Only visible inside of the module the item is declared in, and its
+    /// children.
+    Inherited,
+    /// Visible everywhere.
+    Public,
+    /// Visible everywhere in the unit being compiled.
+    Crate,
+    /// Visible inside of the parent of the module the item is declared in.
+    Super,
+}
+
+impl From<&ast::Visibility> for Visibility {
+    fn from(visibility: &ast::Visibility) -> Self {
+        match visibility {
+            ast::Visibility::Inherited => Self::Inherited,
+            ast::Visibility::Public(..) => Self::Public,
+            ast::Visibility::Crate(..) => Self::Crate,
+            ast::Visibility::Super(..) => Self::Super,
+        }
+    }
+}
+
 /// An entry in the build queue.
 pub(crate) enum Build {
     Function(Function),
@@ -133,6 +208,16 @@ pub(crate) struct Query {
     pub(crate) consts: Rc<RefCell<Consts>>,
     pub(crate) queue: VecDeque<BuildEntry>,
     pub(crate) indexed: HashMap<Item, IndexedEntry>,
+    /// Trait implementations to complete once all functions have been built.
+    pub(crate) impl_traits: Vec<ImplTrait>,
+    /// Type annotations of functions and structs.
+    pub(crate) annotations: HashMap<Item, TypeAnnotations>,
+    /// Modules which have been indexed.
+    pub(crate) modules: HashSet<Item>,
+    /// The visibility of indexed items and modules.
+    pub(crate) visibility: HashMap<Item, Visibility>,
+    /// The visibility of the fields of indexed structs.
+    pub(crate) field_visibility: HashMap<Item, HashMap<String, Visibility>>,
 }
 
 impl Query {
@@ -148,6 +233,11 @@ impl Query {
             consts,
             queue: VecDeque::new(),
             indexed: HashMap::new(),
+            impl_traits: Vec::new(),
+            annotations: HashMap::new(),
+            modules: HashSet::new(),
+            visibility: HashMap::new(),
+            field_visibility: HashMap::new(),
         }
     }
 
@@ -209,6 +299,31 @@ impl Query {
         log::trace!("new struct: {}", item);
         let span = ast.span();
 
+        let fields = match &ast.body {
+            ast::ItemStructBody::EmptyBody(..) => &[][..],
+            ast::ItemStructBody::TupleBody(body, ..) => &body.fields[..],
+            ast::ItemStructBody::StructBody(body) => &body.fields[..],
+        };
+
+        let mut annotations = Vec::new();
+
+        for field in fields {
+            let name = field.name.resolve(&self.storage, &*source)?;
+            let ty = field.ty.as_ref().map(|ty| ty.path.clone());
+            annotations.push((name.as_ref().to_owned(), ty));
+        }
+
+        self.index_annotations(
+            item.clone(),
+            TypeAnnotations {
+                base: item.clone(),
+                source: source.clone(),
+                source_id,
+                fields: annotations,
+                output: None,
+            },
+        );
+
         self.index(
             item,
             IndexedEntry {
@@ -222,6 +337,36 @@ impl Query {
         Ok(())
     }
 
+    /// Add the type annotations of a function or a struct.
+    pub fn index_annotations(&mut self, item: Item, annotations: TypeAnnotations) {
+        log::trace!("new annotations: {}", item);
+        self.annotations.insert(item, annotations);
+    }
+
+    /// Add a new trait item that can be queried.
+    pub fn index_trait(
+        &mut self,
+        item: Item,
+        ast: ast::ItemTrait,
+        source: Arc<Source>,
+        source_id: usize,
+    ) -> Result<(), CompileError> {
+        log::trace!("new trait: {}", item);
+        let span = ast.span();
+
+        self.index(
+            item,
+            IndexedEntry {
+                span,
+                source,
+                source_id,
+                indexed: Indexed::Trait(Trait::new(ast)),
+            },
+        )?;
+
+        Ok(())
+    }
+
     /// Add a new variant item that can be queried.
     pub fn index_variant(
         &mut self,
@@ -307,6 +452,104 @@ impl Query {
         Ok(())
     }
 
+    /// Index a module with the given visibility.
+    pub fn index_mod(&mut self, item: Item, visibility: Visibility) {
+        log::trace!("new module: {}", item);
+        self.modules.insert(item.clone());
+        self.visibility.insert(item, visibility);
+    }
+
+    /// Index the visibility of an item.
+    pub fn index_visibility(&mut self, item: Item, visibility: Visibility) {
+        self.visibility.insert(item, visibility);
+    }
+
+    /// Check that the given item, and every item it's nested in, is visible
+    /// from the item `from`.
+    ///
+    /// Items which haven't been indexed, like the ones provided by the
+    /// context, are always visible.
+    pub(crate) fn check_visibility(
+        &self,
+        from: &Item,
+        item: &Item,
+        span: Span,
+    ) -> Result<(), CompileError> {
+        let mut current = Item::new();
+
+        for c in item {
+            current.push(c);
+
+            if let Some(visibility) = self.visibility.get(&current) {
+                if !self.is_visible(from, &current, *visibility) {
+                    return Err(CompileError::new(
+                        span,
+                        CompileErrorKind::NotVisible {
+                            item: current,
+                            from: from.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the given field of a struct is visible from the item
+    /// `from`.
+    pub(crate) fn check_field_visibility(
+        &self,
+        from: &Item,
+        item: &Item,
+        field: &str,
+        span: Span,
+    ) -> Result<(), CompileError> {
+        let visibility = match self.field_visibility.get(item).and_then(|f| f.get(field)) {
+            Some(visibility) => *visibility,
+            None => return Ok(()),
+        };
+
+        if !self.is_visible(from, item, visibility) {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::NotVisibleField {
+                    item: item.clone(),
+                    field: field.to_owned(),
+                    from: from.clone(),
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Test if something declared next to `item` with the given visibility is
+    /// visible from the item `from`.
+    fn is_visible(&self, from: &Item, item: &Item, visibility: Visibility) -> bool {
+        match visibility {
+            Visibility::Public | Visibility::Crate => true,
+            Visibility::Inherited => from.starts_with(&self.module_of(item)),
+            Visibility::Super => {
+                let module = self.module_of(item);
+                from.starts_with(&self.module_of(&module))
+            }
+        }
+    }
+
+    /// Get the module that the given item is declared in.
+    fn module_of(&self, item: &Item) -> Item {
+        let mut module = item.clone();
+
+        while module.pop().is_some() {
+            if self.modules.contains(&module) {
+                return module;
+            }
+        }
+
+        Item::new()
+    }
+
     /// Index the given element.
     pub fn index(&mut self, item: Item, entry: IndexedEntry) -> Result<(), CompileError> {
         log::trace!("indexed: {}", item);
@@ -405,6 +648,7 @@ impl Query {
                 self.variant_into_item_decl(item, variant.ast, Some(variant.enum_item), &*source)?
             }
             Indexed::Struct(st) => self.struct_into_item_decl(item, st.ast.body, None, &*source)?,
+            Indexed::Trait(t) => self.trait_into_item_decl(item, t.ast, &*source)?,
             Indexed::Function(f) => {
                 self.queue.push_back(BuildEntry {
                     item: item.clone(),
@@ -493,6 +737,167 @@ impl Query {
         Ok(meta)
     }
 
+    /// Look up the meta of the trait at the given item, relative to the
+    /// module `base` and the modules it's nested in.
+    fn lookup_trait_meta(
+        &mut self,
+        base: &Item,
+        item: &Item,
+    ) -> CompileResult<Option<CompileMeta>> {
+        let mut base = base.clone();
+
+        loop {
+            if let Some(meta) = self.query_meta(&base.join(item))? {
+                return Ok(Some(meta));
+            }
+
+            if base.pop().is_none() {
+                break;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Complete an implementation of a trait, checking that it implements
+    /// all functions required by the trait and providing the default
+    /// implementations of the functions which it doesn't implement.
+    pub(crate) fn build_impl_trait(&mut self, impl_trait: ImplTrait) -> CompileResult<()> {
+        let ImplTrait {
+            trait_path,
+            base,
+            source,
+            impl_item,
+            functions,
+            ..
+        } = impl_trait;
+
+        let trait_span = trait_path.span();
+
+        let trait_item =
+            self.unit
+                .borrow()
+                .convert_path(&base, &trait_path, &self.storage, &source)?;
+
+        let meta = match self.lookup_trait_meta(&base, &trait_item)? {
+            Some(meta) => meta,
+            None => {
+                return Err(CompileError::new(
+                    trait_span,
+                    CompileErrorKind::MissingTrait { item: trait_item },
+                ));
+            }
+        };
+
+        let trait_functions = match &meta.kind {
+            CompileMetaKind::Trait { functions, .. } => functions.clone(),
+            _ => {
+                return Err(CompileError::new(
+                    trait_span,
+                    CompileErrorKind::ExpectedTrait { meta: meta.clone() },
+                ));
+            }
+        };
+
+        let trait_item = meta.item().clone();
+
+        let type_of = match self.query_meta(&impl_item)? {
+            Some(meta) => match meta.type_of() {
+                Some(type_of) => type_of,
+                None => {
+                    return Err(CompileError::new(
+                        trait_span,
+                        CompileErrorKind::UnsupportedInstanceFunction { meta },
+                    ));
+                }
+            },
+            None => {
+                return Err(CompileError::new(
+                    trait_span,
+                    CompileErrorKind::MissingType { item: impl_item },
+                ));
+            }
+        };
+
+        for (name, args, span) in &functions {
+            let trait_fn = match trait_functions.iter().find(|f| f.name == *name) {
+                Some(trait_fn) => trait_fn,
+                None => {
+                    return Err(CompileError::new(
+                        span,
+                        CompileErrorKind::UnsupportedTraitFunction {
+                            name: name.clone(),
+                            item: trait_item,
+                        },
+                    ));
+                }
+            };
+
+            if trait_fn.args != *args {
+                return Err(CompileError::new(
+                    span,
+                    CompileErrorKind::TraitFunctionArgumentMismatch {
+                        name: name.clone(),
+                        item: trait_item,
+                        expected: trait_fn.args,
+                        actual: *args,
+                    },
+                ));
+            }
+        }
+
+        let trait_type = Type::from(Hash::type_hash(&trait_item));
+
+        for trait_fn in trait_functions.iter() {
+            if functions.iter().any(|(name, ..)| *name == trait_fn.name) {
+                continue;
+            }
+
+            if !trait_fn.default {
+                return Err(CompileError::new(
+                    trait_span,
+                    CompileErrorKind::MissingTraitFunction {
+                        name: trait_fn.name.clone(),
+                        item: trait_item,
+                    },
+                ));
+            }
+
+            self.unit.borrow_mut().new_instance_function_alias(
+                trait_type,
+                type_of,
+                &trait_fn.name,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Construct metadata for a trait.
+    fn trait_into_item_decl(
+        &self,
+        item: &Item,
+        ast: ast::ItemTrait,
+        source: &Source,
+    ) -> CompileResult<CompileMetaKind> {
+        let mut functions = Vec::new();
+
+        for trait_fn in &ast.functions {
+            let name = trait_fn.name.resolve(&self.storage, source)?;
+
+            functions.push(CompileMetaTraitFn {
+                name: name.to_string(),
+                args: trait_fn.args.items.len(),
+                default: trait_fn.body.is_some(),
+            });
+        }
+
+        Ok(CompileMetaKind::Trait {
+            item: item.clone(),
+            functions: Arc::new(functions),
+        })
+    }
+
     /// Construct metadata for an empty body.
     fn empty_body_meta(&self, item: &Item, enum_item: Option<Item>) -> CompileMetaKind {
         let type_of = Type::from(Hash::type_hash(item));
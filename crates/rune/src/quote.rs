@@ -143,6 +143,10 @@ macro_rules! quote {
         $crate::quote!(@token $ctx, $s, Break => $($tt)*);
     }};
 
+    (@push $ctx:expr, $s:expr => continue $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $s, Continue => $($tt)*);
+    }};
+
     (@push $ctx:expr, $s:expr => yield $($tt:tt)*) => {{
         $crate::quote!(@token $ctx, $s, Yield => $($tt)*);
     }};
@@ -171,10 +175,26 @@ macro_rules! quote {
         $crate::quote!(@token $ctx, $s, Impl => $($tt)*);
     }};
 
+    (@push $ctx:expr, $s:expr => trait $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $s, Trait => $($tt)*);
+    }};
+
     (@push $ctx:expr, $s:expr => mod $($tt:tt)*) => {{
         $crate::quote!(@token $ctx, $s, Mod => $($tt)*);
     }};
 
+    (@push $ctx:expr, $s:expr => pub $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $s, Pub => $($tt)*);
+    }};
+
+    (@push $ctx:expr, $s:expr => crate $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $s, Crate => $($tt)*);
+    }};
+
+    (@push $ctx:expr, $s:expr => super $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $s, Super => $($tt)*);
+    }};
+
     (@push $ctx:expr, $s:expr => # $($tt:tt)*) => {{
         $crate::quote!(@token $ctx, $s, Pound => $($tt)*);
     }};
@@ -263,6 +283,10 @@ macro_rules! quote {
         $crate::quote!(@token $ctx, $s, QuestionMark => $($tt)*);
     }};
 
+    (@push $ctx:expr, $s:expr => ..= $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $s, DotDotEq => $($tt)*);
+    }};
+
     (@push $ctx:expr, $s:expr => .. $($tt:tt)*) => {{
         $crate::quote!(@token $ctx, $s, DotDot => $($tt)*);
     }};
@@ -413,6 +437,7 @@ mod tests {
         assert_eq!(vec![token(True)], quote!(ctx => true));
         assert_eq!(vec![token(False)], quote!(ctx => false));
         assert_eq!(vec![token(Break)], quote!(ctx => break));
+        assert_eq!(vec![token(Continue)], quote!(ctx => continue));
         assert_eq!(vec![token(Yield)], quote!(ctx => yield));
         assert_eq!(vec![token(Return)], quote!(ctx => return));
         assert_eq!(vec![token(Await)], quote!(ctx => await));
@@ -420,7 +445,11 @@ mod tests {
         assert_eq!(vec![token(Select)], quote!(ctx => select));
         assert_eq!(vec![token(Default)], quote!(ctx => default));
         assert_eq!(vec![token(Impl)], quote!(ctx => impl));
+        assert_eq!(vec![token(Trait)], quote!(ctx => trait));
         assert_eq!(vec![token(Mod)], quote!(ctx => mod));
+        assert_eq!(vec![token(Pub)], quote!(ctx => pub));
+        assert_eq!(vec![token(Crate)], quote!(ctx => crate));
+        assert_eq!(vec![token(Super)], quote!(ctx => super));
         assert_eq!(vec![token(Pound)], quote!(ctx => #));
         assert_eq!(vec![token(Dot)], quote!(ctx => .));
         assert_eq!(vec![token(ColonColon)], quote!(ctx => ::));
@@ -444,6 +473,7 @@ mod tests {
         assert_eq!(vec![token(Bang)], quote!(ctx => !));
         assert_eq!(vec![token(QuestionMark)], quote!(ctx => ?));
         assert_eq!(vec![token(DotDot)], quote!(ctx => ..));
+        assert_eq!(vec![token(DotDotEq)], quote!(ctx => ..=));
         assert_eq!(vec![token(AmpAmp)], quote!(ctx => &&));
         assert_eq!(vec![token(PipePipe)], quote!(ctx => ||));
         assert_eq!(vec![token(Pipe)], quote!(ctx => |));
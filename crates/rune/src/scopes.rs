@@ -1,6 +1,7 @@
 use crate::collections::HashMap;
 use crate::CompileResult;
 use crate::{Assembly, CompileError, CompileErrorKind, CompileVisitor};
+use runestick::debug::DebugVariable;
 use runestick::{Inst, SourceId, Span};
 
 /// A locally declared variable, its calculated stack offset and where it was
@@ -53,6 +54,9 @@ pub(crate) struct Scope {
     pub(crate) total_var_count: usize,
     /// The number of variables local to this scope.
     pub(crate) local_var_count: usize,
+    /// Indexes of the debug information of named variables declared in this
+    /// scope.
+    variables: Vec<usize>,
 }
 
 impl Scope {
@@ -63,6 +67,7 @@ impl Scope {
             anon: Vec::new(),
             total_var_count: 0,
             local_var_count: 0,
+            variables: Vec::new(),
         }
     }
 
@@ -73,6 +78,7 @@ impl Scope {
             anon: Vec::new(),
             total_var_count: self.total_var_count,
             local_var_count: 0,
+            variables: Vec::new(),
         }
     }
 
@@ -162,6 +168,8 @@ pub(crate) struct ScopeGuard(usize);
 
 pub(crate) struct Scopes {
     scopes: Vec<Scope>,
+    /// Every named variable declared, in the order they were declared.
+    variables: Vec<DebugVariable>,
 }
 
 impl Scopes {
@@ -169,6 +177,7 @@ impl Scopes {
     pub(crate) fn new() -> Self {
         Self {
             scopes: vec![Scope::new()],
+            variables: Vec::new(),
         }
     }
 
@@ -215,12 +224,37 @@ impl Scopes {
 
     /// Construct a new variable.
     pub(crate) fn new_var(&mut self, name: &str, span: Span) -> CompileResult<usize> {
-        self.last_mut(span)?.new_var(name, span)
+        let offset = self.last_mut(span)?.new_var(name, span)?;
+        self.record_var(name, offset, span);
+        Ok(offset)
     }
 
     /// Declare the given variable.
     pub(crate) fn decl_var(&mut self, name: &str, span: Span) -> CompileResult<usize> {
-        Ok(self.last_mut(span)?.decl_var(name, span))
+        let offset = self.last_mut(span)?.decl_var(name, span);
+        self.record_var(name, offset, span);
+        Ok(offset)
+    }
+
+    /// Take all variables declared so far, for use in debug information.
+    pub(crate) fn take_variables(&mut self) -> Vec<DebugVariable> {
+        std::mem::take(&mut self.variables)
+    }
+
+    /// Record a declared variable.
+    ///
+    /// Its scope ends when the scope it's declared in is popped.
+    fn record_var(&mut self, name: &str, offset: usize, span: Span) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.variables.push(self.variables.len());
+        }
+
+        self.variables.push(DebugVariable {
+            name: name.to_owned(),
+            offset,
+            span,
+            scope_end: span.end,
+        });
     }
 
     /// Declare an anonymous variable.
@@ -265,6 +299,14 @@ impl Scopes {
             .pop()
             .ok_or_else(|| CompileError::internal(&span, "missing parent scope"))?;
 
+        // NB: a scope can be popped and pushed again, like the scope of the
+        // pattern in `if let`, so its variables end at the last pop.
+        for index in &scope.variables {
+            if let Some(var) = self.variables.get_mut(*index) {
+                var.scope_end = usize::max(var.scope_end, span.end);
+            }
+        }
+
         Ok(scope)
     }
 
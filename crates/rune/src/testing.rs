@@ -49,8 +49,14 @@ pub fn compile_source(
         return Err(errors);
     }
 
-    let unit = Rc::try_unwrap(unit).unwrap().into_inner();
-    Ok((unit.into_unit(), warnings))
+    let unit = Rc::try_unwrap(unit).unwrap().into_inner().into_unit();
+
+    // NB: everything produced by the compiler is expected to pass verification.
+    if let Err(errors) = unit.verify(context) {
+        panic!("unit failed verification: {:?}", errors);
+    }
+
+    Ok((unit, warnings))
 }
 
 /// Call the specified function in the given script.
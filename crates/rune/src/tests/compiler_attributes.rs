@@ -9,3 +9,22 @@ fn test_bad_attributes() {
         }
     };
 }
+
+#[test]
+fn test_protocol_attribute() {
+    assert_compile_error! {
+        r#"struct Point; impl Point { #[protocol] fn length(self) { 0 } } fn main() {}"#,
+        span, UnsupportedProtocol { name } => {
+            assert_eq!(name, "length");
+            assert_eq!(span, Span::new(27, 38));
+        }
+    };
+
+    assert_compile_error! {
+        r#"#[protocol] fn add(a, b) { a } fn main() {}"#,
+        span, UnsupportedProtocol { name } => {
+            assert_eq!(name, "add");
+            assert_eq!(span, Span::new(0, 11));
+        }
+    };
+}
@@ -0,0 +1,139 @@
+use crate::bytecode_cache::{FORMAT, MAGIC};
+use crate::{Errors, Options, Sources, Warnings};
+use runestick::{Context, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Construct an empty directory to hold the sources of a single test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("rune-bytecode-cache")
+        .join(format!("{}-{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Load the source at the given path, returning the loaded sources and the
+/// number of warnings produced while compiling it.
+fn load(context: &Context, options: &Options, path: &Path) -> (Sources, usize) {
+    let mut sources = Sources::new();
+    sources.insert(Source::from_path(path).unwrap());
+
+    let mut errors = Errors::new();
+    let mut warnings = Warnings::new();
+
+    crate::load_sources(context, options, &mut sources, &mut errors, &mut warnings)
+        .expect("sources to load");
+
+    (sources, warnings.iter().count())
+}
+
+#[test]
+fn test_bytecode_cache() {
+    let dir = test_dir("test_bytecode_cache");
+    let path = dir.join("main.rn");
+    fs::write(&path, "fn main() { 1; 42 }").unwrap();
+
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+    options.bytecode(true);
+
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+    assert!(dir.join("main.rnc").is_file());
+
+    // cache hit, so nothing is compiled and no warnings are emitted.
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 0);
+
+    // different options invalidate the cache.
+    options.debug_info(false);
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 0);
+
+    // as does changing the source.
+    fs::write(&path, "fn main() { 2; 42 }").unwrap();
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+
+    // as does using a different context.
+    let context = Context::new();
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+}
+
+#[test]
+fn test_bytecode_cache_modules() {
+    let dir = test_dir("test_bytecode_cache_modules");
+    let path = dir.join("main.rn");
+    let module = dir.join("foo.rn");
+    fs::write(&path, "mod foo; fn main() { foo::value() }").unwrap();
+    fs::write(&module, "pub fn value() { 1; 42 }").unwrap();
+
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+    options.bytecode(true);
+
+    let (sources, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+    assert_eq!(sources.iter().count(), 2);
+
+    // modules loaded during compilation are restored on a cache hit.
+    let (sources, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 0);
+    assert_eq!(sources.iter().count(), 2);
+
+    fs::write(&module, "pub fn value() { 2; 42 }").unwrap();
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+}
+
+#[test]
+fn test_bytecode_cache_format() {
+    let dir = test_dir("test_bytecode_cache_format");
+    let path = dir.join("main.rn");
+    let cache = dir.join("main.rnc");
+    fs::write(&path, "fn main() { 1; 42 }").unwrap();
+
+    let context = Context::with_default_modules().unwrap();
+    let mut options = Options::default();
+    options.bytecode(true);
+
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+
+    let mut bytes = fs::read(&cache).unwrap();
+    assert_eq!(&bytes[..4], &MAGIC[..]);
+    assert_eq!(&bytes[4..8], &FORMAT.to_le_bytes()[..]);
+
+    // a cache stored in a different format is recompiled and replaced.
+    bytes[4..8].copy_from_slice(&(FORMAT + 1).to_le_bytes());
+    fs::write(&cache, &bytes).unwrap();
+
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 0);
+
+    // as is a truncated one.
+    fs::write(&cache, MAGIC).unwrap();
+    let (_, warnings) = load(&context, &options, &path);
+    assert_eq!(warnings, 1);
+}
+
+#[test]
+fn test_bytecode_cache_disabled() {
+    let dir = test_dir("test_bytecode_cache_disabled");
+    let path = dir.join("main.rn");
+    fs::write(&path, "fn main() { 42 }").unwrap();
+
+    let context = Context::with_default_modules().unwrap();
+    load(&context, &Options::default(), &path);
+    assert!(!dir.join("main.rnc").exists());
+}
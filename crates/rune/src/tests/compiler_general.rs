@@ -20,6 +20,16 @@ fn break_outside_of_loop() {
     };
 }
 
+#[test]
+fn continue_outside_of_loop() {
+    assert_compile_error! {
+        r#"fn main() { continue; }"#,
+        span, ContinueOutsideOfLoop => {
+            assert_eq!(span, Span::new(12, 20));
+        }
+    };
+}
+
 #[test]
 fn test_pointers() {
     assert_compile_error! {
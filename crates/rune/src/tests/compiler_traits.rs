@@ -0,0 +1,78 @@
+use crate::testing::*;
+
+#[test]
+fn test_missing_trait_function() {
+    assert_compile_error! {
+        r#"
+        trait Shape { fn area(self); }
+        struct Square;
+        impl Shape for Square {}
+        fn main() {}
+        "#,
+        span, MissingTraitFunction { name, .. } => {
+            assert_eq!(name, "area");
+            assert_eq!(span, Span::new(76, 81));
+        }
+    };
+}
+
+#[test]
+fn test_unsupported_trait_function() {
+    assert_compile_error! {
+        r#"
+        trait Shape { fn area(self); }
+        struct Square;
+        impl Shape for Square { fn area(self) { 1 } fn sides(self) { 4 } }
+        fn main() {}
+        "#,
+        span, UnsupportedTraitFunction { name, .. } => {
+            assert_eq!(name, "sides");
+            assert_eq!(span, Span::new(115, 129));
+        }
+    };
+}
+
+#[test]
+fn test_trait_function_argument_mismatch() {
+    assert_compile_error! {
+        r#"
+        trait Shape { fn area(self); }
+        struct Square;
+        impl Shape for Square { fn area(self, scale) { scale } }
+        fn main() {}
+        "#,
+        span, TraitFunctionArgumentMismatch { expected, actual, .. } => {
+            assert_eq!(expected, 1);
+            assert_eq!(actual, 2);
+            assert_eq!(span, Span::new(95, 115));
+        }
+    };
+}
+
+#[test]
+fn test_missing_trait() {
+    assert_compile_error! {
+        r#"struct Square; impl Shape for Square {} fn main() {}"#,
+        span, MissingTrait { .. } => {
+            assert_eq!(span, Span::new(20, 25));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Square; struct Shape; impl Shape for Square {} fn main() {}"#,
+        span, ExpectedTrait { .. } => {
+            assert_eq!(span, Span::new(34, 39));
+        }
+    };
+}
+
+#[test]
+fn test_trait_default_function_without_self() {
+    assert_compile_error! {
+        r#"trait Shape { fn new() { 1 } } fn main() {}"#,
+        span, TraitDefaultFunctionWithoutSelf { name } => {
+            assert_eq!(name, "new");
+            assert_eq!(span, Span::new(14, 22));
+        }
+    };
+}
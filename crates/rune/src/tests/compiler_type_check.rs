@@ -0,0 +1,233 @@
+use crate::testing::*;
+use crate::{Errors, LoadErrorKind, Options, Sources, Spanned as _, Warnings};
+use runestick::{Context, Source};
+
+/// Compile the given source with type checking enabled, returning the first
+/// error if there is one.
+fn check(source: &str) -> Result<(), (Span, crate::CompileErrorKind)> {
+    let context = Context::with_default_modules().unwrap();
+
+    let mut options = Options::default();
+    options.type_check(true);
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+    let mut errors = Errors::new();
+
+    let result = crate::load_sources(
+        &context,
+        &options,
+        &mut sources,
+        &mut errors,
+        &mut Warnings::disabled(),
+    );
+
+    if result.is_ok() {
+        return Ok(());
+    }
+
+    let error = errors.into_iter().next().expect("expected one error");
+
+    match error.into_kind() {
+        LoadErrorKind::CompileError(error) => Err((error.span(), error.into_kind())),
+        kind => panic!("expected compile error but was `{:?}`", kind),
+    }
+}
+
+#[test]
+fn test_well_typed() {
+    let source = r#"
+    struct Point { x: int, y: int }
+
+    impl Point {
+        fn add(self, other: Point) -> Point {
+            Point { x: self.x + other.x, y: self.y + other.y }
+        }
+
+        fn sum(self) -> int {
+            self.x + self.y
+        }
+    }
+
+    fn double(n: int) -> int {
+        n * 2
+    }
+
+    fn main() {
+        let a = Point { x: 1, y: 2 };
+        let b: Point = a.add(Point { x: 3, y: 4 });
+        let c: int = double(b.sum());
+        let f = |n: float| n * 2.0;
+        let v: Vec = [c, f(1.5)];
+        v
+    }
+    "#;
+
+    assert!(check(source).is_ok());
+}
+
+#[test]
+fn test_disabled_by_default() {
+    assert_eq! {
+        rune!(i64 => r#"fn main() { let a: String = 1; a }"#),
+        1,
+    };
+}
+
+#[test]
+fn test_let_mismatch() {
+    let (span, kind) = check(r#"fn main() { let a: String = 1 + 2; }"#).unwrap_err();
+
+    match kind {
+        TypeMismatch { expected, actual } => {
+            assert_eq!(expected, "String");
+            assert_eq!(actual, "int");
+            assert_eq!(span, Span::new(28, 33));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_return_mismatch() {
+    let (span, kind) =
+        check(r#"fn f(a) -> int { if a { return "no"; } 1 } fn main() { f(true) }"#).unwrap_err();
+
+    match kind {
+        TypeMismatch { expected, actual } => {
+            assert_eq!(expected, "int");
+            assert_eq!(actual, "String");
+            assert_eq!(span, Span::new(31, 35));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let (span, kind) = check(r#"fn f() -> float { 1 } fn main() { f() }"#).unwrap_err();
+
+    match kind {
+        TypeMismatch { expected, actual } => {
+            assert_eq!(expected, "float");
+            assert_eq!(actual, "int");
+            assert_eq!(span, Span::new(18, 19));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_argument_mismatch() {
+    let (span, kind) = check(r#"fn f(a: int, b) { a } fn main() { f(1.0, 2) }"#).unwrap_err();
+
+    match kind {
+        TypeMismatch { expected, actual } => {
+            assert_eq!(expected, "int");
+            assert_eq!(actual, "float");
+            assert_eq!(span, Span::new(36, 39));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let (span, kind) = check(r#"fn f(a, b) { a } fn main() { f(1) }"#).unwrap_err();
+
+    match kind {
+        ArgumentCountMismatch {
+            item,
+            expected,
+            actual,
+        } => {
+            assert_eq!(item.to_string(), "f");
+            assert_eq!(expected, 2);
+            assert_eq!(actual, 1);
+            assert_eq!(span, Span::new(29, 33));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_native_argument_count() {
+    let (span, kind) = check(r#"fn main() { let v = []; v.push(1, 2); }"#).unwrap_err();
+
+    match kind {
+        ArgumentCountMismatch {
+            expected, actual, ..
+        } => {
+            assert_eq!(expected, 1);
+            assert_eq!(actual, 2);
+            assert_eq!(span, Span::new(24, 36));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_unsupported_operation() {
+    let (span, kind) = check(r#"fn main() { let a: int = 1; a + 2.0 }"#).unwrap_err();
+
+    match kind {
+        UnsupportedOperation { lhs, rhs, .. } => {
+            assert_eq!(lhs, "int");
+            assert_eq!(rhs, "float");
+            assert_eq!(span, Span::new(28, 35));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_comparison() {
+    check(r#"fn main() { let a: String = "a"; (a < "b", [1] < [2], (1, 2) >= (1, 3)) }"#).unwrap();
+
+    let (span, kind) = check(r#"fn main() { let a: String = "a"; a < 1 }"#).unwrap_err();
+
+    match kind {
+        UnsupportedOperation { lhs, rhs, .. } => {
+            assert_eq!(lhs, "String");
+            assert_eq!(rhs, "int");
+            assert_eq!(span, Span::new(33, 38));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_struct_field_mismatch() {
+    let (span, kind) =
+        check(r#"struct A { a: int, b } fn main() { A { a: "a", b: "b" } }"#).unwrap_err();
+
+    match kind {
+        TypeMismatch { expected, actual } => {
+            assert_eq!(expected, "int");
+            assert_eq!(actual, "String");
+            assert_eq!(span, Span::new(42, 45));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_assign_mismatch() {
+    let (span, kind) = check(r#"fn main() { let a: int = 1; a = 2; a = true; }"#).unwrap_err();
+
+    match kind {
+        TypeMismatch { expected, actual } => {
+            assert_eq!(expected, "int");
+            assert_eq!(actual, "bool");
+            assert_eq!(span, Span::new(39, 43));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_missing_type() {
+    let (span, kind) = check(r#"fn f(a: Missing) { a } fn main() { f(1) }"#).unwrap_err();
+
+    match kind {
+        MissingType { item } => {
+            assert_eq!(item.to_string(), "Missing");
+            assert_eq!(span, Span::new(8, 15));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
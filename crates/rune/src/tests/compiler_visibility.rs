@@ -0,0 +1,141 @@
+use crate::testing::*;
+use runestick::Item;
+
+#[test]
+fn test_private_fn() {
+    assert_compile_error! {
+        r#"mod a { fn f() {} } fn main() { a::f() }"#,
+        span, NotVisible { item, .. } => {
+            assert_eq!(item, Item::of(&["a", "f"]));
+            assert_eq!(span, Span::new(32, 36));
+        }
+    };
+}
+
+#[test]
+fn test_private_mod() {
+    assert_compile_error! {
+        r#"mod a { mod b { pub fn f() {} } } fn main() { a::b::f() }"#,
+        span, NotVisible { item, .. } => {
+            assert_eq!(item, Item::of(&["a", "b"]));
+            assert_eq!(span, Span::new(46, 53));
+        }
+    };
+}
+
+#[test]
+fn test_private_use() {
+    assert_compile_error! {
+        r#"mod a { fn f() {} } use a::f; fn main() {}"#,
+        span, NotVisible { item, .. } => {
+            assert_eq!(item, Item::of(&["a", "f"]));
+            assert_eq!(span, Span::new(20, 29));
+        }
+    };
+}
+
+#[test]
+fn test_pub_use() {
+    assert_compile_error! {
+        r#"mod a { pub fn f() {} } pub use a::f; fn main() {}"#,
+        span, UnsupportedUseVisibility => {
+            assert_eq!(span, Span::new(24, 27));
+        }
+    };
+}
+
+#[test]
+fn test_pub_super() {
+    assert_compile_error! {
+        r#"mod a { mod b { pub(super) fn f() {} } pub fn g() { b::f() } } fn main() { a::b::f() }"#,
+        span, NotVisible { item, .. } => {
+            assert_eq!(item, Item::of(&["a", "b"]));
+            assert_eq!(span, Span::new(75, 82));
+        }
+    };
+}
+
+#[test]
+fn test_private_impl_fn() {
+    assert_compile_error! {
+        r#"mod a { pub struct S; impl S { fn new() { S } } } fn main() { a::S::new() }"#,
+        span, NotVisible { item, .. } => {
+            assert_eq!(item, Item::of(&["a", "S", "new"]));
+            assert_eq!(span, Span::new(62, 71));
+        }
+    };
+}
+
+#[test]
+fn test_private_field() {
+    assert_compile_error! {
+        r#"mod a { pub struct S { pub x, y } } fn main() { a::S { x: 1, y: 2 } }"#,
+        span, NotVisibleField { item, field, .. } => {
+            assert_eq!(item, Item::of(&["a", "S"]));
+            assert_eq!(field, "y");
+            assert_eq!(span, Span::new(61, 62));
+        }
+    };
+
+    assert_compile_error! {
+        r#"mod a { pub struct S(pub x, y); } fn main() { a::S(1, 2) }"#,
+        span, NotVisibleField { item, field, .. } => {
+            assert_eq!(item, Item::of(&["a", "S"]));
+            assert_eq!(field, "1");
+            assert_eq!(span, Span::new(46, 50));
+        }
+    };
+
+    assert_compile_error! {
+        r#"mod a { pub struct S { pub x, y } pub fn s() { S { x: 1, y: 2 } } } fn main() { match a::s() { a::S { y, .. } => y } }"#,
+        span, NotVisibleField { field, .. } => {
+            assert_eq!(field, "y");
+            assert_eq!(span, Span::new(102, 103));
+        }
+    };
+}
+
+#[test]
+fn test_visibility_not_supported() {
+    assert_parse_error! {
+        r#"mod a { pub struct S; } pub impl a::S {} fn main() {}"#,
+        span, VisibilityNotSupported => {
+            assert_eq!(span, Span::new(24, 27));
+        }
+    };
+}
+
+#[test]
+fn test_visible() {
+    assert_eq! {
+        rune! { i64 => r#"
+        mod a {
+            pub struct S { pub x, y }
+
+            impl S {
+                pub fn new(x) { S { x, y: x * 2 } }
+                pub(crate) fn sum(self) { self.x + self.y }
+            }
+
+            pub mod b {
+                pub(super) fn private() { 10 }
+                pub fn value() { c::value() + private() }
+            }
+
+            mod c {
+                pub(crate) fn value() { helper() }
+                fn helper() { 100 }
+            }
+        }
+
+        use a::b::value;
+
+        fn main() {
+            let s = a::S::new(1);
+            let a::S { x, .. } = s;
+            x + s.sum() + value()
+        }
+        "# },
+        114
+    };
+}
@@ -1,34 +1,50 @@
 mod compiler_attributes;
+mod compiler_bytecode_cache;
 mod compiler_expr_assign;
 mod compiler_expr_binary;
 mod compiler_general;
 mod compiler_literals;
+mod compiler_traits;
+mod compiler_type_check;
+mod compiler_visibility;
 mod compiler_warnings;
 mod vm_arithmetic;
 mod vm_assign_exprs;
 mod vm_async_block;
 mod vm_blocks;
 mod vm_closures;
+mod vm_collections;
+mod vm_compare;
 mod vm_const_exprs;
+mod vm_debugger;
 mod vm_early_termination;
+mod vm_format;
 mod vm_function;
 mod vm_general;
 mod vm_generators;
 mod vm_is;
+mod vm_iter;
 mod vm_lazy_and_or;
+mod vm_limits;
 mod vm_literals;
 mod vm_match;
 mod vm_option;
 mod vm_pat;
+mod vm_protocols;
+mod vm_range;
 mod vm_result;
+mod vm_send;
 mod vm_streams;
+mod vm_string;
 mod vm_test_external_fn_ptr;
 mod vm_test_from_value_derive;
 mod vm_test_instance_fns;
 mod vm_test_linked_list;
 mod vm_test_mod;
 mod vm_test_references;
+mod vm_traits;
 mod vm_try;
 mod vm_tuples;
 mod vm_typed_tuple;
 mod vm_types;
+mod vm_vec;
@@ -0,0 +1,356 @@
+use crate::testing::*;
+use std::sync::Arc;
+
+#[test]
+fn test_hash_map() {
+    assert_eq! {
+        rune! {
+            (i64, Option<i64>, bool, usize, Vec<(i64, String)>) => r#"
+            use std::collections::HashMap;
+
+            enum Kind { A, B }
+
+            fn main() {
+                let map = HashMap::new();
+                map[1] = "one";
+                map.insert(2, "two");
+                map[(1, 'a')] = 10;
+                map[Kind::A] = 20;
+                map[Kind::B] = 30;
+                map.insert("three", 3);
+
+                let sum = map[(1, 'a')] + map[Kind::A] + map["three"];
+                let removed = map.remove(Kind::B);
+
+                let pairs = map.iter().filter(|p| p.0 is int).collect_vec();
+                pairs.sort_by(|a, b| a.0 - b.0);
+                (sum, removed, map.contains_key(Kind::B), map.len(), pairs)
+            }
+            "#
+        },
+        (
+            10 + 20 + 3,
+            Some(30),
+            false,
+            5,
+            vec![(1, String::from("one")), (2, String::from("two"))],
+        ),
+    };
+}
+
+#[test]
+fn test_hash_set() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, usize, Vec<String>) => r#"
+            use std::collections::HashSet;
+
+            fn main() {
+                let set = HashSet::from(["a", "b", "a"]);
+                let inserted = set.insert("c");
+                let removed = set.remove("b");
+                set.extend("d e".split(' '));
+
+                let keys = set.iter().collect_vec();
+                keys.sort();
+                (inserted, removed, set.contains("b"), set.len(), keys)
+            }
+            "#
+        },
+        (
+            true,
+            true,
+            false,
+            4,
+            vec![String::from("a"), String::from("c"), String::from("d"), String::from("e")],
+        ),
+    };
+}
+
+#[test]
+fn test_btree_map() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<String>, Option<(i64, String)>, Option<(i64, String)>) => r#"
+            use std::collections::BTreeMap;
+
+            fn main() {
+                let map = BTreeMap::from([(3, "c"), (1, "a")]);
+                map[2] = "b";
+
+                map.extend([(0, "z")]);
+
+                (map.keys().collect_vec(), map.values().rev().collect_vec(), map.first(), map.last())
+            }
+            "#
+        },
+        (
+            vec![0, 1, 2, 3],
+            vec![String::from("c"), String::from("b"), String::from("a"), String::from("z")],
+            Some((0, String::from("z"))),
+            Some((3, String::from("c"))),
+        ),
+    };
+}
+
+#[test]
+fn test_vec_deque() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Option<i64>, Option<i64>, Option<i64>, i64) => r#"
+            use std::collections::VecDeque;
+
+            fn main() {
+                let deque = VecDeque::from([2, 3]);
+                deque.push_front(1);
+                deque.push_back(4);
+                deque[0] = 0;
+
+                let front = deque.pop_front();
+                let back = deque.pop_back();
+                let values = [];
+
+                for n in deque {
+                    values.push(n);
+                }
+
+                (values, front, back, deque.front(), deque[1])
+            }
+            "#
+        },
+        (vec![2, 3], Some(0), Some(4), Some(2), 3),
+    };
+}
+
+#[test]
+fn test_struct_keys() {
+    assert_eq! {
+        rune! {
+            (i64, i64, usize) => r#"
+            use std::collections::HashMap;
+
+            struct Point { x, y }
+            enum Shape { Circle { radius }, Square { side } }
+
+            fn main() {
+                let map = HashMap::new();
+                map[Point { x: 1, y: 2 }] = 10;
+                map[Point { y: 2, x: 1 }] = 11;
+                map[Shape::Circle { radius: 2 }] = 20;
+                map[Shape::Square { side: 2 }] = 30;
+                (map[Point { x: 1, y: 2 }], map[Shape::Circle { radius: 2 }], map.len())
+            }
+            "#
+        },
+        (11, 20, 3),
+    };
+
+    assert_vm_error!(
+        r#"
+        use std::collections::HashMap;
+        struct Point { x, y }
+        fn main() { let map = HashMap::new(); map[Point { x: 1, y: 2 }] }
+        "#,
+        BadReturn { error, .. } => {
+            match error.into_kind() {
+                MissingKey { key, .. } => assert_eq!(key, "Point { x: 1, y: 2 }"),
+                kind => panic!("unexpected error: {:?}", kind),
+            }
+        }
+    );
+}
+
+#[test]
+fn test_protocol_keys() {
+    assert_eq! {
+        rune! {
+            (usize, String, bool, Vec<i64>) => r#"
+            use std::collections::BTreeMap;
+            use std::collections::HashMap;
+
+            struct Version { major, minor }
+
+            impl Version {
+                #[protocol]
+                fn eq(self, other) {
+                    self.major == other.major
+                }
+
+                #[protocol]
+                fn partial_cmp(self, other) {
+                    self.major - other.major
+                }
+
+                #[protocol]
+                fn hash(self) {
+                    self.major
+                }
+            }
+
+            fn main() {
+                let map = HashMap::new();
+                map[Version { major: 1, minor: 0 }] = "a";
+                map[Version { major: 1, minor: 2 }] = "b";
+                map[(Version { major: 2, minor: 0 }, 1)] = "c";
+
+                let tree = BTreeMap::new();
+                tree[Version { major: 3, minor: 0 }] = 3;
+                tree[Version { major: 1, minor: 0 }] = 1;
+                tree[Version { major: 1, minor: 1 }] = 1;
+
+                (
+                    map.len(),
+                    map[Version { major: 1, minor: 5 }],
+                    map.contains_key((Version { major: 2, minor: 9 }, 1)),
+                    tree.keys().map(|v| v.major).collect_vec(),
+                )
+            }
+            "#
+        },
+        (2, String::from("b"), true, vec![1, 3]),
+    };
+
+    assert_vm_error!(
+        r#"
+        use std::collections::BTreeMap;
+
+        struct Version { major }
+
+        impl Version {
+            #[protocol]
+            fn partial_cmp(self, other) {
+                None
+            }
+        }
+
+        fn main() {
+            let tree = BTreeMap::new();
+            tree[Version { major: 1 }] = 1;
+            tree[Version { major: 2 }] = 2;
+        }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), UnorderedKey { .. }));
+        }
+    );
+}
+
+#[derive(runestick::Any)]
+struct Meters(i64);
+
+#[test]
+fn test_native_protocol_keys() -> runestick::Result<()> {
+    let mut module = runestick::Module::empty();
+    module.ty::<Meters>()?;
+    module.inst_fn(runestick::EQ, |a: &Meters, b: &Meters| a.0 == b.0)?;
+    module.inst_fn(runestick::HASH, |a: &Meters| a.0)?;
+
+    let mut context = runestick::Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let source = r#"
+    use std::collections::HashSet;
+
+    fn main(a, b, c) {
+        let set = HashSet::from([a, b]);
+        (set.len(), set.contains(c))
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source).expect("source to compile");
+    let vm = runestick::Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(&["main"], (Meters(1), Meters(1), Meters(2)))?;
+    let output = <(usize, bool)>::from_value(output)?;
+
+    assert_eq!(output, (1, false));
+    Ok(())
+}
+
+#[test]
+fn test_collection_debug() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            use std::collections::BTreeMap;
+            use std::collections::HashMap;
+            use std::collections::HashSet;
+            use std::collections::VecDeque;
+
+            struct Point { x, y }
+
+            fn main() {
+                let map = HashMap::from([(Point { x: 1, y: 2 }, [1])]);
+                let set = HashSet::from(["a"]);
+                let tree = BTreeMap::from([(2, 'b'), (1, 'a')]);
+                let deque = VecDeque::from([1, 2]);
+                `{map:?} {set:?} {tree:?} {deque:?}`
+            }
+            "#
+        },
+        String::from("{Point { x: 1, y: 2 }: [1]} {\"a\"} {1: 'a', 2: 'b'} [1, 2]"),
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, runestick::Any)]
+#[rune(key)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+#[test]
+fn test_native_key() -> runestick::Result<()> {
+    let (value, keys): (i64, Vec<Point>) = run(
+        &["main"],
+        (Point { x: 1, y: 2 }, Point { x: 1, y: 2 }),
+        r#"
+        use std::collections::HashMap;
+
+        fn main(a, b) {
+            let map = HashMap::new();
+            map[a] = 42;
+            (map[b], map.keys().collect_vec())
+        }
+        "#,
+    )?;
+
+    assert_eq!(value, 42);
+    assert_eq!(keys, vec![Point { x: 1, y: 2 }]);
+    Ok(())
+}
+
+#[test]
+fn test_collection_errors() {
+    assert_vm_error!(
+        r#"
+        use std::collections::HashMap;
+        fn main() { let map = HashMap::new(); map[1.5] = 1; }
+        "#,
+        BadArgument { error, .. } => {
+            assert!(matches!(error.into_kind(), UnsupportedKey { .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        use std::collections::HashMap;
+        fn main() { let map = HashMap::new(); map[(1, "a")] }
+        "#,
+        BadReturn { error, .. } => {
+            match error.into_kind() {
+                MissingKey { key, .. } => assert_eq!(key, "(1, \"a\")"),
+                kind => panic!("unexpected error: {:?}", kind),
+            }
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        use std::collections::VecDeque;
+        fn main() { let deque = VecDeque::new(); deque[0] }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), MissingIndex { .. }));
+        }
+    );
+}
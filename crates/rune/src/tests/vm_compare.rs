@@ -0,0 +1,129 @@
+use crate::testing::*;
+use std::sync::Arc;
+
+#[test]
+fn test_deep_eq() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool, bool) => r#"
+            struct Point { x, y }
+            struct Pair(a, b);
+            enum Shape { Circle(radius), Rect { w, h } }
+
+            fn main() {
+                (
+                    [1, (2, "three"), #{a: [4]}] == [1, (2, "three"), #{a: [4]}],
+                    (1, [2]) != (1, [3]),
+                    Point { x: 1, y: [2] } == Point { x: 1, y: [2] },
+                    Pair(1, Some(2)) == Pair(1, Some(3)),
+                    Shape::Circle(1) == Shape::Circle(1),
+                    Shape::Circle(1) == Shape::Rect { w: 1, h: 1 },
+                    Ok([1]) == Err([1]),
+                )
+            }
+            "#
+        },
+        (true, true, true, false, true, false, false),
+    };
+}
+
+#[test]
+fn test_deep_cmp() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool, bool) => r#"
+            struct Point { x, y }
+            struct Pair(a, b);
+
+            fn main() {
+                (
+                    [1, 2, 3] < [1, 3],
+                    (1, "b") > (1, "a"),
+                    [1, 2] <= [1, 2],
+                    None < Some(0),
+                    Pair(1, 2) < Pair(1, 3),
+                    Point { x: 2, y: 1 } > Point { x: 1, y: 2 },
+                    "abc" < "abd",
+                )
+            }
+            "#
+        },
+        (true, true, true, true, true, true, true),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                ([1, 2].partial_cmp([1, 3]), (2, 1).partial_cmp((1, 2)), 1.partial_cmp("a"))
+            }
+            "#
+        },
+        (Some(-1), Some(1), None),
+    };
+}
+
+#[test]
+fn test_hash() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool) => r#"
+            struct Point { x, y }
+
+            fn main() {
+                let a = #{a: 1, b: [2, 3]};
+                let b = #{b: [2, 3], a: 1};
+
+                (
+                    a.hash() == b.hash(),
+                    "abc".hash() == `a{"bc"}`.hash(),
+                    Point { x: 1, y: 2 }.hash() == Point { x: 1, y: 2 }.hash(),
+                    (1, 2).hash() != (2, 1).hash(),
+                )
+            }
+            "#
+        },
+        (true, true, true, true),
+    };
+}
+
+#[derive(runestick::Any)]
+struct Meters(i64);
+
+#[test]
+fn test_native_protocols() -> runestick::Result<()> {
+    let mut module = runestick::Module::empty();
+    module.ty::<Meters>()?;
+    module.inst_fn(runestick::EQ, |a: &Meters, b: &Meters| a.0 == b.0)?;
+    module.inst_fn(runestick::PARTIAL_CMP, |a: &Meters, b: &Meters| a.0 - b.0)?;
+
+    let mut context = runestick::Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let source = r#"
+    fn main(a, b, c) {
+        (a == b, [a] < [c], c >= b, a == 1)
+    }
+    "#;
+
+    let (unit, _) = compile_source(&context, source).expect("source to compile");
+    let vm = runestick::Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(&["main"], (Meters(1), Meters(1), Meters(2)))?;
+    let output = <(bool, bool, bool, bool)>::from_value(output)?;
+
+    assert_eq!(output, (true, true, true, false));
+    Ok(())
+}
+
+#[test]
+fn test_compare_errors() {
+    assert_vm_error!(
+        r#"fn main() { let a = [1]; let b = ["a"]; a < b }"#,
+        UnsupportedBinaryOperation { op: "<", .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let f = main; f.hash() }"#,
+        MissingProtocol { .. } => {}
+    );
+}
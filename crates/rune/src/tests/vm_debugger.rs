@@ -0,0 +1,129 @@
+use crate::testing::*;
+use runestick::{Context, DebugHalt, Debugger, Source, StepMode, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"fn add(a, b) {
+    let c = a + b;
+    c
+}
+
+fn main() {
+    let a = 1;
+    let b = add(a, 2);
+    let d = b * 2;
+    d
+}
+"#;
+
+#[test]
+fn test_breakpoint_and_locals() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (unit, _) = compile_source(&context, SOURCE).expect("source to compile");
+    let unit = Arc::new(unit);
+    let source = Source::new("main", SOURCE);
+
+    let mut debugger = Debugger::new(unit.clone(), |_| Some(&source));
+    let breakpoint = debugger
+        .add_breakpoint(0, 7)
+        .expect("breakpoint to resolve");
+    assert_eq!(breakpoint.location().line, 7);
+
+    let mut execution = Vm::new(context, unit).execute(&["main"], ())?;
+
+    assert!(matches!(
+        debugger.resume(&mut execution)?,
+        DebugHalt::Breakpoint(0)
+    ));
+
+    let frames = debugger.frames(&execution);
+    assert_eq!(frames.len(), 1);
+
+    let a = debugger
+        .local(&execution, &frames[0], "a")
+        .expect("local `a`");
+    assert_eq!(i64::from_value(a)?, 1);
+    assert!(debugger.local(&execution, &frames[0], "b").is_none());
+
+    debugger.step(&execution, StepMode::In)?;
+    assert!(matches!(debugger.resume(&mut execution)?, DebugHalt::Step));
+
+    let frames = debugger.frames(&execution);
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].location().map(|l| l.line), Some(1));
+
+    let locals = debugger.locals(&execution, &frames[0]);
+    let names = locals.iter().map(|l| l.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["a", "b"]);
+
+    debugger.step(&execution, StepMode::Out)?;
+    assert!(matches!(debugger.resume(&mut execution)?, DebugHalt::Step));
+
+    let frames = debugger.frames(&execution);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].location().map(|l| l.line), Some(8));
+
+    let b = debugger
+        .local(&execution, &frames[0], "b")
+        .expect("local `b`");
+    assert_eq!(i64::from_value(b)?, 3);
+
+    debugger.step(&execution, StepMode::Over)?;
+    assert!(matches!(debugger.resume(&mut execution)?, DebugHalt::Step));
+
+    let frames = debugger.frames(&execution);
+    assert_eq!(frames[0].location().map(|l| l.line), Some(9));
+
+    let d = debugger
+        .local(&execution, &frames[0], "d")
+        .expect("local `d`");
+    assert_eq!(i64::from_value(d)?, 6);
+
+    match debugger.resume(&mut execution)? {
+        DebugHalt::Exited(value) => assert_eq!(i64::from_value(value)?, 6),
+        halt => panic!("unexpected halt: {:?}", halt),
+    }
+
+    Ok(())
+}
+
+const SCOPES: &str = r#"fn pair(a, b) { [a, b] }
+
+fn main() {
+    let a = 1;
+
+    if a > 0 {
+        let b = 2;
+    }
+
+    pair(
+        a,
+        a + 3,
+    )
+}
+"#;
+
+#[test]
+fn test_locals_out_of_scope() -> runestick::Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (unit, _) = compile_source(&context, SCOPES).expect("source to compile");
+    let unit = Arc::new(unit);
+    let source = Source::new("main", SCOPES);
+
+    let mut debugger = Debugger::new(unit.clone(), |_| Some(&source));
+    debugger
+        .add_breakpoint(0, 11)
+        .expect("breakpoint to resolve");
+
+    let mut execution = Vm::new(context, unit).execute(&["main"], ())?;
+
+    assert!(matches!(
+        debugger.resume(&mut execution)?,
+        DebugHalt::Breakpoint(0)
+    ));
+
+    let frames = debugger.frames(&execution);
+    let locals = debugger.locals(&execution, &frames[0]);
+    let names = locals.iter().map(|l| l.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["a"]);
+    Ok(())
+}
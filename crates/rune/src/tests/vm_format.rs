@@ -0,0 +1,233 @@
+use crate::testing::*;
+use std::sync::Arc;
+
+/// Compile and run `main` with macros enabled and the macros of the default
+/// context installed.
+fn run_with_macros<T>(source: &str) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: FromValue,
+{
+    let context = crate::default_context()?;
+    let mut options = crate::Options::default();
+    options.macros(true);
+
+    let mut sources = crate::Sources::new();
+    sources.insert(runestick::Source::new("main", source));
+
+    let mut errors = crate::Errors::new();
+    let mut warnings = crate::Warnings::new();
+
+    let unit =
+        match crate::load_sources(&context, &options, &mut sources, &mut errors, &mut warnings) {
+            Ok(unit) => unit,
+            Err(..) => {
+                let error = errors.into_iter().next().expect("expected one error");
+                return Err(Box::new(error));
+            }
+        };
+
+    let vm = runestick::Vm::new(Arc::new(context), Arc::new(unit));
+    Ok(T::from_value(vm.call(["main"], ())?)?)
+}
+
+#[test]
+fn test_format_specs() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                let n = 42;
+                let s = "hello";
+                `{n:>5}|{n:<5}|{n:^6}|{n:+}|{-42:06}|{n:*^+7}|{s:.3}|{s:-^9}|{true:>6}|{'x':3}|`
+            }
+            "#
+        },
+        String::from("   42|42   |  42  |+42|-00042|**+42**|hel|--hello--|  true|x  |"),
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                `{255:x}|{255:#X}|{5:#010b}|{8:o}|{-1:x}|{b'a':#x}|{3.14159:.2}|{1.5:>8.3}|{-0.5:+}|{2.0:+.1}`
+            }
+            "#
+        },
+        String::from("ff|0xFF|0b00000101|10|ffffffffffffffff|0x61|3.14|   1.500|-0.5|+2.0"),
+    };
+}
+
+#[test]
+fn test_format_debug() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            struct Point { x, y }
+            struct Pair(a, b);
+            struct Empty;
+            enum Shape { Circle(radius), Rect { w, h } }
+
+            fn main() {
+                let values = [1, "a\n", 'c', (1,), #{b: 2, a: ()}, Some(1.5), Err(false), 1..=3, b"a\n"];
+                let typed = (Point { x: 1, y: [2] }, Pair("a", None), Empty, Shape::Circle(1), Shape::Rect { w: 1, h: 2 });
+                `{values:?}|{typed:?}|{"a":?}|{"s":>5?}`
+            }
+            "#
+        },
+        String::from(concat!(
+            r#"[1, "a\n", 'c', (1,), #{"a": (), "b": 2}, Some(1.5), Err(false), 1..=3, b"a\n"]|"#,
+            r#"(Point { x: 1, y: [2] }, Pair("a", None), Empty, Shape::Circle(1), Shape::Rect { h: 2, w: 1 })|"#,
+            r#""a"|  "s""#,
+        )),
+    };
+}
+
+#[test]
+fn test_format_debug_protocol() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            struct Meters(n);
+
+            impl Meters {
+                #[protocol]
+                fn string_debug(self, buf) {
+                    buf.push_str(`{self.0}m`);
+                }
+            }
+
+            fn main() {
+                let m = Meters(5);
+                `{[m]:?}|{m:>4?}`
+            }
+            "#
+        },
+        String::from("[5m]|  5m"),
+    };
+}
+
+#[test]
+fn test_format_const() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            const VALUE = `{42:>4}|{1.5:.2}|{"hi":?}|{255:#x}`;
+            fn main() { VALUE }
+            "#
+        },
+        String::from("  42|1.50|\"hi\"|0xff"),
+    };
+}
+
+#[test]
+fn test_format_errors() {
+    assert_compile_error! {
+        r#"fn main() { `{1:y}` }"#,
+        span, ParseError { error: BadFormatSpec { .. } } => {
+            assert_eq!(span, Span::new(16, 17));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { `{1:.}` }"#,
+        span, ParseError { error: BadFormatSpec { .. } } => {
+            assert_eq!(span, Span::new(16, 17));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { `{1:70000}` }"#,
+        span, ParseError { error: BadFormatSpec { error, .. } } => {
+            assert_eq!(span, Span::new(16, 21));
+            assert!(matches!(error, runestick::FormatSpecError::Overflow));
+        }
+    };
+
+    assert_vm_error!(
+        r#"fn main() { let f = 1.5; `{f:x}` }"#,
+        UnsupportedFormat { spec, .. } => {
+            assert_eq!(spec.to_string(), "x");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let v = [1]; `{v:x}` }"#,
+        UnsupportedFormat { .. } => {}
+    );
+}
+
+#[test]
+fn test_format_spec_bounds() {
+    let mut vm = runestick::Vm::new(
+        Arc::new(runestick::Context::new()),
+        Arc::new(runestick::Unit::default()),
+    );
+
+    let mut spec = runestick::FormatSpec {
+        width: Some(runestick::FormatSpec::MAX_COUNT),
+        ..Default::default()
+    };
+
+    let mut buf = String::new();
+    vm.format_value(Value::from(1i64), &spec, &mut buf).unwrap();
+    assert_eq!(buf.len(), runestick::FormatSpec::MAX_COUNT as usize);
+
+    spec.width = None;
+    spec.precision = Some(u32::MAX);
+
+    let error = vm
+        .format_value(Value::from(1.5), &spec, &mut String::new())
+        .unwrap_err();
+
+    assert!(matches!(error.into_kind(), InvalidFormatSpec { .. }));
+}
+
+#[test]
+fn test_format_macro() {
+    let output = run_with_macros::<(String, String, String)>(
+        r#"
+        fn main() {
+            let value = [1];
+            let count = 0;
+
+            let a = format!("{} {:>4} {1:#x} {name}-{value:?} {{}}", "a", 255, name = "b");
+            let b = format!("{0}{0}", { count += 1; count });
+            let c = format!("no arguments {{}}");
+            (a, b, c)
+        }
+        "#,
+    )
+    .expect("program to run successfully");
+
+    assert_eq!(
+        output,
+        (
+            String::from("a  255 0xff b-[1] {}"),
+            String::from("11"),
+            String::from("no arguments {}"),
+        )
+    );
+
+    run_with_macros::<()>(r#"fn main() { print!("{}", 1); println!("{:?}", "a"); }"#)
+        .expect("program to run successfully");
+}
+
+#[test]
+fn test_format_macro_errors() {
+    let cases = [
+        (r#"fn main() { format!("{}") }"#, "positional argument 0"),
+        (r#"fn main() { format!("{}", 1, 2) }"#, "never used"),
+        (
+            r#"fn main() { format!("{a}", a = 1, b = 2) }"#,
+            "`b` never used",
+        ),
+        (r#"fn main() { format!("{") }"#, "unterminated"),
+        (r#"fn main() { format!("}") }"#, "unmatched"),
+    ];
+
+    for (source, expected) in cases.iter() {
+        let error = run_with_macros::<String>(source).unwrap_err();
+        let message = format!("{:?}", error);
+        assert!(message.contains(expected), "{}: {}", source, message);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::testing::*;
+use runestick::{Future, TypedFunction};
+use std::sync::Arc;
 
 #[test]
 fn test_function() {
@@ -68,3 +70,82 @@ fn test_function() {
     let value: Value = function.call(()).unwrap();
     assert!(matches!(value, Value::Integer(3)));
 }
+
+#[test]
+fn test_function_async_call() {
+    // async function which awaits another function.
+    let function = rune! {
+        Function => r#"
+        async fn double(n) { n * 2 }
+        async fn foo(n) { double(n).await + 1 }
+        fn main() { foo }
+        "#
+    };
+
+    let value: i64 = block_on(function.async_call((2i64,))).unwrap();
+    assert_eq!(value, 5);
+    assert!(function.call::<_, Future>((2i64,)).is_ok());
+
+    // closure which selects over futures it captures.
+    let function: Function = run(
+        &["main"],
+        (3i64,),
+        r#"
+        async fn value(n) { n }
+
+        fn main(n) {
+            async || {
+                let a = value(n);
+                let b = value(n + 1);
+
+                select {
+                    v = a => v,
+                    v = b => v,
+                }
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    let value: i64 = block_on(function.async_call(())).unwrap();
+    assert_eq!(value, 3);
+
+    // immediate functions and native functions can be called as well.
+    let function = rune! {
+        Function => r#"fn main() { |a, b| a + b }"#
+    };
+
+    let value: i64 = block_on(function.async_call((1i64, 2i64))).unwrap();
+    assert_eq!(value, 3);
+}
+
+#[test]
+fn test_function_async_callback() {
+    async fn call_twice(f: TypedFunction<(i64,), i64>) -> Result<i64, VmError> {
+        Ok(f.async_call((1,)).await? + f.async_call((2,)).await?)
+    }
+
+    let mut module = runestick::Module::new(&["test"]);
+    module.async_function(&["call_twice"], call_twice).unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        async fn scale(n) { n * 10 }
+
+        async fn main() {
+            let offset = 1;
+            test::call_twice(async |n| scale(n).await + offset).await
+        }
+        "#,
+    )
+    .unwrap();
+
+    let vm = runestick::Vm::new(Arc::new(context), Arc::new(unit));
+    let output = block_on(vm.async_call(&["main"], ())).unwrap();
+    assert_eq!(i64::from_value(output).unwrap(), 32);
+}
@@ -653,6 +653,153 @@ fn test_break_label() {
     };
 }
 
+#[test]
+fn test_continue() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 0;
+                let sum = 0;
+
+                while n < 10 {
+                    n = n + 1;
+                    let odd = n % 2 == 1;
+
+                    if odd {
+                        continue;
+                    }
+
+                    sum = sum + n;
+                }
+
+                sum
+            }
+            "#
+        },
+        30,
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 0;
+                let sum = 0;
+
+                loop {
+                    let current = n;
+                    n = n + 1;
+
+                    if current > 10 {
+                        break;
+                    }
+
+                    if current % 3 != 0 {
+                        continue;
+                    }
+
+                    sum = sum + current;
+                }
+
+                sum
+            }
+            "#
+        },
+        18,
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            use std::iter::range;
+
+            fn main() {
+                let sum = 0;
+
+                for n in range(0, 10) {
+                    let half = n / 2;
+
+                    if n % 2 == 0 {
+                        continue;
+                    }
+
+                    sum = sum + half;
+                }
+
+                sum
+            }
+            "#
+        },
+        10,
+    };
+}
+
+#[test]
+fn test_continue_label() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            use std::iter::range;
+
+            fn main() {
+                let count = 0;
+
+                'outer:
+                for a in range(0, 5) {
+                    let value = a;
+
+                    for b in range(0, 5) {
+                        let product = value * b;
+
+                        if b > a {
+                            continue 'outer;
+                        }
+
+                        count = count + 1;
+                    }
+                }
+
+                count
+            }
+            "#
+        },
+        15,
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let values = [Some(1), Some(2), None, Some(3)];
+                let n = 0;
+                let sum = 0;
+
+                'outer:
+                while n < 4 {
+                    let current = values[n];
+                    n = n + 1;
+
+                    loop {
+                        if let Some(value) = current {
+                            sum = sum + value;
+                            continue 'outer;
+                        }
+
+                        break;
+                    }
+
+                    sum = sum + 100;
+                }
+
+                sum
+            }
+            "#
+        },
+        106,
+    };
+}
+
 #[test]
 fn test_literal() {
     assert_eq! {
@@ -0,0 +1,201 @@
+use crate::testing::*;
+
+#[test]
+fn test_iter_adapters() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                [1, 2, 3, 4, 5, 6]
+                    .iter()
+                    .filter(|n| n % 2 == 0)
+                    .map(|n| n * 10)
+                    .collect_vec()
+            }
+            "#
+        },
+        vec![20, 40, 60],
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<(i64, i64)> => r#"
+            fn main() {
+                (0..10).skip(2).take(3).enumerate().collect_vec()
+            }
+            "#
+        },
+        vec![(0, 2), (1, 3), (2, 4)],
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                (0..3).map(|n| n * 2).collect()
+            }
+            "#
+        },
+        vec![0, 2, 4],
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                [1, 2].iter()
+                    .chain([3])
+                    .flat_map(|n| [n, n * 100])
+                    .filter_map(|n| if n != 200 { Some(n + 1) } else { None })
+                    .collect_vec()
+            }
+            "#
+        },
+        vec![2, 101, 3, 4, 301],
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<(i64, String)> => r#"
+            fn main() {
+                [1, 2, 3].iter().rev().zip(["a", "b"]).collect_vec()
+            }
+            "#
+        },
+        vec![(3, String::from("a")), (2, String::from("b"))],
+    };
+}
+
+#[test]
+fn test_iter_consumers() {
+    assert_eq! {
+        rune! {
+            (i64, bool, bool, Option<i64>, usize, f64) => r#"
+            fn main() {
+                let values = [1, 2, 3, 4];
+
+                (
+                    values.fold(0, |acc, n| acc + n),
+                    values.any(|n| n > 3),
+                    values.all(|n| n > 3),
+                    values.find(|n| n > 2),
+                    values.iter().filter(|n| n > 1).count(),
+                    [1.5, 2.5].iter().sum(),
+                )
+            }
+            "#
+        },
+        (10, true, false, Some(3), 3, 4.0),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, String, i64) => r#"
+            fn main() {
+                let object = [("a", 1), ("b", 2)].iter().collect_object();
+                let string = ['a', 'b'].iter().chain(["cd"]).collect_string();
+                (object.b, string, [].iter().sum())
+            }
+            "#
+        },
+        (2, String::from("abcd"), 0),
+    };
+}
+
+#[test]
+fn test_iter_generator() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn numbers() {
+                yield 1;
+                yield 2;
+                yield 3;
+            }
+
+            fn main() {
+                let out = [];
+
+                for n in numbers() {
+                    out.push(n);
+                }
+
+                numbers().map(|n| n * 2).chain(out).collect_vec()
+            }
+            "#
+        },
+        vec![2, 4, 6, 1, 2, 3],
+    };
+}
+
+#[test]
+fn test_iter_protocol() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, i64, i64) => r#"
+            struct Counter { n }
+
+            impl Counter {
+                #[protocol]
+                fn next(self) {
+                    if self.n < 3 {
+                        self.n += 1;
+                        Some(self.n)
+                    } else {
+                        None
+                    }
+                }
+            }
+
+            struct Numbers { values }
+
+            impl Numbers {
+                #[protocol]
+                fn into_iter(self) {
+                    self.values.iter()
+                }
+            }
+
+            fn main() {
+                let counter = Counter { n: 0 };
+                let numbers = Numbers { values: [10, 20] };
+                let doubled = counter.map(|n| n * 2).collect_vec();
+
+                let counter = Counter { n: 1 };
+                let sum = 0;
+
+                for n in counter {
+                    sum += n;
+                }
+
+                let counter = Counter { n: 0 };
+                let product = numbers.zip(counter.iter()).fold(0, |a, p| a + p.0 * p.1);
+                (doubled, product, sum)
+            }
+            "#
+        },
+        (vec![2, 4, 6], 10 + 40, 2 + 3),
+    };
+}
+
+#[test]
+fn test_iter_errors() {
+    assert_vm_error!(
+        r#"fn main() { let n = 42; n.map(|n| n) }"#,
+        MissingInstanceFunction { .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { [1].iter().map(|n| n).rev() }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), NotDoubleEndedIterator { name: "Map" }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { [1].iter().zip(42) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), UnsupportedIterator { .. }));
+        }
+    );
+}
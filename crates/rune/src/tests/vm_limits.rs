@@ -0,0 +1,541 @@
+use crate::testing::*;
+use runestick::{Vm, VmHaltInfo, VmLimits};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Compile the given source into a virtual machine with the given limits.
+fn vm(source: &str, limits: &VmLimits) -> Vm {
+    let context = runestick::Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.set_limits(limits.clone());
+    vm
+}
+
+#[test]
+fn test_fuel() {
+    let limits = VmLimits::new();
+    limits.set_fuel(Some(100));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            let n = 0;
+
+            while n < 1000 {
+                n += 1;
+            }
+
+            n
+        }
+        "#,
+        &limits,
+    );
+
+    let mut execution = vm.execute(["main"], ()).unwrap();
+    let mut refuels = 0;
+
+    let output = loop {
+        match execution.complete() {
+            Ok(output) => break output,
+            Err(error) => match error.as_unwound().0 {
+                Halted {
+                    halt: VmHaltInfo::OutOfFuel,
+                } => {
+                    assert_eq!(limits.fuel(), Some(0));
+                    limits.add_fuel(100);
+                    refuels += 1;
+                }
+                kind => panic!("unexpected error: {:?}", kind),
+            },
+        }
+    };
+
+    assert_eq!(output.into_integer().unwrap(), 1000);
+    assert!(refuels > 10);
+}
+
+#[test]
+fn test_fuel_in_closures() {
+    let limits = VmLimits::new();
+    limits.set_fuel(Some(10_000));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            [1, 2, 3].iter().map(|n| { loop { n += 1; } }).collect_vec()
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    // NB: closures called from native functions run to completion, so they
+    // can't be resumed.
+    match error.as_unwound().0 {
+        BadReturn { error, .. } => match error.kind() {
+            OutOfFuel => (),
+            kind => panic!("unexpected error: {:?}", kind),
+        },
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert_eq!(limits.fuel(), Some(0));
+}
+
+#[test]
+fn test_fuel_in_protocols() {
+    let limits = VmLimits::new();
+    limits.set_fuel(Some(10_000));
+
+    let vm = vm(
+        r#"
+        struct Point { x }
+
+        impl Point {
+            #[protocol]
+            fn eq(self, other) {
+                loop {}
+            }
+        }
+
+        fn main() {
+            Point { x: 1 } == Point { x: 2 }
+        }
+        "#,
+        &limits,
+    );
+
+    let mut execution = vm.execute(["main"], ()).unwrap();
+    let error = execution.complete().unwrap_err();
+
+    // NB: protocols are run to completion in a nested virtual machine, so
+    // the execution calling them can't be resumed.
+    match error.as_unwound().0 {
+        OutOfFuel => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert_eq!(limits.fuel(), Some(0));
+}
+
+#[test]
+fn test_fuel_in_async_callbacks() {
+    async fn call(f: runestick::Function) -> Result<Value, runestick::VmError> {
+        f.async_call(()).await
+    }
+
+    let mut module = runestick::Module::new(&["test"]);
+    module.async_function(&["call"], call).unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        async fn main() {
+            test::call(|| {
+                let n = 0;
+
+                while n < 100000 {
+                    n += 1;
+                }
+
+                n
+            }).await
+        }
+        "#,
+    )
+    .unwrap();
+
+    let limits = VmLimits::new();
+    limits.set_fuel(Some(1000));
+
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.set_limits(limits.clone());
+
+    // NB: the closure is called from a native future, which is polled outside
+    // of the virtual machine awaiting it.
+    let error = futures_executor::block_on(vm.async_call(["main"], ())).unwrap_err();
+
+    match error.as_unwound().0 {
+        OutOfFuel => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert_eq!(limits.fuel(), Some(0));
+}
+
+#[test]
+fn test_deadline() {
+    let limits = VmLimits::new();
+    limits.set_timeout(Duration::from_millis(10));
+
+    let vm = vm(r#"fn main() { loop {} }"#, &limits);
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        DeadlineExceeded => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_max_call_frames() {
+    let limits = VmLimits::new();
+    limits.set_max_call_frames(Some(64));
+
+    let source = r#"
+    fn depth(n, limit) {
+        if n == limit {
+            return n;
+        }
+
+        depth(n + 1, limit)
+    }
+
+    fn main(limit) {
+        depth(0, limit)
+    }
+    "#;
+
+    let output = vm(source, &limits).call(["main"], (63i64,)).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 63);
+
+    let error = vm(source, &limits).call(["main"], (64i64,)).unwrap_err();
+
+    match error.as_unwound().0 {
+        CallFrameLimitExceeded { limit } => assert_eq!(*limit, 64),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_max_call_frames_through_native() {
+    let limits = VmLimits::new();
+    limits.set_max_call_frames(Some(64));
+
+    let vm = vm(
+        r#"
+        fn recurse(n) {
+            [n].iter().map(|n| recurse(n + 1)).collect_vec()
+        }
+
+        fn main() {
+            recurse(0)
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("call frame limit of 64 exceeded"));
+}
+
+#[test]
+fn test_max_memory() {
+    let limits = VmLimits::new();
+    limits.set_max_memory(Some(64 * 1024));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            let values = [];
+
+            loop {
+                values.push([1, 2, 3]);
+            }
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        MemoryLimitExceeded { limit } => assert_eq!(*limit, 64 * 1024),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert!(limits.peak_memory_usage() > 64 * 1024);
+}
+
+#[test]
+fn test_max_memory_strings() {
+    let limits = VmLimits::new();
+    limits.set_max_memory(Some(64 * 1024));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            let s = String::from_str("hello");
+
+            loop {
+                s += `{s}`;
+            }
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("memory limit of 65536 bytes exceeded"));
+}
+
+#[test]
+fn test_max_memory_hash_map() {
+    for source in &[
+        "let n = 0; loop { map.insert(n, n); n += 1; }",
+        "let n = 0; loop { map[`key {n}`] = n; n += 1; }",
+    ] {
+        let limits = VmLimits::new();
+        limits.set_max_memory(Some(64 * 1024));
+
+        let vm = vm(
+            &format!(
+                "fn main() {{ let map = std::collections::HashMap::new(); {} }}",
+                source
+            ),
+            &limits,
+        );
+
+        let error = vm.call(["main"], ()).unwrap_err();
+
+        match error.as_unwound().0 {
+            MemoryLimitExceeded { limit } => assert_eq!(*limit, 64 * 1024),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
+    }
+}
+
+#[test]
+fn test_max_memory_string_repeat() {
+    let limits = VmLimits::new();
+    limits.set_max_memory(Some(64 * 1024));
+
+    let vm = vm(r#"fn main() { "hello".repeat(1 << 40) }"#, &limits);
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        BadReturn { error, .. } => assert!(matches!(
+            error.kind(),
+            MemoryLimitExceeded { limit } if *limit == 64 * 1024
+        )),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert!(limits.peak_memory_usage() < 64 * 1024);
+}
+
+#[test]
+fn test_max_memory_string_replace() {
+    let limits = VmLimits::new();
+    limits.set_max_memory(Some(1024 * 1024));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            let s = "a".repeat(65536);
+            s.replace("a", s)
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        BadReturn { error, .. } => assert!(matches!(
+            error.kind(),
+            MemoryLimitExceeded { limit } if *limit == 1024 * 1024
+        )),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert!(limits.peak_memory_usage() < 1024 * 1024);
+}
+
+#[test]
+fn test_max_memory_vec_join() {
+    let limits = VmLimits::new();
+    limits.set_max_memory(Some(1024 * 1024));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            let s = "a".repeat(4096);
+            let values = [];
+
+            for n in 0..4096 {
+                values.push(s);
+            }
+
+            values.join(s)
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        BadReturn { error, .. } => assert!(matches!(
+            error.kind(),
+            MemoryLimitExceeded { limit } if *limit == 1024 * 1024
+        )),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert!(limits.peak_memory_usage() < 1024 * 1024);
+}
+
+#[test]
+fn test_max_memory_range_splice() {
+    let limits = VmLimits::new();
+    limits.set_max_memory(Some(64 * 1024));
+
+    let vm = vm(
+        r#"
+        fn main() {
+            let values = [];
+
+            loop {
+                values[0..0] = [1, 2, 3, 4];
+            }
+        }
+        "#,
+        &limits,
+    );
+
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    match error.as_unwound().0 {
+        MemoryLimitExceeded { limit } => assert_eq!(*limit, 64 * 1024),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_memory_usage() {
+    let limits = VmLimits::new();
+
+    let vm = vm(
+        r#"
+        fn build(n) {
+            let values = [];
+
+            for i in 0..n {
+                values.push(#{ value: `{i}` });
+            }
+
+            values.len()
+        }
+
+        fn main() {
+            let kept = [1, 2, 3];
+            let built = build(1000);
+            (kept, built)
+        }
+        "#,
+        &limits,
+    );
+
+    let output = vm.call(["main"], ()).unwrap();
+    let peak = limits.peak_memory_usage();
+    let current = limits.memory_usage();
+
+    assert!(peak > 1000 * std::mem::size_of::<runestick::Value>());
+    assert!(current > 0 && current < peak);
+
+    drop(output);
+    assert_eq!(limits.memory_usage(), 0);
+}
+
+#[test]
+fn test_interrupt() {
+    let vm = vm(
+        r#"
+        fn main() {
+            let n = 0;
+
+            loop {
+                n += 1;
+            }
+        }
+        "#,
+        &VmLimits::new(),
+    );
+
+    let mut execution = vm.execute(["main"], ()).unwrap();
+    let handle = execution.interrupt_handle();
+
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        handle.interrupt();
+    });
+
+    let error = execution.complete().unwrap_err();
+    thread.join().unwrap();
+
+    match error.as_unwound().0 {
+        Halted {
+            halt: VmHaltInfo::Interrupted,
+        } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    // NB: the stack is left as it was for inspection.
+    let n = execution.vm().unwrap().stack().at_offset(0).unwrap();
+    assert!(matches!(n, Value::Integer(n) if *n > 0));
+}
+
+#[test]
+fn test_interrupt_await() {
+    let mut module = runestick::Module::new(&["test"]);
+    module
+        .async_function(&["pending"], std::future::pending::<()>)
+        .unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        async fn wait() {
+            test::pending().await
+        }
+
+        async fn main() {
+            wait().await
+        }
+        "#,
+    )
+    .unwrap();
+
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let handle = vm.interrupt_handle();
+    let mut execution = vm.execute(["main"], ()).unwrap();
+
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        handle.interrupt();
+    });
+
+    let error = block_on(execution.async_complete()).unwrap_err();
+    thread.join().unwrap();
+
+    match error.as_unwound().0 {
+        Halted {
+            halt: VmHaltInfo::Interrupted,
+        } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
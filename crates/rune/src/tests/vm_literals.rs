@@ -36,3 +36,21 @@ fn test_octal() {
         -63,
     };
 }
+
+#[test]
+fn test_float() {
+    assert_eq! {
+        rune!(f64 => r#"fn main() { 0.5 }"#),
+        0.5,
+    };
+
+    assert_eq! {
+        rune!(f64 => r#"fn main() { -0.5 }"#),
+        -0.5,
+    };
+
+    assert_eq! {
+        rune!(f64 => r#"const N = -1.25; fn main() { N * 2.0 }"#),
+        -2.5,
+    };
+}
@@ -0,0 +1,232 @@
+#[test]
+fn test_script_operators() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64) => r#"
+            struct Vec2 { x, y }
+
+            impl Vec2 {
+                #[protocol]
+                fn add(self, other) {
+                    Vec2 { x: self.x + other.x, y: self.y + other.y }
+                }
+
+                #[protocol]
+                fn sub(self, other) {
+                    Vec2 { x: self.x - other.x, y: self.y - other.y }
+                }
+
+                #[protocol]
+                fn mul(self, scale) {
+                    Vec2 { x: self.x * scale, y: self.y * scale }
+                }
+
+                #[protocol]
+                fn div(self, scale) {
+                    Vec2 { x: self.x / scale, y: self.y / scale }
+                }
+            }
+
+            fn main() {
+                let a = Vec2 { x: 1, y: 2 };
+                let b = Vec2 { x: 3, y: 4 };
+                let c = (a + b) * 2;
+                let d = (b - a) / 2;
+                (c.x, c.y, d.x, d.y)
+            }
+            "#
+        },
+        (8, 12, 1, 1),
+    };
+}
+
+#[test]
+fn test_protocol_opt_in() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            struct Vec2 { x, y }
+
+            impl Vec2 {
+                fn add(self, other) {
+                    Vec2 { x: self.x + other.x, y: self.y + other.y }
+                }
+            }
+
+            fn main() {
+                let a = Vec2 { x: 1, y: 2 };
+                a.add(a).y
+            }
+            "#
+        },
+        4,
+    };
+
+    assert_vm_error!(
+        r#"
+        struct Vec2 { x, y }
+
+        impl Vec2 {
+            fn add(self, other) {
+                Vec2 { x: self.x + other.x, y: self.y + other.y }
+            }
+        }
+
+        fn main() {
+            let a = Vec2 { x: 1, y: 2 };
+            a + a
+        }
+        "#,
+        runestick::VmErrorKind::UnsupportedBinaryOperation { op, .. } => {
+            assert_eq!(op, "+");
+        }
+    );
+}
+
+#[test]
+fn test_script_assign_operators() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            struct Counter { value }
+
+            impl Counter {
+                #[protocol]
+                fn add_assign(self, n) {
+                    self.value += n;
+                }
+
+                #[protocol]
+                fn shl_assign(self, n) {
+                    self.value <<= n;
+                }
+            }
+
+            struct Wrapper { counter }
+
+            fn main() {
+                let a = Counter { value: 1 };
+                a += 2;
+                a <<= 2;
+
+                let w = Wrapper { counter: Counter { value: 10 } };
+                w.counter += 5;
+
+                let rest = #{ counter: Counter { value: 0 } };
+                rest.counter += 7;
+
+                (a.value, w.counter.value, rest.counter.value)
+            }
+            "#
+        },
+        (12, 15, 7),
+    };
+}
+
+#[test]
+fn test_bitwise_operators() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64, i64) => r#"
+            struct Flags { bits }
+
+            impl Flags {
+                #[protocol]
+                fn bit_and(self, other) { Flags { bits: self.bits & other.bits } }
+                #[protocol]
+                fn bit_or(self, other) { Flags { bits: self.bits | other.bits } }
+                #[protocol]
+                fn bit_xor(self, other) { Flags { bits: self.bits ^ other.bits } }
+                #[protocol]
+                fn shl(self, n) { Flags { bits: self.bits << n } }
+                #[protocol]
+                fn shr(self, n) { Flags { bits: self.bits >> n } }
+                #[protocol]
+                fn rem(self, n) { Flags { bits: self.bits % n } }
+            }
+
+            fn main() {
+                let a = Flags { bits: 0b1100 };
+                let b = Flags { bits: 0b1010 };
+                ((a & b).bits, (a | b).bits, (a ^ b).bits, (a << 1).bits, (a >> 2).bits, (a % 5).bits)
+            }
+            "#
+        },
+        (0b1000, 0b1110, 0b0110, 0b11000, 0b11, 2),
+    };
+}
+
+#[test]
+fn test_trait_operators() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            trait Sum {
+                fn value(self);
+
+                #[protocol]
+                fn add(self, other) {
+                    self.value() + other.value()
+                }
+            }
+
+            struct One;
+            struct Two;
+
+            impl Sum for One {
+                fn value(self) { 1 }
+            }
+
+            impl Sum for Two {
+                fn value(self) { 2 }
+            }
+
+            fn main() {
+                (One + Two) + (Two + Two)
+            }
+            "#
+        },
+        7,
+    };
+}
+
+#[test]
+fn test_script_comparison_protocols() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool) => r#"
+            struct Version { major, minor, label }
+
+            impl Version {
+                #[protocol]
+                fn eq(self, other) {
+                    self.major == other.major && self.minor == other.minor
+                }
+
+                #[protocol]
+                fn partial_cmp(self, other) {
+                    if self.major != other.major {
+                        Some(self.major - other.major)
+                    } else {
+                        Some(self.minor - other.minor)
+                    }
+                }
+
+                #[protocol]
+                fn hash(self) {
+                    self.major * 1000 + self.minor
+                }
+            }
+
+            fn main() {
+                let a = Version { major: 1, minor: 2, label: "a" };
+                let b = Version { major: 1, minor: 2, label: "b" };
+                let c = Version { major: 1, minor: 10, label: "c" };
+
+                (a == b, a != c, a < c, c >= b, [a] == [b], a.hash() == b.hash())
+            }
+            "#
+        },
+        (true, true, true, true, true, true),
+    };
+}
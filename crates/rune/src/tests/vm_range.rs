@@ -0,0 +1,310 @@
+use crate::testing::*;
+
+#[test]
+fn test_range_iter() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 0;
+
+                for i in 0..10 {
+                    n += i;
+                }
+
+                for i in 0..=10 {
+                    n += i;
+                }
+
+                let end = 3;
+
+                for i in 1 + 1..end + 1 {
+                    n += i;
+                }
+
+                n
+            }
+            "#
+        },
+        45 + 55 + 2 + 3,
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                let out = String::new();
+
+                for c in 'a'..='e' {
+                    out.push(c);
+                }
+
+                for c in 'x'..'z' {
+                    out.push(c);
+                }
+
+                out
+            }
+            "#
+        },
+        "abcdexy",
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 0;
+
+                for i in 10.. {
+                    if i == 15 {
+                        break;
+                    }
+
+                    n += 1;
+                }
+
+                n
+            }
+            "#
+        },
+        5,
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                let values = [];
+
+                for i in 9223372036854775806.. {
+                    values.push(i);
+                }
+
+                values
+            }
+            "#
+        },
+        vec![i64::MAX - 1, i64::MAX],
+    };
+}
+
+#[test]
+fn test_range_values() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool) => r#"
+            fn main() {
+                let a = 1..5;
+                let b = ..=5;
+                let c = ..;
+
+                (
+                    a.contains(1),
+                    a.contains(5),
+                    b.contains(5),
+                    b.contains(-100),
+                    c.contains("anything"),
+                    a.contains('a'),
+                )
+            }
+            "#
+        },
+        (true, false, true, true, true, false),
+    };
+
+    assert_eq! {
+        rune! {
+            bool => r#"
+            fn main() {
+                let a = 1..=5;
+                let b = a;
+                b is std::ops::Range
+            }
+            "#
+        },
+        true,
+    };
+}
+
+#[test]
+fn test_range_slices() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, Vec<i64>, Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3, 4, 5];
+                (v[1..3], v[..2], v[3..], v[1..=3], v[..])
+            }
+            "#
+        },
+        (vec![2, 3], vec![1, 2], vec![4, 5], vec![2, 3, 4], vec![1, 2, 3, 4, 5]),
+    };
+
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            fn main() {
+                let s = "hello world";
+                (s[..5], s[6..], "åäö"[2..4])
+            }
+            "#
+        },
+        (String::from("hello"), String::from("world"), String::from("ä")),
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<u8> => r#"
+            fn main() {
+                let b = b"hello";
+                let b = b[1..3];
+                b.into_vec()
+            }
+            "#
+        },
+        b"el".to_vec(),
+    };
+}
+
+#[test]
+fn test_range_slice_errors() {
+    assert_vm_error!(
+        r#"fn main() { let v = [1, 2, 3]; v[1..4] }"#,
+        SliceOutOfBounds { len, .. } => {
+            assert_eq!(len, 3);
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let v = [1, 2, 3]; v[2..1] }"#,
+        SliceOutOfBounds { .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { "åäö"[1..] }"#,
+        NotCharBoundary { index } => {
+            assert_eq!(index, 1);
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { for i in ..10 {} }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), UnboundedRangeIter));
+        }
+    );
+}
+
+#[test]
+fn test_range_splice() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let a = [1, 2, 3, 4, 5];
+                a[1..3] = [9];
+
+                let b = [1, 2, 3];
+                b[1..1] = [7, 8];
+
+                let c = [1, 2, 3];
+                c[..] = c;
+
+                (a, b, c)
+            }
+            "#
+        },
+        (vec![1, 9, 4, 5], vec![1, 7, 8, 2, 3], vec![1, 2, 3]),
+    };
+
+    assert_eq! {
+        rune! {
+            (String, Vec<u8>) => r#"
+            fn main() {
+                let s = String::from_str("hello world");
+                s[..5] = "goodbye";
+
+                let b = b"hello";
+                b[1..=3] = b"ipp";
+
+                (s, b.into_vec())
+            }
+            "#
+        },
+        (String::from("goodbye world"), b"hippo".to_vec()),
+    };
+}
+
+#[test]
+fn test_range_splice_errors() {
+    assert_vm_error!(
+        r#"fn main() { let v = [1, 2, 3]; v[2..5] = [1]; }"#,
+        SliceOutOfBounds { len, .. } => {
+            assert_eq!(len, 3);
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let s = String::from_str("åäö"); s[..1] = "a"; }"#,
+        NotCharBoundary { index } => {
+            assert_eq!(index, 1);
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let v = [1, 2, 3]; v[..] = 42; }"#,
+        Expected { actual, .. } => {
+            assert_eq!(actual.to_string(), "integer");
+        }
+    );
+}
+
+#[test]
+fn test_range_patterns() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64) => r#"
+            fn classify(n) {
+                match n {
+                    -10..0 => 0,
+                    0..10 => 1,
+                    10..=20 => 2,
+                    'a'..='z' => 3,
+                    21.. => 4,
+                    _ => 5,
+                }
+            }
+
+            fn main() {
+                (classify(5), classify(20), classify('q'), classify(1000), classify("x"))
+            }
+            "#
+        },
+        (1, 2, 3, 4, 5),
+    };
+}
+
+#[test]
+fn test_range_precedence() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool) => r#"
+            fn main() {
+                let r = 1 + 1..2 * 3;
+                let n = 0;
+                n = ..10;
+                (r.contains(1), r.contains(2), r.contains(6), n.contains(9))
+            }
+            "#
+        },
+        (false, true, false, true),
+    };
+
+    assert_parse_error! {
+        r#"fn main() { 1..2..3 }"#,
+        span, PrecedenceGroupRequired => {
+            assert_eq!(span, Span::new(12, 16));
+        }
+    };
+}
@@ -0,0 +1,178 @@
+use crate::testing::*;
+use runestick::{SendValue, Vm};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn test_send_value_roundtrip() {
+    let value: Value = rune! {
+        Value => r#"
+        struct Point { x, y }
+        enum Shape { Circle(radius) }
+
+        fn main() {
+            let object = #{a: [1, 2.5, 'c'], b: Some(Ok(b"bytes"))};
+            (Point { x: 1, y: 2 }, Shape::Circle(3), object, 1..=3, "string")
+        }
+        "#
+    };
+
+    let sendable = value.to_sendable().unwrap();
+    let sendable = thread::spawn(move || sendable).join().unwrap();
+    let value = sendable.into_value();
+
+    let output: String = run(&["main"], (value,), r#"fn main(value) { `{value:?}` }"#).unwrap();
+
+    assert_eq!(
+        output,
+        r#"(Point { x: 1, y: 2 }, Shape::Circle(3), #{"a": [1, 2.5, 'c'], "b": Some(Ok(b"bytes"))}, 1..=3, "string")"#
+    );
+}
+
+#[test]
+fn test_send_value_unsupported() {
+    let value: Value = rune! {
+        Value => r#"fn main() { let a = 1; [|| a] }"#
+    };
+
+    let error = value.to_sendable().unwrap_err();
+
+    match error.into_kind() {
+        UnsupportedSendValue { actual } => assert_eq!(actual.to_string(), "Function"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_send_value_threads() {
+    let context = Arc::new(runestick::Context::with_default_modules().unwrap());
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn main(values) {
+            let sum = 0;
+
+            for v in values {
+                sum += v;
+            }
+
+            #{sum: sum, count: values.len()}
+        }
+        "#,
+    )
+    .unwrap();
+
+    let unit = Arc::new(unit);
+    let mut handles = Vec::new();
+
+    for n in 1..=4i64 {
+        let context = context.clone();
+        let unit = unit.clone();
+        let input = SendValue::Vec((0..n).map(SendValue::Integer).collect());
+
+        handles.push(thread::spawn(move || {
+            let vm = Vm::new(context, unit);
+            let output = vm.call(["main"], (input.into_value(),)).unwrap();
+            output.to_sendable().unwrap()
+        }));
+    }
+
+    let mut outputs = Vec::new();
+
+    for handle in handles {
+        let object = handle.join().unwrap().into_value().into_object().unwrap();
+        let object = object.borrow_ref().unwrap();
+        let sum = object.get("sum").unwrap().clone().into_integer().unwrap();
+        let count = object.get("count").unwrap().clone().into_integer().unwrap();
+        outputs.push((sum, count));
+    }
+
+    assert_eq!(outputs, vec![(0, 1), (1, 2), (3, 3), (6, 4)]);
+}
+
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[test]
+fn test_send_execution() {
+    let context = Arc::new(runestick::Context::with_default_modules().unwrap());
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        async fn double(values) {
+            values.iter().map(|v| v * 2).collect_vec()
+        }
+
+        async fn main(values, name) {
+            #{name: name, values: double(values).await}
+        }
+        "#,
+    )
+    .unwrap();
+
+    let mut vm = Vm::new(context, Arc::new(unit));
+    let limits = runestick::VmLimits::new();
+    limits.set_fuel(Some(10_000));
+    vm.set_limits(limits.clone());
+
+    // Safety: the default modules only have native types and futures which
+    // are `Send`.
+    let execution = unsafe {
+        vm.send_execute(["main"], (vec![1i64, 2, 3], String::from("doubled")))
+            .unwrap()
+    };
+
+    let execution = assert_send(execution);
+
+    let output = thread::spawn(move || {
+        let future = assert_send(execution.async_complete());
+        futures_executor::block_on(future)
+    });
+
+    let output = output.join().unwrap().unwrap();
+    let output: String = run(
+        &["main"],
+        (output.into_value(),),
+        r#"fn main(value) { `{value:?}` }"#,
+    )
+    .unwrap();
+
+    assert!(output.contains(r#""name": "doubled""#));
+    assert!(output.contains(r#""values": [2, 4, 6]"#));
+
+    // NB: the execution runs with a copy of the limits.
+    assert_eq!(limits.fuel(), Some(10_000));
+}
+
+/// A native type which isn't `Send`.
+#[derive(runestick::Any)]
+struct Local(std::marker::PhantomData<std::rc::Rc<()>>);
+
+#[test]
+fn test_send_execution_not_send() {
+    let mut module = runestick::Module::new(&["test"]);
+    module.ty::<Local>().unwrap();
+    module
+        .function(&["local"], || Local(std::marker::PhantomData))
+        .unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = compile_source(&context, r#"fn main() { test::local() }"#).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+
+    // Safety: the execution constructs a `Local`, so it's completed on this
+    // thread instead of being sent to another one.
+    let execution = unsafe { vm.send_execute(["main"], ()).unwrap() };
+    let error = futures_executor::block_on(execution.async_complete()).unwrap_err();
+
+    // NB: the `Local` can't escape the execution through its output.
+    match error.into_kind() {
+        UnsupportedSendValue { actual } => assert_eq!(actual.to_string(), "Local"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
@@ -0,0 +1,160 @@
+use crate::testing::*;
+
+#[test]
+fn test_string_split() {
+    assert_eq! {
+        rune! {
+            (Vec<String>, Vec<String>, Vec<String>, Vec<String>) => r#"
+            fn main() {
+                (
+                    "a,b,,c".split(',').collect_vec(),
+                    "a::b".split("::").collect_vec(),
+                    "  a \t b\n c ".split_whitespace().collect_vec(),
+                    "one\ntwo\r\nthree".lines().rev().collect_vec(),
+                )
+            }
+            "#
+        },
+        (
+            vec![String::from("a"), String::from("b"), String::new(), String::from("c")],
+            vec![String::from("a"), String::from("b")],
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            vec![String::from("three"), String::from("two"), String::from("one")],
+        ),
+    };
+}
+
+#[test]
+fn test_string_search() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, Option<usize>, Option<usize>, Option<usize>) => r#"
+            fn main() {
+                let s = "häj hopp";
+
+                (
+                    s.starts_with("hä"),
+                    s.ends_with('p'),
+                    s.contains("j h"),
+                    s.contains('x'),
+                    s.find("hopp"),
+                    s.find('j'),
+                    s.find("x"),
+                )
+            }
+            "#
+        },
+        (true, true, true, false, Some(5), Some(3), None),
+    };
+}
+
+#[test]
+fn test_string_transform() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String, String, String, String) => r#"
+            fn main() {
+                let s = "  Åke Ölund  ";
+
+                (
+                    s.trim(),
+                    s.trim_start(),
+                    s.trim_end(),
+                    s.trim().to_lowercase(),
+                    s.trim().to_uppercase(),
+                    "ab".repeat(3),
+                    "a-b-c".replace('-', "::"),
+                )
+            }
+            "#
+        },
+        (
+            String::from("Åke Ölund"),
+            String::from("Åke Ölund  "),
+            String::from("  Åke Ölund"),
+            String::from("åke ölund"),
+            String::from("ÅKE ÖLUND"),
+            String::from("ababab"),
+            String::from("a::b::c"),
+        ),
+    };
+}
+
+#[test]
+fn test_string_chars_bytes() {
+    assert_eq! {
+        rune! {
+            (Vec<char>, Vec<u8>, usize, String) => r#"
+            fn main() {
+                let s = "aö";
+                let reversed = s.chars().rev().collect_string();
+                (s.chars().collect_vec(), s.bytes().collect_vec(), s.len(), reversed)
+            }
+            "#
+        },
+        (vec!['a', 'ö'], vec![b'a', 0xc3, 0xb6], 3, String::from("öa")),
+    };
+}
+
+#[test]
+fn test_string_parse() {
+    assert_eq! {
+        rune! {
+            (i64, f64, char, bool, bool) => r#"
+            fn main() {
+                let n = " 42 ".trim().parse_int()?;
+                let f = "1.5".parse_float()?;
+                let c = "ö".parse_char()?;
+                (n, f, c, "4x".parse_int().is_err(), "ab".parse_char().is_err())
+            }
+            "#
+        },
+        (42, 1.5, 'ö', true, true),
+    };
+}
+
+#[test]
+fn test_string_repeat_overflow() {
+    assert_vm_error!(
+        r#"fn main() { "abc".repeat(9223372036854775807) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), Overflow));
+        }
+    );
+}
+
+#[test]
+fn test_vec_join() {
+    assert_eq! {
+        rune! {
+            (String, String) => r#"
+            fn main() {
+                let words = "b a c".split(' ').collect_vec();
+                (words.join(", "), [].join("-"))
+            }
+            "#
+        },
+        (String::from("b, a, c"), String::new()),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { ["a", 1].join("") }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), Expected { .. }));
+        }
+    );
+}
+
+#[test]
+fn test_string_replace() {
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            fn main() {
+                ("a-b-c".replace("-", "--"), "aéa".replace('é', "e"), "ab".replace("", "."))
+            }
+            "#
+        },
+        (String::from("a--b--c"), String::from("aea"), String::from(".a.b.")),
+    };
+}
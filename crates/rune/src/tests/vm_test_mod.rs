@@ -6,12 +6,12 @@ fn test_nested_mods() {
             i64 => r#"
             mod hello {
                 mod inner {
-                    fn test() {
+                    pub fn test() {
                         2
                     }
                 }
 
-                fn test() {
+                pub fn test() {
                     1 + inner::test()
                 }
             }
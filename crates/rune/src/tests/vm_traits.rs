@@ -0,0 +1,227 @@
+#[test]
+fn test_trait_default_functions() {
+    assert_eq! {
+        rune! {
+            (String, String, i64) => r#"
+            trait Shape {
+                fn area(self);
+
+                fn describe(self) {
+                    `shape with area {self.area()}`
+                }
+            }
+
+            struct Square { side }
+            struct Circle { r }
+
+            impl Shape for Square {
+                fn area(self) {
+                    self.side * self.side
+                }
+            }
+
+            impl Shape for Circle {
+                fn area(self) {
+                    self.r * self.r * 3
+                }
+
+                fn describe(self) {
+                    `circle with area {self.area()}`
+                }
+            }
+
+            fn main() {
+                let square = Square { side: 2 };
+                let circle = Circle { r: 2 };
+                (square.describe(), circle.describe(), square.area() + circle.area())
+            }
+            "#
+        },
+        (
+            String::from("shape with area 4"),
+            String::from("circle with area 12"),
+            16
+        ),
+    };
+}
+
+#[test]
+fn test_trait_in_module() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            mod shapes {
+                trait Shape {
+                    fn sides(self);
+
+                    fn corners(self) {
+                        self.sides()
+                    }
+                }
+            }
+
+            struct Triangle;
+
+            impl shapes::Shape for Triangle {
+                fn sides(self) {
+                    3
+                }
+            }
+
+            fn main() {
+                Triangle.corners()
+            }
+            "#
+        },
+        3,
+    };
+}
+
+#[test]
+fn test_trait_imported() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            mod shapes {
+                pub trait Shape {
+                    fn sides(self);
+
+                    fn corners(self) {
+                        self.sides()
+                    }
+                }
+            }
+
+            mod polygons {
+                use shapes::Shape;
+
+                pub struct Square;
+
+                impl Shape for Square {
+                    fn sides(self) {
+                        4
+                    }
+                }
+            }
+
+            use shapes::Shape;
+
+            struct Triangle;
+
+            impl Shape for Triangle {
+                fn sides(self) {
+                    3
+                }
+            }
+
+            fn main() {
+                (Triangle.corners(), polygons::Square.corners())
+            }
+            "#
+        },
+        (3, 4),
+    };
+}
+
+#[test]
+fn test_script_protocols() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, String) => r#"
+            struct Countdown { n }
+
+            impl Countdown {
+                #[protocol]
+                fn into_iter(self) {
+                    self
+                }
+
+                #[protocol]
+                fn next(self) {
+                    if self.n == 0 {
+                        return None;
+                    }
+
+                    self.n -= 1;
+                    Some(self.n)
+                }
+
+                #[protocol]
+                fn string_display(self, buf) {
+                    buf.push_str(`Countdown({self.n})`);
+                }
+            }
+
+            fn main() {
+                let out = [];
+
+                for n in (Countdown { n: 3 }) {
+                    out.push(n);
+                }
+
+                let countdown = Countdown { n: 5 };
+                (out, `{countdown}!`)
+            }
+            "#
+        },
+        (vec![2, 1, 0], String::from("Countdown(5)!")),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            struct Doubler { last }
+
+            impl Doubler {
+                #[protocol]
+                fn index_get(self, index) {
+                    index * 2
+                }
+
+                #[protocol]
+                fn index_set(self, index, value) {
+                    self.last = index + value;
+                }
+            }
+
+            fn main() {
+                let d = Doubler { last: 0 };
+                d[1] = 10;
+                (d[2], d[21], d.last)
+            }
+            "#
+        },
+        (4, 42, 11),
+    };
+}
+
+#[test]
+fn test_trait_protocols() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            trait Named {
+                fn name(self);
+
+                #[protocol]
+                fn string_display(self, buf) {
+                    buf.push_str(`<{self.name()}>`);
+                }
+            }
+
+            struct Dog;
+
+            impl Named for Dog {
+                fn name(self) {
+                    "dog"
+                }
+            }
+
+            fn main() {
+                `{Dog} and {Dog}`
+            }
+            "#
+        },
+        "<dog> and <dog>",
+    };
+}
@@ -0,0 +1,187 @@
+use crate::testing::*;
+
+#[test]
+fn test_vec_modify() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, i64, Vec<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                v.insert(0, 0);
+                v.insert(4, 4);
+                let removed = v.remove(2);
+                v.extend([5, 6]);
+                v.extend(7..9);
+                let tail = v.split_off(5);
+                (v, removed, tail)
+            }
+            "#
+        },
+        (vec![0, 1, 3, 4, 5], 2, vec![6, 7, 8]),
+    };
+
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let a = [1, 1, 2, 3, 3, 3, 1];
+                a.dedup();
+                a.reverse();
+
+                let b = [1, 2, 3, 4, 5, 6];
+                b.retain(|n| n % 3 != 0);
+                (a, b)
+            }
+            "#
+        },
+        (vec![1, 3, 2, 1], vec![1, 2, 4, 5]),
+    };
+}
+
+#[test]
+fn test_vec_access() {
+    assert_eq! {
+        rune! {
+            (bool, bool, Option<i64>, Option<i64>, Option<i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                (v.contains(2), v.contains("2"), v.get(1), v.get(3), v.first(), v.last(), [].last())
+            }
+            "#
+        },
+        (true, false, Some(2), None, Some(1), Some(3), None),
+    };
+}
+
+#[test]
+fn test_vec_sort() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<String>, Vec<(i64, i64)>) => r#"
+            fn main() {
+                let numbers = [3, 1, 4, 1, 5, 9, 2, 6];
+                numbers.sort();
+
+                let strings = ["b", "c", "a"];
+                strings.sort();
+
+                let pairs = [(2, 1), (1, 2), (2, 0), (1, 1)];
+                pairs.sort_by(|a, b| a.0 - b.0);
+                (numbers, strings, pairs)
+            }
+            "#
+        },
+        (
+            vec![1, 1, 2, 3, 4, 5, 6, 9],
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            vec![(1, 2), (1, 1), (2, 1), (2, 0)],
+        ),
+    };
+
+    assert_eq! {
+        rune! {
+            (Result<usize, usize>, Result<usize, usize>, Result<usize, usize>) => r#"
+            fn main() {
+                let v = [1, 3, 5, 7];
+                (v.binary_search(5), v.binary_search(4), v.binary_search(8))
+            }
+            "#
+        },
+        (Ok(2), Err(2), Err(4)),
+    };
+}
+
+#[test]
+fn test_vec_sort_structs() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            struct Point { x, y }
+
+            fn main() {
+                let points = [Point { x: 2, y: 1 }, Point { x: 1, y: 2 }, Point { x: 1, y: 1 }];
+                points.sort();
+                (points.iter().map(|p| p.x).collect_vec(), points.iter().map(|p| p.y).collect_vec())
+            }
+            "#
+        },
+        (vec![1, 1, 2], vec![1, 2, 1]),
+    };
+
+    assert_eq! {
+        rune! {
+            (Vec<String>, bool, bool, Result<usize, usize>, usize) => r#"
+            struct Version { major, minor, label }
+
+            impl Version {
+                #[protocol]
+                fn eq(self, other) {
+                    self.major == other.major && self.minor == other.minor
+                }
+
+                #[protocol]
+                fn partial_cmp(self, other) {
+                    if self.major != other.major {
+                        Some(self.major - other.major)
+                    } else {
+                        Some(self.minor - other.minor)
+                    }
+                }
+            }
+
+            fn main() {
+                let versions = [
+                    Version { major: 1, minor: 10, label: "c" },
+                    Version { major: 0, minor: 9, label: "a" },
+                    Version { major: 1, minor: 2, label: "b" },
+                ];
+
+                versions.sort();
+
+                let labels = versions.iter().map(|v| v.label).collect_vec();
+                let b = Version { major: 1, minor: 2, label: "other" };
+                let d = Version { major: 2, minor: 0, label: "d" };
+
+                let search = versions.binary_search(b);
+                let contains = (versions.contains(b), versions.contains(d));
+
+                let duplicated = [b, versions[1], versions[1], d];
+                duplicated.dedup();
+
+                (labels, contains.0, contains.1, search, duplicated.len())
+            }
+            "#
+        },
+        (
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            true,
+            false,
+            Ok(1),
+            2,
+        ),
+    };
+}
+
+#[test]
+fn test_vec_errors() {
+    assert_vm_error!(
+        r#"fn main() { let v = [1, 2]; v.remove(2) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), MissingIndex { .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let v = [1, "a"]; v.sort() }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.into_kind(), UnsupportedBinaryOperation { op: "cmp", .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { let v = [2, 1]; v.sort_by(|a, b| { v.push(3); a - b }) }"#,
+        BadArgument { error, .. } => {
+            assert!(matches!(error.into_kind(), AccessError { .. }));
+        }
+    );
+}
@@ -0,0 +1,1222 @@
+//! An optional type checking pass.
+//!
+//! The pass runs over functions before they are compiled and reports values
+//! which are used in a way that is inconsistent with their type annotations.
+//! Values whose type is neither annotated nor can be inferred from the
+//! expression producing them are unknown, and never cause an error.
+
+use crate::ast;
+use crate::collections::HashMap;
+use crate::query::Query;
+use crate::{
+    CompileError, CompileErrorKind, CompileResult, Errors, LoadError, Resolve as _, Spanned as _,
+    Storage,
+};
+use runestick::{
+    CompileMeta, CompileMetaKind, Context, ContextSignature, Hash, Item, Protocol, Source, Span,
+    StaticType, Type, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE, CHAR_TYPE, FLOAT_TYPE, INTEGER_TYPE,
+    OBJECT_TYPE, OPTION_TYPE, RESULT_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
+};
+use std::sync::Arc;
+
+/// The names that builtin types are referred to by in type annotations.
+static BUILTIN_TYPES: &[(&StaticType, &str)] = &[
+    (UNIT_TYPE, "unit"),
+    (BOOL_TYPE, "bool"),
+    (BYTE_TYPE, "byte"),
+    (CHAR_TYPE, "char"),
+    (INTEGER_TYPE, "int"),
+    (FLOAT_TYPE, "float"),
+    (STRING_TYPE, "String"),
+    (BYTES_TYPE, "Bytes"),
+    (VEC_TYPE, "Vec"),
+    (TUPLE_TYPE, "Tuple"),
+    (OBJECT_TYPE, "Object"),
+];
+
+/// A type known to the type checker.
+#[derive(Debug, Clone)]
+struct Ty {
+    /// The type hash of the type.
+    type_of: Type,
+    /// The name of the type, as it's presented in diagnostics.
+    name: String,
+    /// The item of the type, if it's declared as an item.
+    item: Option<Item>,
+    /// If the type is declared natively, as opposed to in a script.
+    native: bool,
+}
+
+impl Ty {
+    /// Construct a builtin type.
+    fn builtin(static_type: &'static StaticType) -> Self {
+        let type_of = Type::from(static_type);
+
+        Self {
+            type_of,
+            name: builtin_name(type_of).unwrap_or_default().to_owned(),
+            item: None,
+            native: true,
+        }
+    }
+
+    /// Construct a type from the meta of an item, if the item is a type.
+    fn from_meta(meta: &CompileMeta) -> Option<Self> {
+        let (type_of, item) = match &meta.kind {
+            CompileMetaKind::Tuple { type_of, tuple } => (*type_of, &tuple.item),
+            CompileMetaKind::Struct { type_of, object } => (*type_of, &object.item),
+            CompileMetaKind::Enum { type_of, item } => (*type_of, item),
+            _ => return None,
+        };
+
+        let name = match builtin_name(type_of) {
+            Some(name) => name.to_owned(),
+            None => item.to_string(),
+        };
+
+        Some(Self {
+            type_of,
+            name,
+            item: Some(item.clone()),
+            native: meta.source.is_none(),
+        })
+    }
+
+    /// Test if the type is the given builtin type.
+    fn is(&self, static_type: &'static StaticType) -> bool {
+        self.type_of == Type::from(static_type)
+    }
+}
+
+/// Get the name of a builtin type.
+fn builtin_name(type_of: Type) -> Option<&'static str> {
+    BUILTIN_TYPES
+        .iter()
+        .find(|(static_type, _)| Type::from(*static_type) == type_of)
+        .map(|(_, name)| *name)
+}
+
+/// Get the protocol used to implement the given binary operation.
+fn protocol(op: ast::BinOp) -> Option<Protocol> {
+    Some(match op {
+        ast::BinOp::Add => runestick::ADD,
+        ast::BinOp::Sub => runestick::SUB,
+        ast::BinOp::Mul => runestick::MUL,
+        ast::BinOp::Div => runestick::DIV,
+        ast::BinOp::Rem => runestick::REM,
+        ast::BinOp::Shl => runestick::SHL,
+        ast::BinOp::Shr => runestick::SHR,
+        ast::BinOp::BitAnd => runestick::BIT_AND,
+        ast::BinOp::BitXor => runestick::BIT_XOR,
+        ast::BinOp::BitOr => runestick::BIT_OR,
+        ast::BinOp::AddAssign => runestick::ADD_ASSIGN,
+        ast::BinOp::SubAssign => runestick::SUB_ASSIGN,
+        ast::BinOp::MulAssign => runestick::MUL_ASSIGN,
+        ast::BinOp::DivAssign => runestick::DIV_ASSIGN,
+        ast::BinOp::RemAssign => runestick::REM_ASSIGN,
+        ast::BinOp::ShlAssign => runestick::SHL_ASSIGN,
+        ast::BinOp::ShrAssign => runestick::SHR_ASSIGN,
+        ast::BinOp::BitAndAssign => runestick::BIT_AND_ASSIGN,
+        ast::BinOp::BitXorAssign => runestick::BIT_XOR_ASSIGN,
+        ast::BinOp::BitOrAssign => runestick::BIT_OR_ASSIGN,
+        _ => return None,
+    })
+}
+
+/// Look up the meta of the given item, relative to `base`.
+fn lookup_meta(
+    context: &Context,
+    query: &mut Query,
+    base: &Item,
+    item: &Item,
+) -> CompileResult<Option<CompileMeta>> {
+    if let Some(meta) = context.lookup_meta(item) {
+        return Ok(Some(meta));
+    }
+
+    let mut base = base.clone();
+
+    loop {
+        if let Some(meta) = query.query_meta(&base.join(item))? {
+            return Ok(Some(meta));
+        }
+
+        if base.pop().is_none() {
+            break;
+        }
+    }
+
+    Ok(None)
+}
+
+/// Resolve a type annotation relative to `base`.
+fn resolve_type(
+    context: &Context,
+    storage: &Storage,
+    query: &mut Query,
+    base: &Item,
+    source: &Source,
+    path: &ast::Path,
+) -> CompileResult<Ty> {
+    let span = path.span();
+    let item = query
+        .unit
+        .borrow()
+        .convert_path(base, path, storage, source)?;
+
+    let meta = match lookup_meta(context, query, base, &item)? {
+        Some(meta) => meta,
+        None => {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::MissingType { item },
+            ))
+        }
+    };
+
+    match Ty::from_meta(&meta) {
+        Some(ty) => Ok(ty),
+        None => Err(CompileError::new(
+            span,
+            CompileErrorKind::UnsupportedType { meta },
+        )),
+    }
+}
+
+/// Check that the type annotations of all functions and structs refer to
+/// types.
+pub(crate) fn check_annotations(
+    context: &Context,
+    storage: &Storage,
+    query: &mut Query,
+    errors: &mut Errors,
+) {
+    let mut paths = Vec::new();
+
+    for annotations in query.annotations.values() {
+        let fields = annotations.fields.iter().filter_map(|(_, ty)| ty.as_ref());
+
+        for path in fields.chain(annotations.output.as_ref()) {
+            paths.push((
+                annotations.source_id,
+                annotations.base.clone(),
+                annotations.source.clone(),
+                path.clone(),
+            ));
+        }
+    }
+
+    paths.sort_by_key(|(source_id, _, _, path)| (*source_id, path.span().start));
+
+    for (source_id, base, source, path) in paths {
+        if let Err(error) = resolve_type(context, storage, query, &base, &source, &path) {
+            errors.push(LoadError::new(source_id, error));
+        }
+    }
+}
+
+/// The resolved argument or field types and the return type of a function or
+/// a struct.
+type Annotations = (Vec<(String, Option<Ty>)>, Option<Ty>);
+
+/// A variable binding.
+struct Binding {
+    /// The type of the variable, if known.
+    ty: Option<Ty>,
+    /// If the type of the variable is annotated, in which case every value
+    /// assigned to it must be of that type.
+    annotated: bool,
+}
+
+/// The type checker for a single function.
+pub(crate) struct TypeChecker<'a> {
+    context: &'a Context,
+    storage: &'a Storage,
+    query: &'a mut Query,
+    source: Arc<Source>,
+    /// The item of the function being checked.
+    item: Item,
+    /// Variables in scope.
+    scopes: Vec<HashMap<String, Binding>>,
+    /// The return type of the function or closure being checked.
+    output: Option<Ty>,
+}
+
+impl<'a> TypeChecker<'a> {
+    /// Construct a new type checker for the function with the given item.
+    pub(crate) fn new(
+        context: &'a Context,
+        storage: &'a Storage,
+        query: &'a mut Query,
+        source: Arc<Source>,
+        item: Item,
+    ) -> Self {
+        Self {
+            context,
+            storage,
+            query,
+            source,
+            item,
+            scopes: Vec::new(),
+            output: None,
+        }
+    }
+
+    /// Check a function. If it's an instance function, `impl_meta` is the
+    /// meta of the type it's implemented for.
+    pub(crate) fn check_fn(
+        &mut self,
+        item_fn: &ast::ItemFn,
+        impl_meta: Option<&CompileMeta>,
+    ) -> CompileResult<()> {
+        let mut scope = HashMap::new();
+
+        for (arg, _) in &item_fn.args.items {
+            match arg {
+                ast::FnArg::Self_(..) => {
+                    let ty = impl_meta.and_then(Ty::from_meta);
+                    let annotated = ty.is_some();
+                    scope.insert(String::from("self"), Binding { ty, annotated });
+                }
+                ast::FnArg::Ident(ident, ty) => {
+                    let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+                    // NB: errors in the signature are reported separately by
+                    // `check_annotations`.
+                    let ty = ty.as_ref().and_then(|ty| self.resolve(&ty.path).ok());
+                    let annotated = ty.is_some();
+                    scope.insert(name, Binding { ty, annotated });
+                }
+                ast::FnArg::Ignore(..) => (),
+            }
+        }
+
+        self.scopes.push(scope);
+
+        self.output = item_fn
+            .output
+            .as_ref()
+            .and_then(|output| self.resolve(&output.path).ok());
+
+        let actual = self.block(&item_fn.body)?;
+
+        if let (Some(expected), Some(actual)) = (self.output.clone(), actual) {
+            let span = match item_fn.body.statements.last() {
+                Some(stmt) => stmt.span(),
+                None => item_fn.body.span(),
+            };
+
+            self.check(&expected, &actual, span)?;
+        }
+
+        self.scopes.pop();
+        Ok(())
+    }
+
+    /// Resolve a type annotation in the function being checked.
+    fn resolve(&mut self, path: &ast::Path) -> CompileResult<Ty> {
+        resolve_type(
+            self.context,
+            self.storage,
+            self.query,
+            &self.item,
+            &self.source,
+            path,
+        )
+    }
+
+    /// Resolve the meta of the given path.
+    fn lookup_path(&mut self, path: &ast::Path) -> CompileResult<Option<CompileMeta>> {
+        let item =
+            self.query
+                .unit
+                .borrow()
+                .convert_path(&self.item, path, self.storage, &*self.source)?;
+
+        lookup_meta(self.context, self.query, &self.item, &item)
+    }
+
+    /// Get the annotated fields or arguments, and the annotated return type of
+    /// the given item. Annotations which can't be resolved are treated as
+    /// unknown.
+    fn annotations(&mut self, item: &Item) -> Option<Annotations> {
+        let annotations = self.query.annotations.get(item)?;
+        let base = annotations.base.clone();
+        let source = annotations.source.clone();
+        let fields = annotations.fields.clone();
+        let output = annotations.output.clone();
+
+        let mut resolve = |path: Option<ast::Path>| {
+            let path = path?;
+            resolve_type(
+                self.context,
+                self.storage,
+                self.query,
+                &base,
+                &source,
+                &path,
+            )
+            .ok()
+        };
+
+        let fields = fields
+            .into_iter()
+            .map(|(name, ty)| (name, resolve(ty)))
+            .collect();
+
+        Some((fields, resolve(output)))
+    }
+
+    /// Get the annotated type of a field in the given type.
+    fn field_type(&mut self, ty: &Ty, name: &str) -> Option<Ty> {
+        if ty.native {
+            return None;
+        }
+
+        let (fields, _) = self.annotations(ty.item.as_ref()?)?;
+
+        fields
+            .into_iter()
+            .find(|(field, _)| field == name)
+            .and_then(|(_, ty)| ty)
+    }
+
+    /// Check that the actual type of a value matches the expected type.
+    fn check(&self, expected: &Ty, actual: &Ty, span: Span) -> CompileResult<()> {
+        if expected.type_of != actual.type_of {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::TypeMismatch {
+                    expected: expected.name.clone(),
+                    actual: actual.name.clone(),
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check the arguments of a call against the annotated arguments of the
+    /// function being called.
+    fn check_args(
+        &self,
+        item: &Item,
+        expected: &[(String, Option<Ty>)],
+        args: &[(Option<Ty>, Span)],
+        span: Span,
+    ) -> CompileResult<()> {
+        if expected.len() != args.len() {
+            return Err(CompileError::new(
+                span,
+                CompileErrorKind::ArgumentCountMismatch {
+                    item: item.clone(),
+                    expected: expected.len(),
+                    actual: args.len(),
+                },
+            ));
+        }
+
+        for ((_, expected), (actual, span)) in expected.iter().zip(args) {
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                self.check(expected, actual, *span)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Declare a variable in the current scope.
+    fn declare(&mut self, name: &str, ty: Option<Ty>, annotated: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), Binding { ty, annotated });
+        }
+    }
+
+    /// Look up a variable.
+    fn binding_mut(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+    }
+
+    /// Declare the variables bound by a pattern, which have an unknown type.
+    fn declare_pat(&mut self, pat: &ast::Pat) -> CompileResult<()> {
+        match pat {
+            ast::Pat::PatPath(pat) => {
+                if let Some(ident) = pat.path.try_as_ident() {
+                    let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+                    self.declare(&name, None, false);
+                }
+            }
+            ast::Pat::PatVec(pat) => {
+                for (pat, _) in &pat.items {
+                    self.declare_pat(pat)?;
+                }
+            }
+            ast::Pat::PatTuple(pat) => {
+                for (pat, _) in &pat.items {
+                    self.declare_pat(pat)?;
+                }
+            }
+            ast::Pat::PatObject(pat) => {
+                for (item, _) in &pat.fields {
+                    match &item.binding {
+                        Some((_, pat)) => self.declare_pat(pat)?,
+                        None => {
+                            let name = item.key.resolve(self.storage, &*self.source)?.into_owned();
+                            self.declare(&name, None, false);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Check a block and return the type it evaluates to.
+    fn block(&mut self, block: &ast::Block) -> CompileResult<Option<Ty>> {
+        self.scopes.push(HashMap::new());
+
+        let mut ty = Some(Ty::builtin(UNIT_TYPE));
+
+        for stmt in &block.statements {
+            ty = match stmt {
+                ast::Stmt::Item(..) => Some(Ty::builtin(UNIT_TYPE)),
+                ast::Stmt::Expr(expr) => self.expr(expr)?,
+                ast::Stmt::Semi(expr, _) => {
+                    self.expr(expr)?;
+
+                    // NB: these might diverge, in which case the block doesn't
+                    // evaluate to anything.
+                    match expr {
+                        ast::Expr::ExprReturn(..)
+                        | ast::Expr::ExprBreak(..)
+                        | ast::Expr::ExprContinue(..)
+                        | ast::Expr::ExprCall(..)
+                        | ast::Expr::MacroCall(..) => None,
+                        _ => Some(Ty::builtin(UNIT_TYPE)),
+                    }
+                }
+            };
+        }
+
+        self.scopes.pop();
+        Ok(ty)
+    }
+
+    /// Check a condition, declaring any variables it binds in the current
+    /// scope.
+    fn condition(&mut self, condition: &ast::Condition) -> CompileResult<()> {
+        match condition {
+            ast::Condition::Expr(expr) => {
+                self.expr(expr)?;
+            }
+            ast::Condition::ExprLet(expr_let) => {
+                self.expr_let(expr_let)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check an expression and return its type.
+    fn expr(&mut self, expr: &ast::Expr) -> CompileResult<Option<Ty>> {
+        let ty = match expr {
+            ast::Expr::Self_(..) => self.binding_mut("self").and_then(|b| b.ty.clone()),
+            ast::Expr::Path(path) => match path.try_as_ident() {
+                Some(ident) => {
+                    let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+                    self.binding_mut(&name).and_then(|b| b.ty.clone())
+                }
+                None => None,
+            },
+            ast::Expr::Item(..) => None,
+            ast::Expr::ExprWhile(expr_while) => {
+                self.scopes.push(HashMap::new());
+                self.condition(&expr_while.condition)?;
+                self.block(&expr_while.body.block)?;
+                self.scopes.pop();
+                None
+            }
+            ast::Expr::ExprLoop(expr_loop) => {
+                self.block(&expr_loop.body.block)?;
+                None
+            }
+            ast::Expr::ExprFor(expr_for) => {
+                self.expr(&expr_for.iter)?;
+                self.scopes.push(HashMap::new());
+                let name = expr_for
+                    .var
+                    .resolve(self.storage, &*self.source)?
+                    .into_owned();
+                self.declare(&name, None, false);
+                self.block(&expr_for.body.block)?;
+                self.scopes.pop();
+                None
+            }
+            ast::Expr::ExprLet(expr_let) => {
+                self.expr_let(expr_let)?;
+                Some(Ty::builtin(UNIT_TYPE))
+            }
+            ast::Expr::ExprIndexSet(expr_index_set) => {
+                self.expr(&expr_index_set.target)?;
+                self.expr(&expr_index_set.index)?;
+                self.expr(&expr_index_set.value)?;
+                None
+            }
+            ast::Expr::ExprIf(expr_if) => self.expr_if(expr_if)?,
+            ast::Expr::ExprMatch(expr_match) => {
+                self.expr(&expr_match.expr)?;
+
+                for (branch, _) in &expr_match.branches {
+                    self.scopes.push(HashMap::new());
+                    self.declare_pat(&branch.pat)?;
+
+                    if let Some((_, condition)) = &branch.condition {
+                        self.expr(condition)?;
+                    }
+
+                    self.expr(&branch.body)?;
+                    self.scopes.pop();
+                }
+
+                None
+            }
+            ast::Expr::ExprCall(expr_call) => self.expr_call(expr_call)?,
+            ast::Expr::MacroCall(..) => None,
+            ast::Expr::ExprFieldAccess(expr_field_access) => {
+                let target = self.expr(&expr_field_access.expr)?;
+
+                match (target, &expr_field_access.expr_field) {
+                    (Some(target), ast::ExprField::Ident(ident)) => {
+                        let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+                        self.field_type(&target, &name)
+                    }
+                    _ => None,
+                }
+            }
+            ast::Expr::ExprGroup(expr_group) => self.expr(&expr_group.expr)?,
+            ast::Expr::ExprBinary(expr_binary) => self.expr_binary(expr_binary)?,
+            ast::Expr::ExprUnary(expr_unary) => {
+                let ty = self.expr(&expr_unary.expr)?;
+
+                match (expr_unary.op, ty) {
+                    (ast::UnaryOp::Not, Some(ty)) if ty.is(BOOL_TYPE) || ty.is(INTEGER_TYPE) => {
+                        Some(ty)
+                    }
+                    _ => None,
+                }
+            }
+            ast::Expr::ExprRange(expr_range) => {
+                if let Some(from) = &expr_range.from {
+                    self.expr(from)?;
+                }
+
+                if let Some(to) = &expr_range.to {
+                    self.expr(to)?;
+                }
+
+                None
+            }
+            ast::Expr::ExprIndexGet(expr_index_get) => {
+                self.expr(&expr_index_get.target)?;
+                self.expr(&expr_index_get.index)?;
+                None
+            }
+            ast::Expr::ExprBreak(expr_break) => {
+                if let Some(ast::ExprBreakValue::Expr(expr)) = &expr_break.expr {
+                    self.expr(expr)?;
+                }
+
+                None
+            }
+            ast::Expr::ExprContinue(..) => None,
+            ast::Expr::ExprYield(expr_yield) => {
+                if let Some(expr) = &expr_yield.expr {
+                    self.expr(expr)?;
+                }
+
+                None
+            }
+            ast::Expr::ExprBlock(expr_block) => self.block(&expr_block.block)?,
+            ast::Expr::ExprAsync(expr_async) => {
+                let output = self.output.take();
+                self.block(&expr_async.block)?;
+                self.output = output;
+                None
+            }
+            ast::Expr::ExprReturn(expr_return) => {
+                let (actual, span) = match &expr_return.expr {
+                    Some(expr) => (self.expr(expr)?, expr.span()),
+                    None => (Some(Ty::builtin(UNIT_TYPE)), expr_return.span()),
+                };
+
+                if let (Some(expected), Some(actual)) = (&self.output, &actual) {
+                    self.check(expected, actual, span)?;
+                }
+
+                None
+            }
+            ast::Expr::ExprAwait(expr_await) => {
+                self.expr(&expr_await.expr)?;
+                None
+            }
+            ast::Expr::ExprTry(expr_try) => {
+                self.expr(&expr_try.expr)?;
+                None
+            }
+            ast::Expr::ExprSelect(expr_select) => {
+                for (branch, _) in &expr_select.branches {
+                    self.expr(&branch.expr)?;
+                    self.scopes.push(HashMap::new());
+                    self.declare_pat(&branch.pat)?;
+                    self.expr(&branch.body)?;
+                    self.scopes.pop();
+                }
+
+                if let Some((branch, _)) = &expr_select.default_branch {
+                    self.expr(&branch.body)?;
+                }
+
+                None
+            }
+            ast::Expr::ExprClosure(expr_closure) => {
+                self.expr_closure(expr_closure)?;
+                None
+            }
+            ast::Expr::ExprLit(expr_lit) => self.lit(&expr_lit.lit)?,
+        };
+
+        Ok(ty)
+    }
+
+    /// Check a let expression, declaring the variables it binds in the
+    /// current scope.
+    fn expr_let(&mut self, expr_let: &ast::ExprLet) -> CompileResult<()> {
+        let actual = self.expr(&expr_let.expr)?;
+
+        let expected = match &expr_let.ty {
+            Some(ty) => Some(self.resolve(&ty.path)?),
+            None => None,
+        };
+
+        if let (Some(expected), Some(actual)) = (&expected, &actual) {
+            self.check(expected, actual, expr_let.expr.span())?;
+        }
+
+        if let ast::Pat::PatPath(pat) = &expr_let.pat {
+            if let Some(ident) = pat.path.try_as_ident() {
+                let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+                let annotated = expected.is_some();
+                self.declare(&name, expected.or(actual), annotated);
+                return Ok(());
+            }
+        }
+
+        self.declare_pat(&expr_let.pat)
+    }
+
+    /// Check an if expression.
+    fn expr_if(&mut self, expr_if: &ast::ExprIf) -> CompileResult<Option<Ty>> {
+        self.scopes.push(HashMap::new());
+        self.condition(&expr_if.condition)?;
+        let mut ty = self.block(&expr_if.block.block)?;
+        self.scopes.pop();
+
+        for expr_else_if in &expr_if.expr_else_ifs {
+            self.scopes.push(HashMap::new());
+            self.condition(&expr_else_if.condition)?;
+            let other = self.block(&expr_else_if.block.block)?;
+            self.scopes.pop();
+            ty = same(ty, other);
+        }
+
+        match &expr_if.expr_else {
+            Some(expr_else) => {
+                let other = self.block(&expr_else.block.block)?;
+                Ok(same(ty, other))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Check a closure.
+    fn expr_closure(&mut self, expr_closure: &ast::ExprClosure) -> CompileResult<()> {
+        let output = self.output.take();
+        self.scopes.push(HashMap::new());
+
+        if let ast::ExprClosureArgs::List { args, .. } = &expr_closure.args {
+            for (arg, _) in args {
+                if let ast::FnArg::Ident(ident, ty) = arg {
+                    let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+
+                    let ty = match ty {
+                        Some(ty) => Some(self.resolve(&ty.path)?),
+                        None => None,
+                    };
+
+                    let annotated = ty.is_some();
+                    self.declare(&name, ty, annotated);
+                }
+            }
+        }
+
+        self.expr(&expr_closure.body)?;
+        self.scopes.pop();
+        self.output = output;
+        Ok(())
+    }
+
+    /// Check the arguments of a call.
+    fn args(&mut self, expr_call: &ast::ExprCall) -> CompileResult<Vec<(Option<Ty>, Span)>> {
+        let mut args = Vec::new();
+
+        for (expr, _) in &expr_call.args.items {
+            args.push((self.expr(expr)?, expr.span()));
+        }
+
+        Ok(args)
+    }
+
+    /// Check a call expression.
+    fn expr_call(&mut self, expr_call: &ast::ExprCall) -> CompileResult<Option<Ty>> {
+        let span = expr_call.span();
+
+        match &*expr_call.expr {
+            ast::Expr::Path(path) => {
+                let args = self.args(expr_call)?;
+
+                if let Some(ident) = path.try_as_ident() {
+                    let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+
+                    // NB: calling a closure stored in a variable.
+                    if self.binding_mut(&name).is_some() {
+                        return Ok(None);
+                    }
+                }
+
+                let meta = match self.lookup_path(path)? {
+                    Some(meta) => meta,
+                    None => return Ok(None),
+                };
+
+                match &meta.kind {
+                    CompileMetaKind::Function { item, .. } => self.call_fn(item, &args, span),
+                    CompileMetaKind::Tuple { tuple, .. } => {
+                        // NB: the number of arguments is checked by the
+                        // compiler.
+                        if let Some((fields, _)) = self.annotations(&tuple.item) {
+                            if fields.len() == args.len() {
+                                self.check_args(&tuple.item, &fields, &args, span)?;
+                            }
+                        }
+
+                        Ok(Ty::from_meta(&meta))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            ast::Expr::ExprFieldAccess(ast::ExprFieldAccess {
+                expr,
+                expr_field: ast::ExprField::Ident(ident),
+                ..
+            }) => {
+                let target = self.expr(expr)?;
+                let args = self.args(expr_call)?;
+
+                let target = match target {
+                    Some(target) => target,
+                    None => return Ok(None),
+                };
+
+                let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+                self.call_instance_fn(&target, &name, &args, span)
+            }
+            expr => {
+                self.expr(expr)?;
+                self.args(expr_call)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Check a call to the function with the given item.
+    fn call_fn(
+        &mut self,
+        item: &Item,
+        args: &[(Option<Ty>, Span)],
+        span: Span,
+    ) -> CompileResult<Option<Ty>> {
+        if let Some((fields, output)) = self.annotations(item) {
+            self.check_args(item, &fields, args, span)?;
+            return Ok(output);
+        }
+
+        if let Some(ContextSignature::Function {
+            args: Some(expected),
+            ..
+        }) = self.context.lookup_signature(Hash::type_hash(item))
+        {
+            if *expected != args.len() {
+                return Err(CompileError::new(
+                    span,
+                    CompileErrorKind::ArgumentCountMismatch {
+                        item: item.clone(),
+                        expected: *expected,
+                        actual: args.len(),
+                    },
+                ));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Check a call to an instance function on a value of the given type.
+    fn call_instance_fn(
+        &mut self,
+        target: &Ty,
+        name: &str,
+        args: &[(Option<Ty>, Span)],
+        span: Span,
+    ) -> CompileResult<Option<Ty>> {
+        if !target.native {
+            let item = match &target.item {
+                Some(item) => item.extended(name),
+                None => return Ok(None),
+            };
+
+            let (fields, output) = match self.annotations(&item) {
+                Some(annotations) => annotations,
+                None => return Ok(None),
+            };
+
+            match fields.first() {
+                Some((first, _)) if first == "self" => (),
+                _ => return Ok(None),
+            }
+
+            self.check_args(&item, &fields[1..], args, span)?;
+            return Ok(output);
+        }
+
+        let hash = Hash::instance_function(target.type_of, name);
+
+        if let Some(ContextSignature::Instance {
+            item,
+            args: Some(expected),
+            ..
+        }) = self.context.lookup_signature(hash)
+        {
+            // NB: the number of arguments includes the instance.
+            let expected = expected.saturating_sub(1);
+
+            if expected != args.len() {
+                return Err(CompileError::new(
+                    span,
+                    CompileErrorKind::ArgumentCountMismatch {
+                        item: item.extended(name),
+                        expected,
+                        actual: args.len(),
+                    },
+                ));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Check a binary expression.
+    fn expr_binary(&mut self, expr_binary: &ast::ExprBinary) -> CompileResult<Option<Ty>> {
+        let span = expr_binary.span();
+
+        match expr_binary.op {
+            ast::BinOp::Assign => {
+                let actual = self.expr(&expr_binary.rhs)?;
+                let span = expr_binary.rhs.span();
+
+                match &*expr_binary.lhs {
+                    ast::Expr::ExprFieldAccess(ast::ExprFieldAccess {
+                        expr,
+                        expr_field: ast::ExprField::Ident(ident),
+                        ..
+                    }) => {
+                        let target = self.expr(expr)?;
+                        let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+
+                        let expected = match target {
+                            Some(target) => self.field_type(&target, &name),
+                            None => None,
+                        };
+
+                        if let (Some(expected), Some(actual)) = (expected, actual) {
+                            self.check(&expected, &actual, span)?;
+                        }
+                    }
+                    lhs => {
+                        self.expr(lhs)?;
+                        self.assign(lhs, actual, span)?;
+                    }
+                }
+
+                Ok(Some(Ty::builtin(UNIT_TYPE)))
+            }
+            ast::BinOp::AddAssign
+            | ast::BinOp::SubAssign
+            | ast::BinOp::MulAssign
+            | ast::BinOp::DivAssign
+            | ast::BinOp::RemAssign
+            | ast::BinOp::BitAndAssign
+            | ast::BinOp::BitXorAssign
+            | ast::BinOp::BitOrAssign
+            | ast::BinOp::ShlAssign
+            | ast::BinOp::ShrAssign => {
+                let lhs = self.expr(&expr_binary.lhs)?;
+                let rhs = self.expr(&expr_binary.rhs)?;
+                let actual = self.operation(expr_binary.op, lhs, rhs, span)?;
+                self.assign(&expr_binary.lhs, actual, span)?;
+                Ok(Some(Ty::builtin(UNIT_TYPE)))
+            }
+            ast::BinOp::Eq
+            | ast::BinOp::Neq
+            | ast::BinOp::Is
+            | ast::BinOp::IsNot
+            | ast::BinOp::And
+            | ast::BinOp::Or => {
+                self.expr(&expr_binary.lhs)?;
+                self.expr(&expr_binary.rhs)?;
+                Ok(Some(Ty::builtin(BOOL_TYPE)))
+            }
+            ast::BinOp::Gt | ast::BinOp::Lt | ast::BinOp::Gte | ast::BinOp::Lte => {
+                let lhs = self.expr(&expr_binary.lhs)?;
+                let rhs = self.expr(&expr_binary.rhs)?;
+
+                if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    if !self.comparable(&lhs, &rhs) {
+                        return Err(CompileError::new(
+                            span,
+                            CompileErrorKind::UnsupportedOperation {
+                                op: expr_binary.op,
+                                lhs: lhs.name,
+                                rhs: rhs.name,
+                            },
+                        ));
+                    }
+                }
+
+                Ok(Some(Ty::builtin(BOOL_TYPE)))
+            }
+            op => {
+                let lhs = self.expr(&expr_binary.lhs)?;
+                let rhs = self.expr(&expr_binary.rhs)?;
+                self.operation(op, lhs, rhs, span)
+            }
+        }
+    }
+
+    /// Test if values of the given types can be compared with an ordering
+    /// operator, like `<`.
+    fn comparable(&self, lhs: &Ty, rhs: &Ty) -> bool {
+        // NB: script types are compared structurally, or through an instance
+        // function which we don't know about until it's been compiled.
+        if !lhs.native {
+            return true;
+        }
+
+        if lhs.type_of != rhs.type_of {
+            return false;
+        }
+
+        let builtin = [
+            UNIT_TYPE,
+            BOOL_TYPE,
+            BYTE_TYPE,
+            CHAR_TYPE,
+            INTEGER_TYPE,
+            FLOAT_TYPE,
+            STRING_TYPE,
+            BYTES_TYPE,
+            VEC_TYPE,
+            TUPLE_TYPE,
+            OPTION_TYPE,
+            RESULT_TYPE,
+        ];
+
+        if builtin.iter().any(|ty| lhs.is(ty)) {
+            return true;
+        }
+
+        let hash = Hash::instance_function(lhs.type_of, runestick::PARTIAL_CMP);
+        self.context.lookup(hash).is_some()
+    }
+
+    /// Check an arithmetic or bitwise operation, and return its result.
+    fn operation(
+        &self,
+        op: ast::BinOp,
+        lhs: Option<Ty>,
+        rhs: Option<Ty>,
+        span: Span,
+    ) -> CompileResult<Option<Ty>> {
+        let (lhs, rhs) = match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => (lhs, rhs),
+            _ => return Ok(None),
+        };
+
+        if lhs.is(INTEGER_TYPE) && rhs.is(INTEGER_TYPE) {
+            return Ok(Some(lhs));
+        }
+
+        let arithmetic = matches!(
+            op,
+            ast::BinOp::Add
+                | ast::BinOp::Sub
+                | ast::BinOp::Mul
+                | ast::BinOp::Div
+                | ast::BinOp::Rem
+                | ast::BinOp::AddAssign
+                | ast::BinOp::SubAssign
+                | ast::BinOp::MulAssign
+                | ast::BinOp::DivAssign
+                | ast::BinOp::RemAssign
+        );
+
+        if arithmetic && lhs.is(FLOAT_TYPE) && rhs.is(FLOAT_TYPE) {
+            return Ok(Some(lhs));
+        }
+
+        // NB: script types might implement the operation through an instance
+        // function, which we don't know about until it's been compiled.
+        if !lhs.native {
+            return Ok(None);
+        }
+
+        if let Some(protocol) = protocol(op) {
+            let hash = Hash::instance_function(lhs.type_of, protocol);
+
+            if self.context.lookup(hash).is_some() {
+                return Ok(None);
+            }
+        }
+
+        Err(CompileError::new(
+            span,
+            CompileErrorKind::UnsupportedOperation {
+                op,
+                lhs: lhs.name,
+                rhs: rhs.name,
+            },
+        ))
+    }
+
+    /// Assign a value to the given expression, if it's a variable.
+    fn assign(&mut self, lhs: &ast::Expr, actual: Option<Ty>, span: Span) -> CompileResult<()> {
+        let ident = match lhs {
+            ast::Expr::Path(path) => match path.try_as_ident() {
+                Some(ident) => ident,
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+
+        let name = ident.resolve(self.storage, &*self.source)?.into_owned();
+
+        let binding = match self.binding_mut(&name) {
+            Some(binding) => binding,
+            None => return Ok(()),
+        };
+
+        if !binding.annotated {
+            // NB: the variable might now hold a value of any type.
+            binding.ty = None;
+            return Ok(());
+        }
+
+        if let (Some(expected), Some(actual)) = (binding.ty.clone(), actual) {
+            self.check(&expected, &actual, span)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check a literal.
+    fn lit(&mut self, lit: &ast::Lit) -> CompileResult<Option<Ty>> {
+        let ty = match lit {
+            ast::Lit::Unit(..) => Ty::builtin(UNIT_TYPE),
+            ast::Lit::Bool(..) => Ty::builtin(BOOL_TYPE),
+            ast::Lit::Byte(..) => Ty::builtin(BYTE_TYPE),
+            ast::Lit::ByteStr(..) => Ty::builtin(BYTES_TYPE),
+            ast::Lit::Char(..) => Ty::builtin(CHAR_TYPE),
+            ast::Lit::Number(number) => match number.resolve(self.storage, &*self.source)? {
+                ast::Number::Integer(..) => Ty::builtin(INTEGER_TYPE),
+                ast::Number::Float(..) => Ty::builtin(FLOAT_TYPE),
+            },
+            ast::Lit::Str(..) | ast::Lit::Template(..) => Ty::builtin(STRING_TYPE),
+            ast::Lit::Tuple(lit_tuple) => {
+                for (expr, _) in &lit_tuple.items {
+                    self.expr(expr)?;
+                }
+
+                Ty::builtin(TUPLE_TYPE)
+            }
+            ast::Lit::Vec(lit_vec) => {
+                for expr in &lit_vec.items {
+                    self.expr(expr)?;
+                }
+
+                Ty::builtin(VEC_TYPE)
+            }
+            ast::Lit::Object(lit_object) => return self.lit_object(lit_object),
+        };
+
+        Ok(Some(ty))
+    }
+
+    /// Check an object literal, including the fields of a struct literal
+    /// against the annotated fields of the struct.
+    fn lit_object(&mut self, lit_object: &ast::LitObject) -> CompileResult<Option<Ty>> {
+        let ty = match &lit_object.ident {
+            ast::LitObjectIdent::Anonymous(..) => Some(Ty::builtin(OBJECT_TYPE)),
+            ast::LitObjectIdent::Named(path) => match self.lookup_path(path)? {
+                Some(meta) => Ty::from_meta(&meta),
+                None => None,
+            },
+        };
+
+        for assign in &lit_object.assignments {
+            let key = assign
+                .key
+                .resolve(self.storage, &*self.source)?
+                .into_owned();
+
+            let (actual, span) = match &assign.assign {
+                Some((_, expr)) => (self.expr(expr)?, expr.span()),
+                None => {
+                    let actual = self.binding_mut(&key).and_then(|b| b.ty.clone());
+                    (actual, assign.span())
+                }
+            };
+
+            let expected = match &ty {
+                Some(ty) => self.field_type(ty, &key),
+                None => None,
+            };
+
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                self.check(&expected, &actual, span)?;
+            }
+        }
+
+        Ok(ty)
+    }
+}
+
+/// The type of an expression which evaluates to one of two branches.
+fn same(a: Option<Ty>, b: Option<Ty>) -> Option<Ty> {
+    match (a, b) {
+        (Some(a), Some(b)) if a.type_of == b.type_of => Some(a),
+        _ => None,
+    }
+}
@@ -11,8 +11,8 @@ use crate::{Errors, LoadError, Resolve as _, Storage};
 use runestick::debug::{DebugArgs, DebugSignature};
 use runestick::{
     Call, CompileMeta, CompileMetaKind, Component, Context, DebugInfo, DebugInst, Hash, Inst,
-    IntoComponent, Item, Label, Names, Rtti, Source, Span, StaticString, Type, Unit, UnitFn,
-    UnitTypeInfo, VariantRtti,
+    IntoComponent, Item, Label, Names, Protocol, Rtti, Source, Span, StaticString, Type, Unit,
+    UnitFn, UnitTypeInfo, VariantRtti,
 };
 use std::sync::Arc;
 use thiserror::Error;
@@ -751,6 +751,7 @@ impl UnitBuilder {
             CompileMetaKind::AsyncBlock { item, .. } => item.clone(),
             CompileMetaKind::Macro { item, .. } => item.clone(),
             CompileMetaKind::Const { item, .. } => item.clone(),
+            CompileMetaKind::Trait { item, .. } => item.clone(),
         };
 
         if let Some(existing) = self.meta.insert(item, meta.clone()) {
@@ -797,6 +798,7 @@ impl UnitBuilder {
     }
 
     /// Declare a new instance function at the current instruction pointer.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new_instance_function(
         &mut self,
         source_id: usize,
@@ -807,6 +809,7 @@ impl UnitBuilder {
         assembly: Assembly,
         call: Call,
         debug_args: Vec<String>,
+        protocol: Option<Protocol>,
     ) -> Result<(), UnitBuilderError> {
         log::trace!("instance fn: {}", path);
 
@@ -829,6 +832,20 @@ impl UnitBuilder {
             });
         }
 
+        if let Some(protocol) = protocol {
+            let protocol_fn = Hash::instance_function(type_of, protocol);
+
+            if self.functions.insert(protocol_fn, info).is_some() {
+                return Err(UnitBuilderError::FunctionConflict {
+                    existing: signature,
+                });
+            }
+
+            self.debug_info_mut()
+                .functions
+                .insert(protocol_fn, signature.clone());
+        }
+
         self.debug_info_mut()
             .functions
             .insert(instance_fn, signature);
@@ -837,6 +854,54 @@ impl UnitBuilder {
         Ok(())
     }
 
+    /// Make the instance function `name` declared for the type `from`
+    /// available as an instance function of the type `to`.
+    ///
+    /// This is used to provide the default implementations of trait functions,
+    /// along with the protocol they implement if they opted in to one.
+    pub(crate) fn new_instance_function_alias(
+        &mut self,
+        from: Type,
+        to: Type,
+        name: &str,
+    ) -> Result<(), UnitBuilderError> {
+        let mut hashes = vec![(
+            Hash::instance_function(from, name),
+            Hash::instance_function(to, name),
+        )];
+
+        if let Some(protocol) = script_protocol(name) {
+            hashes.push((
+                Hash::instance_function(from, protocol),
+                Hash::instance_function(to, protocol),
+            ));
+        }
+
+        for (from, to) in hashes {
+            // NB: the function is missing if it failed to build, which has
+            // already been reported.
+            let info = match self.functions.get(&from) {
+                Some(info) => *info,
+                None => continue,
+            };
+
+            let signature = match self.debug_info_mut().functions.get(&from) {
+                Some(signature) => signature.clone(),
+                None => continue,
+            };
+
+            if self.functions.insert(to, info).is_some() {
+                return Err(UnitBuilderError::FunctionConflict {
+                    existing: signature,
+                });
+            }
+
+            self.debug_info_mut().functions.insert(to, signature);
+        }
+
+        Ok(())
+    }
+
     /// Translate the given assembly into instructions.
     fn add_assembly(
         &mut self,
@@ -847,6 +912,14 @@ impl UnitBuilder {
 
         self.required_functions.extend(assembly.required_functions);
 
+        if !assembly.variables.is_empty() {
+            let offset = self.instructions.len();
+
+            self.debug_info_mut()
+                .variables
+                .insert(offset, assembly.variables);
+        }
+
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
             let mut comment = None;
             let label = assembly.labels_rev.get(&pos).copied();
@@ -956,6 +1029,45 @@ impl UnitBuilder {
     }
 }
 
+/// Get the protocol which is implemented by an instance function with the
+/// given name in a script, which opts in to it with the `#[protocol]`
+/// attribute.
+pub(crate) fn script_protocol(name: &str) -> Option<Protocol> {
+    Some(match name {
+        "index_get" => runestick::INDEX_GET,
+        "index_set" => runestick::INDEX_SET,
+        "add" => runestick::ADD,
+        "add_assign" => runestick::ADD_ASSIGN,
+        "sub" => runestick::SUB,
+        "sub_assign" => runestick::SUB_ASSIGN,
+        "mul" => runestick::MUL,
+        "mul_assign" => runestick::MUL_ASSIGN,
+        "div" => runestick::DIV,
+        "div_assign" => runestick::DIV_ASSIGN,
+        "rem" => runestick::REM,
+        "rem_assign" => runestick::REM_ASSIGN,
+        "bit_and" => runestick::BIT_AND,
+        "bit_and_assign" => runestick::BIT_AND_ASSIGN,
+        "bit_xor" => runestick::BIT_XOR,
+        "bit_xor_assign" => runestick::BIT_XOR_ASSIGN,
+        "bit_or" => runestick::BIT_OR,
+        "bit_or_assign" => runestick::BIT_OR_ASSIGN,
+        "shl" => runestick::SHL,
+        "shl_assign" => runestick::SHL_ASSIGN,
+        "shr" => runestick::SHR,
+        "shr_assign" => runestick::SHR_ASSIGN,
+        "string_display" => runestick::STRING_DISPLAY,
+        "string_debug" => runestick::STRING_DEBUG,
+        "into_iter" => runestick::INTO_ITER,
+        "next" => runestick::NEXT,
+        "into_future" => runestick::INTO_FUTURE,
+        "eq" => runestick::EQ,
+        "partial_cmp" => runestick::PARTIAL_CMP,
+        "hash" => runestick::HASH,
+        _ => return None,
+    })
+}
+
 /// An error raised during linking.
 #[derive(Debug, Error)]
 pub enum LinkerError {
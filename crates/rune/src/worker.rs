@@ -216,11 +216,7 @@ impl<'a> Worker<'a> {
 
                     let source_id = import.source_id;
 
-                    let result = import.process(
-                        self.context,
-                        &self.query.storage,
-                        &mut *self.query.unit.borrow_mut(),
-                    );
+                    let result = import.process(self.context, &self.query);
 
                     if let Err(error) = result {
                         self.errors.push(LoadError::new(source_id, error));
@@ -357,12 +353,7 @@ pub(crate) struct Import {
 
 impl Import {
     /// Process the import, populating the unit.
-    pub(crate) fn process(
-        self,
-        context: &Context,
-        storage: &Storage,
-        unit: &mut UnitBuilder,
-    ) -> CompileResult<()> {
+    pub(crate) fn process(self, context: &Context, query: &Query) -> CompileResult<()> {
         let Self {
             item,
             ast: decl_use,
@@ -371,6 +362,8 @@ impl Import {
         } = self;
 
         let span = decl_use.span();
+        let storage = &query.storage;
+        let mut unit = query.unit.borrow_mut();
 
         let mut name = Item::new();
         let first = decl_use.first.resolve(storage, &*source)?;
@@ -402,6 +395,8 @@ impl Import {
                         ));
                     }
 
+                    query.check_visibility(&item, &name, span)?;
+
                     let iter = context
                         .iter_components(&name)
                         .chain(unit.iter_components(&name));
@@ -409,7 +404,12 @@ impl Import {
                     for c in iter {
                         let mut name = name.clone();
                         name.push(c);
-                        new_names.push(name);
+
+                        // NB: wildcard imports skip over items which aren't
+                        // visible from the importing module.
+                        if query.check_visibility(&item, &name, span).is_ok() {
+                            new_names.push(name);
+                        }
                     }
 
                     for name in new_names {
@@ -1,4 +1,5 @@
 use crate::context::Context;
+use crate::internals::MEMORY;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::spanned::Spanned as _;
@@ -7,19 +8,35 @@ use syn::spanned::Spanned as _;
 pub struct InternalCall {
     path: syn::Path,
     name: Option<(syn::Token![,], syn::LitStr)>,
+    /// Trailing `, memory` to use the `MemoryUsage` implementation of the
+    /// type.
+    memory: bool,
 }
 
 impl syn::parse::Parse for InternalCall {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let path = input.parse()?;
 
-        let name = if input.peek(syn::Token![,]) {
+        let name = if input.peek(syn::Token![,]) && input.peek2(syn::LitStr) {
             Some((input.parse()?, input.parse()?))
         } else {
             None
         };
 
-        Ok(Self { path, name })
+        let memory = if input.peek(syn::Token![,]) {
+            input.parse::<syn::Token![,]>()?;
+            let ident = input.parse::<syn::Ident>()?;
+
+            if ident != MEMORY {
+                return Err(syn::Error::new_spanned(ident, "expected `memory`"));
+            }
+
+            true
+        } else {
+            false
+        };
+
+        Ok(Self { path, name, memory })
     }
 }
 
@@ -41,7 +58,7 @@ impl InternalCall {
             },
         };
 
-        ctx.expand_any(&self.path, &name)
+        ctx.expand_any(&self.path, &name, false, self.memory)
     }
 }
 
@@ -73,6 +90,6 @@ impl Derive {
         };
 
         let name = &quote!(#name);
-        ctx.expand_any(&self.input.ident, &name)
+        ctx.expand_any(&self.input.ident, &name, attrs.key, attrs.memory)
     }
 }
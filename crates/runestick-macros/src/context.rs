@@ -15,6 +15,11 @@ pub(crate) struct FieldAttrs {}
 pub(crate) struct DeriveAttrs {
     /// `#[rune(name = "TypeName")]` to override the default type name.
     pub(crate) name: Option<syn::LitStr>,
+    /// `#[rune(key)]` to support using the type as a key in collections.
+    pub(crate) key: bool,
+    /// `#[rune(memory)]` to account for the memory used by the type through
+    /// its `MemoryUsage` implementation.
+    pub(crate) memory: bool,
 }
 
 pub(crate) struct Context {
@@ -39,6 +44,8 @@ pub(crate) struct Context {
     pub(crate) unsafe_to_value: TokenStream,
     pub(crate) type_of: TokenStream,
     pub(crate) raw_str: TokenStream,
+    pub(crate) key: TokenStream,
+    pub(crate) memory_usage: TokenStream,
 }
 
 impl Context {
@@ -74,6 +81,8 @@ impl Context {
             unsafe_to_value: quote!(#module::UnsafeToValue),
             type_of: quote!(#module::TypeOf),
             raw_str: quote!(#module::RawStr),
+            key: quote!(#module::Key),
+            memory_usage: quote!(#module::MemoryUsage),
         }
     }
 
@@ -133,6 +142,14 @@ impl Context {
                     })) if path == NAME => {
                         output.name = Some(name);
                     }
+                    // Parse `#[rune(key)]`.
+                    Meta(Path(path)) if path == KEY => {
+                        output.key = true;
+                    }
+                    // Parse `#[rune(memory)]`.
+                    Meta(Path(path)) if path == MEMORY => {
+                        output.memory = true;
+                    }
                     meta => {
                         self.errors
                             .push(syn::Error::new_spanned(meta, "unsupported attribute"));
@@ -151,6 +168,8 @@ impl Context {
         &self,
         ident: T,
         name: &TokenStream,
+        is_key: bool,
+        is_memory: bool,
     ) -> Result<TokenStream, Vec<syn::Error>>
     where
         T: Copy + ToTokens,
@@ -170,6 +189,28 @@ impl Context {
         let type_of = &self.type_of;
         let vm_error = &self.vm_error;
         let raw_str = &self.raw_str;
+        let key = &self.key;
+        let memory_usage = &self.memory_usage;
+
+        let key_fn = if is_key {
+            Some(quote! {
+                fn key(&self) -> Option<#key> {
+                    Some(#key::from_any(self.clone()))
+                }
+            })
+        } else {
+            None
+        };
+
+        let memory_usage_fn = if is_memory {
+            Some(quote! {
+                fn memory_usage(&self) -> Option<&dyn #memory_usage> {
+                    Some(self)
+                }
+            })
+        } else {
+            None
+        };
 
         Ok(quote! {
             impl #any for #ident {
@@ -178,6 +219,9 @@ impl Context {
                     // TODO: remove this once we can have transmute-like functionality in a const fn.
                     #hash::from_type_id(std::any::TypeId::of::<#ident>())
                 }
+
+                #key_fn
+                #memory_usage_fn
             }
 
             impl #named for #ident {
@@ -8,6 +8,8 @@ pub struct Symbol(&'static str);
 pub const RUNESTICK: Symbol = Symbol("runestick");
 pub const RUNE: Symbol = Symbol("rune");
 pub const NAME: Symbol = Symbol("name");
+pub const KEY: Symbol = Symbol("key");
+pub const MEMORY: Symbol = Symbol("memory");
 
 impl PartialEq<Symbol> for syn::Ident {
     fn eq(&self, word: &Symbol) -> bool {
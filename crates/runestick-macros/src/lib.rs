@@ -101,6 +101,48 @@ pub fn to_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///     Ok(module)
 /// }
 /// ```
+///
+/// ## `#[rune(key)]` attribute
+///
+/// Allows the type to be used as a key in collections like `HashMap` and
+/// `BTreeMap` without implementing the `hash` and `eq` protocols, which
+/// requires it to implement `Clone`, `Hash` and `Ord`.
+///
+/// ```rust
+/// use runestick::Any;
+///
+/// #[derive(Any, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// #[rune(key)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+/// ```
+///
+/// ## `#[rune(memory)]` attribute
+///
+/// Accounts for the memory used by the type against the memory limits of a
+/// virtual machine, which requires it to implement `MemoryUsage`.
+///
+/// ```rust
+/// use runestick::{Any, MemoryUsage, Value};
+///
+/// #[derive(Any)]
+/// #[rune(memory)]
+/// struct Buffer {
+///     values: Vec<Value>,
+/// }
+///
+/// impl MemoryUsage for Buffer {
+///     fn memory_size(&self) -> usize {
+///         self.values.capacity() * std::mem::size_of::<Value>()
+///     }
+///
+///     fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+///         self.values.iter().for_each(visit);
+///     }
+/// }
+/// ```
 #[proc_macro_derive(Any, attributes(rune))]
 pub fn any(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let derive = syn::parse_macro_input!(input as any::Derive);
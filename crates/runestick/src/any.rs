@@ -1,4 +1,4 @@
-use crate::{Hash, Named};
+use crate::{Hash, Key, MemoryUsage, Named};
 
 /// A trait which can be stored inside of an [AnyObj](crate::AnyObj).
 ///
@@ -21,6 +21,25 @@ pub trait Any: Named + std::any::Any {
     ///
     /// TODO: make const field when `TypeId::of` is const.
     fn type_hash() -> Hash;
+
+    /// Convert the value into a [Key], so that it can be used in a collection
+    /// like `HashMap`.
+    ///
+    /// This is implemented by deriving `Any` with the `#[rune(key)]`
+    /// attribute. Otherwise values can only be used as keys if they implement
+    /// the [HASH][crate::HASH] and [EQ][crate::EQ] protocols.
+    fn key(&self) -> Option<Key> {
+        None
+    }
+
+    /// Access the [MemoryUsage] of the value, so that the memory it allocates
+    /// is accounted against the limits of a virtual machine.
+    ///
+    /// This is implemented by deriving `Any` with the `#[rune(memory)]`
+    /// attribute. By default values aren't accounted for.
+    fn memory_usage(&self) -> Option<&dyn MemoryUsage> {
+        None
+    }
 }
 
 // Internal any impls for useful types in the std library.
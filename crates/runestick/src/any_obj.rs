@@ -1,6 +1,6 @@
 //! Helper types for a holder of data.
 
-use crate::{Any, Hash, RawStr};
+use crate::{Any, Hash, Key, RawStr, Value};
 use std::any;
 use std::fmt;
 use std::mem::ManuallyDrop;
@@ -38,6 +38,9 @@ impl AnyObj {
                 debug: debug_impl::<T>,
                 type_name: type_name_impl::<T>,
                 type_hash: type_hash_impl::<T>,
+                key: key_impl::<T>,
+                memory_size: memory_size_impl::<T>,
+                memory_values: memory_values_impl::<T>,
             },
             data: data as *mut (),
         }
@@ -56,6 +59,9 @@ impl AnyObj {
                 debug: debug_ref_impl::<T>,
                 type_name: type_name_impl::<T>,
                 type_hash: type_hash_impl::<T>,
+                key: key_impl::<T>,
+                memory_size: memory_size_impl::<T>,
+                memory_values: memory_values_impl::<T>,
             },
             data: data as *const _ as *const (),
         }
@@ -74,6 +80,9 @@ impl AnyObj {
                 debug: debug_mut_impl::<T>,
                 type_name: type_name_impl::<T>,
                 type_hash: type_hash_impl::<T>,
+                key: key_impl::<T>,
+                memory_size: memory_size_impl::<T>,
+                memory_values: memory_values_impl::<T>,
             },
             data: data as *mut _ as *mut () as *const (),
         }
@@ -233,6 +242,42 @@ impl AnyObj {
     pub fn type_hash(&self) -> Hash {
         (self.vtable.type_hash)()
     }
+
+    /// Convert the underlying data into a key, if it supports being used as
+    /// one.
+    pub fn key(&self) -> Option<Key> {
+        // Safety: The safety of the called implementation is guaranteed at
+        // compile time.
+        unsafe { (self.vtable.key)(self.data) }
+    }
+
+    /// The approximate number of bytes allocated by the underlying data, as
+    /// reported by its [MemoryUsage][crate::MemoryUsage] implementation.
+    ///
+    /// Data which isn't owned by the `AnyObj` doesn't allocate anything.
+    pub fn memory_size(&self) -> usize {
+        match self.vtable.kind {
+            AnyObjKind::Owned => (),
+            _ => return 0,
+        }
+
+        // Safety: The safety of the called implementation is guaranteed at
+        // compile time.
+        unsafe { (self.vtable.memory_size)(self.data) }
+    }
+
+    /// Visit the values held by the underlying data, as reported by its
+    /// [MemoryUsage][crate::MemoryUsage] implementation.
+    pub fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+        match self.vtable.kind {
+            AnyObjKind::Owned => (),
+            _ => return,
+        }
+
+        // Safety: The safety of the called implementation is guaranteed at
+        // compile time.
+        unsafe { (self.vtable.memory_values)(self.data, visit) }
+    }
 }
 
 impl Drop for AnyObj {
@@ -260,6 +305,15 @@ pub type TypeNameFn = fn() -> RawStr;
 /// The signature of a type hash function.
 pub type TypeHashFn = fn() -> Hash;
 
+/// The signature of a key conversion function.
+pub type KeyFn = unsafe fn(*const ()) -> Option<Key>;
+
+/// The signature of a memory size function.
+pub type MemorySizeFn = unsafe fn(*const ()) -> usize;
+
+/// The signature of a function visiting the values held by the data.
+pub type MemoryValuesFn = unsafe fn(*const (), &mut dyn FnMut(&Value));
+
 /// The kind of the stored value in the `AnyObj`.
 enum AnyObjKind {
     /// A boxed value that is owned.
@@ -289,6 +343,12 @@ pub struct AnyObjVtable {
     type_name: TypeNameFn,
     /// Get the type hash of the stored type.
     type_hash: TypeHashFn,
+    /// Convert the stored value into a key.
+    key: KeyFn,
+    /// Get the number of bytes allocated by the stored value.
+    memory_size: MemorySizeFn,
+    /// Visit the values held by the stored value.
+    memory_values: MemoryValuesFn,
 }
 
 unsafe fn drop_impl<T>(this: *const ()) {
@@ -308,6 +368,32 @@ where
 
 fn noop_drop_impl<T>(_: *const ()) {}
 
+unsafe fn key_impl<T>(this: *const ()) -> Option<Key>
+where
+    T: Any,
+{
+    (*(this as *const T)).key()
+}
+
+unsafe fn memory_size_impl<T>(this: *const ()) -> usize
+where
+    T: Any,
+{
+    match (*(this as *const T)).memory_usage() {
+        Some(memory_usage) => memory_usage.memory_size(),
+        None => 0,
+    }
+}
+
+unsafe fn memory_values_impl<T>(this: *const (), visit: &mut dyn FnMut(&Value))
+where
+    T: Any,
+{
+    if let Some(memory_usage) = (*(this as *const T)).memory_usage() {
+        memory_usage.memory_values(visit);
+    }
+}
+
 fn debug_impl<T>(f: &mut fmt::Formatter<'_>) -> fmt::Result
 where
     T: Any,
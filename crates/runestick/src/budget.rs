@@ -5,6 +5,10 @@
 //!
 //! By default the budget is disabled, but can be enabled by wrapping your
 //! function call in [with].
+//!
+//! Limits which are configured on a single virtual machine, like fuel, a
+//! deadline and a maximum number of call frames, are available through
+//! [VmLimits][crate::VmLimits].
 
 use pin_project::pin_project;
 use std::cell::Cell;
@@ -45,6 +45,7 @@ impl CompileMeta {
             CompileMetaKind::AsyncBlock { item, .. } => item,
             CompileMetaKind::Macro { item, .. } => item,
             CompileMetaKind::Const { item, .. } => item,
+            CompileMetaKind::Trait { item, .. } => item,
         }
     }
 
@@ -61,6 +62,7 @@ impl CompileMeta {
             CompileMetaKind::AsyncBlock { type_of, .. } => Some(*type_of),
             CompileMetaKind::Macro { .. } => None,
             CompileMetaKind::Const { .. } => None,
+            CompileMetaKind::Trait { .. } => None,
         }
     }
 }
@@ -98,6 +100,9 @@ impl fmt::Display for CompileMeta {
             CompileMetaKind::Const { item, .. } => {
                 write!(fmt, "const {}", item)?;
             }
+            CompileMetaKind::Trait { item, .. } => {
+                write!(fmt, "trait {}", item)?;
+            }
         }
 
         Ok(())
@@ -183,6 +188,13 @@ pub enum CompileMetaKind {
         /// The item of the macro.
         item: Item,
     },
+    /// A trait declaration.
+    Trait {
+        /// The item of the trait.
+        item: Item,
+        /// The functions declared in the trait.
+        functions: Arc<Vec<CompileMetaTraitFn>>,
+    },
 }
 
 /// The metadata about a type.
@@ -194,6 +206,17 @@ pub struct CompileMetaStruct {
     pub fields: Option<HashSet<String>>,
 }
 
+/// The metadata about a function declared in a trait.
+#[derive(Debug, Clone)]
+pub struct CompileMetaTraitFn {
+    /// The name of the function.
+    pub name: String,
+    /// The number of arguments the function takes, including `self`.
+    pub args: usize,
+    /// If the function has a default implementation.
+    pub default: bool,
+}
+
 /// The metadata about a variant.
 #[derive(Debug, Clone)]
 pub struct CompileMetaTuple {
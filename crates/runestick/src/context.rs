@@ -98,11 +98,12 @@ pub enum ContextError {
 }
 
 /// A function handler.
-pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Sync;
+pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Send + Sync;
 
 /// A (type erased) macro handler.
-pub(crate) type Macro =
-    dyn Fn(&mut dyn any::Any, &dyn any::Any) -> Result<Box<dyn any::Any>, crate::Error> + Sync;
+pub(crate) type Macro = dyn Fn(&mut dyn any::Any, &dyn any::Any) -> Result<Box<dyn any::Any>, crate::Error>
+    + Send
+    + Sync;
 
 /// Information on a specific type.
 #[derive(Debug, Clone)]
@@ -129,12 +130,14 @@ impl fmt::Display for ContextTypeInfo {
 /// A description of a function signature.
 #[derive(Debug, Clone)]
 pub enum ContextSignature {
+    /// A free function.
     Function {
         /// Path to the function.
         item: Item,
         /// Arguments.
         args: Option<usize>,
     },
+    /// An instance function.
     Instance {
         /// Path to the instance function.
         item: Item,
@@ -255,8 +258,10 @@ impl Context {
         this.install(&crate::modules::float::module()?)?;
         this.install(&crate::modules::test::module()?)?;
         this.install(&crate::modules::iter::module()?)?;
+        this.install(&crate::modules::ops::module()?)?;
         this.install(&crate::modules::vec::module()?)?;
         this.install(&crate::modules::object::module()?)?;
+        this.install(&crate::modules::collections::module()?)?;
         this.install(&crate::modules::result::module()?)?;
         this.install(&crate::modules::option::module()?)?;
         this.install(&crate::modules::future::module()?)?;
@@ -314,6 +319,11 @@ impl Context {
         self.meta.get(name).cloned()
     }
 
+    /// Lookup the signature of the native function with the given hash.
+    pub fn lookup_signature(&self, hash: Hash) -> Option<&ContextSignature> {
+        self.functions_info.get(&hash)
+    }
+
     /// Iterate over all available functions
     pub fn iter_functions(&self) -> impl Iterator<Item = (Hash, &ContextSignature)> {
         let mut it = self.functions_info.iter();
@@ -487,13 +497,24 @@ impl Context {
 
         self.macros.insert(hash, m.handler.clone());
 
-        self.meta.insert(
-            name.clone(),
-            CompileMeta {
-                kind: CompileMetaKind::Macro { item: name },
-                source: None,
-            },
-        );
+        // NB: macros live in a namespace of their own, so a macro can share
+        // its name with a function, like `std::println`. In that case the
+        // function is what the name refers to outside of macro calls.
+        if !matches!(
+            self.meta.get(&name),
+            Some(CompileMeta {
+                kind: CompileMetaKind::Function { .. },
+                ..
+            })
+        ) {
+            self.meta.insert(
+                name.clone(),
+                CompileMeta {
+                    kind: CompileMetaKind::Macro { item: name },
+                    source: None,
+                },
+            );
+        }
 
         Ok(())
     }
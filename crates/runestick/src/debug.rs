@@ -14,6 +14,9 @@ pub struct DebugInfo {
     pub functions: HashMap<Hash, DebugSignature>,
     /// Reverse lookup of a function.
     pub functions_rev: HashMap<usize, Hash>,
+    /// Local variables declared in functions, keyed by the instruction offset
+    /// at which the function starts.
+    pub variables: HashMap<usize, Vec<DebugVariable>>,
 }
 
 impl DebugInfo {
@@ -28,6 +31,33 @@ impl DebugInfo {
         let signature = self.functions.get(&hash)?;
         Some((hash, signature))
     }
+
+    /// Get the instruction offset of the function which contains the given
+    /// instruction pointer.
+    ///
+    /// Functions are laid out contiguously, so this is the closest function
+    /// start which is at or before the instruction pointer.
+    pub fn function_offset_at(&self, ip: usize) -> Option<usize> {
+        self.functions_rev
+            .keys()
+            .copied()
+            .filter(|offset| *offset <= ip)
+            .max()
+    }
+
+    /// Get the local variables declared in the function which contains the
+    /// given instruction pointer.
+    pub fn variables_at(&self, ip: usize) -> &[DebugVariable] {
+        let offset = match self.function_offset_at(ip) {
+            Some(offset) => offset,
+            None => return &[],
+        };
+
+        match self.variables.get(&offset) {
+            Some(variables) => variables,
+            None => &[],
+        }
+    }
 }
 
 /// Debug information for every instruction.
@@ -43,8 +73,21 @@ pub struct DebugInst {
     pub label: Option<DebugLabel>,
 }
 
+/// Debug information on a local variable declared in a function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugVariable {
+    /// The name of the variable.
+    pub name: String,
+    /// The offset of the variable relative to the bottom of the stack frame.
+    pub offset: usize,
+    /// The span where the variable was declared.
+    pub span: Span,
+    /// The offset in the source at which the scope of the variable ends.
+    pub scope_end: usize,
+}
+
 /// Debug information on function arguments.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DebugArgs {
     /// A tuple, with the given number of arguments.
     TupleArgs(usize),
@@ -53,7 +96,7 @@ pub enum DebugArgs {
 }
 
 /// A description of a function signature.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebugSignature {
     /// The path of the function.
     pub path: Item,
@@ -0,0 +1,495 @@
+//! An interactive debugger for the virtual machine.
+
+use crate::{Item, Source, SourceId, Span, Unit, Value, Vm, VmError, VmExecution};
+use std::sync::Arc;
+
+/// A line in a source file.
+///
+/// Lines are zero-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceLine {
+    /// The source the line belongs to.
+    pub source_id: SourceId,
+    /// The zero-based line number.
+    pub line: usize,
+}
+
+/// A breakpoint which has been resolved against the instructions of a unit.
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    /// The identifier of the breakpoint.
+    id: usize,
+    /// The line the breakpoint was resolved to.
+    location: SourceLine,
+    /// Instruction pointers the breakpoint triggers on.
+    ips: Vec<usize>,
+}
+
+impl Breakpoint {
+    /// Get the identifier of the breakpoint.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Get the line the breakpoint was resolved to.
+    ///
+    /// This might be a later line than the one requested if the requested line
+    /// didn't have any instructions associated with it.
+    pub fn location(&self) -> SourceLine {
+        self.location
+    }
+
+    /// Get the instruction pointers the breakpoint triggers on.
+    pub fn ips(&self) -> &[usize] {
+        &self.ips
+    }
+}
+
+/// How to step through the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepMode {
+    /// Stop at the next line, stepping into any function being called.
+    In,
+    /// Stop at the next line in the current function, or in a caller if the
+    /// function returns.
+    Over,
+    /// Stop once the current function has returned.
+    Out,
+}
+
+/// The reason why the debugger stopped execution.
+#[derive(Debug)]
+pub enum DebugHalt {
+    /// Execution reached the breakpoint with the given identifier.
+    Breakpoint(usize),
+    /// A requested step was completed.
+    Step,
+    /// Execution ran to completion and produced the given value.
+    Exited(Value),
+}
+
+/// A single frame of the call stack being debugged.
+#[derive(Debug, Clone)]
+pub struct DebugFrame {
+    /// The virtual machine the frame belongs to in the execution.
+    vm: usize,
+    /// The instruction pointer of the frame.
+    ip: usize,
+    /// The bottom of the stack of the frame.
+    stack_bottom: usize,
+    /// The top of the stack of the frame.
+    stack_top: usize,
+    /// The line the frame is currently at.
+    location: Option<SourceLine>,
+    /// The span of the instruction the frame is currently at.
+    span: Option<Span>,
+    /// The function the frame is executing.
+    function: Option<Item>,
+}
+
+impl DebugFrame {
+    /// Get the instruction pointer of the frame.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    /// Get the line the frame is currently at, if known.
+    pub fn location(&self) -> Option<SourceLine> {
+        self.location
+    }
+
+    /// Get the span of the instruction the frame is at, if known.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Get the function the frame is executing, if known.
+    pub fn function(&self) -> Option<&Item> {
+        self.function.as_ref()
+    }
+}
+
+/// A local variable in a frame.
+#[derive(Debug, Clone)]
+pub struct DebugLocal {
+    /// The name of the local variable.
+    pub name: String,
+    /// The current value of the local variable.
+    pub value: Value,
+    /// The span where the variable was declared.
+    pub span: Span,
+}
+
+/// A pending step.
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    mode: StepMode,
+    depth: usize,
+    location: Option<SourceLine>,
+}
+
+/// A debugger which drives a [VmExecution] one instruction at a time,
+/// stopping at breakpoints and steps.
+///
+/// Locations are resolved through the [DebugInfo][crate::DebugInfo] of the
+/// unit, so the unit must be compiled with debug info enabled.
+pub struct Debugger {
+    /// The unit being debugged.
+    unit: Arc<Unit>,
+    /// The source line of every instruction in the unit.
+    lines: Vec<Option<SourceLine>>,
+    /// Registered breakpoints.
+    breakpoints: Vec<Breakpoint>,
+    /// The identifier to use for the next breakpoint.
+    next_breakpoint: usize,
+    /// The current step, if any.
+    step: Option<Step>,
+    /// If the debugger has moved the execution since it was constructed.
+    started: bool,
+}
+
+impl Debugger {
+    /// Construct a new debugger for the given unit.
+    ///
+    /// The `source` callback is used to look up the source of each source id
+    /// referenced in the debug info, so that instructions can be mapped to
+    /// lines.
+    pub fn new<'a, F>(unit: Arc<Unit>, mut source: F) -> Self
+    where
+        F: FnMut(SourceId) -> Option<&'a Source>,
+    {
+        let mut lines = Vec::new();
+
+        if let Some(debug) = unit.debug_info() {
+            for inst in &debug.instructions {
+                let line = source(inst.source_id).map(|source| SourceLine {
+                    source_id: inst.source_id,
+                    line: source.position_to_unicode_line_char(inst.span.start).0,
+                });
+
+                lines.push(line);
+            }
+        }
+
+        Self {
+            unit,
+            lines,
+            breakpoints: Vec::new(),
+            next_breakpoint: 0,
+            step: None,
+            started: false,
+        }
+    }
+
+    /// Add a breakpoint on the given zero-based line.
+    ///
+    /// If the line doesn't have any instructions associated with it, the
+    /// breakpoint is moved to the closest following line that does. Returns
+    /// `None` if no such line exists.
+    pub fn add_breakpoint(&mut self, source_id: SourceId, line: usize) -> Option<&Breakpoint> {
+        let location = self
+            .lines
+            .iter()
+            .flatten()
+            .filter(|l| l.source_id == source_id && l.line >= line)
+            .min_by_key(|l| l.line)
+            .copied()?;
+
+        let mut ips = Vec::new();
+        let mut previous = None;
+
+        for (ip, l) in self.lines.iter().enumerate() {
+            if *l == Some(location) && previous != Some(location) {
+                ips.push(ip);
+            }
+
+            previous = *l;
+        }
+
+        let id = self.next_breakpoint;
+        self.next_breakpoint += 1;
+
+        self.breakpoints.push(Breakpoint { id, location, ips });
+        self.breakpoints.last()
+    }
+
+    /// Remove the breakpoint with the given identifier.
+    ///
+    /// Returns `true` if the breakpoint existed.
+    pub fn remove_breakpoint(&mut self, id: usize) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|b| b.id != id);
+        len != self.breakpoints.len()
+    }
+
+    /// Remove all breakpoints.
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Iterate over all registered breakpoints.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Get the line associated with the given instruction pointer.
+    pub fn line_at(&self, ip: usize) -> Option<SourceLine> {
+        self.lines.get(ip).copied().flatten()
+    }
+
+    /// Get the line the execution is currently at.
+    pub fn current_location(&self, execution: &VmExecution) -> Result<Option<SourceLine>, VmError> {
+        Ok(self.location_of(execution.vm()?))
+    }
+
+    /// Request that the next call to [resume][Self::resume] or
+    /// [async_resume][Self::async_resume] stops after stepping according to
+    /// the given mode.
+    pub fn step(&mut self, execution: &VmExecution, mode: StepMode) -> Result<(), VmError> {
+        self.step = Some(Step {
+            mode,
+            depth: Self::depth(execution),
+            location: self.location_of(execution.vm()?),
+        });
+
+        Ok(())
+    }
+
+    /// Resume the execution until a breakpoint is hit, a requested step has
+    /// completed, or the execution exits.
+    ///
+    /// If any async instructions are encountered, this will error.
+    pub fn resume(&mut self, execution: &mut VmExecution) -> Result<DebugHalt, VmError> {
+        if let Some(halt) = self.start(execution)? {
+            return Ok(halt);
+        }
+
+        loop {
+            if let Some(value) = execution.step()? {
+                self.step = None;
+                return Ok(DebugHalt::Exited(value));
+            }
+
+            if let Some(halt) = self.check(execution)? {
+                return Ok(halt);
+            }
+        }
+    }
+
+    /// Resume the execution with support for async instructions until a
+    /// breakpoint is hit, a requested step has completed, or the execution
+    /// exits.
+    pub async fn async_resume(
+        &mut self,
+        execution: &mut VmExecution,
+    ) -> Result<DebugHalt, VmError> {
+        if let Some(halt) = self.start(execution)? {
+            return Ok(halt);
+        }
+
+        loop {
+            if let Some(value) = execution.async_step().await? {
+                self.step = None;
+                return Ok(DebugHalt::Exited(value));
+            }
+
+            if let Some(halt) = self.check(execution)? {
+                return Ok(halt);
+            }
+        }
+    }
+
+    /// Get the call stack of the execution, starting with the innermost frame.
+    pub fn frames(&self, execution: &VmExecution) -> Vec<DebugFrame> {
+        let mut frames = Vec::new();
+
+        for (n, vm) in execution.vms().iter().enumerate() {
+            let stack = vm.stack();
+            let call_frames = vm.call_frames();
+
+            for (i, frame) in call_frames.iter().enumerate() {
+                let stack_top = match call_frames.get(i + 1) {
+                    Some(next) => next.stack_bottom(),
+                    None => stack.stack_bottom(),
+                };
+
+                frames.push(self.frame(n, vm, frame.ip(), frame.stack_bottom(), stack_top));
+            }
+
+            frames.push(self.frame(n, vm, vm.ip(), stack.stack_bottom(), stack.len()));
+        }
+
+        frames.reverse();
+        frames
+    }
+
+    /// Get the local variables which are in scope in the given frame.
+    pub fn locals(&self, execution: &VmExecution, frame: &DebugFrame) -> Vec<DebugLocal> {
+        let vm = match execution.vms().get(frame.vm) {
+            Some(vm) => vm,
+            None => return Vec::new(),
+        };
+
+        let debug = match vm.unit().debug_info() {
+            Some(debug) => debug,
+            None => return Vec::new(),
+        };
+
+        let position = match debug.instruction_at(frame.ip) {
+            Some(inst) => inst.span.start,
+            None => return Vec::new(),
+        };
+
+        let len = frame.stack_top.saturating_sub(frame.stack_bottom);
+        let mut visible = Vec::<&crate::debug::DebugVariable>::new();
+
+        // NB: variables are recorded in declaration order, so later
+        // declarations shadow earlier ones which occupy the same slot or
+        // have the same name.
+        for var in debug.variables_at(frame.ip) {
+            if var.span.start > position || position >= var.scope_end || var.offset >= len {
+                continue;
+            }
+
+            visible.retain(|v| v.offset != var.offset && v.name != var.name);
+            visible.push(var);
+        }
+
+        visible.sort_by_key(|v| v.offset);
+
+        visible
+            .into_iter()
+            .filter_map(|var| {
+                let value = vm.stack().get(frame.stack_bottom + var.offset)?.clone();
+
+                Some(DebugLocal {
+                    name: var.name.clone(),
+                    value,
+                    span: var.span,
+                })
+            })
+            .collect()
+    }
+
+    /// Look up the local variable with the given name in the given frame.
+    pub fn local(&self, execution: &VmExecution, frame: &DebugFrame, name: &str) -> Option<Value> {
+        self.locals(execution, frame)
+            .into_iter()
+            .find(|local| local.name == name)
+            .map(|local| local.value)
+    }
+
+    /// Check the initial position of the execution before it has been moved
+    /// by the debugger.
+    fn start(&mut self, execution: &VmExecution) -> Result<Option<DebugHalt>, VmError> {
+        if self.started {
+            return Ok(None);
+        }
+
+        self.started = true;
+
+        let vm = execution.vm()?;
+
+        if !vm.is_same_unit(&self.unit) {
+            return Ok(None);
+        }
+
+        Ok(self.breakpoint_at(vm.ip()).map(DebugHalt::Breakpoint))
+    }
+
+    /// Check if the execution should stop at its current position.
+    fn check(&mut self, execution: &VmExecution) -> Result<Option<DebugHalt>, VmError> {
+        let vm = execution.vm()?;
+
+        if !vm.is_same_unit(&self.unit) {
+            return Ok(None);
+        }
+
+        if let Some(id) = self.breakpoint_at(vm.ip()) {
+            self.step = None;
+            return Ok(Some(DebugHalt::Breakpoint(id)));
+        }
+
+        let step = match self.step {
+            Some(step) => step,
+            None => return Ok(None),
+        };
+
+        let location = match self.location_of(vm) {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+
+        let depth = Self::depth(execution);
+
+        let stop = match step.mode {
+            StepMode::In => depth != step.depth || step.location != Some(location),
+            StepMode::Over => {
+                depth < step.depth || depth == step.depth && step.location != Some(location)
+            }
+            StepMode::Out => depth < step.depth,
+        };
+
+        if !stop {
+            return Ok(None);
+        }
+
+        self.step = None;
+        Ok(Some(DebugHalt::Step))
+    }
+
+    /// Find a breakpoint which triggers on the given instruction pointer.
+    fn breakpoint_at(&self, ip: usize) -> Option<usize> {
+        self.breakpoints
+            .iter()
+            .find(|b| b.ips.contains(&ip))
+            .map(|b| b.id)
+    }
+
+    /// Get the location of the given virtual machine.
+    fn location_of(&self, vm: &Vm) -> Option<SourceLine> {
+        if !vm.is_same_unit(&self.unit) {
+            return None;
+        }
+
+        self.line_at(vm.ip())
+    }
+
+    /// The total number of call frames in the execution.
+    fn depth(execution: &VmExecution) -> usize {
+        execution
+            .vms()
+            .iter()
+            .map(|vm| vm.call_frames().len() + 1)
+            .sum()
+    }
+
+    /// Construct a single frame.
+    fn frame(
+        &self,
+        n: usize,
+        vm: &Vm,
+        ip: usize,
+        stack_bottom: usize,
+        stack_top: usize,
+    ) -> DebugFrame {
+        let same_unit = vm.is_same_unit(&self.unit);
+        let debug = vm.unit().debug_info();
+
+        let function = debug.and_then(|debug| {
+            let offset = debug.function_offset_at(ip)?;
+            let (_, signature) = debug.function_at(offset)?;
+            Some(signature.path.clone())
+        });
+
+        DebugFrame {
+            vm: n,
+            ip,
+            stack_bottom,
+            stack_top,
+            location: if same_unit { self.line_at(ip) } else { None },
+            span: debug.and_then(|d| d.instruction_at(ip)).map(|d| d.span),
+            function,
+        }
+    }
+}
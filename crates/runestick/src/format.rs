@@ -0,0 +1,408 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Write as _;
+use std::str;
+use thiserror::Error;
+
+/// The alignment of a formatted value inside of its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Alignment {
+    /// Align to the left, `<`.
+    Left,
+    /// Align to the center, `^`.
+    Center,
+    /// Align to the right, `>`.
+    Right,
+}
+
+impl Alignment {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '<' => Some(Self::Left),
+            '^' => Some(Self::Center),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left => write!(f, "<"),
+            Self::Center => write!(f, "^"),
+            Self::Right => write!(f, ">"),
+        }
+    }
+}
+
+/// How a value is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormatType {
+    /// Format the value for display, which is the default.
+    Display,
+    /// Format the value for debugging, `?`.
+    Debug,
+    /// Format an integer as lower-case hexadecimal, `x`.
+    LowerHex,
+    /// Format an integer as upper-case hexadecimal, `X`.
+    UpperHex,
+    /// Format an integer as binary, `b`.
+    Binary,
+    /// Format an integer as octal, `o`.
+    Octal,
+}
+
+impl fmt::Display for FormatType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Display => Ok(()),
+            Self::Debug => write!(f, "?"),
+            Self::LowerHex => write!(f, "x"),
+            Self::UpperHex => write!(f, "X"),
+            Self::Binary => write!(f, "b"),
+            Self::Octal => write!(f, "o"),
+        }
+    }
+}
+
+/// Error raised when parsing a bad format specification.
+#[derive(Debug, Clone, Copy, Error)]
+pub enum FormatSpecError {
+    /// A `.` which isn't followed by a precision.
+    #[error("expected precision after `.`")]
+    ExpectedPrecision,
+    /// A width or precision which is larger than [FormatSpec::MAX_COUNT].
+    #[error("width or precision is larger than {}", FormatSpec::MAX_COUNT)]
+    Overflow,
+    /// An unsupported format type, or trailing characters.
+    #[error("unsupported format `{actual}`")]
+    UnsupportedFormat {
+        /// The first unsupported character.
+        actual: char,
+    },
+}
+
+/// A format specification, like the `>8.2` in `{value:>8.2}`.
+///
+/// The syntax is the same as for format strings in Rust:
+///
+/// ```text
+/// [[fill]align][sign]['#']['0'][width]['.' precision][type]
+/// ```
+///
+/// Where `align` is one of `<`, `^` or `>`, `sign` is `+` or `-`, and `type`
+/// is empty for display, `?` for debug, or one of `x`, `X`, `b` and `o` to
+/// format integers in another radix.
+///
+/// ```rust
+/// use runestick::{Alignment, FormatSpec, FormatType};
+///
+/// let spec = "*^+#012.3x".parse::<FormatSpec>().unwrap();
+/// assert_eq!(spec.fill, '*');
+/// assert_eq!(spec.align, Some(Alignment::Center));
+/// assert_eq!(spec.width, Some(12));
+/// assert_eq!(spec.precision, Some(3));
+/// assert_eq!(spec.format_type, FormatType::LowerHex);
+/// assert_eq!(spec.to_string(), "*^+#012.3x");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FormatSpec {
+    /// The character used to pad the value to its width.
+    pub fill: char,
+    /// The alignment of the value, if specified.
+    pub align: Option<Alignment>,
+    /// Always include the sign of numbers, `+`.
+    pub sign_plus: bool,
+    /// Use the alternate form, `#`. Adds a prefix like `0x` to integers
+    /// formatted in another radix.
+    pub alternate: bool,
+    /// Pad numbers with zeros after their sign, `0`.
+    pub zero_pad: bool,
+    /// The minimum width of the formatted value, in characters. At most
+    /// [FormatSpec::MAX_COUNT].
+    pub width: Option<u32>,
+    /// The precision of floats, or the maximum width of displayed strings.
+    /// At most [FormatSpec::MAX_COUNT].
+    pub precision: Option<u32>,
+    /// How the value is formatted.
+    pub format_type: FormatType,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            sign_plus: false,
+            alternate: false,
+            zero_pad: false,
+            width: None,
+            precision: None,
+            format_type: FormatType::Display,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// The largest supported width or precision, which is the same as in
+    /// Rust.
+    pub const MAX_COUNT: u32 = u16::MAX as u32;
+
+    /// Test if this is the default specification, which formats values the
+    /// same way as `{value}`.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check that the width and precision of the specification are within
+    /// [FormatSpec::MAX_COUNT].
+    ///
+    /// Specifications which are parsed are always checked, but ones which
+    /// are constructed or deserialized might not be.
+    pub fn check(&self) -> Result<(), FormatSpecError> {
+        for count in self.width.iter().chain(self.precision.iter()) {
+            if *count > Self::MAX_COUNT {
+                return Err(FormatSpecError::Overflow);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Format an integer according to the specification.
+    ///
+    /// Integers support all format types. Like in Rust, negative integers
+    /// formatted in another radix are formatted as their two's complement.
+    pub fn format_integer(&self, n: i64, out: &mut String) {
+        let (negative, digits) = match self.format_type {
+            FormatType::Display | FormatType::Debug => (n < 0, n.unsigned_abs().to_string()),
+            FormatType::LowerHex => (false, format!("{:x}", n)),
+            FormatType::UpperHex => (false, format!("{:X}", n)),
+            FormatType::Binary => (false, format!("{:b}", n)),
+            FormatType::Octal => (false, format!("{:o}", n)),
+        };
+
+        let prefix = match self.format_type {
+            _ if !self.alternate => "",
+            FormatType::LowerHex | FormatType::UpperHex => "0x",
+            FormatType::Binary => "0b",
+            FormatType::Octal => "0o",
+            _ => "",
+        };
+
+        self.pad_number(negative, prefix, &digits, out);
+    }
+
+    /// Format a float according to the specification.
+    ///
+    /// Floats only support the display and debug format types, which the
+    /// caller is responsible for checking.
+    pub fn format_float(&self, f: f64, out: &mut String) {
+        let negative = f.is_sign_negative() && !f.is_nan();
+
+        let digits = match self.precision {
+            Some(precision) => format!("{:.*}", precision as usize, f.abs()),
+            None => {
+                let mut buffer = ryu::Buffer::new();
+                buffer.format(f.abs()).to_owned()
+            }
+        };
+
+        self.pad_number(negative, "", &digits, out);
+    }
+
+    /// Format a string according to the specification.
+    ///
+    /// Displayed strings are truncated to the precision, if specified, and
+    /// debugged strings are quoted and escaped.
+    pub fn format_str(&self, s: &str, out: &mut String) {
+        match self.format_type {
+            FormatType::Debug => self.pad(&format!("{:?}", s), out),
+            _ => match self
+                .precision
+                .and_then(|p| s.char_indices().nth(p as usize))
+            {
+                Some((end, _)) => self.pad(&s[..end], out),
+                None => self.pad(s, out),
+            },
+        }
+    }
+
+    /// Pad an already formatted value to the width of the specification.
+    ///
+    /// Values are left-aligned unless an alignment is specified.
+    pub fn pad(&self, s: &str, out: &mut String) {
+        self.pad_with(s, Alignment::Left, out);
+    }
+
+    fn pad_number(&self, negative: bool, prefix: &str, digits: &str, out: &mut String) {
+        let sign = if negative {
+            "-"
+        } else if self.sign_plus {
+            "+"
+        } else {
+            ""
+        };
+
+        let len = sign.len() + prefix.len() + digits.chars().count();
+
+        match self.width {
+            // NB: zero padding goes in between the sign and the digits, and
+            // overrides any fill and alignment.
+            Some(width) if self.zero_pad && width as usize > len => {
+                out.push_str(sign);
+                out.push_str(prefix);
+                out.push_str(&"0".repeat(width as usize - len));
+                out.push_str(digits);
+            }
+            _ => {
+                let number = format!("{}{}{}", sign, prefix, digits);
+                self.pad_with(&number, Alignment::Right, out);
+            }
+        }
+    }
+
+    fn pad_with(&self, s: &str, default: Alignment, out: &mut String) {
+        let len = s.chars().count();
+
+        let padding = match self.width {
+            Some(width) if width as usize > len => width as usize - len,
+            _ => {
+                out.push_str(s);
+                return;
+            }
+        };
+
+        let (before, after) = match self.align.unwrap_or(default) {
+            Alignment::Left => (0, padding),
+            Alignment::Center => (padding / 2, padding - padding / 2),
+            Alignment::Right => (padding, 0),
+        };
+
+        out.extend((0..before).map(|_| self.fill));
+        out.push_str(s);
+        out.extend((0..after).map(|_| self.fill));
+    }
+}
+
+impl str::FromStr for FormatSpec {
+    type Err = FormatSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut spec = Self::default();
+        let mut it = s.chars().peekable();
+
+        let mut lookahead = s.chars();
+        let first = lookahead.next();
+        let second = lookahead.next();
+
+        if let Some(align) = second.and_then(Alignment::from_char) {
+            spec.fill = first.unwrap_or(' ');
+            spec.align = Some(align);
+            it.next();
+            it.next();
+        } else if let Some(align) = first.and_then(Alignment::from_char) {
+            spec.align = Some(align);
+            it.next();
+        }
+
+        match it.peek() {
+            Some('+') => {
+                spec.sign_plus = true;
+                it.next();
+            }
+            Some('-') => {
+                it.next();
+            }
+            _ => (),
+        }
+
+        if it.peek() == Some(&'#') {
+            spec.alternate = true;
+            it.next();
+        }
+
+        if it.peek() == Some(&'0') {
+            spec.zero_pad = true;
+            it.next();
+        }
+
+        spec.width = parse_count(&mut it)?;
+
+        if it.peek() == Some(&'.') {
+            it.next();
+            spec.precision = Some(parse_count(&mut it)?.ok_or(FormatSpecError::ExpectedPrecision)?);
+        }
+
+        spec.format_type = match it.next() {
+            None => return Ok(spec),
+            Some('?') => FormatType::Debug,
+            Some('x') => FormatType::LowerHex,
+            Some('X') => FormatType::UpperHex,
+            Some('b') => FormatType::Binary,
+            Some('o') => FormatType::Octal,
+            Some(actual) => return Err(FormatSpecError::UnsupportedFormat { actual }),
+        };
+
+        if let Some(actual) = it.next() {
+            return Err(FormatSpecError::UnsupportedFormat { actual });
+        }
+
+        Ok(spec)
+    }
+}
+
+/// Parse a width or a precision.
+fn parse_count<I>(it: &mut std::iter::Peekable<I>) -> Result<Option<u32>, FormatSpecError>
+where
+    I: Iterator<Item = char>,
+{
+    let mut count = None::<u32>;
+
+    while let Some(digit) = it.peek().and_then(|c| c.to_digit(10)) {
+        it.next();
+
+        count = Some(
+            count
+                .unwrap_or_default()
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(digit))
+                .filter(|n| *n <= FormatSpec::MAX_COUNT)
+                .ok_or(FormatSpecError::Overflow)?,
+        );
+    }
+
+    Ok(count)
+}
+
+impl fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(align) = self.align {
+            f.write_char(self.fill)?;
+            write!(f, "{}", align)?;
+        }
+
+        if self.sign_plus {
+            write!(f, "+")?;
+        }
+
+        if self.alternate {
+            write!(f, "#")?;
+        }
+
+        if self.zero_pad {
+            write!(f, "0")?;
+        }
+
+        if let Some(width) = self.width {
+            write!(f, "{}", width)?;
+        }
+
+        if let Some(precision) = self.precision {
+            write!(f, ".{}", precision)?;
+        }
+
+        write!(f, "{}", self.format_type)
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     Tuple, Unit, UnsafeFromValue, Value, VariantRtti, Vm, VmCall, VmError, VmHalt,
 };
 use std::fmt;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
 /// A stored function, of some specific kind.
@@ -43,6 +44,38 @@ impl Function {
         Ok(T::from_value(value)?)
     }
 
+    /// Perform an asynchronous call over the function represented by this
+    /// function pointer.
+    ///
+    /// Unlike [call][Function::call], script functions are run to completion
+    /// in a fresh execution which supports async instructions like `.await`
+    /// and `select`. If the function produces a future, like async script
+    /// functions and async native functions do, it is awaited and its output
+    /// is returned instead.
+    pub async fn async_call<A, T>(&self, args: A) -> Result<T, VmError>
+    where
+        A: Args,
+        T: FromValue,
+    {
+        let value = match &self.inner {
+            Inner::FnOffset(fn_offset) => fn_offset.async_call(args, ()).await?,
+            Inner::FnClosureOffset(closure) => {
+                closure
+                    .fn_offset
+                    .async_call(args, (closure.environment.clone(),))
+                    .await?
+            }
+            _ => self.call::<A, Value>(args)?,
+        };
+
+        let value = match value {
+            Value::Future(future) => future.take()?.await?,
+            value => value,
+        };
+
+        T::from_value(value)
+    }
+
     /// Call with the given virtual machine. This allows for certain
     /// optimizations, like avoiding the allocation of a new vm state in case
     /// the call is internal.
@@ -53,6 +86,7 @@ impl Function {
         let reason = match &self.inner {
             Inner::FnHandler(handler) => {
                 (handler.handler)(vm.stack_mut(), args)?;
+                vm.account_top()?;
                 None
             }
             Inner::FnOffset(fn_offset) => {
@@ -79,6 +113,7 @@ impl Function {
                 let value =
                     Value::typed_tuple(tuple.rtti.clone(), vm.stack_mut().pop_sequence(args)?);
                 vm.stack_mut().push(value);
+                vm.account_top()?;
                 None
             }
             Inner::FnVariantTuple(tuple) => {
@@ -88,6 +123,7 @@ impl Function {
                     Value::variant_tuple(tuple.rtti.clone(), vm.stack_mut().pop_sequence(args)?);
 
                 vm.stack_mut().push(value);
+                vm.account_top()?;
                 None
             }
         };
@@ -250,13 +286,7 @@ impl FnOffset {
         A: Args,
         E: Args,
     {
-        Function::check_args(A::count(), self.args)?;
-
-        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
-
-        vm.set_ip(self.offset);
-        args.into_stack(vm.stack_mut())?;
-        extra.into_stack(vm.stack_mut())?;
+        let vm = self.vm(args, extra)?;
 
         Ok(match self.call {
             Call::Stream => Value::from(Stream::new(vm)),
@@ -266,6 +296,43 @@ impl FnOffset {
         })
     }
 
+    /// Perform an asynchronous call into the specified offset and return the
+    /// produced value.
+    ///
+    /// Immediate and async functions are driven to completion, while streams
+    /// and generators are returned without being resumed.
+    async fn async_call<A, E>(&self, args: A, extra: E) -> Result<Value, VmError>
+    where
+        A: Args,
+        E: Args,
+    {
+        let vm = self.vm(args, extra)?;
+
+        Ok(match self.call {
+            Call::Stream => Value::from(Stream::new(vm)),
+            Call::Generator => Value::from(Generator::new(vm)),
+            Call::Immediate | Call::Async => vm.async_complete().await?,
+        })
+    }
+
+    /// Construct a virtual machine which is set up to call into the specified
+    /// offset.
+    fn vm<A, E>(&self, args: A, extra: E) -> Result<Vm, VmError>
+    where
+        A: Args,
+        E: Args,
+    {
+        Function::check_args(A::count(), self.args)?;
+
+        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
+
+        vm.set_ip(self.offset);
+        vm.inherit_limits()?;
+        args.into_stack(vm.stack_mut())?;
+        extra.into_stack(vm.stack_mut())?;
+        Ok(vm)
+    }
+
     /// Perform a potentially optimized call into the specified vm.
     ///
     /// This will cause a halt in case the vm being called into isn't the same
@@ -289,6 +356,7 @@ impl FnOffset {
         extra.into_stack(&mut new_stack)?;
         let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), new_stack);
         vm.set_ip(self.offset);
+        vm.inherit_limits()?;
         Ok(Some(VmCall::new(self.call, vm)))
     }
 }
@@ -329,6 +397,69 @@ struct FnVariantTuple {
     args: usize,
 }
 
+/// A function with a known signature, which takes the arguments `A` and
+/// returns a value of type `T`.
+///
+/// This can be used as an argument to native functions which call back into
+/// scripts, like `fn on_request(handler: TypedFunction<(Request,), Response>)`.
+/// Arguments and return values are converted when the function is called, so
+/// a mismatch is raised as an error at that point.
+pub struct TypedFunction<A, T> {
+    function: Function,
+    _marker: PhantomData<fn(A) -> T>,
+}
+
+impl<A, T> TypedFunction<A, T>
+where
+    A: Args,
+    T: FromValue,
+{
+    /// Construct a typed function from a function pointer.
+    pub fn new(function: Function) -> Self {
+        Self {
+            function,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Perform a call over the function, see [Function::call].
+    pub fn call(&self, args: A) -> Result<T, VmError> {
+        self.function.call(args)
+    }
+
+    /// Perform an asynchronous call over the function, see
+    /// [Function::async_call].
+    pub async fn async_call(&self, args: A) -> Result<T, VmError> {
+        self.function.async_call(args).await
+    }
+
+    /// Access the underlying function pointer.
+    pub fn function(&self) -> &Function {
+        &self.function
+    }
+
+    /// Convert into the underlying function pointer.
+    pub fn into_function(self) -> Function {
+        self.function
+    }
+}
+
+impl<A, T> fmt::Debug for TypedFunction<A, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.function.fmt(f)
+    }
+}
+
+impl<A, T> FromValue for TypedFunction<A, T>
+where
+    A: 'static + Args,
+    T: 'static + FromValue,
+{
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(Self::new(Function::from_value(value)?))
+    }
+}
+
 impl FromValue for Function {
     fn from_value(value: Value) -> Result<Self, VmError> {
         Ok(value.into_function()?.take()?)
@@ -42,7 +42,7 @@ impl Generator {
             execution.vm_mut()?.stack_mut().push(value);
         }
 
-        let state = execution.resume()?;
+        let state = execution.resume().map_err(VmError::into_unresumable)?;
 
         if state.is_complete() {
             self.execution = None;
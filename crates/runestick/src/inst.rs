@@ -1,4 +1,4 @@
-use crate::{Hash, Value};
+use crate::{FormatSpec, Hash, RangeLimits, Value};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -600,6 +600,20 @@ pub enum Inst {
         /// The static slot of the object keys.
         slot: usize,
     },
+    /// Construct a range out of the bounds on the stack. Which bounds are
+    /// present on the stack is determined by `range`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <start?>
+    /// <end?>
+    /// => <range>
+    /// ```
+    Range {
+        /// The kind of range to construct.
+        range: InstRange,
+    },
     /// Load a literal string from a static string slot.
     ///
     /// # Operation
@@ -639,6 +653,22 @@ pub enum Inst {
         /// The minimum string size used.
         size_hint: usize,
     },
+    /// Pop a value from the stack and format it into a string according to
+    /// the given specification.
+    ///
+    /// This is used by template strings for expressions with a format
+    /// specification, like `{value:>8}`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <string>
+    /// ```
+    Format {
+        /// The format specification to use.
+        spec: FormatSpec,
+    },
     /// Test if the top of the stack is an instance of the second item on the
     /// stack.
     ///
@@ -761,6 +791,21 @@ pub enum Inst {
         /// The slot to test against.
         slot: usize,
     },
+    /// Test if a value is contained in the range formed by the bounds on the
+    /// stack. Which bounds are present on the stack is determined by `range`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// <start?>
+    /// <end?>
+    /// => <boolean>
+    /// ```
+    MatchRange {
+        /// The kind of range to test against.
+        range: InstRange,
+    },
     /// Test that the top of the stack is a tuple with the given length
     /// requirements.
     ///
@@ -1042,6 +1087,9 @@ impl fmt::Display for Inst {
             Self::Object { slot } => {
                 write!(fmt, "object {}", slot)?;
             }
+            Self::Range { range } => {
+                write!(fmt, "range {}", range)?;
+            }
             Self::String { slot } => {
                 write!(fmt, "string {}", slot)?;
             }
@@ -1051,6 +1099,9 @@ impl fmt::Display for Inst {
             Self::StringConcat { len, size_hint } => {
                 write!(fmt, "string-concat {}, {}", len, size_hint)?;
             }
+            Self::Format { spec } => {
+                write!(fmt, "format {:?}", spec.to_string())?;
+            }
             Self::Is => {
                 write!(fmt, "is")?;
             }
@@ -1084,6 +1135,9 @@ impl fmt::Display for Inst {
             Self::EqStaticString { slot } => {
                 write!(fmt, "eq-static-string {}", slot)?;
             }
+            Self::MatchRange { range } => {
+                write!(fmt, "match-range {}", range)?;
+            }
             Self::MatchSequence {
                 type_check,
                 len,
@@ -1140,6 +1194,56 @@ impl fmt::Display for InstTarget {
     }
 }
 
+/// The kind of range constructed or tested by an instruction.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum InstRange {
+    /// A full range `..`.
+    RangeFull,
+    /// A range with only a start `start..`.
+    RangeFrom,
+    /// A half-open range with only an end `..end`.
+    RangeTo,
+    /// A closed range with only an end `..=end`.
+    RangeToInclusive,
+    /// A half-open range `start..end`.
+    Range,
+    /// A closed range `start..=end`.
+    RangeInclusive,
+}
+
+impl InstRange {
+    /// Test if the range has a start.
+    pub fn has_start(self) -> bool {
+        matches!(self, Self::RangeFrom | Self::Range | Self::RangeInclusive)
+    }
+
+    /// Test if the range has an end.
+    pub fn has_end(self) -> bool {
+        !matches!(self, Self::RangeFull | Self::RangeFrom)
+    }
+
+    /// The limits of the range.
+    pub fn limits(self) -> RangeLimits {
+        match self {
+            Self::RangeToInclusive | Self::RangeInclusive => RangeLimits::Closed,
+            _ => RangeLimits::HalfOpen,
+        }
+    }
+}
+
+impl fmt::Display for InstRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RangeFull => write!(f, ".."),
+            Self::RangeFrom => write!(f, "start.."),
+            Self::RangeTo => write!(f, "..end"),
+            Self::RangeToInclusive => write!(f, "..=end"),
+            Self::Range => write!(f, "start..end"),
+            Self::RangeInclusive => write!(f, "start..=end"),
+        }
+    }
+}
+
 /// An operation between two values on the machine.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum InstOp {
@@ -0,0 +1,141 @@
+use crate::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+/// A handle which interrupts a running virtual machine from any thread,
+/// obtained through [Vm::interrupt_handle][crate::Vm::interrupt_handle] or
+/// [VmExecution::interrupt_handle][crate::VmExecution::interrupt_handle].
+///
+/// An interrupted virtual machine stops before executing its next
+/// instruction, and any future it's awaiting is dropped. Either way its
+/// execution errors with [VmHaltInfo::Interrupted], and the stack of the
+/// execution is left as it was so that it can be inspected.
+///
+/// Interruption is permanent, so every virtual machine sharing the handle
+/// stays interrupted.
+///
+/// ```rust
+/// use runestick::{Context, Unit, Vm};
+/// use std::sync::Arc;
+///
+/// let mut vm = Vm::new(Arc::new(Context::new()), Arc::new(Unit::default()));
+/// let handle = vm.interrupt_handle();
+///
+/// std::thread::spawn(move || handle.interrupt()).join().unwrap();
+/// assert!(vm.interrupt_handle().is_interrupted());
+/// ```
+///
+/// [VmHaltInfo::Interrupted]: crate::VmHaltInfo::Interrupted
+#[derive(Debug, Clone, Default)]
+pub struct InterruptHandle {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// If the handle has been interrupted.
+    interrupted: AtomicBool,
+    /// Wakers of the tasks waiting for the handle to be interrupted.
+    waiters: Mutex<Waiters>,
+}
+
+#[derive(Debug, Default)]
+struct Waiters {
+    /// The key of the next waiter.
+    next: usize,
+    /// Wakers of waiters by key.
+    wakers: HashMap<usize, Waker>,
+}
+
+impl InterruptHandle {
+    /// Construct a new handle which hasn't been interrupted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interrupt every virtual machine sharing this handle.
+    pub fn interrupt(&self) {
+        self.inner.interrupted.store(true, Ordering::SeqCst);
+
+        let wakers = std::mem::take(&mut self.waiters().wakers);
+
+        for (_, waker) in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Test if the handle has been interrupted.
+    pub fn is_interrupted(&self) -> bool {
+        self.inner.interrupted.load(Ordering::SeqCst)
+    }
+
+    /// Get a future which completes once the handle has been interrupted.
+    pub(crate) fn interrupted(&self) -> Interrupted {
+        Interrupted {
+            handle: self.clone(),
+            key: None,
+        }
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, Waiters> {
+        // NB: the waiters are always left in a consistent state, so a
+        // poisoned lock is safe to use.
+        match self.inner.waiters.lock() {
+            Ok(waiters) => waiters,
+            Err(error) => error.into_inner(),
+        }
+    }
+}
+
+/// Future returned by [InterruptHandle::interrupted].
+pub(crate) struct Interrupted {
+    handle: InterruptHandle,
+    key: Option<usize>,
+}
+
+impl Future for Interrupted {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+
+        if this.handle.is_interrupted() {
+            return Poll::Ready(());
+        }
+
+        {
+            let mut waiters = this.handle.waiters();
+
+            let key = match this.key {
+                Some(key) => key,
+                None => {
+                    let key = waiters.next;
+                    waiters.next = waiters.next.wrapping_add(1);
+                    this.key = Some(key);
+                    key
+                }
+            };
+
+            waiters.wakers.insert(key, cx.waker().clone());
+        }
+
+        // NB: check again in case the handle was interrupted while the waker
+        // was being registered.
+        if this.handle.is_interrupted() {
+            return Poll::Ready(());
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Interrupted {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.handle.waiters().wakers.remove(&key);
+        }
+    }
+}
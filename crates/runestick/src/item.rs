@@ -125,6 +125,23 @@ impl Item {
         Self { content }
     }
 
+    /// Test if the item starts with the given item, or is equal to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Item;
+    ///
+    /// let item = Item::of(&["foo", "bar"]);
+    /// assert!(item.starts_with(&Item::of(&["foo"])));
+    /// assert!(item.starts_with(&Item::new()));
+    /// assert!(!item.starts_with(&Item::of(&["foo", "bar", "baz"])));
+    /// assert!(!item.starts_with(&Item::of(&["fo"])));
+    /// ```
+    pub fn starts_with(&self, other: &Item) -> bool {
+        self.content.starts_with(&other.content)
+    }
+
     /// Access the last component in the path.
     pub fn last(&self) -> Option<Component> {
         self.iter().next_back()
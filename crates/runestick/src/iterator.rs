@@ -0,0 +1,645 @@
+use crate::{
+    FromValue as _, Function, Generator, MemoryUsage, Object, Shared, ToValue, Value, VmError,
+    VmErrorKind,
+};
+use std::fmt;
+use std::mem;
+
+/// An iterator which can be used in a virtual machine, together with the lazy
+/// adapters which can be applied to it.
+///
+/// Adapters like [map][Iterator::map] and [filter][Iterator::filter] call
+/// back into functions, which is why advancing an iterator is fallible.
+pub struct Iterator {
+    name: &'static str,
+    iter: Iter,
+}
+
+impl Iterator {
+    /// Construct an iterator from a rust iterator.
+    pub fn from<I>(name: &'static str, iter: I) -> Self
+    where
+        I: 'static + std::iter::Iterator,
+        I::Item: ToValue,
+    {
+        Self {
+            name,
+            iter: Iter::Iterator(Box::new(FromIter(iter))),
+        }
+    }
+
+    /// Construct an iterator from a rust iterator which can be reversed.
+    pub fn from_double_ended<I>(name: &'static str, iter: I) -> Self
+    where
+        I: 'static + DoubleEndedIterator,
+        I::Item: ToValue,
+    {
+        Self {
+            name,
+            iter: Iter::DoubleEndedIterator(Box::new(FromIter(iter))),
+        }
+    }
+
+    /// Construct an iterator over the values of a vector, which accounts for
+    /// the memory allocated by the vector against the limits of a virtual
+    /// machine.
+    pub fn from_vec<T>(name: &'static str, vec: Vec<T>) -> Self
+    where
+        T: 'static + ToValue,
+    {
+        Self {
+            name,
+            iter: Iter::DoubleEndedIterator(Box::new(FromVec(vec.into_iter()))),
+        }
+    }
+
+    /// Construct an iterator which calls the given `next` function on `value`
+    /// to advance it, as in the [NEXT][crate::NEXT] protocol.
+    pub fn from_protocol(value: Value, next: Function) -> Self {
+        Self::adapter("Protocol", Protocol { value, next })
+    }
+
+    /// Construct an iterator over the values yielded by a generator.
+    pub fn from_generator(generator: Shared<Generator>) -> Self {
+        Self::adapter("Generator", generator)
+    }
+
+    /// Get the next value produced by the iterator.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.iter.next()
+    }
+
+    /// Get the next value from the back of the iterator.
+    pub fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        match &mut self.iter {
+            Iter::DoubleEndedIterator(iter) => iter.next_back(),
+            Iter::Iterator(..) => Err(self.not_double_ended()),
+        }
+    }
+
+    /// Convert the iterator into itself, as in the [INTO_ITER][crate::INTO_ITER]
+    /// protocol.
+    #[allow(clippy::should_implement_trait)]
+    pub fn into_iter(self) -> Self {
+        self
+    }
+
+    /// Reverse the iterator.
+    pub fn rev(self) -> Result<Self, VmError> {
+        match self.iter {
+            Iter::DoubleEndedIterator(iter) => Ok(Self {
+                name: "Rev",
+                iter: Iter::DoubleEndedIterator(Box::new(Rev(iter))),
+            }),
+            Iter::Iterator(..) => Err(self.not_double_ended()),
+        }
+    }
+
+    /// Map each value with the given function.
+    pub fn map(self, f: Shared<Function>) -> Self {
+        Self::adapter("Map", Map { iter: self, f })
+    }
+
+    /// Only keep values for which the given function returns `true`.
+    pub fn filter(self, f: Shared<Function>) -> Self {
+        Self::adapter("Filter", Filter { iter: self, f })
+    }
+
+    /// Map each value with the given function, skipping values for which it
+    /// returns `None`.
+    pub fn filter_map(self, f: Shared<Function>) -> Self {
+        Self::adapter("FilterMap", FilterMap { iter: self, f })
+    }
+
+    /// Map each value into an iterator with the given function, and iterate
+    /// over the produced values.
+    pub fn flat_map(self, f: Shared<Function>) -> Self {
+        Self::adapter(
+            "FlatMap",
+            FlatMap {
+                iter: self,
+                f,
+                current: None,
+            },
+        )
+    }
+
+    /// Pair each value with its index.
+    pub fn enumerate(self) -> Self {
+        Self::adapter(
+            "Enumerate",
+            Enumerate {
+                iter: self,
+                count: 0,
+            },
+        )
+    }
+
+    /// Pair each value with a value from `other`, until either is exhausted.
+    pub fn zip(self, other: Value) -> Result<Self, VmError> {
+        let b = other.into_iterator()?;
+        Ok(Self::adapter("Zip", Zip { a: self, b }))
+    }
+
+    /// Produce the values of `other` after the values of this iterator.
+    pub fn chain(self, other: Value) -> Result<Self, VmError> {
+        let b = other.into_iterator()?;
+
+        Ok(Self::adapter("Chain", Chain { a: Some(self), b }))
+    }
+
+    /// Only produce the first `n` values.
+    pub fn take(self, n: usize) -> Self {
+        Self::adapter("Take", Take { iter: self, n })
+    }
+
+    /// Skip the first `n` values.
+    pub fn skip(self, n: usize) -> Self {
+        Self::adapter("Skip", Skip { iter: self, n })
+    }
+
+    /// Fold every value into an accumulator with the given function.
+    pub fn fold(mut self, init: Value, f: Shared<Function>) -> Result<Value, VmError> {
+        let f = f.borrow_ref()?;
+        let mut acc = init;
+
+        while let Some(value) = self.next()? {
+            acc = f.call((acc, value))?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Test if the given function returns `true` for any value.
+    pub fn any(mut self, f: Shared<Function>) -> Result<bool, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            if f.call::<_, bool>((value,))? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Test if the given function returns `true` for all values.
+    pub fn all(mut self, f: Shared<Function>) -> Result<bool, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            if !f.call::<_, bool>((value,))? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Find the first value for which the given function returns `true`.
+    pub fn find(mut self, f: Shared<Function>) -> Result<Option<Value>, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            if f.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Count the number of values produced.
+    pub fn count(mut self) -> Result<usize, VmError> {
+        let mut count = 0;
+
+        while self.next()?.is_some() {
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Sum all integers or floats produced. The sum of no values is `0`.
+    pub fn sum(mut self) -> Result<Value, VmError> {
+        let mut sum = match self.next()? {
+            Some(value) => value,
+            None => return Ok(Value::Integer(0)),
+        };
+
+        while let Some(value) = self.next()? {
+            sum = match (sum, value) {
+                (Value::Integer(a), Value::Integer(b)) => {
+                    Value::Integer(a.checked_add(b).ok_or(VmErrorKind::Overflow)?)
+                }
+                (Value::Float(a), Value::Float(b)) => Value::Float(a + b),
+                (lhs, rhs) => {
+                    return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                        op: "+",
+                        lhs: lhs.type_info()?,
+                        rhs: rhs.type_info()?,
+                    }));
+                }
+            };
+        }
+
+        Ok(sum)
+    }
+
+    /// Collect all values into a vector.
+    ///
+    /// This is also available as `collect` in scripts. Since scripts can't
+    /// hint at the type to collect into, it always collects into a vector,
+    /// and other collections have functions like
+    /// [collect_object][Self::collect_object] instead.
+    pub fn collect_vec(mut self) -> Result<Vec<Value>, VmError> {
+        let mut vec = Vec::new();
+
+        while let Some(value) = self.next()? {
+            vec.push(value);
+        }
+
+        Ok(vec)
+    }
+
+    /// Collect all `(key, value)` pairs into an object.
+    pub fn collect_object(mut self) -> Result<Object, VmError> {
+        let mut object = Object::new();
+
+        while let Some(value) = self.next()? {
+            let (key, value) = <(String, Value)>::from_value(value)?;
+            object.insert(key, value);
+        }
+
+        Ok(object)
+    }
+
+    /// Collect all strings and characters into a string.
+    pub fn collect_string(mut self) -> Result<String, VmError> {
+        let mut string = String::new();
+
+        while let Some(value) = self.next()? {
+            match value {
+                Value::Char(c) => string.push(c),
+                Value::String(s) => string.push_str(&s.borrow_ref()?),
+                Value::StaticString(s) => string.push_str(&s),
+                actual => {
+                    return Err(VmError::expected::<String>(actual.type_info()?));
+                }
+            }
+        }
+
+        Ok(string)
+    }
+
+    fn adapter<T>(name: &'static str, iter: T) -> Self
+    where
+        T: 'static + RuneIterator,
+    {
+        Self {
+            name,
+            iter: Iter::Iterator(Box::new(iter)),
+        }
+    }
+
+    fn not_double_ended(&self) -> VmError {
+        VmError::from(VmErrorKind::NotDoubleEndedIterator { name: self.name })
+    }
+}
+
+impl fmt::Debug for Iterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iterator").field(&self.name).finish()
+    }
+}
+
+impl MemoryUsage for Iterator {
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+
+    // NB: the values produced by an iterator are accounted for by the
+    // collections they're produced from.
+    fn memory_values(&self, _: &mut dyn FnMut(&Value)) {}
+}
+
+crate::__internal_impl_any!(Iterator, memory);
+
+enum Iter {
+    Iterator(Box<dyn RuneIterator>),
+    DoubleEndedIterator(Box<dyn RuneDoubleEndedIterator>),
+}
+
+impl Iter {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        match self {
+            Self::Iterator(iter) => iter.next(),
+            Self::DoubleEndedIterator(iter) => iter.next(),
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        match self {
+            Self::Iterator(iter) => iter.memory_size(),
+            Self::DoubleEndedIterator(iter) => iter.memory_size(),
+        }
+    }
+}
+
+trait RuneIterator {
+    fn next(&mut self) -> Result<Option<Value>, VmError>;
+
+    /// The approximate number of bytes allocated by the iterator.
+    fn memory_size(&self) -> usize {
+        0
+    }
+}
+
+trait RuneDoubleEndedIterator: RuneIterator {
+    fn next_back(&mut self) -> Result<Option<Value>, VmError>;
+}
+
+struct FromIter<I>(I);
+
+impl<I> RuneIterator for FromIter<I>
+where
+    I: std::iter::Iterator,
+    I::Item: ToValue,
+{
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.0.next().map(ToValue::to_value).transpose()
+    }
+}
+
+impl<I> RuneDoubleEndedIterator for FromIter<I>
+where
+    I: DoubleEndedIterator,
+    I::Item: ToValue,
+{
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        self.0.next_back().map(ToValue::to_value).transpose()
+    }
+}
+
+struct FromVec<T>(std::vec::IntoIter<T>);
+
+impl<T> RuneIterator for FromVec<T>
+where
+    T: ToValue,
+{
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.0.next().map(ToValue::to_value).transpose()
+    }
+
+    fn memory_size(&self) -> usize {
+        self.0.len() * mem::size_of::<T>()
+    }
+}
+
+impl<T> RuneDoubleEndedIterator for FromVec<T>
+where
+    T: ToValue,
+{
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        self.0.next_back().map(ToValue::to_value).transpose()
+    }
+}
+
+struct Rev(Box<dyn RuneDoubleEndedIterator>);
+
+impl RuneIterator for Rev {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.0.next_back()
+    }
+
+    fn memory_size(&self) -> usize {
+        self.0.memory_size()
+    }
+}
+
+impl RuneDoubleEndedIterator for Rev {
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        self.0.next()
+    }
+}
+
+struct Protocol {
+    value: Value,
+    next: Function,
+}
+
+impl RuneIterator for Protocol {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.next.call((self.value.clone(),))
+    }
+}
+
+impl RuneIterator for Shared<Generator> {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.borrow_mut()?.next()
+    }
+}
+
+struct Map {
+    iter: Iterator,
+    f: Shared<Function>,
+}
+
+impl RuneIterator for Map {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        match self.iter.next()? {
+            Some(value) => Ok(Some(self.f.borrow_ref()?.call((value,))?)),
+            None => Ok(None),
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+}
+
+struct Filter {
+    iter: Iterator,
+    f: Shared<Function>,
+}
+
+impl RuneIterator for Filter {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next()? {
+            if self.f.borrow_ref()?.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+}
+
+struct FilterMap {
+    iter: Iterator,
+    f: Shared<Function>,
+}
+
+impl RuneIterator for FilterMap {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next()? {
+            if let Some(value) = self.f.borrow_ref()?.call::<_, Option<Value>>((value,))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+}
+
+struct FlatMap {
+    iter: Iterator,
+    f: Shared<Function>,
+    current: Option<Iterator>,
+}
+
+impl RuneIterator for FlatMap {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        loop {
+            if let Some(current) = &mut self.current {
+                if let Some(value) = current.next()? {
+                    return Ok(Some(value));
+                }
+            }
+
+            let value = match self.iter.next()? {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+
+            let value = self.f.borrow_ref()?.call::<_, Value>((value,))?;
+            self.current = Some(value.into_iterator()?);
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size() + self.current.as_ref().map_or(0, |c| c.memory_size())
+    }
+}
+
+struct Enumerate {
+    iter: Iterator,
+    count: usize,
+}
+
+impl RuneIterator for Enumerate {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let value = match self.iter.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let index = self.count;
+        self.count += 1;
+        Ok(Some((index, value).to_value()?))
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+}
+
+struct Zip {
+    a: Iterator,
+    b: Iterator,
+}
+
+impl RuneIterator for Zip {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let a = match self.a.next()? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let b = match self.b.next()? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+
+        Ok(Some((a, b).to_value()?))
+    }
+
+    fn memory_size(&self) -> usize {
+        self.a.memory_size() + self.b.memory_size()
+    }
+}
+
+struct Chain {
+    a: Option<Iterator>,
+    b: Iterator,
+}
+
+impl RuneIterator for Chain {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if let Some(a) = &mut self.a {
+            if let Some(value) = a.next()? {
+                return Ok(Some(value));
+            }
+
+            self.a = None;
+        }
+
+        self.b.next()
+    }
+
+    fn memory_size(&self) -> usize {
+        self.a.as_ref().map_or(0, |a| a.memory_size()) + self.b.memory_size()
+    }
+}
+
+struct Take {
+    iter: Iterator,
+    n: usize,
+}
+
+impl RuneIterator for Take {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.n == 0 {
+            return Ok(None);
+        }
+
+        self.n -= 1;
+        self.iter.next()
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+}
+
+struct Skip {
+    iter: Iterator,
+    n: usize,
+}
+
+impl RuneIterator for Skip {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while self.n > 0 {
+            self.n -= 1;
+
+            if self.iter.next()?.is_none() {
+                return Ok(None);
+            }
+        }
+
+        self.iter.next()
+    }
+
+    fn memory_size(&self) -> usize {
+        self.iter.memory_size()
+    }
+}
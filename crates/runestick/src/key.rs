@@ -0,0 +1,576 @@
+use crate::vm_env::VmEnv;
+use crate::{
+    Any, Bytes, FromValue, Hash, Object, ObjectVariant, Rtti, Shared, ToValue, TupleVariant,
+    TypedObject, TypedTuple, Value, VariantRtti, VmError, VmErrorKind,
+};
+use std::any;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash;
+use std::mem;
+use std::sync::Arc;
+
+thread_local!(static ERROR: RefCell<Option<Option<VmError>>> = const { RefCell::new(None) });
+
+/// A value which can be used as a key in a map or a set, like a `HashMap` or
+/// a `BTreeMap`.
+///
+/// A key is a snapshot of a [Value] that can be hashed and totally ordered,
+/// so mutating the value it was constructed from doesn't affect the key.
+/// Keys of different kinds are never equal, and are ordered by kind first.
+///
+/// Script structs and enums are used as keys by their fields, unless they
+/// implement the [EQ][crate::EQ], [HASH][crate::HASH] or
+/// [PARTIAL_CMP][crate::PARTIAL_CMP] protocol. Then they are hashed and
+/// compared through the protocols, see [ValueKey].
+///
+/// Native types can be used as keys by implementing the [HASH][crate::HASH]
+/// and [EQ][crate::EQ] protocols, or by deriving `Any` with the
+/// `#[rune(key)]` attribute, which requires the type to implement `Clone`,
+/// `Hash` and `Ord`.
+///
+/// ```rust
+/// use runestick::Any;
+///
+/// #[derive(Any, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// #[rune(key)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Key {
+    /// The unit value.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// A single byte.
+    Byte(u8),
+    /// A character.
+    Char(char),
+    /// An integer.
+    Integer(i64),
+    /// A string.
+    String(String),
+    /// A byte array.
+    Bytes(Bytes),
+    /// A vector of keys.
+    Vec(Box<[Key]>),
+    /// A tuple of keys.
+    Tuple(Box<[Key]>),
+    /// An optional key.
+    Option(Option<Box<Key>>),
+    /// A typed tuple, like `struct Point(x, y)`.
+    TypedTuple(Arc<Rtti>, Box<[Key]>),
+    /// A typed object, like `struct Point { x, y }`, with fields sorted by
+    /// name.
+    TypedObject(Arc<Rtti>, Box<[(String, Key)]>),
+    /// A variant of an enum, like `Kind::A` or `Kind::B(1)`.
+    Variant(Arc<VariantRtti>, Box<[Key]>),
+    /// An object variant of an enum, like `Kind::C { a: 1 }`, with fields
+    /// sorted by name.
+    ObjectVariant(Arc<VariantRtti>, Box<[(String, Key)]>),
+    /// A native type which has opted in to being used as a key.
+    Any(AnyKey),
+    /// A value which is hashed and compared through its protocols.
+    Value(ValueKey),
+}
+
+impl Key {
+    /// Construct a key from a native value.
+    pub fn from_any<T>(value: T) -> Self
+    where
+        T: Any + Clone + hash::Hash + Ord,
+    {
+        Self::Any(AnyKey {
+            key: Box::new(value),
+        })
+    }
+
+    /// Construct a key from a value.
+    ///
+    /// Errors with [VmErrorKind::UnsupportedKey] if the value, or any value it
+    /// contains, can't be used as a key.
+    pub fn from_value(value: &Value) -> Result<Self, VmError> {
+        if let Value::TypedTuple(..)
+        | Value::TupleVariant(..)
+        | Value::TypedObject(..)
+        | Value::ObjectVariant(..) = value
+        {
+            let type_of = value.type_of()?;
+
+            if [crate::EQ, crate::HASH, crate::PARTIAL_CMP]
+                .iter()
+                .any(|protocol| VmEnv::has_protocol(type_of, *protocol))
+            {
+                return Ok(Self::Value(ValueKey::new(value)?));
+            }
+        }
+
+        Ok(match value {
+            Value::Unit => Self::Unit,
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Byte(b) => Self::Byte(*b),
+            Value::Char(c) => Self::Char(*c),
+            Value::Integer(n) => Self::Integer(*n),
+            Value::StaticString(s) => Self::String((***s).clone()),
+            Value::String(s) => Self::String(s.borrow_ref()?.clone()),
+            Value::Bytes(b) => Self::Bytes(b.borrow_ref()?.clone()),
+            Value::Vec(vec) => Self::Vec(Self::from_slice(&vec.borrow_ref()?)?),
+            Value::Tuple(tuple) => Self::Tuple(Self::from_slice(&tuple.borrow_ref()?)?),
+            Value::Option(option) => Self::Option(match &*option.borrow_ref()? {
+                Some(value) => Some(Box::new(Self::from_value(value)?)),
+                None => None,
+            }),
+            Value::TypedTuple(typed) => {
+                let typed = typed.borrow_ref()?;
+                Self::TypedTuple(typed.rtti.clone(), Self::from_slice(&typed.tuple)?)
+            }
+            Value::TupleVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                Self::Variant(variant.rtti.clone(), Self::from_slice(&variant.tuple)?)
+            }
+            Value::TypedObject(typed) => {
+                let typed = typed.borrow_ref()?;
+                Self::TypedObject(typed.rtti.clone(), Self::from_object(&typed.object)?)
+            }
+            Value::ObjectVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                Self::ObjectVariant(variant.rtti.clone(), Self::from_object(&variant.object)?)
+            }
+            Value::Any(any) => {
+                if let Some(key) = any.borrow_ref()?.key() {
+                    return Ok(key);
+                }
+
+                let type_of = value.type_of()?;
+
+                if !VmEnv::has_protocol(type_of, crate::HASH)
+                    || !VmEnv::has_protocol(type_of, crate::EQ)
+                {
+                    return Err(Self::unsupported(value)?);
+                }
+
+                Self::Value(ValueKey::new(value)?)
+            }
+            value => return Err(Self::unsupported(value)?),
+        })
+    }
+
+    /// Convert the key back into a value.
+    pub fn into_value(self) -> Value {
+        match self {
+            Self::Unit => Value::Unit,
+            Self::Bool(b) => Value::Bool(b),
+            Self::Byte(b) => Value::Byte(b),
+            Self::Char(c) => Value::Char(c),
+            Self::Integer(n) => Value::Integer(n),
+            Self::String(s) => Value::from(s),
+            Self::Bytes(b) => Value::from(b),
+            Self::Vec(keys) => Value::Vec(Shared::new(Self::into_values(keys))),
+            Self::Tuple(keys) => Value::from(crate::Tuple::from(Self::into_values(keys))),
+            Self::Option(key) => Value::Option(Shared::new(key.map(|key| key.into_value()))),
+            Self::TypedTuple(rtti, keys) => Value::from(TypedTuple {
+                rtti,
+                tuple: crate::Tuple::from(Self::into_values(keys)),
+            }),
+            Self::TypedObject(rtti, fields) => Value::from(TypedObject {
+                rtti,
+                object: Self::into_object(fields),
+            }),
+            Self::Variant(rtti, keys) => Value::from(TupleVariant {
+                rtti,
+                tuple: crate::Tuple::from(Self::into_values(keys)),
+            }),
+            Self::ObjectVariant(rtti, fields) => Value::from(ObjectVariant {
+                rtti,
+                object: Self::into_object(fields),
+            }),
+            Self::Any(key) => key.key.to_value(),
+            Self::Value(key) => key.value,
+        }
+    }
+
+    /// Perform an operation over keys, like looking up a key in a map.
+    ///
+    /// Comparing a [ValueKey] calls into the virtual machine, which can fail.
+    /// Since comparisons can't return errors, the first error raised while
+    /// performing the operation is returned instead of its output.
+    pub(crate) fn guard<T>(f: impl FnOnce() -> T) -> Result<T, VmError> {
+        let previous = ERROR.with(|error| error.replace(Some(None)));
+        let output = f();
+
+        match ERROR.with(|error| error.replace(previous)).flatten() {
+            Some(error) => Err(error),
+            None => Ok(output),
+        }
+    }
+
+    /// Visit the values held by the key, which are referenced instead of
+    /// being copied into it.
+    pub(crate) fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+        match self {
+            Self::Vec(keys) | Self::Tuple(keys) => {
+                keys.iter().for_each(|key| key.memory_values(visit))
+            }
+            Self::TypedTuple(_, keys) | Self::Variant(_, keys) => {
+                keys.iter().for_each(|key| key.memory_values(visit))
+            }
+            Self::TypedObject(_, fields) | Self::ObjectVariant(_, fields) => {
+                fields.iter().for_each(|(_, key)| key.memory_values(visit))
+            }
+            Self::Option(Some(key)) => key.memory_values(visit),
+            Self::Value(key) => visit(&key.value),
+            _ => (),
+        }
+    }
+
+    /// The approximate number of bytes allocated by the key, not counting the
+    /// key itself.
+    pub(crate) fn memory_size(&self) -> usize {
+        match self {
+            Self::String(s) => s.capacity(),
+            Self::Bytes(b) => b.capacity(),
+            Self::Vec(keys) | Self::Tuple(keys) => Self::slice_memory_size(keys),
+            Self::TypedTuple(_, keys) | Self::Variant(_, keys) => Self::slice_memory_size(keys),
+            Self::TypedObject(_, fields) | Self::ObjectVariant(_, fields) => fields
+                .iter()
+                .map(|(name, key)| {
+                    mem::size_of::<(String, Self)>() + name.capacity() + key.memory_size()
+                })
+                .sum(),
+            Self::Option(Some(key)) => mem::size_of::<Self>() + key.memory_size(),
+            _ => 0,
+        }
+    }
+
+    fn slice_memory_size(keys: &[Self]) -> usize {
+        keys.iter()
+            .map(|key| mem::size_of::<Self>() + key.memory_size())
+            .sum()
+    }
+
+    fn from_slice(values: &[Value]) -> Result<Box<[Self]>, VmError> {
+        values.iter().map(Self::from_value).collect()
+    }
+
+    fn into_values(keys: Box<[Self]>) -> Vec<Value> {
+        keys.into_vec().into_iter().map(Self::into_value).collect()
+    }
+
+    fn from_object(object: &Object) -> Result<Box<[(String, Self)]>, VmError> {
+        let mut fields = object
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), Self::from_value(value)?)))
+            .collect::<Result<Vec<_>, VmError>>()?;
+
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(fields.into_boxed_slice())
+    }
+
+    fn into_object(fields: Box<[(String, Self)]>) -> Object {
+        let mut object = Object::with_capacity(fields.len());
+
+        for (name, key) in fields.into_vec() {
+            object.insert(name, key.into_value());
+        }
+
+        object
+    }
+
+    fn unsupported(value: &Value) -> Result<VmError, VmError> {
+        Ok(VmError::from(VmErrorKind::UnsupportedKey {
+            actual: value.type_info()?,
+        }))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unit => write!(f, "()"),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Byte(b) => write!(f, "{:?}", b),
+            Self::Char(c) => write!(f, "{:?}", c),
+            Self::Integer(n) => write!(f, "{}", n),
+            Self::String(s) => write!(f, "{:?}", s),
+            Self::Bytes(b) => write!(f, "{:?}", b),
+            Self::Vec(keys) => display_list(f, "[", keys, "]"),
+            Self::Tuple(keys) => display_list(f, "(", keys, ")"),
+            Self::Option(Some(key)) => write!(f, "Some({})", key),
+            Self::Option(None) => write!(f, "None"),
+            Self::TypedTuple(rtti, keys) => display_typed(f, &rtti.item, keys),
+            Self::TypedObject(rtti, fields) => display_object(f, &rtti.item, fields),
+            Self::Variant(rtti, keys) => display_typed(f, &rtti.item, keys),
+            Self::ObjectVariant(rtti, fields) => display_object(f, &rtti.item, fields),
+            Self::Any(key) => write!(f, "{}", key.key.type_name()),
+            Self::Value(key) => {
+                let mut buf = String::new();
+
+                match VmEnv::vm().and_then(|mut vm| vm.debug_value(&key.value, &mut buf)) {
+                    Ok(()) => write!(f, "{}", buf),
+                    Err(..) => write!(f, "{:?}", key.value),
+                }
+            }
+        }
+    }
+}
+
+fn display_list(f: &mut fmt::Formatter<'_>, open: &str, keys: &[Key], close: &str) -> fmt::Result {
+    write!(f, "{}", open)?;
+
+    let mut it = keys.iter().peekable();
+
+    while let Some(key) = it.next() {
+        write!(f, "{}", key)?;
+
+        if it.peek().is_some() {
+            write!(f, ", ")?;
+        }
+    }
+
+    write!(f, "{}", close)
+}
+
+fn display_typed(f: &mut fmt::Formatter<'_>, item: &crate::Item, keys: &[Key]) -> fmt::Result {
+    write!(f, "{}", item)?;
+
+    if !keys.is_empty() {
+        display_list(f, "(", keys, ")")?;
+    }
+
+    Ok(())
+}
+
+fn display_object(
+    f: &mut fmt::Formatter<'_>,
+    item: &crate::Item,
+    fields: &[(String, Key)],
+) -> fmt::Result {
+    write!(f, "{} {{", item)?;
+
+    let mut it = fields.iter().peekable();
+
+    while let Some((name, key)) = it.next() {
+        write!(f, " {}: {}", name, key)?;
+
+        if it.peek().is_some() {
+            write!(f, ",")?;
+        }
+    }
+
+    write!(f, " }}")
+}
+
+impl FromValue for Key {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Self::from_value(&value)
+    }
+}
+
+impl ToValue for Key {
+    fn to_value(self) -> Result<Value, VmError> {
+        Ok(self.into_value())
+    }
+}
+
+/// A native value which is used as a [Key].
+pub struct AnyKey {
+    key: Box<dyn DynKey>,
+}
+
+impl Clone for AnyKey {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.dyn_clone(),
+        }
+    }
+}
+
+impl fmt::Debug for AnyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key.type_name())
+    }
+}
+
+impl PartialEq for AnyKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for AnyKey {}
+
+impl PartialOrd for AnyKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AnyKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.dyn_cmp(&*other.key)
+    }
+}
+
+impl hash::Hash for AnyKey {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.key.dyn_hash(state);
+    }
+}
+
+/// A value which is used as a [Key] by hashing it through the
+/// [HASH][crate::HASH] protocol, and comparing it through the
+/// [EQ][crate::EQ] and [PARTIAL_CMP][crate::PARTIAL_CMP] protocols.
+///
+/// The value is hashed when the key is constructed, but unlike other keys it
+/// isn't copied. So mutating it while it's used as a key is a logic error,
+/// like it is in Rust.
+///
+/// Keys of different types are ordered by their type hash. If two keys of the
+/// same type can't be ordered, comparing them errors with
+/// [VmErrorKind::UnorderedKey].
+#[derive(Clone)]
+pub struct ValueKey {
+    value: Value,
+    type_hash: Hash,
+    hash: i64,
+}
+
+impl ValueKey {
+    fn new(value: &Value) -> Result<Self, VmError> {
+        Ok(Self {
+            value: value.clone(),
+            type_hash: *value.type_of()?,
+            hash: VmEnv::vm()?.value_hash(value)?,
+        })
+    }
+
+    /// Record an error raised while comparing keys, to be returned by
+    /// [Key::guard].
+    fn record(error: VmError) {
+        ERROR.with(|slot| {
+            if let Some(slot @ None) = &mut *slot.borrow_mut() {
+                *slot = Some(error);
+            }
+        });
+    }
+
+    fn partial_cmp_values(&self, other: &Self) -> Result<Ordering, VmError> {
+        match VmEnv::vm()?.value_partial_cmp(&self.value, &other.value)? {
+            Some(ordering) => Ok(ordering),
+            None => Err(VmError::from(VmErrorKind::UnorderedKey {
+                actual: self.value.type_info()?,
+            })),
+        }
+    }
+}
+
+impl fmt::Debug for ValueKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl PartialEq for ValueKey {
+    fn eq(&self, other: &Self) -> bool {
+        if self.type_hash != other.type_hash || self.hash != other.hash {
+            return false;
+        }
+
+        match VmEnv::vm().and_then(|mut vm| vm.value_eq(&self.value, &other.value)) {
+            Ok(eq) => eq,
+            Err(error) => {
+                Self::record(error);
+                false
+            }
+        }
+    }
+}
+
+impl Eq for ValueKey {}
+
+impl PartialOrd for ValueKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.type_hash != other.type_hash {
+            return self.type_hash.cmp(&other.type_hash);
+        }
+
+        match self.partial_cmp_values(other) {
+            Ok(ordering) => ordering,
+            Err(error) => {
+                Self::record(error);
+                self.hash.cmp(&other.hash)
+            }
+        }
+    }
+}
+
+impl hash::Hash for ValueKey {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+/// Object-safe operations over a native key.
+trait DynKey {
+    fn as_any(&self) -> &dyn any::Any;
+
+    fn type_name(&self) -> crate::RawStr;
+
+    fn dyn_type_hash(&self) -> crate::Hash;
+
+    fn dyn_clone(&self) -> Box<dyn DynKey>;
+
+    fn dyn_cmp(&self, other: &dyn DynKey) -> Ordering;
+
+    fn dyn_hash(&self, state: &mut dyn hash::Hasher);
+
+    fn to_value(&self) -> Value;
+}
+
+impl<T> DynKey for T
+where
+    T: Any + Clone + hash::Hash + Ord,
+{
+    fn as_any(&self) -> &dyn any::Any {
+        self
+    }
+
+    fn type_name(&self) -> crate::RawStr {
+        T::NAME
+    }
+
+    fn dyn_type_hash(&self) -> crate::Hash {
+        T::type_hash()
+    }
+
+    fn dyn_clone(&self) -> Box<dyn DynKey> {
+        Box::new(self.clone())
+    }
+
+    fn dyn_cmp(&self, other: &dyn DynKey) -> Ordering {
+        // Keys of different native types are ordered by their type hash.
+        match other.as_any().downcast_ref::<T>() {
+            Some(other) => self.cmp(other),
+            None => T::type_hash().cmp(&other.dyn_type_hash()),
+        }
+    }
+
+    fn dyn_hash(&self, mut state: &mut dyn hash::Hasher) {
+        hash::Hash::hash(&T::type_hash(), &mut state);
+        hash::Hash::hash(self, &mut state);
+    }
+
+    fn to_value(&self) -> Value {
+        Value::from(crate::AnyObj::new(self.clone()))
+    }
+}
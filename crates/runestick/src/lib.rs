@@ -63,6 +63,8 @@ mod call;
 mod compile_meta;
 mod const_value;
 pub mod debug;
+mod debugger;
+mod format;
 mod from_value;
 mod function;
 mod future;
@@ -71,7 +73,10 @@ mod generator_state;
 mod guarded_args;
 mod hash;
 mod inst;
+mod interrupt_handle;
 mod item;
+mod iterator;
+mod key;
 mod label;
 pub mod module;
 pub mod modules;
@@ -80,8 +85,10 @@ mod names;
 mod object;
 mod panic;
 mod protocol;
+mod range;
 mod raw_str;
 mod select;
+mod send_value;
 mod serde;
 mod shared;
 mod source;
@@ -97,10 +104,14 @@ mod type_info;
 mod type_of;
 mod unit;
 mod vec_tuple;
+mod verify;
 mod vm_call;
+mod vm_env;
 mod vm_error;
 mod vm_execution;
 mod vm_halt;
+mod vm_limits;
+mod vm_send_execution;
 
 /// The identifier of a source file.
 pub type SourceId = usize;
@@ -114,8 +125,8 @@ pub type Error = anyhow::Error;
 pub use self::any_obj::{AnyObj, AnyObjVtable};
 pub use self::args::Args;
 pub use self::compile_meta::{
-    CompileMeta, CompileMetaCapture, CompileMetaKind, CompileMetaStruct, CompileMetaTuple,
-    CompileSource,
+    CompileMeta, CompileMetaCapture, CompileMetaKind, CompileMetaStruct, CompileMetaTraitFn,
+    CompileMetaTuple, CompileSource,
 };
 pub use self::const_value::ConstValue;
 pub use self::from_value::{FromValue, UnsafeFromValue};
@@ -125,6 +136,7 @@ pub use self::guarded_args::GuardedArgs;
 pub use self::label::{DebugLabel, Label};
 pub use self::module::{InstFnNameHash, Module};
 pub use self::named::Named;
+pub use self::range::{Range, RangeLimits};
 pub use self::raw_str::RawStr;
 pub use self::select::Select;
 pub use self::source::Source;
@@ -133,7 +145,7 @@ pub use self::static_string::StaticString;
 pub use self::static_type::{
     StaticType, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE, CHAR_TYPE, FLOAT_TYPE, FUNCTION_TYPE,
     FUTURE_TYPE, GENERATOR_STATE_TYPE, GENERATOR_TYPE, INTEGER_TYPE, OBJECT_TYPE, OPTION_TYPE,
-    RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
+    RANGE_TYPE, RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
 };
 pub use self::stream::Stream;
 pub use self::to_value::{ToValue, UnsafeToValue};
@@ -148,21 +160,30 @@ pub use crate::any::Any;
 pub use crate::awaited::Awaited;
 pub use crate::bytes::Bytes;
 pub use crate::call::Call;
-pub use crate::context::{Context, ContextError};
+pub use crate::context::{Context, ContextError, ContextSignature};
 pub use crate::debug::{DebugInfo, DebugInst};
-pub use crate::function::Function;
+pub use crate::debugger::{
+    Breakpoint, DebugFrame, DebugHalt, DebugLocal, Debugger, SourceLine, StepMode,
+};
+pub use crate::format::{Alignment, FormatSpec, FormatSpecError, FormatType};
+pub use crate::function::{Function, TypedFunction};
 pub use crate::future::Future;
 pub use crate::hash::{Hash, IntoTypeHash};
-pub use crate::inst::{Inst, InstOp, InstTarget, InstValue, PanicReason, TypeCheck};
+pub use crate::inst::{Inst, InstOp, InstRange, InstTarget, InstValue, PanicReason, TypeCheck};
+pub use crate::interrupt_handle::InterruptHandle;
 pub use crate::item::{Component, IntoComponent, Item};
+pub use crate::iterator::Iterator;
+pub use crate::key::{AnyKey, Key, ValueKey};
 pub use crate::names::Names;
 pub use crate::object::Object;
 pub use crate::panic::Panic;
 pub use crate::protocol::{
     Protocol, ADD, ADD_ASSIGN, BIT_AND, BIT_AND_ASSIGN, BIT_OR, BIT_OR_ASSIGN, BIT_XOR,
-    BIT_XOR_ASSIGN, DIV, DIV_ASSIGN, INDEX_GET, INDEX_SET, INTO_FUTURE, INTO_ITER, MUL, MUL_ASSIGN,
-    NEXT, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN, STRING_DISPLAY, SUB, SUB_ASSIGN,
+    BIT_XOR_ASSIGN, DIV, DIV_ASSIGN, EQ, HASH, INDEX_GET, INDEX_SET, INTO_FUTURE, INTO_ITER, MUL,
+    MUL_ASSIGN, NEXT, PARTIAL_CMP, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN, STRING_DEBUG,
+    STRING_DISPLAY, SUB, SUB_ASSIGN,
 };
+pub use crate::send_value::SendValue;
 pub use crate::shared::{Mut, RawMut, RawRef, Ref, Shared, SharedPointerGuard};
 pub use crate::stack::{Stack, StackError};
 pub use crate::type_of::TypeOf;
@@ -171,11 +192,14 @@ pub use crate::value::{
     ObjectVariant, Rtti, TupleVariant, TypedObject, TypedTuple, Value, VariantRtti,
 };
 pub use crate::vec_tuple::VecTuple;
+pub use crate::verify::{VerifyError, VerifyErrors};
 pub use crate::vm::{CallFrame, Vm};
 pub use crate::vm_call::VmCall;
 pub use crate::vm_error::{VmError, VmErrorKind, VmIntegerRepr};
 pub use crate::vm_execution::VmExecution;
 pub use crate::vm_halt::{VmHalt, VmHaltInfo};
+pub use crate::vm_limits::{MemoryUsage, VmLimits};
+pub use crate::vm_send_execution::VmSendExecution;
 pub(crate) use runestick_macros::__internal_impl_any;
 pub use runestick_macros::{Any, FromValue};
 
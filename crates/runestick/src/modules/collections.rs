@@ -0,0 +1,632 @@
+//! The `std::collections` module.
+
+use crate::vm_env::VmEnv;
+use crate::{
+    ContextError, Iterator, Key, MemoryUsage, Module, TypeOf as _, Value, VmError, VmErrorKind,
+    VmIntegerRepr,
+};
+use std::mem;
+
+/// Construct the `std::collections` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "collections"]);
+
+    module.ty::<HashMap>()?;
+    module.function(&["HashMap", "new"], HashMap::new)?;
+    module.function(&["HashMap", "from"], HashMap::from)?;
+    module.inst_fn("len", HashMap::len)?;
+    module.inst_fn("is_empty", HashMap::is_empty)?;
+    module.inst_fn("insert", HashMap::insert)?;
+    module.inst_fn("get", HashMap::get)?;
+    module.inst_fn("contains_key", HashMap::contains_key)?;
+    module.inst_fn("remove", HashMap::remove)?;
+    module.inst_fn("clear", HashMap::clear)?;
+    module.inst_fn("extend", HashMap::extend)?;
+    module.inst_fn("iter", HashMap::iter)?;
+    module.inst_fn("keys", HashMap::keys)?;
+    module.inst_fn("values", HashMap::values)?;
+    module.inst_fn(crate::INTO_ITER, HashMap::iter)?;
+    module.inst_fn(crate::INDEX_GET, HashMap::index_get)?;
+    module.inst_fn(crate::INDEX_SET, HashMap::index_set)?;
+    module.inst_fn(crate::STRING_DEBUG, HashMap::string_debug)?;
+
+    module.ty::<HashSet>()?;
+    module.function(&["HashSet", "new"], HashSet::new)?;
+    module.function(&["HashSet", "from"], HashSet::from)?;
+    module.inst_fn("len", HashSet::len)?;
+    module.inst_fn("is_empty", HashSet::is_empty)?;
+    module.inst_fn("insert", HashSet::insert)?;
+    module.inst_fn("contains", HashSet::contains)?;
+    module.inst_fn("remove", HashSet::remove)?;
+    module.inst_fn("clear", HashSet::clear)?;
+    module.inst_fn("extend", HashSet::extend)?;
+    module.inst_fn("iter", HashSet::iter)?;
+    module.inst_fn(crate::INTO_ITER, HashSet::iter)?;
+    module.inst_fn(crate::STRING_DEBUG, HashSet::string_debug)?;
+
+    module.ty::<BTreeMap>()?;
+    module.function(&["BTreeMap", "new"], BTreeMap::new)?;
+    module.function(&["BTreeMap", "from"], BTreeMap::from)?;
+    module.inst_fn("len", BTreeMap::len)?;
+    module.inst_fn("is_empty", BTreeMap::is_empty)?;
+    module.inst_fn("insert", BTreeMap::insert)?;
+    module.inst_fn("get", BTreeMap::get)?;
+    module.inst_fn("contains_key", BTreeMap::contains_key)?;
+    module.inst_fn("remove", BTreeMap::remove)?;
+    module.inst_fn("clear", BTreeMap::clear)?;
+    module.inst_fn("extend", BTreeMap::extend)?;
+    module.inst_fn("first", BTreeMap::first)?;
+    module.inst_fn("last", BTreeMap::last)?;
+    module.inst_fn("iter", BTreeMap::iter)?;
+    module.inst_fn("keys", BTreeMap::keys)?;
+    module.inst_fn("values", BTreeMap::values)?;
+    module.inst_fn(crate::INTO_ITER, BTreeMap::iter)?;
+    module.inst_fn(crate::INDEX_GET, BTreeMap::index_get)?;
+    module.inst_fn(crate::INDEX_SET, BTreeMap::index_set)?;
+    module.inst_fn(crate::STRING_DEBUG, BTreeMap::string_debug)?;
+
+    module.ty::<VecDeque>()?;
+    module.function(&["VecDeque", "new"], VecDeque::new)?;
+    module.function(&["VecDeque", "from"], VecDeque::from)?;
+    module.inst_fn("len", VecDeque::len)?;
+    module.inst_fn("is_empty", VecDeque::is_empty)?;
+    module.inst_fn("push_front", VecDeque::push_front)?;
+    module.inst_fn("push_back", VecDeque::push_back)?;
+    module.inst_fn("pop_front", VecDeque::pop_front)?;
+    module.inst_fn("pop_back", VecDeque::pop_back)?;
+    module.inst_fn("front", VecDeque::front)?;
+    module.inst_fn("back", VecDeque::back)?;
+    module.inst_fn("get", VecDeque::get)?;
+    module.inst_fn("clear", VecDeque::clear)?;
+    module.inst_fn("extend", VecDeque::extend)?;
+    module.inst_fn("iter", VecDeque::iter)?;
+    module.inst_fn(crate::INTO_ITER, VecDeque::iter)?;
+    module.inst_fn(crate::INDEX_GET, VecDeque::index_get)?;
+    module.inst_fn(crate::INDEX_SET, VecDeque::index_set)?;
+    module.inst_fn(crate::STRING_DEBUG, VecDeque::string_debug)?;
+    Ok(module)
+}
+
+/// A hash map from keys to values.
+#[derive(Default)]
+struct HashMap {
+    map: crate::collections::HashMap<Key, Value>,
+    /// The number of bytes allocated by the keys in the map.
+    key_bytes: usize,
+}
+
+impl HashMap {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a map from anything which can be iterated over and produces
+    /// `(key, value)` pairs.
+    fn from(pairs: Value) -> Result<Self, VmError> {
+        let mut map = Self::new();
+        map.extend(pairs)?;
+        Ok(map)
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn insert(&mut self, key: Key, value: Value) -> Result<Option<Value>, VmError> {
+        // NB: look the key up first, so that a key which fails to compare
+        // isn't inserted.
+        if let Some(slot) = Key::guard(|| self.map.get_mut(&key))? {
+            return Ok(Some(mem::replace(slot, value)));
+        }
+
+        self.key_bytes += key.memory_size();
+        Key::guard(|| self.map.insert(key, value))
+    }
+
+    fn get(&self, key: Key) -> Result<Option<Value>, VmError> {
+        Key::guard(|| self.map.get(&key).cloned())
+    }
+
+    fn contains_key(&self, key: Key) -> Result<bool, VmError> {
+        Key::guard(|| self.map.contains_key(&key))
+    }
+
+    fn remove(&mut self, key: Key) -> Result<Option<Value>, VmError> {
+        let (key, value) = match Key::guard(|| self.map.remove_entry(&key))? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        self.key_bytes -= key.memory_size();
+        Ok(Some(value))
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.key_bytes = 0;
+    }
+
+    fn extend(&mut self, pairs: Value) -> Result<(), VmError> {
+        let mut pairs = pairs.into_iterator()?;
+
+        while let Some(pair) = pairs.next()? {
+            let (key, value) = into_pair(pair)?;
+            self.insert(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn iter(&self) -> Iterator {
+        let pairs = self
+            .map
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>();
+
+        Iterator::from_vec("HashMap", pairs)
+    }
+
+    fn keys(&self) -> Iterator {
+        let keys = self.map.keys().cloned().collect::<Vec<_>>();
+        Iterator::from_vec("Keys", keys)
+    }
+
+    fn values(&self) -> Iterator {
+        let values = self.map.values().cloned().collect::<Vec<_>>();
+        Iterator::from_vec("Values", values)
+    }
+
+    fn index_get(&self, key: Key) -> Result<Value, VmError> {
+        match Key::guard(|| self.map.get(&key).cloned())? {
+            Some(value) => Ok(value),
+            None => Err(missing_key(Self::type_info(), &key)),
+        }
+    }
+
+    fn index_set(&mut self, key: Key, value: Value) -> Result<(), VmError> {
+        self.insert(key, value)?;
+        Ok(())
+    }
+
+    fn string_debug(&self, buf: &mut String) -> Result<(), VmError> {
+        debug_pairs(buf, self.map.iter())
+    }
+}
+
+impl MemoryUsage for HashMap {
+    fn memory_size(&self) -> usize {
+        self.map.capacity() * (mem::size_of::<Key>() + mem::size_of::<Value>()) + self.key_bytes
+    }
+
+    fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+        for (key, value) in &self.map {
+            key.memory_values(visit);
+            visit(value);
+        }
+    }
+}
+
+crate::__internal_impl_any!(HashMap, memory);
+
+/// A hash set of keys.
+#[derive(Default)]
+struct HashSet {
+    set: crate::collections::HashSet<Key>,
+    /// The number of bytes allocated by the keys in the set.
+    key_bytes: usize,
+}
+
+impl HashSet {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a set from anything which can be iterated over.
+    fn from(keys: Value) -> Result<Self, VmError> {
+        let mut set = Self::new();
+        set.extend(keys)?;
+        Ok(set)
+    }
+
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    fn insert(&mut self, key: Key) -> Result<bool, VmError> {
+        // NB: look the key up first, so that a key which fails to compare
+        // isn't inserted.
+        if Key::guard(|| self.set.contains(&key))? {
+            return Ok(false);
+        }
+
+        self.key_bytes += key.memory_size();
+        Key::guard(|| self.set.insert(key))
+    }
+
+    fn contains(&self, key: Key) -> Result<bool, VmError> {
+        Key::guard(|| self.set.contains(&key))
+    }
+
+    fn remove(&mut self, key: Key) -> Result<bool, VmError> {
+        match Key::guard(|| self.set.take(&key))? {
+            Some(key) => {
+                self.key_bytes -= key.memory_size();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.set.clear();
+        self.key_bytes = 0;
+    }
+
+    fn extend(&mut self, keys: Value) -> Result<(), VmError> {
+        let mut keys = keys.into_iterator()?;
+
+        while let Some(key) = keys.next()? {
+            self.insert(Key::from_value(&key)?)?;
+        }
+
+        Ok(())
+    }
+
+    fn iter(&self) -> Iterator {
+        let keys = self.set.iter().cloned().collect::<Vec<_>>();
+        Iterator::from_vec("HashSet", keys)
+    }
+
+    fn string_debug(&self, buf: &mut String) -> Result<(), VmError> {
+        let mut vm = VmEnv::vm()?;
+        buf.push('{');
+
+        let mut it = self.set.iter().peekable();
+
+        while let Some(key) = it.next() {
+            vm.debug_value(&key.clone().into_value(), buf)?;
+
+            if it.peek().is_some() {
+                buf.push_str(", ");
+            }
+        }
+
+        buf.push('}');
+        Ok(())
+    }
+}
+
+impl MemoryUsage for HashSet {
+    fn memory_size(&self) -> usize {
+        self.set.capacity() * mem::size_of::<Key>() + self.key_bytes
+    }
+
+    fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+        for key in &self.set {
+            key.memory_values(visit);
+        }
+    }
+}
+
+crate::__internal_impl_any!(HashSet, memory);
+
+/// An ordered map from keys to values.
+#[derive(Default)]
+struct BTreeMap {
+    map: std::collections::BTreeMap<Key, Value>,
+    /// The number of bytes allocated by the keys in the map.
+    key_bytes: usize,
+}
+
+impl BTreeMap {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a map from anything which can be iterated over and produces
+    /// `(key, value)` pairs.
+    fn from(pairs: Value) -> Result<Self, VmError> {
+        let mut map = Self::new();
+        map.extend(pairs)?;
+        Ok(map)
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn insert(&mut self, key: Key, value: Value) -> Result<Option<Value>, VmError> {
+        // NB: look the key up first, so that a key which fails to compare
+        // isn't inserted.
+        if let Some(slot) = Key::guard(|| self.map.get_mut(&key))? {
+            return Ok(Some(mem::replace(slot, value)));
+        }
+
+        self.key_bytes += key.memory_size();
+        Key::guard(|| self.map.insert(key, value))
+    }
+
+    fn get(&self, key: Key) -> Result<Option<Value>, VmError> {
+        Key::guard(|| self.map.get(&key).cloned())
+    }
+
+    fn contains_key(&self, key: Key) -> Result<bool, VmError> {
+        Key::guard(|| self.map.contains_key(&key))
+    }
+
+    fn remove(&mut self, key: Key) -> Result<Option<Value>, VmError> {
+        let (key, value) = match Key::guard(|| self.map.remove_entry(&key))? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        self.key_bytes -= key.memory_size();
+        Ok(Some(value))
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.key_bytes = 0;
+    }
+
+    fn extend(&mut self, pairs: Value) -> Result<(), VmError> {
+        let mut pairs = pairs.into_iterator()?;
+
+        while let Some(pair) = pairs.next()? {
+            let (key, value) = into_pair(pair)?;
+            self.insert(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the pair with the smallest key.
+    fn first(&self) -> Option<(Key, Value)> {
+        let (k, v) = self.map.iter().next()?;
+        Some((k.clone(), v.clone()))
+    }
+
+    /// Get the pair with the largest key.
+    fn last(&self) -> Option<(Key, Value)> {
+        let (k, v) = self.map.iter().next_back()?;
+        Some((k.clone(), v.clone()))
+    }
+
+    fn iter(&self) -> Iterator {
+        let pairs = self
+            .map
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<Vec<_>>();
+
+        Iterator::from_vec("BTreeMap", pairs)
+    }
+
+    fn keys(&self) -> Iterator {
+        let keys = self.map.keys().cloned().collect::<Vec<_>>();
+        Iterator::from_vec("Keys", keys)
+    }
+
+    fn values(&self) -> Iterator {
+        let values = self.map.values().cloned().collect::<Vec<_>>();
+        Iterator::from_vec("Values", values)
+    }
+
+    fn index_get(&self, key: Key) -> Result<Value, VmError> {
+        match Key::guard(|| self.map.get(&key).cloned())? {
+            Some(value) => Ok(value),
+            None => Err(missing_key(Self::type_info(), &key)),
+        }
+    }
+
+    fn index_set(&mut self, key: Key, value: Value) -> Result<(), VmError> {
+        self.insert(key, value)?;
+        Ok(())
+    }
+
+    fn string_debug(&self, buf: &mut String) -> Result<(), VmError> {
+        debug_pairs(buf, self.map.iter())
+    }
+}
+
+impl MemoryUsage for BTreeMap {
+    fn memory_size(&self) -> usize {
+        self.map.len() * (mem::size_of::<Key>() + mem::size_of::<Value>()) + self.key_bytes
+    }
+
+    fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+        for (key, value) in &self.map {
+            key.memory_values(visit);
+            visit(value);
+        }
+    }
+}
+
+crate::__internal_impl_any!(BTreeMap, memory);
+
+/// A double-ended queue of values.
+#[derive(Default)]
+struct VecDeque {
+    deque: std::collections::VecDeque<Value>,
+}
+
+impl VecDeque {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a queue from anything which can be iterated over.
+    fn from(values: Value) -> Result<Self, VmError> {
+        let mut deque = Self::new();
+        deque.extend(values)?;
+        Ok(deque)
+    }
+
+    fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    fn push_front(&mut self, value: Value) {
+        self.deque.push_front(value);
+    }
+
+    fn push_back(&mut self, value: Value) {
+        self.deque.push_back(value);
+    }
+
+    fn pop_front(&mut self) -> Option<Value> {
+        self.deque.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<Value> {
+        self.deque.pop_back()
+    }
+
+    fn front(&self) -> Option<Value> {
+        self.deque.front().cloned()
+    }
+
+    fn back(&self) -> Option<Value> {
+        self.deque.back().cloned()
+    }
+
+    fn get(&self, index: usize) -> Option<Value> {
+        self.deque.get(index).cloned()
+    }
+
+    fn clear(&mut self) {
+        self.deque.clear();
+    }
+
+    fn extend(&mut self, values: Value) -> Result<(), VmError> {
+        let mut values = values.into_iterator()?;
+
+        while let Some(value) = values.next()? {
+            self.deque.push_back(value);
+        }
+
+        Ok(())
+    }
+
+    fn iter(&self) -> Iterator {
+        let values = self.deque.iter().cloned().collect::<Vec<_>>();
+        Iterator::from_vec("VecDeque", values)
+    }
+
+    fn index_get(&self, index: usize) -> Result<Value, VmError> {
+        match self.deque.get(index) {
+            Some(value) => Ok(value.clone()),
+            None => Err(missing_index(index)),
+        }
+    }
+
+    fn index_set(&mut self, index: usize, value: Value) -> Result<(), VmError> {
+        match self.deque.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(missing_index(index)),
+        }
+    }
+
+    fn string_debug(&self, buf: &mut String) -> Result<(), VmError> {
+        let mut vm = VmEnv::vm()?;
+        buf.push('[');
+
+        let mut it = self.deque.iter().peekable();
+
+        while let Some(value) = it.next() {
+            vm.debug_value(value, buf)?;
+
+            if it.peek().is_some() {
+                buf.push_str(", ");
+            }
+        }
+
+        buf.push(']');
+        Ok(())
+    }
+}
+
+impl MemoryUsage for VecDeque {
+    fn memory_size(&self) -> usize {
+        self.deque.capacity() * mem::size_of::<Value>()
+    }
+
+    fn memory_values(&self, visit: &mut dyn FnMut(&Value)) {
+        self.deque.iter().for_each(visit);
+    }
+}
+
+crate::__internal_impl_any!(VecDeque, memory);
+
+/// Convert a `(key, value)` tuple into a pair, without taking the tuple.
+fn into_pair(pair: Value) -> Result<(Key, Value), VmError> {
+    let pair = pair.into_tuple()?;
+    let pair = pair.borrow_ref()?;
+
+    match &**pair {
+        [key, value] => Ok((Key::from_value(key)?, value.clone())),
+        _ => Err(VmError::from(VmErrorKind::ExpectedTupleLength {
+            actual: pair.len(),
+            expected: 2,
+        })),
+    }
+}
+
+/// Append the debug representation of the pairs of a map to `buf`, like
+/// `{1: "a", 2: "b"}`.
+fn debug_pairs<'a>(
+    buf: &mut String,
+    pairs: impl std::iter::Iterator<Item = (&'a Key, &'a Value)>,
+) -> Result<(), VmError> {
+    let mut vm = VmEnv::vm()?;
+    buf.push('{');
+
+    let mut it = pairs.peekable();
+
+    while let Some((key, value)) = it.next() {
+        vm.debug_value(&key.clone().into_value(), buf)?;
+        buf.push_str(": ");
+        vm.debug_value(value, buf)?;
+
+        if it.peek().is_some() {
+            buf.push_str(", ");
+        }
+    }
+
+    buf.push('}');
+    Ok(())
+}
+
+fn missing_key(target: crate::TypeInfo, key: &Key) -> VmError {
+    VmError::from(VmErrorKind::MissingKey {
+        target,
+        key: key.to_string(),
+    })
+}
+
+fn missing_index(index: usize) -> VmError {
+    VmError::from(VmErrorKind::MissingIndex {
+        target: VecDeque::type_info(),
+        index: VmIntegerRepr::Usize(index),
+    })
+}
@@ -9,9 +9,14 @@ pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "fmt"]);
     module.ty::<std::fmt::Error>()?;
     module.inst_fn(crate::STRING_DISPLAY, format_fmt_error)?;
+    module.inst_fn(crate::STRING_DEBUG, debug_fmt_error)?;
     Ok(module)
 }
 
 fn format_fmt_error(error: &std::fmt::Error, buf: &mut String) -> fmt::Result {
     write!(buf, "{}", error)
 }
+
+fn debug_fmt_error(error: &std::fmt::Error, buf: &mut String) -> fmt::Result {
+    write!(buf, "{:?}", error)
+}
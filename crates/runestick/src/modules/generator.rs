@@ -1,6 +1,6 @@
 //! The `std::generator` module.
 
-use crate::{ContextError, Generator, Module};
+use crate::{ContextError, Generator, Iterator, Module, Shared};
 
 /// Construct the `std::generator` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -10,5 +10,12 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.inst_fn("next", Generator::next)?;
     module.inst_fn("resume", Generator::resume)?;
+    module.inst_fn("iter", generator_iter)?;
+    module.inst_fn(crate::NEXT, Generator::next)?;
+    module.inst_fn(crate::INTO_ITER, generator_iter)?;
     Ok(module)
 }
+
+fn generator_iter(generator: Shared<Generator>) -> Iterator {
+    Iterator::from_generator(generator)
+}
@@ -1,76 +1,45 @@
 //! The `std::iter` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, Iterator, Module};
 
 /// Construct the `std::iter` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "iter"]);
-    module.ty::<Range>()?;
-    module.ty::<Rev>()?;
-    module.function(&["range"], Range::new)?;
-    module.inst_fn(crate::INTO_ITER, Range::into_iter)?;
-    module.inst_fn(crate::NEXT, Range::next)?;
-    module.inst_fn("rev", Range::rev)?;
-    module.inst_fn(crate::INTO_ITER, Rev::into_iter)?;
-    module.inst_fn(crate::NEXT, Rev::next)?;
+    module.ty::<Iterator>()?;
+    module.function(&["range"], range)?;
+
+    module.inst_fn("iter", Iterator::into_iter)?;
+    module.inst_fn("next", Iterator::next)?;
+    module.inst_fn("next_back", Iterator::next_back)?;
+    module.inst_fn("rev", Iterator::rev)?;
+    module.inst_fn(crate::INTO_ITER, Iterator::into_iter)?;
+    module.inst_fn(crate::NEXT, Iterator::next)?;
+
+    module.inst_fn("map", Iterator::map)?;
+    module.inst_fn("filter", Iterator::filter)?;
+    module.inst_fn("filter_map", Iterator::filter_map)?;
+    module.inst_fn("flat_map", Iterator::flat_map)?;
+    module.inst_fn("enumerate", Iterator::enumerate)?;
+    module.inst_fn("zip", Iterator::zip)?;
+    module.inst_fn("chain", Iterator::chain)?;
+    module.inst_fn("take", Iterator::take)?;
+    module.inst_fn("skip", Iterator::skip)?;
+
+    module.inst_fn("fold", Iterator::fold)?;
+    module.inst_fn("any", Iterator::any)?;
+    module.inst_fn("all", Iterator::all)?;
+    module.inst_fn("find", Iterator::find)?;
+    module.inst_fn("count", Iterator::count)?;
+    module.inst_fn("sum", Iterator::sum)?;
+    module.inst_fn("collect", Iterator::collect_vec)?;
+    module.inst_fn("collect_vec", Iterator::collect_vec)?;
+    module.inst_fn("collect_object", Iterator::collect_object)?;
+    module.inst_fn("collect_string", Iterator::collect_string)?;
     Ok(module)
 }
 
-#[derive(Debug)]
-struct Rev {
-    current: i64,
-    start: i64,
+/// Construct an iterator over the integers from `start` up to, but not
+/// including, `end`.
+fn range(start: i64, end: i64) -> Iterator {
+    Iterator::from_double_ended("Range", start..end)
 }
-
-impl Iterator for Rev {
-    type Item = i64;
-
-    fn next(&mut self) -> Option<i64> {
-        if self.current <= self.start {
-            return None;
-        }
-
-        self.current -= 1;
-        Some(self.current)
-    }
-}
-
-#[derive(Debug)]
-struct Range {
-    current: i64,
-    end: i64,
-}
-
-impl Range {
-    fn new(start: i64, end: i64) -> Self {
-        Self {
-            current: start,
-            end,
-        }
-    }
-
-    fn rev(self) -> Rev {
-        Rev {
-            current: self.end,
-            start: self.current,
-        }
-    }
-}
-
-impl Iterator for Range {
-    type Item = i64;
-
-    fn next(&mut self) -> Option<i64> {
-        let value = self.current;
-
-        if self.current < self.end {
-            self.current += 1;
-            return Some(value);
-        }
-
-        None
-    }
-}
-
-crate::__internal_impl_any!(Range);
-crate::__internal_impl_any!(Rev);
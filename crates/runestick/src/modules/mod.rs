@@ -2,6 +2,7 @@
 //! machines.
 
 pub mod bytes;
+pub mod collections;
 pub mod core;
 pub mod float;
 pub mod fmt;
@@ -11,6 +12,7 @@ pub mod int;
 pub mod io;
 pub mod iter;
 pub mod object;
+pub mod ops;
 pub mod option;
 pub mod result;
 pub mod stream;
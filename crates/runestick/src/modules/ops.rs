@@ -0,0 +1,57 @@
+//! The `std::ops` module.
+
+use crate::{ContextError, Iterator, Module, Range, RangeLimits, Value, VmError, VmErrorKind};
+
+/// Construct the `std::ops` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "ops"]);
+    module.ty::<Range>()?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn(crate::INTO_ITER, range_iter)?;
+    Ok(module)
+}
+
+/// Test if the given value is contained in the range.
+fn contains(range: &Range, value: Value) -> bool {
+    range.contains(&value)
+}
+
+/// Construct an iterator over a range of integers or characters.
+pub(crate) fn range_iter(range: &Range) -> Result<Iterator, VmError> {
+    let start = match &range.start {
+        Some(start) => start,
+        None => return Err(VmError::from(VmErrorKind::UnboundedRangeIter)),
+    };
+
+    Ok(match (start, &range.end, range.limits) {
+        // NB: like a range of characters, a range of integers without an end
+        // stops at the largest value instead of overflowing.
+        (Value::Integer(start), None, _) => Iterator::from("RangeFrom", *start..=i64::MAX),
+        (Value::Integer(start), Some(Value::Integer(end)), RangeLimits::HalfOpen) => {
+            Iterator::from_double_ended("Range", *start..*end)
+        }
+        (Value::Integer(start), Some(Value::Integer(end)), RangeLimits::Closed) => {
+            Iterator::from_double_ended("RangeInclusive", *start..=*end)
+        }
+        (Value::Char(start), None, _) => {
+            Iterator::from_double_ended("RangeFrom", *start..=char::MAX)
+        }
+        (Value::Char(start), Some(Value::Char(end)), RangeLimits::HalfOpen) => {
+            Iterator::from_double_ended("Range", *start..*end)
+        }
+        (Value::Char(start), Some(Value::Char(end)), RangeLimits::Closed) => {
+            Iterator::from_double_ended("RangeInclusive", *start..=*end)
+        }
+        (Value::Integer(..), Some(end), _) | (Value::Char(..), Some(end), _) => {
+            return Err(VmError::from(VmErrorKind::Expected {
+                expected: start.type_info()?,
+                actual: end.type_info()?,
+            }));
+        }
+        (start, _, _) => {
+            return Err(VmError::from(VmErrorKind::UnsupportedRangeIter {
+                start: start.type_info()?,
+            }));
+        }
+    })
+}
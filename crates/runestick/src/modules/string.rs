@@ -1,12 +1,17 @@
 //! The `std::string` module.
 
-use crate::{Bytes, ContextError, Module};
+use crate::{
+    Bytes, ContextError, FromValue, Iterator, Module, Value, VmError, VmErrorKind, VmLimits,
+};
+use std::char::ParseCharError;
+use std::num::{ParseFloatError, ParseIntError};
 
 /// Construct the `std::string` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "string"]);
 
     module.ty::<String>()?;
+    module.ty::<ParseCharError>()?;
 
     module.function(&["String", "from_str"], <String as From<&str>>::from)?;
     module.function(&["String", "new"], String::new)?;
@@ -23,6 +28,30 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("clone", String::clone)?;
     module.inst_fn("shrink_to_fit", String::shrink_to_fit)?;
     module.inst_fn("char_at", char_at)?;
+
+    module.inst_fn("split", split)?;
+    module.inst_fn("split_whitespace", split_whitespace)?;
+    module.inst_fn("lines", lines)?;
+    module.inst_fn("chars", chars)?;
+    module.inst_fn("bytes", bytes)?;
+
+    module.inst_fn("trim", trim)?;
+    module.inst_fn("trim_start", trim_start)?;
+    module.inst_fn("trim_end", trim_end)?;
+    module.inst_fn("to_lowercase", str::to_lowercase)?;
+    module.inst_fn("to_uppercase", str::to_uppercase)?;
+    module.inst_fn("repeat", repeat)?;
+    module.inst_fn("replace", replace)?;
+
+    module.inst_fn("starts_with", starts_with)?;
+    module.inst_fn("ends_with", ends_with)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("find", find)?;
+    module.inst_fn("is_empty", str::is_empty)?;
+
+    module.inst_fn("parse_int", parse_int)?;
+    module.inst_fn("parse_float", parse_float)?;
+    module.inst_fn("parse_char", parse_char)?;
     module.inst_fn(crate::ADD, add)?;
     module.inst_fn(crate::ADD_ASSIGN, String::push_str)?;
     Ok(module)
@@ -44,6 +73,151 @@ fn char_at(s: &str, index: usize) -> Result<Option<char>, NotCharBoundary> {
     Ok(s[index..].chars().next())
 }
 
+/// A pattern used to search a string, which is either a string or a character.
+enum Pattern {
+    String(String),
+    Char(char),
+}
+
+impl FromValue for Pattern {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Char(c) => Self::Char(c),
+            value => Self::String(String::from_value(value)?),
+        })
+    }
+}
+
+/// Split a string by the given pattern.
+fn split(s: &str, pattern: Pattern) -> Iterator {
+    let parts = match &pattern {
+        Pattern::String(p) => collect(s.split(p.as_str())),
+        Pattern::Char(c) => collect(s.split(*c)),
+    };
+
+    Iterator::from_vec("Split", parts)
+}
+
+/// Split a string by whitespace.
+fn split_whitespace(s: &str) -> Iterator {
+    Iterator::from_vec("SplitWhitespace", collect(s.split_whitespace()))
+}
+
+/// Iterate over the lines of a string.
+fn lines(s: &str) -> Iterator {
+    Iterator::from_vec("Lines", collect(s.lines()))
+}
+
+/// Iterate over the characters of a string.
+fn chars(s: &str) -> Iterator {
+    Iterator::from_vec("Chars", s.chars().collect::<Vec<_>>())
+}
+
+/// Iterate over the bytes of a string.
+fn bytes(s: &str) -> Iterator {
+    Iterator::from_vec("Bytes", s.as_bytes().to_vec())
+}
+
+fn collect<'a>(iter: impl std::iter::Iterator<Item = &'a str>) -> Vec<String> {
+    iter.map(String::from).collect()
+}
+
+fn trim(s: &str) -> String {
+    s.trim().to_owned()
+}
+
+fn trim_start(s: &str) -> String {
+    s.trim_start().to_owned()
+}
+
+fn trim_end(s: &str) -> String {
+    s.trim_end().to_owned()
+}
+
+/// Repeat a string the given number of times.
+///
+/// The size of the result is checked against the memory limits before it's
+/// allocated.
+fn repeat(s: &str, count: usize) -> Result<String, VmError> {
+    let len = s
+        .len()
+        .checked_mul(count)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))?;
+
+    if let Some(limits) = VmLimits::current() {
+        limits.check_allocation(len)?;
+    }
+
+    Ok(s.repeat(count))
+}
+
+/// Replace all matches of a pattern with another string.
+///
+/// The size of the result is checked against the memory limits before it's
+/// allocated.
+fn replace(s: &str, from: Pattern, to: &str) -> Result<String, VmError> {
+    let (matches, from_len) = match &from {
+        Pattern::String(from) => (s.matches(from.as_str()).count(), from.len()),
+        Pattern::Char(from) => (s.matches(*from).count(), from.len_utf8()),
+    };
+
+    // NB: matches don't overlap, so they can't be longer than the string.
+    let len = matches
+        .checked_mul(to.len())
+        .and_then(|added| (s.len() - matches * from_len).checked_add(added))
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))?;
+
+    if let Some(limits) = VmLimits::current() {
+        limits.check_allocation(len)?;
+    }
+
+    Ok(match &from {
+        Pattern::String(from) => s.replace(from.as_str(), to),
+        Pattern::Char(from) => s.replace(*from, to),
+    })
+}
+
+fn starts_with(s: &str, pattern: Pattern) -> bool {
+    match &pattern {
+        Pattern::String(p) => s.starts_with(p.as_str()),
+        Pattern::Char(c) => s.starts_with(*c),
+    }
+}
+
+fn ends_with(s: &str, pattern: Pattern) -> bool {
+    match &pattern {
+        Pattern::String(p) => s.ends_with(p.as_str()),
+        Pattern::Char(c) => s.ends_with(*c),
+    }
+}
+
+fn contains(s: &str, pattern: Pattern) -> bool {
+    match &pattern {
+        Pattern::String(p) => s.contains(p.as_str()),
+        Pattern::Char(c) => s.contains(*c),
+    }
+}
+
+/// Find the byte index of the first match of a pattern.
+fn find(s: &str, pattern: Pattern) -> Option<usize> {
+    match &pattern {
+        Pattern::String(p) => s.find(p.as_str()),
+        Pattern::Char(c) => s.find(*c),
+    }
+}
+
+fn parse_int(s: &str) -> Result<i64, ParseIntError> {
+    str::parse::<i64>(s)
+}
+
+fn parse_float(s: &str) -> Result<f64, ParseFloatError> {
+    str::parse::<f64>(s)
+}
+
+fn parse_char(s: &str) -> Result<char, ParseCharError> {
+    str::parse::<char>(s)
+}
+
 /// The add operation for strings.
 fn add(a: &str, b: &str) -> String {
     let mut string = String::with_capacity(a.len() + b.len());
@@ -53,3 +227,4 @@ fn add(a: &str, b: &str) -> String {
 }
 
 crate::__internal_impl_any!(NotCharBoundary);
+crate::__internal_impl_any!(ParseCharError);
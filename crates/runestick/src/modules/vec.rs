@@ -1,15 +1,17 @@
 //! The `std::vec` module.
 
-use crate::{ContextError, Module, Value};
-use std::iter::Rev;
+use crate::vm_env::VmEnv;
+use crate::{
+    ContextError, Function, Iterator, Module, TypeOf as _, Value, Vm, VmError, VmErrorKind,
+    VmIntegerRepr, VmLimits,
+};
+use std::cmp::Ordering;
 
 /// Construct the `std::vec` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "vec"]);
 
     module.ty::<Vec<Value>>()?;
-    module.ty::<Iter>()?;
-    module.ty::<Rev<Iter>>()?;
 
     module.function(&["Vec", "new"], Vec::<Value>::new)?;
     module.inst_fn("iter", vec_iter)?;
@@ -17,44 +19,269 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("push", Vec::<Value>::push)?;
     module.inst_fn("clear", Vec::<Value>::clear)?;
     module.inst_fn("pop", Vec::<Value>::pop)?;
+    module.inst_fn("join", join)?;
+    module.inst_fn("insert", insert)?;
+    module.inst_fn("remove", remove)?;
+    module.inst_fn("extend", extend)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("get", get)?;
+    module.inst_fn("first", first)?;
+    module.inst_fn("last", last)?;
+    module.inst_fn("reverse", reverse)?;
+    module.inst_fn("dedup", dedup)?;
+    module.inst_fn("retain", retain)?;
+    module.inst_fn("sort", sort)?;
+    module.inst_fn("sort_by", sort_by)?;
+    module.inst_fn("binary_search", binary_search)?;
+    module.inst_fn("split_off", split_off)?;
 
     module.inst_fn(crate::INTO_ITER, vec_iter)?;
-    module.inst_fn("next", Iter::next)?;
-    module.inst_fn(crate::NEXT, Iter::next)?;
-    module.inst_fn(crate::INTO_ITER, Iter::into_iter)?;
-
-    module.inst_fn("rev", Iter::rev)?;
-    module.inst_fn("next", Rev::<Iter>::next)?;
-    module.inst_fn("next_back", Rev::<Iter>::next_back)?;
-    module.inst_fn(crate::NEXT, Rev::<Iter>::next)?;
-    module.inst_fn(crate::INTO_ITER, Rev::<Iter>::into_iter)?;
     Ok(module)
 }
 
-/// An iterator over a vector.
-pub struct Iter {
-    iter: std::vec::IntoIter<Value>,
+fn vec_iter(vec: &[Value]) -> Iterator {
+    Iterator::from_vec("Vec", vec.to_vec())
 }
 
-impl Iterator for Iter {
-    type Item = Value;
+/// Join a vector of strings with the given separator.
+///
+/// The size of the result is checked against the memory limits before it's
+/// allocated.
+fn join(vec: &[Value], separator: &str) -> Result<String, VmError> {
+    let mut len = separator
+        .len()
+        .checked_mul(vec.len().saturating_sub(1))
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))?;
 
-    fn next(&mut self) -> Option<Value> {
-        self.iter.next()
+    for value in vec {
+        len = with_str(value, str::len)?
+            .checked_add(len)
+            .ok_or_else(|| VmError::from(VmErrorKind::Overflow))?;
+    }
+
+    if let Some(limits) = VmLimits::current() {
+        limits.check_allocation(len)?;
+    }
+
+    let mut string = String::with_capacity(len);
+
+    for (n, value) in vec.iter().enumerate() {
+        if n > 0 {
+            string.push_str(separator);
+        }
+
+        with_str(value, |s| string.push_str(s))?;
+    }
+
+    Ok(string)
+}
+
+/// Call the given function with the string stored in a value.
+fn with_str<T>(value: &Value, f: impl FnOnce(&str) -> T) -> Result<T, VmError> {
+    match value {
+        Value::String(string) => Ok(f(&string.borrow_ref()?)),
+        Value::StaticString(string) => Ok(f(string)),
+        actual => Err(VmError::expected::<String>(actual.type_info()?)),
     }
 }
 
-impl DoubleEndedIterator for Iter {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back()
+/// Insert a value at the given index, shifting all values after it.
+fn insert(vec: &mut Vec<Value>, index: usize, value: Value) -> Result<(), VmError> {
+    if index > vec.len() {
+        return Err(missing_index(index));
     }
+
+    vec.insert(index, value);
+    Ok(())
 }
 
-fn vec_iter(vec: &[Value]) -> Iter {
-    Iter {
-        iter: vec.to_vec().into_iter(),
+/// Remove the value at the given index, shifting all values after it.
+fn remove(vec: &mut Vec<Value>, index: usize) -> Result<Value, VmError> {
+    if index >= vec.len() {
+        return Err(missing_index(index));
     }
+
+    Ok(vec.remove(index))
 }
 
-crate::__internal_impl_any!(Iter, "Iter");
-crate::__internal_impl_any!(Rev<Iter>, "Rev");
+/// Split the vector in two at the given index, returning the values after it.
+fn split_off(vec: &mut Vec<Value>, at: usize) -> Result<Vec<Value>, VmError> {
+    if at > vec.len() {
+        return Err(missing_index(at));
+    }
+
+    Ok(vec.split_off(at))
+}
+
+/// Extend the vector with the values of anything which can be iterated over.
+fn extend(vec: &mut Vec<Value>, values: Value) -> Result<(), VmError> {
+    let mut values = values.into_iterator()?;
+
+    while let Some(value) = values.next()? {
+        vec.push(value);
+    }
+
+    Ok(())
+}
+
+/// Test if the vector contains the given value, as compared by
+/// [Vm::value_eq].
+fn contains(vec: &[Value], value: Value) -> Result<bool, VmError> {
+    let mut vm = VmEnv::vm()?;
+
+    for v in vec {
+        if vm.value_eq(v, &value)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn get(vec: &[Value], index: usize) -> Option<Value> {
+    vec.get(index).cloned()
+}
+
+fn first(vec: &[Value]) -> Option<Value> {
+    vec.first().cloned()
+}
+
+fn last(vec: &[Value]) -> Option<Value> {
+    vec.last().cloned()
+}
+
+// NB: mutable slices can't be borrowed from a value, so this takes the vector.
+#[allow(clippy::ptr_arg)]
+fn reverse(vec: &mut Vec<Value>) {
+    vec.reverse();
+}
+
+/// Remove consecutive values which are equal to each other, as compared by
+/// [Vm::value_eq].
+fn dedup(vec: &mut Vec<Value>) -> Result<(), VmError> {
+    let mut vm = VmEnv::vm()?;
+    let mut deduped = Vec::<Value>::with_capacity(vec.len());
+
+    for value in vec.iter() {
+        if let Some(last) = deduped.last() {
+            if vm.value_eq(last, value)? {
+                continue;
+            }
+        }
+
+        deduped.push(value.clone());
+    }
+
+    *vec = deduped;
+    Ok(())
+}
+
+/// Only keep the values for which the given function returns `true`.
+fn retain(vec: &mut Vec<Value>, f: &Function) -> Result<(), VmError> {
+    let mut retained = Vec::with_capacity(vec.len());
+
+    for value in vec.iter() {
+        if f.call::<_, bool>((value.clone(),))? {
+            retained.push(value.clone());
+        }
+    }
+
+    *vec = retained;
+    Ok(())
+}
+
+/// Sort the vector, which requires all values to be comparable with each
+/// other as compared by [Vm::value_partial_cmp].
+fn sort(vec: &mut Vec<Value>) -> Result<(), VmError> {
+    let mut vm = VmEnv::vm()?;
+    sort_with(vec, &mut |a, b| compare(&mut vm, a, b))
+}
+
+/// Sort the vector with a comparison function, which returns a negative
+/// integer, zero, or a positive integer if its first argument is less than,
+/// equal to, or greater than its second argument.
+fn sort_by(vec: &mut Vec<Value>, f: &Function) -> Result<(), VmError> {
+    sort_with(vec, &mut |a, b| {
+        Ok(f.call::<_, i64>((a.clone(), b.clone()))?.cmp(&0))
+    })
+}
+
+/// Binary search a sorted vector for the given value.
+///
+/// Returns `Ok` with the index of a matching value, or `Err` with the index
+/// where the value could be inserted to keep the vector sorted.
+fn binary_search(vec: &[Value], value: Value) -> Result<Result<usize, usize>, VmError> {
+    let mut vm = VmEnv::vm()?;
+    let mut low = 0;
+    let mut high = vec.len();
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        match compare(&mut vm, &vec[mid], &value)? {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Ok(Ok(mid)),
+        }
+    }
+
+    Ok(Err(low))
+}
+
+fn compare(vm: &mut Vm, a: &Value, b: &Value) -> Result<Ordering, VmError> {
+    match vm.value_partial_cmp(a, b)? {
+        Some(ordering) => Ok(ordering),
+        None => Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+            op: "cmp",
+            lhs: a.type_info()?,
+            rhs: b.type_info()?,
+        })),
+    }
+}
+
+/// Sort the vector with a fallible comparison.
+///
+/// This is a stable merge sort over a copy of the vector, which unlike the
+/// sort in std can't panic if the comparison isn't a total order, and leaves
+/// the vector untouched if the comparison fails.
+fn sort_with<F>(vec: &mut Vec<Value>, cmp: &mut F) -> Result<(), VmError>
+where
+    F: FnMut(&Value, &Value) -> Result<Ordering, VmError>,
+{
+    *vec = merge_sort(vec.clone(), cmp)?;
+    Ok(())
+}
+
+fn merge_sort<F>(mut left: Vec<Value>, cmp: &mut F) -> Result<Vec<Value>, VmError>
+where
+    F: FnMut(&Value, &Value) -> Result<Ordering, VmError>,
+{
+    if left.len() <= 1 {
+        return Ok(left);
+    }
+
+    let right = left.split_off(left.len() / 2);
+    let mut left = merge_sort(left, cmp)?.into_iter().peekable();
+    let mut right = merge_sort(right, cmp)?.into_iter().peekable();
+
+    let mut out = Vec::with_capacity(left.len() + right.len());
+
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        if cmp(b, a)? == Ordering::Less {
+            out.extend(right.next());
+        } else {
+            out.extend(left.next());
+        }
+    }
+
+    out.extend(left);
+    out.extend(right);
+    Ok(out)
+}
+
+fn missing_index(index: usize) -> VmError {
+    VmError::from(VmErrorKind::MissingIndex {
+        target: Vec::<Value>::type_info(),
+        index: VmIntegerRepr::Usize(index),
+    })
+}
@@ -182,6 +182,13 @@ pub const STRING_DISPLAY: Protocol = Protocol {
     hash: Hash::new(0x811b62957ea9d9f9),
 };
 
+/// Protocol function used by debug formatting, like `{value:?}` in template
+/// strings.
+pub const STRING_DEBUG: Protocol = Protocol {
+    name: "string_debug",
+    hash: Hash::new(0x4064e3867aaa0717),
+};
+
 /// Function used to convert an argument into an iterator.
 pub const INTO_ITER: Protocol = Protocol {
     name: "into_iter",
@@ -194,6 +201,32 @@ pub const NEXT: Protocol = Protocol {
     hash: Hash::new(0xc3cde069de2ba320),
 };
 
+/// The function to implement for equality, like `a == b`.
+pub const EQ: Protocol = Protocol {
+    name: "eq",
+    hash: Hash::new(0x418f5becbf885806),
+};
+
+/// The function to implement for ordering comparisons, like `a < b`.
+///
+/// The function returns a negative integer, zero, or a positive integer if the
+/// instance is less than, equal to, or greater than its argument. The integer
+/// can be wrapped in an option, where `None` means that the values can't be
+/// compared.
+pub const PARTIAL_CMP: Protocol = Protocol {
+    name: "partial_cmp",
+    hash: Hash::new(0x8d4430991253343c),
+};
+
+/// The function to implement to hash a value, which returns an integer.
+///
+/// Together with [EQ] it allows a value to be used as a key in a collection,
+/// like a `HashMap`.
+pub const HASH: Protocol = Protocol {
+    name: "hash",
+    hash: Hash::new(0xf6cf2d9f416cef08),
+};
+
 /// Function used to convert an argument into a future.
 pub const INTO_FUTURE: Protocol = Protocol {
     name: "into_future",
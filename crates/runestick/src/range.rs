@@ -0,0 +1,183 @@
+use crate::{
+    FromValue, Mut, Named, RawMut, RawRef, RawStr, Ref, Shared, UnsafeFromValue, Value, VmError,
+    VmErrorKind, VmIntegerRepr,
+};
+use std::cmp::Ordering;
+use std::convert::TryFrom as _;
+use std::fmt;
+use std::ops;
+
+/// The limits of a [Range].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeLimits {
+    /// A half-open range `a..b`, which doesn't include its end.
+    HalfOpen,
+    /// A closed range `a..=b`, which includes its end.
+    Closed,
+}
+
+/// A range value, as constructed by the range expressions `a..b`, `a..=b`,
+/// `a..`, `..b`, `..=b` and `..`.
+#[derive(Clone)]
+pub struct Range {
+    /// The start of the range, if it's bounded.
+    pub start: Option<Value>,
+    /// The end of the range, if it's bounded.
+    pub end: Option<Value>,
+    /// The limits of the range.
+    pub limits: RangeLimits,
+}
+
+impl Range {
+    /// Construct a new range.
+    pub fn new(start: Option<Value>, end: Option<Value>, limits: RangeLimits) -> Self {
+        Self { start, end, limits }
+    }
+
+    /// Test if the given value is contained in the range.
+    ///
+    /// Only integers, bytes, characters and floats are compared, and a value
+    /// is never contained in a range with bounds of a different type.
+    pub fn contains(&self, value: &Value) -> bool {
+        if let Some(start) = &self.start {
+            match compare(start, value) {
+                Some(Ordering::Less) | Some(Ordering::Equal) => (),
+                _ => return false,
+            }
+        }
+
+        if let Some(end) = &self.end {
+            match (compare(value, end), self.limits) {
+                (Some(Ordering::Less), _) => (),
+                (Some(Ordering::Equal), RangeLimits::Closed) => (),
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Calculate the indexes selected by this range when it's used to slice a
+    /// sequence of the given length.
+    ///
+    /// Returns `None` if the range is out of bounds.
+    pub(crate) fn slice_bounds(&self, len: usize) -> Result<Option<ops::Range<usize>>, VmError> {
+        let start = match &self.start {
+            Some(start) => slice_index(start)?,
+            None => 0,
+        };
+
+        let end = match &self.end {
+            Some(end) => {
+                let end = slice_index(end)?;
+
+                match self.limits {
+                    RangeLimits::HalfOpen => end,
+                    RangeLimits::Closed => match end.checked_add(1) {
+                        Some(end) => end,
+                        None => return Ok(None),
+                    },
+                }
+            }
+            None => len,
+        };
+
+        if start > end || end > len {
+            return Ok(None);
+        }
+
+        Ok(Some(start..end))
+    }
+}
+
+impl fmt::Debug for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = &self.start {
+            write!(f, "{:?}", start)?;
+        }
+
+        match self.limits {
+            RangeLimits::HalfOpen => write!(f, "..")?,
+            RangeLimits::Closed => write!(f, "..=")?,
+        }
+
+        if let Some(end) = &self.end {
+            write!(f, "{:?}", end)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare two values which can be used as the bounds of a range.
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.partial_cmp(b),
+        (Value::Byte(a), Value::Byte(b)) => a.partial_cmp(b),
+        (Value::Char(a), Value::Char(b)) => a.partial_cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+/// Convert the bound of a range into a slice index.
+fn slice_index(value: &Value) -> Result<usize, VmError> {
+    let index = match value {
+        Value::Integer(index) => *index,
+        actual => return Err(VmError::expected::<i64>(actual.type_info()?)),
+    };
+
+    match usize::try_from(index) {
+        Ok(index) => Ok(index),
+        Err(..) => Err(VmError::from(VmErrorKind::ValueToIntegerCoercionError {
+            from: VmIntegerRepr::I64(index),
+            to: "usize",
+        })),
+    }
+}
+
+impl Named for Range {
+    const NAME: RawStr = RawStr::from_str("Range");
+}
+
+impl FromValue for Shared<Range> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(value.into_range()?)
+    }
+}
+
+impl FromValue for Range {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        let range = value.into_range()?;
+        let range = range.borrow_ref()?.clone();
+        Ok(range)
+    }
+}
+
+impl UnsafeFromValue for &Range {
+    type Output = *const Range;
+    type Guard = RawRef;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let range = value.into_range()?;
+        Ok(Ref::into_raw(range.into_ref()?))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &*output
+    }
+}
+
+impl UnsafeFromValue for &mut Range {
+    type Output = *mut Range;
+    type Guard = RawMut;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let range = value.into_range()?;
+        Ok(Mut::into_raw(range.into_mut()?))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &mut *output
+    }
+}
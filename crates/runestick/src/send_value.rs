@@ -0,0 +1,236 @@
+use crate::collections::HashMap;
+use crate::{
+    Bytes, FromValue, GeneratorState, Hash, Object, ObjectVariant, Range, RangeLimits, Rtti,
+    Shared, ToValue, Tuple, TupleVariant, TypedObject, TypedTuple, Value, VariantRtti, VmError,
+    VmErrorKind,
+};
+use std::sync::Arc;
+
+/// A deep copy of a [Value] which can be sent to another thread.
+///
+/// Values are reference counted without synchronization, so they can't be
+/// shared across threads. A send value owns all of its data instead, and is
+/// converted back into a [Value] with [SendValue::into_value] on the other
+/// side. Since [Context][crate::Context] and [Unit][crate::Unit] can be
+/// shared across threads, a multi-threaded host constructs one virtual
+/// machine per thread, and passes arguments and results between them as send
+/// values.
+///
+/// Functions, futures, streams, generators and native types can't be
+/// converted into send values.
+///
+/// ```rust
+/// use runestick::{SendValue, Value};
+///
+/// # fn main() -> runestick::Result<()> {
+/// let value = Value::from(String::from("hello"));
+/// let sendable = value.to_sendable()?;
+///
+/// let handle = std::thread::spawn(move || sendable);
+/// let value = handle.join().unwrap().into_value();
+/// assert_eq!(value.into_string()?.borrow_ref()?.as_str(), "hello");
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub enum SendValue {
+    /// The unit value.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// A single byte.
+    Byte(u8),
+    /// A character.
+    Char(char),
+    /// An integer.
+    Integer(i64),
+    /// A float.
+    Float(f64),
+    /// A type hash.
+    Type(Hash),
+    /// A string.
+    String(String),
+    /// A byte array.
+    Bytes(Bytes),
+    /// A vector.
+    Vec(Vec<SendValue>),
+    /// A tuple.
+    Tuple(Box<[SendValue]>),
+    /// An anonymous object.
+    Object(HashMap<String, SendValue>),
+    /// A range.
+    Range(Option<Box<SendValue>>, Option<Box<SendValue>>, RangeLimits),
+    /// An optional value.
+    Option(Option<Box<SendValue>>),
+    /// A result.
+    Result(Result<Box<SendValue>, Box<SendValue>>),
+    /// A generator state which yielded a value.
+    Yielded(Box<SendValue>),
+    /// A generator state which completed with a value.
+    Complete(Box<SendValue>),
+    /// A typed tuple, like `struct Point(x, y)`.
+    TypedTuple(Arc<Rtti>, Box<[SendValue]>),
+    /// A tuple variant, like `Kind::B(1)`.
+    TupleVariant(Arc<VariantRtti>, Box<[SendValue]>),
+    /// A typed object, like `struct Point { x, y }`.
+    TypedObject(Arc<Rtti>, HashMap<String, SendValue>),
+    /// An object variant, like `Kind::C { a: 1 }`.
+    ObjectVariant(Arc<VariantRtti>, HashMap<String, SendValue>),
+}
+
+impl SendValue {
+    /// Construct a send value from a value.
+    ///
+    /// Errors with [VmErrorKind::UnsupportedSendValue] if the value, or any
+    /// value it contains, can't be sent across threads.
+    pub fn from_value(value: &Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Unit => Self::Unit,
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Byte(b) => Self::Byte(*b),
+            Value::Char(c) => Self::Char(*c),
+            Value::Integer(n) => Self::Integer(*n),
+            Value::Float(n) => Self::Float(*n),
+            Value::Type(hash) => Self::Type(*hash),
+            Value::StaticString(s) => Self::String((***s).clone()),
+            Value::String(s) => Self::String(s.borrow_ref()?.clone()),
+            Value::Bytes(b) => Self::Bytes(b.borrow_ref()?.clone()),
+            Value::Vec(vec) => Self::Vec(Self::from_slice(&vec.borrow_ref()?)?.into_vec()),
+            Value::Tuple(tuple) => Self::Tuple(Self::from_slice(&tuple.borrow_ref()?)?),
+            Value::Object(object) => Self::Object(Self::from_object(&*object.borrow_ref()?)?),
+            Value::Range(range) => {
+                let range = range.borrow_ref()?;
+
+                Self::Range(
+                    Self::from_option(range.start.as_ref())?,
+                    Self::from_option(range.end.as_ref())?,
+                    range.limits,
+                )
+            }
+            Value::Option(option) => {
+                Self::Option(Self::from_option(option.borrow_ref()?.as_ref())?)
+            }
+            Value::Result(result) => Self::Result(match &*result.borrow_ref()? {
+                Ok(value) => Ok(Box::new(Self::from_value(value)?)),
+                Err(value) => Err(Box::new(Self::from_value(value)?)),
+            }),
+            Value::GeneratorState(state) => match &*state.borrow_ref()? {
+                GeneratorState::Yielded(value) => Self::Yielded(Box::new(Self::from_value(value)?)),
+                GeneratorState::Complete(value) => {
+                    Self::Complete(Box::new(Self::from_value(value)?))
+                }
+            },
+            Value::TypedTuple(typed) => {
+                let typed = typed.borrow_ref()?;
+                Self::TypedTuple(typed.rtti.clone(), Self::from_slice(&typed.tuple)?)
+            }
+            Value::TupleVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                Self::TupleVariant(variant.rtti.clone(), Self::from_slice(&variant.tuple)?)
+            }
+            Value::TypedObject(typed) => {
+                let typed = typed.borrow_ref()?;
+                Self::TypedObject(typed.rtti.clone(), Self::from_object(&typed.object)?)
+            }
+            Value::ObjectVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                Self::ObjectVariant(variant.rtti.clone(), Self::from_object(&variant.object)?)
+            }
+            value => {
+                return Err(VmError::from(VmErrorKind::UnsupportedSendValue {
+                    actual: value.type_info()?,
+                }))
+            }
+        })
+    }
+
+    /// Convert the send value back into a value.
+    pub fn into_value(self) -> Value {
+        match self {
+            Self::Unit => Value::Unit,
+            Self::Bool(b) => Value::Bool(b),
+            Self::Byte(b) => Value::Byte(b),
+            Self::Char(c) => Value::Char(c),
+            Self::Integer(n) => Value::Integer(n),
+            Self::Float(n) => Value::Float(n),
+            Self::Type(hash) => Value::Type(hash),
+            Self::String(s) => Value::from(s),
+            Self::Bytes(b) => Value::from(b),
+            Self::Vec(values) => Value::Vec(Shared::new(Self::into_values(values))),
+            Self::Tuple(values) => Value::from(Tuple::from(Self::into_values(values.into_vec()))),
+            Self::Object(object) => Value::from(Self::into_object(object)),
+            Self::Range(start, end, limits) => Value::from(Range::new(
+                start.map(|value| value.into_value()),
+                end.map(|value| value.into_value()),
+                limits,
+            )),
+            Self::Option(option) => {
+                Value::Option(Shared::new(option.map(|value| value.into_value())))
+            }
+            Self::Result(result) => Value::Result(Shared::new(match result {
+                Ok(value) => Ok(value.into_value()),
+                Err(value) => Err(value.into_value()),
+            })),
+            Self::Yielded(value) => Value::from(GeneratorState::Yielded(value.into_value())),
+            Self::Complete(value) => Value::from(GeneratorState::Complete(value.into_value())),
+            Self::TypedTuple(rtti, values) => Value::from(TypedTuple {
+                rtti,
+                tuple: Tuple::from(Self::into_values(values.into_vec())),
+            }),
+            Self::TupleVariant(rtti, values) => Value::from(TupleVariant {
+                rtti,
+                tuple: Tuple::from(Self::into_values(values.into_vec())),
+            }),
+            Self::TypedObject(rtti, object) => Value::from(TypedObject {
+                rtti,
+                object: Self::into_object(object),
+            }),
+            Self::ObjectVariant(rtti, object) => Value::from(ObjectVariant {
+                rtti,
+                object: Self::into_object(object),
+            }),
+        }
+    }
+
+    fn from_slice(values: &[Value]) -> Result<Box<[Self]>, VmError> {
+        values.iter().map(Self::from_value).collect()
+    }
+
+    fn from_option(value: Option<&Value>) -> Result<Option<Box<Self>>, VmError> {
+        Ok(match value {
+            Some(value) => Some(Box::new(Self::from_value(value)?)),
+            None => None,
+        })
+    }
+
+    fn from_object(object: &Object) -> Result<HashMap<String, Self>, VmError> {
+        object
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), Self::from_value(value)?)))
+            .collect()
+    }
+
+    fn into_values(values: Vec<Self>) -> Vec<Value> {
+        values.into_iter().map(Self::into_value).collect()
+    }
+
+    fn into_object(object: HashMap<String, Self>) -> Object {
+        Object::from(
+            object
+                .into_iter()
+                .map(|(key, value)| (key, value.into_value()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+}
+
+impl FromValue for SendValue {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Self::from_value(&value)
+    }
+}
+
+impl ToValue for SendValue {
+    fn to_value(self) -> Result<Value, VmError> {
+        Ok(self.into_value())
+    }
+}
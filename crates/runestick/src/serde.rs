@@ -76,6 +76,7 @@ impl ser::Serialize for Value {
             Value::TypedObject(..) => Err(ser::Error::custom("cannot serialize object types")),
             Value::ObjectVariant(..) => Err(ser::Error::custom("cannot serialize variant objects")),
             Value::Result(..) => Err(ser::Error::custom("cannot serialize results")),
+            Value::Range(..) => Err(ser::Error::custom("cannot serialize ranges")),
             Value::Type(..) => Err(ser::Error::custom("cannot serialize types")),
             Value::Future(..) => Err(ser::Error::custom("cannot serialize futures")),
             Value::Stream(..) => Err(ser::Error::custom("cannot serialize streams")),
@@ -1,7 +1,7 @@
 use crate::access::{
     Access, AccessError, AccessKind, BorrowMut, BorrowRef, RawExclusiveGuard, RawSharedGuard,
 };
-use crate::{Any, AnyObj, Hash};
+use crate::{Any, AnyObj, Hash, VmLimits};
 use std::any;
 use std::cell::{Cell, UnsafeCell};
 use std::fmt;
@@ -25,6 +25,8 @@ impl<T> Shared<T> {
         let inner = Box::leak(Box::new(SharedBox {
             access: Access::new(false),
             count: Cell::new(1),
+            owner: Cell::new(None),
+            allocated: Cell::new(0),
             data: data.into(),
         }));
 
@@ -352,6 +354,30 @@ impl<T: ?Sized> Shared<T> {
             Ok(BorrowMut::from_raw(inner.data.get(), guard))
         }
     }
+
+    /// Record the number of bytes allocated by the shared value, as accounted
+    /// for by the given limits.
+    ///
+    /// Returns the number of bytes previously recorded, or `None` if the value
+    /// wasn't accounted for by the given limits. If it was accounted for by
+    /// other limits, its bytes are released from them.
+    pub(crate) fn replace_allocated(&self, limits: &VmLimits, bytes: usize) -> Option<usize> {
+        // Safety: We know that interior value is alive since this container is
+        // alive.
+        unsafe {
+            let inner = self.inner.as_ref();
+            let previous = inner.allocated.replace(bytes);
+
+            match inner.owner.replace(Some(limits.clone())) {
+                Some(owner) if owner.ptr_eq(limits) => Some(previous),
+                Some(owner) => {
+                    owner.release(previous);
+                    None
+                }
+                None => None,
+            }
+        }
+    }
 }
 
 impl Shared<AnyObj> {
@@ -437,6 +463,8 @@ impl Shared<AnyObj> {
         let inner = ptr::NonNull::from(Box::leak(Box::new(SharedBox {
             access: Access::new(true),
             count: Cell::new(2),
+            owner: Cell::new(None),
+            allocated: Cell::new(0),
             data: any.into(),
         })));
 
@@ -723,6 +751,12 @@ struct SharedBox<T: ?Sized> {
     access: Access,
     /// The number of strong references to the shared data.
     count: Cell<usize>,
+    /// The limits which account for the bytes allocated by the shared data,
+    /// if any.
+    owner: Cell<Option<VmLimits>>,
+    /// The number of bytes allocated by the shared data, as accounted for by
+    /// `owner`.
+    allocated: Cell<usize>,
     /// The value being held. Guarded by the `access` field to determine if it
     /// can be access shared or exclusively.
     data: UnsafeCell<T>,
@@ -761,6 +795,10 @@ impl<T: ?Sized> SharedBox<T> {
             return false;
         }
 
+        if let Some(owner) = (*this).owner.take() {
+            owner.release((*this).allocated.get());
+        }
+
         if (*this).access.is_taken() {
             // NB: This prevents the inner `T` from being dropped in case it
             // has already been taken (as indicated by `is_taken`).
@@ -128,6 +128,14 @@ pub static OBJECT_TYPE: &StaticType = &StaticType {
 
 impl_static_type!(crate::Object => OBJECT_TYPE);
 
+/// The specialized type information for a range type.
+pub static RANGE_TYPE: &StaticType = &StaticType {
+    name: RawStr::from_str("Range"),
+    hash: Hash::new(0x7fa8a7c4a9a5e3d2),
+};
+
+impl_static_type!(crate::Range => RANGE_TYPE);
+
 /// The specialized type information for a future type.
 pub static FUTURE_TYPE: &StaticType = &StaticType {
     name: RawStr::from_str("Future"),
@@ -143,6 +151,7 @@ pub static GENERATOR_TYPE: &StaticType = &StaticType {
 };
 
 impl_static_type!(crate::Generator => GENERATOR_TYPE);
+impl_static_type!(crate::Shared<crate::Generator> => GENERATOR_TYPE);
 
 /// The specialized type information for a generator state type.
 pub static GENERATOR_STATE_TYPE: &StaticType = &StaticType {
@@ -185,4 +194,5 @@ pub static FUNCTION_TYPE: &StaticType = &StaticType {
 impl_static_type!(crate::Function => FUNCTION_TYPE);
 impl_static_type!(crate::Shared<crate::Function> => FUNCTION_TYPE);
 impl_static_type!(crate::Ref<crate::Function> => FUNCTION_TYPE);
+impl_static_type!(impl<A, T> crate::TypedFunction<A, T> => FUNCTION_TYPE);
 impl_static_type!(impl<T> std::collections::HashMap<String, T> => crate::OBJECT_TYPE);
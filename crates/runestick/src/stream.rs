@@ -41,7 +41,10 @@ impl Stream {
             execution.vm_mut()?.stack_mut().push(value);
         }
 
-        let state = execution.async_resume().await?;
+        let state = execution
+            .async_resume()
+            .await
+            .map_err(VmError::into_unresumable)?;
 
         if state.is_complete() {
             self.execution = None;
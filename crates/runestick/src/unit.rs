@@ -5,7 +5,8 @@
 
 use crate::collections::HashMap;
 use crate::{
-    Call, DebugInfo, Hash, Inst, Rtti, StaticString, Type, VariantRtti, VmError, VmErrorKind,
+    Call, Context, DebugInfo, Hash, Inst, Rtti, StaticString, Type, VariantRtti, VerifyErrors,
+    VmError, VmErrorKind,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -147,6 +148,18 @@ impl Unit {
     pub fn lookup(&self, hash: Hash) -> Option<UnitFn> {
         self.functions.get(&hash).copied()
     }
+
+    /// Verify that the unit can be safely executed with the given context.
+    ///
+    /// This checks that all jumps stay within the function they are part of,
+    /// that frame offsets and stack usage are consistent with the size of each
+    /// frame, and that all static slots and functions referenced exist.
+    ///
+    /// This should be used for units loaded from an untrusted location, like
+    /// a serialized unit read from disk or received over the network.
+    pub fn verify(&self, context: &Context) -> Result<(), VerifyErrors> {
+        crate::verify::verify(self, context)
+    }
 }
 
 /// The kind and necessary information on registered functions.
@@ -1,9 +1,11 @@
 use crate::access::AccessKind;
 use crate::{
     Any, AnyObj, Bytes, Function, Future, Generator, GeneratorState, Hash, Item, Mut, Object,
-    RawMut, RawRef, Ref, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
+    Range, RawMut, RawRef, Ref, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
+    VmErrorKind,
 };
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
 use std::sync::Arc;
 
@@ -62,7 +64,7 @@ impl fmt::Debug for TupleVariant {
 /// An object with a well-defined type.
 pub struct TypedObject {
     /// The type hash of the object.
-    rtti: Arc<Rtti>,
+    pub(crate) rtti: Arc<Rtti>,
     /// Content of the object.
     pub(crate) object: Object,
 }
@@ -168,6 +170,40 @@ pub struct Rtti {
     pub item: Item,
 }
 
+/// Runtime type information is compared by the hash of the type it describes.
+macro_rules! impl_rtti_cmp {
+    ($ty:ty) => {
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.hash == other.hash
+            }
+        }
+
+        impl Eq for $ty {}
+
+        impl PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $ty {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.hash.cmp(&other.hash)
+            }
+        }
+
+        impl std::hash::Hash for $ty {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.hash.hash(state);
+            }
+        }
+    };
+}
+
+impl_rtti_cmp!(VariantRtti);
+impl_rtti_cmp!(Rtti);
+
 /// An entry on the stack.
 #[derive(Clone)]
 pub enum Value {
@@ -204,6 +240,8 @@ pub enum Value {
     Tuple(Shared<Tuple>),
     /// An object.
     Object(Shared<Object>),
+    /// A range.
+    Range(Shared<Range>),
     /// A stored future.
     Future(Shared<Future>),
     /// A Stream.
@@ -349,6 +387,37 @@ impl Value {
         }
     }
 
+    /// Convert the value into an [Iterator][crate::Iterator].
+    ///
+    /// This supports iterators, vectors, tuples, objects, ranges and
+    /// generators. Other values implementing the [INTO_ITER][crate::INTO_ITER]
+    /// or [NEXT][crate::NEXT] protocols need a virtual machine to be converted,
+    /// which is done by calling `iter()` on them.
+    pub fn into_iterator(self) -> Result<crate::Iterator, VmError> {
+        Ok(match self {
+            Value::Vec(vec) => crate::Iterator::from_vec("Vec", vec.borrow_ref()?.clone()),
+            Value::Tuple(tuple) => crate::Iterator::from_vec("Tuple", tuple.borrow_ref()?.to_vec()),
+            Value::Object(object) => {
+                let object = object.borrow_ref()?;
+
+                let pairs = object
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect::<Vec<_>>();
+
+                crate::Iterator::from_vec("Object", pairs)
+            }
+            Value::Range(range) => crate::modules::ops::range_iter(&*range.borrow_ref()?)?,
+            Value::Generator(generator) => crate::Iterator::from_generator(generator),
+            Value::Any(any) if any.borrow_ref()?.is::<crate::Iterator>() => any.take_downcast()?,
+            actual => {
+                return Err(VmError::from(VmErrorKind::UnsupportedIterator {
+                    actual: actual.type_info()?,
+                }))
+            }
+        })
+    }
+
     /// Try to coerce value into a stream.
     #[inline]
     pub fn into_stream(self) -> Result<Shared<Stream>, VmError> {
@@ -367,6 +436,15 @@ impl Value {
         }
     }
 
+    /// Try to coerce value into a range.
+    #[inline]
+    pub fn into_range(self) -> Result<Shared<Range>, VmError> {
+        match self {
+            Self::Range(range) => Ok(range),
+            actual => Err(VmError::expected::<Range>(actual.type_info()?)),
+        }
+    }
+
     /// Try to coerce value into an option.
     #[inline]
     pub fn into_option(self) -> Result<Shared<Option<Value>>, VmError> {
@@ -502,6 +580,7 @@ impl Value {
             Self::Vec(..) => Type::from(crate::VEC_TYPE),
             Self::Tuple(..) => Type::from(crate::TUPLE_TYPE),
             Self::Object(..) => Type::from(crate::OBJECT_TYPE),
+            Self::Range(..) => Type::from(crate::RANGE_TYPE),
             Self::Future(..) => Type::from(crate::FUTURE_TYPE),
             Self::Stream(..) => Type::from(crate::STREAM_TYPE),
             Self::Generator(..) => Type::from(crate::GENERATOR_TYPE),
@@ -524,6 +603,15 @@ impl Value {
         })
     }
 
+    /// Make a deep copy of the value which can be sent to another thread.
+    ///
+    /// See [SendValue] for which values are supported.
+    ///
+    /// [SendValue]: crate::SendValue
+    pub fn to_sendable(&self) -> Result<crate::SendValue, VmError> {
+        crate::SendValue::from_value(self)
+    }
+
     /// Get the type information for the current value.
     pub fn type_info(&self) -> Result<TypeInfo, VmError> {
         Ok(match self {
@@ -539,6 +627,7 @@ impl Value {
             Self::Vec(..) => TypeInfo::StaticType(crate::VEC_TYPE),
             Self::Tuple(..) => TypeInfo::StaticType(crate::TUPLE_TYPE),
             Self::Object(..) => TypeInfo::StaticType(crate::OBJECT_TYPE),
+            Self::Range(..) => TypeInfo::StaticType(crate::RANGE_TYPE),
             Self::Future(..) => TypeInfo::StaticType(crate::FUTURE_TYPE),
             Self::Stream(..) => TypeInfo::StaticType(crate::STREAM_TYPE),
             Self::Generator(..) => TypeInfo::StaticType(crate::GENERATOR_TYPE),
@@ -624,6 +713,57 @@ impl Value {
             _ => false,
         })
     }
+
+    /// Optimized function to compare two value pointers with each other.
+    ///
+    /// Primitives of the same type are compared by value, and vectors and
+    /// tuples are compared lexicographically. Returns `None` if the values
+    /// can't be compared.
+    ///
+    /// This is the basis for sorting values.
+    pub(crate) fn value_ptr_cmp(a: &Value, b: &Value) -> Result<Option<Ordering>, VmError> {
+        Ok(match (a, b) {
+            (Self::Unit, Self::Unit) => Some(Ordering::Equal),
+            (Self::Bool(a), Self::Bool(b)) => a.partial_cmp(b),
+            (Self::Byte(a), Self::Byte(b)) => a.partial_cmp(b),
+            (Self::Char(a), Self::Char(b)) => a.partial_cmp(b),
+            (Self::Integer(a), Self::Integer(b)) => a.partial_cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::Vec(a), Self::Vec(b)) => {
+                Self::slice_ptr_cmp(&*a.borrow_ref()?, &*b.borrow_ref()?)?
+            }
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                Self::slice_ptr_cmp(&*a.borrow_ref()?, &*b.borrow_ref()?)?
+            }
+            (Self::String(a), Self::String(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                a.as_str().partial_cmp(b.as_str())
+            }
+            (Self::StaticString(a), Self::String(b)) => {
+                let b = b.borrow_ref()?;
+                a.as_str().partial_cmp(b.as_str())
+            }
+            (Self::String(a), Self::StaticString(b)) => {
+                let a = a.borrow_ref()?;
+                a.as_str().partial_cmp(b.as_str())
+            }
+            (Self::StaticString(a), Self::StaticString(b)) => a.as_str().partial_cmp(b.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Compare two slices of values lexicographically.
+    fn slice_ptr_cmp(a: &[Value], b: &[Value]) -> Result<Option<Ordering>, VmError> {
+        for (a, b) in a.iter().zip(b.iter()) {
+            match Self::value_ptr_cmp(a, b)? {
+                Some(Ordering::Equal) => (),
+                ordering => return Ok(ordering),
+            }
+        }
+
+        Ok(a.len().partial_cmp(&b.len()))
+    }
 }
 
 impl fmt::Debug for Value {
@@ -668,6 +808,9 @@ impl fmt::Debug for Value {
             Value::Object(value) => {
                 write!(f, "{:?}", value)?;
             }
+            Value::Range(value) => {
+                write!(f, "{:?}", value)?;
+            }
             Value::Future(value) => {
                 write!(f, "{:?}", value)?;
             }
@@ -783,6 +926,7 @@ impl_from_shared!(Shared<String>, String);
 impl_from!(Shared<Vec<Value>>, Vec);
 impl_from_shared!(Shared<Tuple>, Tuple);
 impl_from_shared!(Shared<Object>, Object);
+impl_from_shared!(Shared<Range>, Range);
 impl_from_shared!(Shared<Future>, Future);
 impl_from_shared!(Shared<Stream>, Stream);
 impl_from_shared!(Shared<Generator>, Generator);
@@ -0,0 +1,647 @@
+//! Verification of units before they are executed.
+//!
+//! Units loaded from an untrusted location, like a cache on disk or over the
+//! network, might be corrupt in ways which would otherwise only be detected by
+//! the virtual machine once the offending instruction is executed.
+
+use crate::collections::{HashMap, HashSet};
+use crate::unit::UnitFn;
+use crate::{Context, Hash, Inst, InstTarget, Unit};
+use std::fmt;
+use std::slice;
+use thiserror::Error;
+
+/// A problem found while verifying a unit.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum VerifyError {
+    /// A function is registered at an offset which doesn't exist.
+    #[error("function {hash} is registered at offset {offset}, which is out of bounds")]
+    FunctionOutOfBounds {
+        /// The hash of the function.
+        hash: Hash,
+        /// The offset of the function.
+        offset: usize,
+    },
+    /// A jump targets an instruction outside of the function it's part of.
+    #[error("jump at {ip} with offset {offset} is out of bounds")]
+    JumpOutOfBounds {
+        /// The instruction pointer of the jump.
+        ip: usize,
+        /// The offset of the jump.
+        offset: isize,
+    },
+    /// Execution can continue past the last instruction of a function.
+    #[error("execution continues past the end of the function at {ip}")]
+    MissingReturn {
+        /// The instruction pointer of the last instruction executed.
+        ip: usize,
+    },
+    /// An instruction pops more values than are available in the frame.
+    #[error(
+        "instruction at {ip} needs {needed} values on the stack, but only {depth} are available"
+    )]
+    StackUnderflow {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The number of values needed by the instruction.
+        needed: usize,
+        /// The number of values available in the frame.
+        depth: usize,
+    },
+    /// An instruction accesses a frame offset which doesn't exist.
+    #[error("instruction at {ip} accesses offset {offset}, but the frame only has {depth} values")]
+    FrameOffsetOutOfBounds {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The offset being accessed.
+        offset: usize,
+        /// The number of values available in the frame.
+        depth: usize,
+    },
+    /// An instruction can be reached with different stack sizes.
+    #[error(
+        "instruction at {ip} is reached with both {expected} and {actual} values on the stack"
+    )]
+    InconsistentStack {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The stack size the instruction was first reached with.
+        expected: usize,
+        /// The conflicting stack size.
+        actual: usize,
+    },
+    /// A return doesn't leave the stack in the state expected by the caller.
+    #[error("return at {ip} has {depth} values on the stack, but expected {expected}")]
+    ReturnStackMismatch {
+        /// The instruction pointer of the return.
+        ip: usize,
+        /// The number of values on the stack.
+        depth: usize,
+        /// The number of values expected on the stack.
+        expected: usize,
+    },
+    /// A closure environment is unpacked outside of a closure which has one.
+    #[error("instruction at {ip} unpacks a closure environment, but there is none")]
+    MissingClosureEnvironment {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+    },
+    /// A static string slot is missing.
+    #[error("instruction at {ip} references missing static string slot {slot}")]
+    MissingStaticString {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The missing slot.
+        slot: usize,
+    },
+    /// A static byte string slot is missing.
+    #[error("instruction at {ip} references missing static byte string slot {slot}")]
+    MissingStaticBytes {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The missing slot.
+        slot: usize,
+    },
+    /// A static object keys slot is missing.
+    #[error("instruction at {ip} references missing static object keys slot {slot}")]
+    MissingStaticObjectKeys {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The missing slot.
+        slot: usize,
+    },
+    /// A function is neither present in the unit nor in the context.
+    #[error("instruction at {ip} references missing function {hash}")]
+    MissingFunction {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The hash of the missing function.
+        hash: Hash,
+    },
+    /// Runtime type information is missing for the given type.
+    #[error("missing runtime information for type {hash}")]
+    MissingRtti {
+        /// The hash of the type.
+        hash: Hash,
+    },
+    /// Runtime type information is missing for the given variant.
+    #[error("missing runtime information for variant {hash}")]
+    MissingVariantRtti {
+        /// The hash of the variant.
+        hash: Hash,
+    },
+}
+
+/// The problems found while verifying a unit.
+#[derive(Debug, Error)]
+pub struct VerifyErrors {
+    errors: Vec<VerifyError>,
+}
+
+impl VerifyErrors {
+    /// Iterate over all problems found.
+    pub fn iter(&self) -> slice::Iter<'_, VerifyError> {
+        self.errors.iter()
+    }
+
+    /// Get the number of problems found.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Test if there are no problems.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for VerifyErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut it = self.errors.iter();
+
+        if let Some(error) = it.next() {
+            write!(f, "{}", error)?;
+        }
+
+        let rest = it.count();
+
+        if rest > 0 {
+            write!(f, " (and {} more)", rest)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a VerifyErrors {
+    type Item = &'a VerifyError;
+    type IntoIter = slice::Iter<'a, VerifyError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for VerifyErrors {
+    type Item = VerifyError;
+    type IntoIter = std::vec::IntoIter<VerifyError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+/// Verify the given unit against the context it will be executed in.
+pub(crate) fn verify(unit: &Unit, context: &Context) -> Result<(), VerifyErrors> {
+    let mut verifier = Verifier {
+        unit,
+        context,
+        instructions: unit.iter_instructions().collect(),
+        errors: Vec::new(),
+    };
+
+    verifier.verify_functions();
+    verifier.verify_instructions();
+    verifier.verify_frames();
+
+    if verifier.errors.is_empty() {
+        return Ok(());
+    }
+
+    Err(VerifyErrors {
+        errors: verifier.errors,
+    })
+}
+
+struct Verifier<'a> {
+    unit: &'a Unit,
+    context: &'a Context,
+    instructions: Vec<Inst>,
+    errors: Vec<VerifyError>,
+}
+
+impl Verifier<'_> {
+    /// Verify the function table of the unit.
+    fn verify_functions(&mut self) {
+        let mut functions = self.unit.iter_functions().collect::<Vec<_>>();
+        functions.sort_by_key(|(hash, _)| *hash);
+
+        for (hash, info) in functions {
+            match *info {
+                UnitFn::Offset { offset, .. } => {
+                    if offset >= self.instructions.len() {
+                        self.errors
+                            .push(VerifyError::FunctionOutOfBounds { hash, offset });
+                    }
+                }
+                UnitFn::Tuple { hash, .. } => {
+                    if self.unit.lookup_rtti(hash).is_none() {
+                        self.errors.push(VerifyError::MissingRtti { hash });
+                    }
+                }
+                UnitFn::TupleVariant { hash, .. } => {
+                    if self.unit.lookup_variant_rtti(hash).is_none() {
+                        self.errors.push(VerifyError::MissingVariantRtti { hash });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Verify the static references of every instruction, regardless of
+    /// whether it's reachable or not.
+    fn verify_instructions(&mut self) {
+        for ip in 0..self.instructions.len() {
+            match self.instructions[ip] {
+                Inst::Call { hash, .. } | Inst::LoadFn { hash }
+                    if self.unit.lookup(hash).is_none() && self.context.lookup(hash).is_none() =>
+                {
+                    self.errors.push(VerifyError::MissingFunction { ip, hash });
+                }
+                Inst::Closure { hash, .. }
+                    if !matches!(self.unit.lookup(hash), Some(UnitFn::Offset { .. })) =>
+                {
+                    self.errors.push(VerifyError::MissingFunction { ip, hash });
+                }
+                Inst::ObjectIndexGet { slot }
+                | Inst::ObjectIndexGetAt { slot, .. }
+                | Inst::String { slot }
+                | Inst::EqStaticString { slot }
+                | Inst::Assign {
+                    target: InstTarget::Field(slot),
+                    ..
+                } if self.unit.lookup_string(slot).is_err() => {
+                    self.errors
+                        .push(VerifyError::MissingStaticString { ip, slot });
+                }
+                Inst::Bytes { slot } if self.unit.lookup_bytes(slot).is_err() => {
+                    self.errors
+                        .push(VerifyError::MissingStaticBytes { ip, slot });
+                }
+                Inst::Object { slot } | Inst::MatchObject { slot, .. } => {
+                    self.verify_object_keys(ip, slot);
+                }
+                Inst::TypedObject { hash, slot } => {
+                    self.verify_object_keys(ip, slot);
+
+                    if self.unit.lookup_rtti(hash).is_none() {
+                        self.errors.push(VerifyError::MissingRtti { hash });
+                    }
+                }
+                Inst::ObjectVariant { hash, slot } => {
+                    self.verify_object_keys(ip, slot);
+
+                    if self.unit.lookup_variant_rtti(hash).is_none() {
+                        self.errors.push(VerifyError::MissingVariantRtti { hash });
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn verify_object_keys(&mut self, ip: usize, slot: usize) {
+        if self.unit.lookup_object_keys(slot).is_none() {
+            self.errors
+                .push(VerifyError::MissingStaticObjectKeys { ip, slot });
+        }
+    }
+
+    /// Verify the stack usage of every function in the unit, by walking all
+    /// instructions reachable from its entrypoint.
+    fn verify_frames(&mut self) {
+        // The number of values captured by closures, which are passed in as
+        // an extra argument and unpacked with `PushTuple`.
+        let mut environments = HashMap::new();
+
+        for inst in &self.instructions {
+            if let Inst::Closure { hash, count } = *inst {
+                if count > 0 {
+                    environments.insert(hash, count);
+                }
+            }
+        }
+
+        let mut entries = self
+            .unit
+            .iter_functions()
+            .filter_map(|(hash, info)| match *info {
+                UnitFn::Offset { offset, args, .. } if offset < self.instructions.len() => {
+                    Some((offset, args, environments.get(&hash).copied()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_unstable();
+        entries.dedup();
+
+        let mut offsets = entries
+            .iter()
+            .map(|(offset, ..)| *offset)
+            .collect::<Vec<_>>();
+        offsets.dedup();
+
+        for (offset, args, environment) in entries {
+            let end = match offsets.binary_search(&offset) {
+                Ok(n) => offsets.get(n + 1).copied(),
+                Err(..) => None,
+            };
+
+            let end = end.unwrap_or(self.instructions.len());
+            let frame = Frame {
+                start: offset,
+                end,
+                environment,
+            };
+
+            let args = args + usize::from(environment.is_some());
+            self.verify_frame(frame, args);
+        }
+    }
+
+    fn verify_frame(&mut self, frame: Frame, args: usize) {
+        let mut depths = HashMap::<usize, usize>::new();
+        let mut reported = HashSet::new();
+        let mut queue = vec![(frame.start, args)];
+
+        while let Some((ip, depth)) = queue.pop() {
+            if let Some(expected) = depths.get(&ip).copied() {
+                if expected != depth && reported.insert(ip) {
+                    self.errors.push(VerifyError::InconsistentStack {
+                        ip,
+                        expected,
+                        actual: depth,
+                    });
+                }
+
+                continue;
+            }
+
+            depths.insert(ip, depth);
+
+            let inst = self.instructions[ip];
+
+            let next = match self.step(&frame, ip, inst, depth) {
+                Ok(next) => next,
+                Err(error) => {
+                    self.errors.push(error);
+                    continue;
+                }
+            };
+
+            let (fallthrough, jump) = match next {
+                Next::Continue(depth) => (Some(depth), None),
+                Next::Jump(offset, depth) => (None, Some((offset, depth))),
+                Next::Branch(depth, offset, jump_depth) => {
+                    (Some(depth), Some((offset, jump_depth)))
+                }
+                Next::Exit => (None, None),
+            };
+
+            if let Some(depth) = fallthrough {
+                if ip + 1 >= frame.end {
+                    self.errors.push(VerifyError::MissingReturn { ip });
+                } else {
+                    queue.push((ip + 1, depth));
+                }
+            }
+
+            if let Some((offset, depth)) = jump {
+                match frame.jump(ip, offset) {
+                    Some(target) => queue.push((target, depth)),
+                    None => self
+                        .errors
+                        .push(VerifyError::JumpOutOfBounds { ip, offset }),
+                }
+            }
+        }
+    }
+
+    /// Calculate the effect of a single instruction on the stack.
+    fn step(
+        &self,
+        frame: &Frame,
+        ip: usize,
+        inst: Inst,
+        depth: usize,
+    ) -> Result<Next, VerifyError> {
+        let stack = Stack { ip, depth };
+
+        let next = match inst {
+            Inst::Not
+            | Inst::LoadInstanceFn { .. }
+            | Inst::TupleIndexGet { .. }
+            | Inst::ObjectIndexGet { .. }
+            | Inst::Await
+            | Inst::IsUnit
+            | Inst::IsValue
+            | Inst::Unwrap
+            | Inst::EqByte { .. }
+            | Inst::EqCharacter { .. }
+            | Inst::EqInteger { .. }
+            | Inst::EqStaticString { .. }
+            | Inst::MatchSequence { .. }
+            | Inst::MatchObject { .. }
+            | Inst::Format { .. }
+            | Inst::Yield => Next::Continue(stack.pop(1)? + 1),
+            Inst::IndexGet
+            | Inst::Lt
+            | Inst::Gt
+            | Inst::Lte
+            | Inst::Gte
+            | Inst::Eq
+            | Inst::Neq
+            | Inst::Is
+            | Inst::IsNot
+            | Inst::And
+            | Inst::Or
+            | Inst::Op { .. } => Next::Continue(stack.pop(2)? + 1),
+            Inst::Closure { count, .. }
+            | Inst::Vec { count }
+            | Inst::Tuple { count }
+            | Inst::StringConcat { len: count, .. } => Next::Continue(stack.pop(count)? + 1),
+            Inst::Call { args, .. } => Next::Continue(stack.pop(args)? + 1),
+            Inst::CallInstance { args, .. } | Inst::CallFn { args } => {
+                Next::Continue(stack.pop(args + 1)? + 1)
+            }
+            // NB: a select pushes either a unit, if there was nothing to
+            // select over, or a value and the index of the branch that
+            // completed. The index is consumed by the `JumpIfBranch` which
+            // matches it, so it's not accounted for here.
+            Inst::Select { len } => Next::Continue(stack.pop(len)? + 1),
+            Inst::TupleIndexSet { .. } => Next::Continue(stack.pop(2)?),
+            Inst::IndexSet => Next::Continue(stack.pop(3)?),
+            Inst::TupleIndexGetAt { offset, .. } | Inst::ObjectIndexGetAt { offset, .. } => {
+                stack.offset(offset)?;
+                Next::Continue(depth + 1)
+            }
+            Inst::Copy { offset } => {
+                stack.offset(offset)?;
+                Next::Continue(depth + 1)
+            }
+            Inst::Drop { offset } => {
+                stack.offset(offset)?;
+                Next::Continue(depth)
+            }
+            Inst::Replace { offset } => {
+                let depth = stack.pop(1)?;
+                Stack { ip, depth }.offset(offset)?;
+                Next::Continue(depth)
+            }
+            Inst::LoadFn { .. }
+            | Inst::Push { .. }
+            | Inst::String { .. }
+            | Inst::Bytes { .. }
+            | Inst::YieldUnit => Next::Continue(depth + 1),
+            Inst::Pop => Next::Continue(stack.pop(1)?),
+            Inst::PopN { count } => Next::Continue(stack.pop(count)?),
+            Inst::Clean { count } => Next::Continue(stack.pop(count + 1)? + 1),
+            Inst::Dup => Next::Continue(stack.pop(1)? + 2),
+            Inst::PushTuple => {
+                let environment = frame
+                    .environment
+                    .ok_or(VerifyError::MissingClosureEnvironment { ip })?;
+
+                Next::Continue(stack.pop(1)? + environment)
+            }
+            Inst::Object { slot }
+            | Inst::TypedObject { slot, .. }
+            | Inst::ObjectVariant { slot, .. } => {
+                // NB: missing slots are reported separately.
+                let count = self
+                    .unit
+                    .lookup_object_keys(slot)
+                    .map(|keys| keys.len())
+                    .unwrap_or_default();
+
+                Next::Continue(stack.pop(count)? + 1)
+            }
+            Inst::Range { range } => {
+                let count = usize::from(range.has_start()) + usize::from(range.has_end());
+                Next::Continue(stack.pop(count)? + 1)
+            }
+            Inst::MatchRange { range } => {
+                let count = 1 + usize::from(range.has_start()) + usize::from(range.has_end());
+                Next::Continue(stack.pop(count)? + 1)
+            }
+            Inst::Assign { target, .. } => match target {
+                InstTarget::Offset(offset) => {
+                    let depth = stack.pop(1)?;
+                    Stack { ip, depth }.offset(offset)?;
+                    Next::Continue(depth)
+                }
+                InstTarget::Field(..) | InstTarget::TupleField(..) => Next::Continue(stack.pop(2)?),
+            },
+            Inst::Return => {
+                stack.exact(1)?;
+                Next::Exit
+            }
+            Inst::ReturnUnit => {
+                stack.exact(0)?;
+                Next::Exit
+            }
+            Inst::Panic { .. } => Next::Exit,
+            Inst::Jump { offset } => Next::Jump(offset, depth),
+            Inst::JumpIf { offset } | Inst::JumpIfNot { offset } => {
+                let depth = stack.pop(1)?;
+                Next::Branch(depth, offset, depth)
+            }
+            Inst::JumpIfOrPop { offset } | Inst::JumpIfNotOrPop { offset } => {
+                Next::Branch(stack.pop(1)?, offset, depth)
+            }
+            Inst::JumpIfBranch { offset, .. } => Next::Branch(depth, offset, depth),
+            Inst::PopAndJumpIfNot { count, offset } => {
+                let depth = stack.pop(1)?;
+                let jump_depth = Stack { ip, depth }.pop(count)?;
+                Next::Branch(depth, offset, jump_depth)
+            }
+        };
+
+        Ok(next)
+    }
+}
+
+/// The function being verified.
+struct Frame {
+    /// The first instruction of the function.
+    start: usize,
+    /// The first instruction after the function.
+    end: usize,
+    /// The size of the closure environment, if the function has one.
+    environment: Option<usize>,
+}
+
+impl Frame {
+    /// Calculate the target of a jump, if it's inside of the function.
+    fn jump(&self, ip: usize, offset: isize) -> Option<usize> {
+        let target = if offset < 0 {
+            (ip + 1).checked_sub(offset.checked_neg()? as usize)?
+        } else {
+            (ip + 1).checked_add(offset as usize)?
+        };
+
+        if target < self.start || target >= self.end {
+            return None;
+        }
+
+        Some(target)
+    }
+}
+
+/// The stack of a frame at a given instruction.
+struct Stack {
+    ip: usize,
+    depth: usize,
+}
+
+impl Stack {
+    /// Pop the given number of values, returning the new depth.
+    fn pop(&self, needed: usize) -> Result<usize, VerifyError> {
+        self.depth
+            .checked_sub(needed)
+            .ok_or(VerifyError::StackUnderflow {
+                ip: self.ip,
+                needed,
+                depth: self.depth,
+            })
+    }
+
+    /// Check that the given frame offset is available.
+    fn offset(&self, offset: usize) -> Result<(), VerifyError> {
+        if offset >= self.depth {
+            return Err(VerifyError::FrameOffsetOutOfBounds {
+                ip: self.ip,
+                offset,
+                depth: self.depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Check that the stack has exactly the expected number of values.
+    fn exact(&self, expected: usize) -> Result<(), VerifyError> {
+        if self.depth != expected {
+            return Err(VerifyError::ReturnStackMismatch {
+                ip: self.ip,
+                depth: self.depth,
+                expected,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Where execution continues after an instruction.
+enum Next {
+    /// Continue with the next instruction.
+    Continue(usize),
+    /// Unconditionally jump to the given offset.
+    Jump(isize, usize),
+    /// Either continue with the next instruction, or jump to the given offset.
+    Branch(usize, isize, usize),
+    /// Leave the function.
+    Exit,
+}
@@ -1,15 +1,23 @@
 use crate::budget;
 use crate::future::SelectFuture;
 use crate::unit::UnitFn;
+use crate::vm_env::VmEnv;
 use crate::{
-    Args, Awaited, BorrowMut, Bytes, Call, Context, FromValue, Function, Future, Generator,
-    GuardedArgs, Hash, Inst, InstFnNameHash, InstOp, InstTarget, IntoTypeHash, Object,
-    ObjectVariant, Panic, Select, Shared, Stack, Stream, Tuple, TypeCheck, TypedObject, Unit,
-    Value, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr,
+    Args, Awaited, BorrowMut, Bytes, Call, Context, FormatSpec, FormatType, FromValue, Function,
+    Future, Generator, GeneratorState, GuardedArgs, Hash, Inst, InstFnNameHash, InstOp, InstRange,
+    InstTarget, InterruptHandle, IntoTypeHash, Item, Iterator, Object, ObjectVariant, Panic,
+    Protocol, Range, RangeLimits, Select, Shared, Stack, Stream, ToValue as _, Tuple, TypeCheck,
+    TypeOf as _, TypedObject, Unit, Value, VmError, VmErrorKind, VmExecution, VmHalt,
+    VmIntegerRepr, VmLimits, VmSendExecution,
 };
+use std::cmp::Ordering;
 use std::fmt;
+use std::fmt::Write as _;
+use std::hash::{Hash as _, Hasher as _};
 use std::mem;
+use std::ops;
 use std::sync::Arc;
+use twox_hash::XxHash64;
 
 macro_rules! target_value {
     ($vm:ident, $target:expr, $guard:ident, $lhs:ident) => {{
@@ -61,6 +69,11 @@ pub struct Vm {
     stack: Stack,
     /// Frames relative to the stack.
     call_frames: Vec<CallFrame>,
+    /// The execution limits of the virtual machine, if any.
+    limits: Option<VmLimits>,
+    /// The number of call frames in the virtual machines calling into this
+    /// one, which counts towards the call frame limit.
+    depth: usize,
 }
 
 impl Vm {
@@ -77,6 +90,8 @@ impl Vm {
             ip: 0,
             stack,
             call_frames: Vec::new(),
+            limits: None,
+            depth: 0,
         }
     }
 
@@ -85,13 +100,16 @@ impl Vm {
     /// If any async instructions are encountered, this will error.
     pub fn complete(self) -> Result<Value, VmError> {
         let mut execution = VmExecution::new(self);
-        Ok(execution.complete()?)
+        execution.complete().map_err(VmError::into_unresumable)
     }
 
     /// Run the given vm to completion with support for async functions.
     pub async fn async_complete(self) -> Result<Value, VmError> {
         let mut execution = VmExecution::new(self);
-        execution.async_complete().await
+        execution
+            .async_complete()
+            .await
+            .map_err(VmError::into_unresumable)
     }
 
     /// Test if the virtual machine is the same context and unit as specified.
@@ -99,12 +117,92 @@ impl Vm {
         Arc::ptr_eq(&self.context, context) && Arc::ptr_eq(&self.unit, unit)
     }
 
+    /// Test if the virtual machine is executing the specified unit.
+    pub fn is_same_unit(&self, unit: &Arc<Unit>) -> bool {
+        Arc::ptr_eq(&self.unit, unit)
+    }
+
     /// Set  the current instruction pointer.
     #[inline]
     pub fn set_ip(&mut self, ip: usize) {
         self.ip = ip;
     }
 
+    /// Get the execution limits of the virtual machine, if any.
+    pub fn limits(&self) -> Option<&VmLimits> {
+        self.limits.as_ref()
+    }
+
+    /// Set the execution limits of the virtual machine.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Unit, Vm, VmLimits};
+    /// use std::sync::Arc;
+    ///
+    /// let mut vm = Vm::new(Arc::new(Context::new()), Arc::new(Unit::default()));
+    ///
+    /// let limits = VmLimits::new();
+    /// limits.set_fuel(Some(10_000));
+    /// limits.set_max_call_frames(Some(256));
+    /// vm.set_limits(limits.clone());
+    /// ```
+    pub fn set_limits(&mut self, limits: VmLimits) {
+        self.limits = Some(limits);
+    }
+
+    /// Get a handle which interrupts the virtual machine from another thread.
+    ///
+    /// This is the handle of the limits of the virtual machine, which are set
+    /// up if it doesn't have any yet.
+    pub fn interrupt_handle(&mut self) -> InterruptHandle {
+        self.limits
+            .get_or_insert_with(VmLimits::new)
+            .interrupt_handle()
+    }
+
+    /// Set up the virtual machine to share the limits of the limited virtual
+    /// machine which is currently running on this thread, if any.
+    ///
+    /// This is used for virtual machines which are called into from another
+    /// one.
+    pub(crate) fn inherit_limits(&mut self) -> Result<(), VmError> {
+        if let Some(limits) = VmLimits::current() {
+            self.depth = limits.depth() + 1;
+            limits.check_depth(self.depth)?;
+            self.limits = Some(limits);
+        }
+
+        Ok(())
+    }
+
+    /// The number of call frames, including the ones of the virtual machines
+    /// calling into this one.
+    pub(crate) fn depth(&self) -> usize {
+        self.depth + self.call_frames.len()
+    }
+
+    /// Account for the memory allocated by the value on top of the stack, if
+    /// the virtual machine is limited.
+    pub(crate) fn account_top(&self) -> Result<(), VmError> {
+        if let Some(limits) = &self.limits {
+            limits.account(self.stack.last()?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Account for the memory allocated by the given value, if the virtual
+    /// machine is limited.
+    fn account(&self, value: &Value) -> Result<(), VmError> {
+        if let Some(limits) = &self.limits {
+            limits.account(value)?;
+        }
+
+        Ok(())
+    }
+
     /// Get the stack.
     #[inline]
     pub fn call_frames(&self) -> &[CallFrame] {
@@ -198,6 +296,54 @@ impl Vm {
         Ok(VmExecution::new(self))
     }
 
+    /// Run the given function in an execution which can be sent to another
+    /// thread, like a task on a multi-threaded runtime.
+    ///
+    /// The arguments have to be [Send], so that the execution only has access
+    /// to values which were constructed for it, and its output is converted
+    /// into a [SendValue][crate::SendValue]. Limits can't be shared across
+    /// threads, so the execution gets a copy of the limits of the virtual
+    /// machine. It can still be interrupted through their
+    /// [InterruptHandle], but their fuel can't be topped up and their memory
+    /// usage isn't updated while it runs.
+    ///
+    /// # Safety
+    ///
+    /// Native types and futures aren't required to be [Send], so the caller
+    /// must make sure that every native type which the execution might
+    /// construct, and every future it might await, is [Send]. This is the case
+    /// if the native functions in the context only produce such types and
+    /// futures. Native functions called by the execution must also not hold on
+    /// to the values passed to them outside of it, like in thread-local
+    /// storage.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use runestick::{Context, Unit, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # async fn run(context: Arc<Context>, unit: Arc<Unit>) -> runestick::Result<()> {
+    /// let vm = Vm::new(context, unit);
+    /// // Safety: the context only has native types and futures which are `Send`.
+    /// let execution = unsafe { vm.send_execute(&["main"], (1i64, String::from("two")))? };
+    ///
+    /// let output = tokio::spawn(execution.async_complete()).await??;
+    /// let output = output.into_value();
+    /// # Ok(()) }
+    /// ```
+    pub unsafe fn send_execute<A, N>(mut self, name: N, args: A) -> Result<VmSendExecution, VmError>
+    where
+        N: IntoTypeHash,
+        A: Send + Args,
+    {
+        if let Some(limits) = &self.limits {
+            self.limits = Some(limits.detach());
+        }
+
+        Ok(VmSendExecution::new(self.execute(name, args)?))
+    }
+
     /// Call the given function immediately, returning the produced value.
     ///
     /// This function permits for using references since it doesn't defer its
@@ -222,7 +368,9 @@ impl Vm {
         // Safety: We hold onto the guard until the vm has completed.
         let guard = unsafe { args.unsafe_into_stack(&mut self.stack)? };
 
-        let value = VmExecution::new(self).complete()?;
+        let value = VmExecution::new(self)
+            .complete()
+            .map_err(VmError::into_unresumable)?;
 
         // Note: this might panic if something in the vm is holding on to a
         // reference of the value. We should prevent it from being possible to
@@ -256,7 +404,10 @@ impl Vm {
         // Safety: We hold onto the guard until the vm has completed.
         let guard = unsafe { args.unsafe_into_stack(&mut self.stack)? };
 
-        let value = VmExecution::new(self).complete()?;
+        let value = VmExecution::new(self)
+            .async_complete()
+            .await
+            .map_err(VmError::into_unresumable)?;
 
         // Note: this might panic if something in the vm is holding on to a
         // reference of the value. We should prevent it from being possible to
@@ -375,6 +526,64 @@ impl Vm {
         Ok(true)
     }
 
+    /// Helper function to call an instance function and get its return value
+    /// immediately.
+    ///
+    /// Unlike [call_instance_fn][Self::call_instance_fn], functions declared
+    /// in the unit are run to completion in a nested virtual machine. This is
+    /// used by operations which don't produce the return value of the
+    /// function as their own result.
+    fn call_instance_fn_immediate<H, A>(
+        &mut self,
+        target: &Value,
+        hash: H,
+        args: A,
+    ) -> Result<Option<Value>, VmError>
+    where
+        H: IntoTypeHash,
+        A: Args,
+    {
+        let count = A::count() + 1;
+        let hash = Hash::instance_function(target.type_of()?, hash.into_type_hash());
+
+        if let Some(UnitFn::Offset {
+            offset,
+            call,
+            args: expected,
+        }) = self.unit.lookup(hash)
+        {
+            Self::check_args(count, expected)?;
+
+            let mut stack = Stack::with_capacity(count);
+            stack.push(target.clone());
+            args.into_stack(&mut stack)?;
+
+            let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), stack);
+            vm.set_ip(offset);
+            vm.inherit_limits()?;
+
+            let value = match call {
+                Call::Immediate => vm.complete()?,
+                Call::Async => Value::from(Future::new(vm.async_complete())),
+                Call::Generator => Value::from(Generator::new(vm)),
+                Call::Stream => Value::from(Stream::new(vm)),
+            };
+
+            return Ok(Some(value));
+        }
+
+        let handler = match self.context.lookup(hash) {
+            Some(handler) => handler,
+            None => return Ok(None),
+        };
+
+        self.stack.push(target.clone());
+        args.into_stack(&mut self.stack)?;
+
+        handler(&mut self.stack, count)?;
+        Ok(Some(self.stack.pop()?))
+    }
+
     /// Helper function to call an external getter.
     fn call_getter<H, A>(&mut self, target: &Value, hash: H, args: A) -> Result<bool, VmError>
     where
@@ -452,19 +661,29 @@ impl Vm {
         Ok(())
     }
 
-    fn internal_boolean_ops(
+    /// Internal impl of an ordering comparison.
+    fn internal_cmp(
         &mut self,
-        int_op: impl FnOnce(i64, i64) -> bool,
-        float_op: impl FnOnce(f64, f64) -> bool,
+        test: impl FnOnce(Ordering) -> bool,
         op: &'static str,
     ) -> Result<(), VmError> {
         let rhs = self.stack.pop()?;
         let lhs = self.stack.pop()?;
 
-        let out = match (lhs, rhs) {
-            (Value::Integer(lhs), Value::Integer(rhs)) => int_op(lhs, rhs),
-            (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs),
-            (lhs, rhs) => {
+        let ordering = match (&lhs, &rhs) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => Some(lhs.cmp(rhs)),
+            (Value::Float(lhs), Value::Float(rhs)) => {
+                // NB: comparisons involving NaN are false.
+                self.stack
+                    .push(lhs.partial_cmp(rhs).map(test).unwrap_or_default());
+                return Ok(());
+            }
+            _ => self.value_partial_cmp(&lhs, &rhs)?,
+        };
+
+        let ordering = match ordering {
+            Some(ordering) => ordering,
+            None => {
                 return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                     op,
                     lhs: lhs.type_info()?,
@@ -473,35 +692,373 @@ impl Vm {
             }
         };
 
-        self.stack.push(out);
+        self.stack.push(test(ordering));
         Ok(())
     }
 
     fn op_gt(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a > b, |a, b| a > b, ">")?;
-        Ok(())
+        self.internal_cmp(|o| o == Ordering::Greater, ">")
     }
 
     fn op_gte(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a >= b, |a, b| a >= b, ">=")?;
-        Ok(())
+        self.internal_cmp(|o| o != Ordering::Less, ">=")
     }
 
     fn op_lt(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a < b, |a, b| a < b, "<")?;
-        Ok(())
+        self.internal_cmp(|o| o == Ordering::Less, "<")
     }
 
     fn op_lte(&mut self) -> Result<(), VmError> {
-        self.internal_boolean_ops(|a, b| a <= b, |a, b| a <= b, "<=")?;
+        self.internal_cmp(|o| o != Ordering::Greater, "<=")
+    }
+
+    /// Test if two values are equal.
+    ///
+    /// Primitives and built-in containers are compared structurally. So are
+    /// script structs and enums, unless they implement the [EQ][crate::EQ]
+    /// protocol. Native types are only equal if they implement the protocol.
+    pub fn value_eq(&mut self, a: &Value, b: &Value) -> Result<bool, VmError> {
+        if let Some(value) = self.call_cmp_protocol(crate::EQ, a, b)? {
+            return value.into_bool();
+        }
+
+        Ok(match (a, b) {
+            (Value::Vec(a), Value::Vec(b)) => self.slice_eq(&a.borrow_ref()?, &b.borrow_ref()?)?,
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                self.slice_eq(&a.borrow_ref()?, &b.borrow_ref()?)?
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                self.object_eq(&*a.borrow_ref()?, &*b.borrow_ref()?)?
+            }
+            (Value::Option(a), Value::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Some(a), Some(b)) => self.value_eq(a, b)?,
+                (None, None) => true,
+                _ => false,
+            },
+            (Value::Result(a), Value::Result(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Ok(a), Ok(b)) => self.value_eq(a, b)?,
+                (Err(a), Err(b)) => self.value_eq(a, b)?,
+                _ => false,
+            },
+            (Value::Bytes(a), Value::Bytes(b)) => *a.borrow_ref()? == *b.borrow_ref()?,
+            (Value::Type(a), Value::Type(b)) => a == b,
+            (Value::TypedTuple(a), Value::TypedTuple(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                a.rtti.hash == b.rtti.hash && self.slice_eq(&a.tuple, &b.tuple)?
+            }
+            (Value::TupleVariant(a), Value::TupleVariant(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                a.rtti.hash == b.rtti.hash && self.slice_eq(&a.tuple, &b.tuple)?
+            }
+            (Value::TypedObject(a), Value::TypedObject(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                a.rtti.hash == b.rtti.hash && self.object_eq(&a.object, &b.object)?
+            }
+            (Value::ObjectVariant(a), Value::ObjectVariant(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+                a.rtti.hash == b.rtti.hash && self.object_eq(&a.object, &b.object)?
+            }
+            _ => Value::value_ptr_eq(a, b)?,
+        })
+    }
+
+    /// Compare two values.
+    ///
+    /// Returns `None` if the values can't be compared. Vectors, tuples and
+    /// the fields of script structs and enums are compared lexicographically,
+    /// where the fields of structs with named fields are compared in the
+    /// order of their names. Script types can override this, and native types
+    /// can be compared, by implementing the
+    /// [PARTIAL_CMP][crate::PARTIAL_CMP] protocol.
+    pub fn value_partial_cmp(&mut self, a: &Value, b: &Value) -> Result<Option<Ordering>, VmError> {
+        if let Some(value) = self.call_cmp_protocol(crate::PARTIAL_CMP, a, b)? {
+            return ordering_from_value(value);
+        }
+
+        Ok(match (a, b) {
+            (Value::Vec(a), Value::Vec(b)) => {
+                self.slice_partial_cmp(&a.borrow_ref()?, &b.borrow_ref()?)?
+            }
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                self.slice_partial_cmp(&a.borrow_ref()?, &b.borrow_ref()?)?
+            }
+            (Value::Option(a), Value::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Some(a), Some(b)) => self.value_partial_cmp(a, b)?,
+                (a, b) => a.is_some().partial_cmp(&b.is_some()),
+            },
+            (Value::Result(a), Value::Result(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Ok(a), Ok(b)) => self.value_partial_cmp(a, b)?,
+                (Err(a), Err(b)) => self.value_partial_cmp(a, b)?,
+                (a, b) => a.is_err().partial_cmp(&b.is_err()),
+            },
+            (Value::Bytes(a), Value::Bytes(b)) => a.borrow_ref()?.partial_cmp(&*b.borrow_ref()?),
+            (Value::TypedTuple(a), Value::TypedTuple(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+
+                if a.rtti.hash != b.rtti.hash {
+                    return Ok(None);
+                }
+
+                self.slice_partial_cmp(&a.tuple, &b.tuple)?
+            }
+            (Value::TupleVariant(a), Value::TupleVariant(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+
+                if a.rtti.hash != b.rtti.hash {
+                    return Ok(None);
+                }
+
+                self.slice_partial_cmp(&a.tuple, &b.tuple)?
+            }
+            (Value::TypedObject(a), Value::TypedObject(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+
+                if a.rtti.hash != b.rtti.hash {
+                    return Ok(None);
+                }
+
+                self.object_partial_cmp(&a.object, &b.object)?
+            }
+            (Value::ObjectVariant(a), Value::ObjectVariant(b)) => {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+
+                if a.rtti.hash != b.rtti.hash {
+                    return Ok(None);
+                }
+
+                self.object_partial_cmp(&a.object, &b.object)?
+            }
+            _ => Value::value_ptr_cmp(a, b)?,
+        })
+    }
+
+    /// Hash a value.
+    ///
+    /// Values which are equal according to [value_eq][Self::value_eq] hash
+    /// the same, unless a type implements the [EQ][crate::EQ] protocol
+    /// without also implementing the [HASH][crate::HASH] protocol. Native
+    /// types can only be hashed if they implement the protocol.
+    pub fn value_hash(&mut self, value: &Value) -> Result<i64, VmError> {
+        let mut hasher = XxHash64::default();
+        self.hash_value(value, &mut hasher)?;
+        Ok(hasher.finish() as i64)
+    }
+
+    fn hash_value(&mut self, value: &Value, hasher: &mut XxHash64) -> Result<(), VmError> {
+        if let Value::TypedTuple(..)
+        | Value::TupleVariant(..)
+        | Value::TypedObject(..)
+        | Value::ObjectVariant(..)
+        | Value::Any(..) = value
+        {
+            if let Some(hash) = self.call_instance_fn_immediate(value, crate::HASH, ())? {
+                value.type_of()?.hash(hasher);
+                hash.into_integer()?.hash(hasher);
+                return Ok(());
+            }
+        }
+
+        match value {
+            Value::Unit => hasher.write_u8(0),
+            Value::Bool(b) => (1u8, b).hash(hasher),
+            Value::Byte(b) => (2u8, b).hash(hasher),
+            Value::Char(c) => (3u8, c).hash(hasher),
+            Value::Integer(n) => (4u8, n).hash(hasher),
+            Value::Float(f) => {
+                // NB: `0.0 == -0.0`, so they need to hash the same.
+                let f = if *f == 0.0 { 0.0 } else { *f };
+                (5u8, f.to_bits()).hash(hasher)
+            }
+            Value::StaticString(s) => (6u8, s.as_str()).hash(hasher),
+            Value::String(s) => (6u8, s.borrow_ref()?.as_str()).hash(hasher),
+            Value::Bytes(b) => (7u8, &*b.borrow_ref()?).hash(hasher),
+            Value::Type(hash) => (8u8, hash).hash(hasher),
+            Value::Vec(vec) => {
+                hasher.write_u8(9);
+                self.hash_slice(&vec.borrow_ref()?, hasher)?;
+            }
+            Value::Tuple(tuple) => {
+                hasher.write_u8(10);
+                self.hash_slice(&tuple.borrow_ref()?, hasher)?;
+            }
+            Value::Object(object) => {
+                hasher.write_u8(11);
+                self.hash_object(&*object.borrow_ref()?, hasher)?;
+            }
+            Value::Option(option) => {
+                hasher.write_u8(12);
+
+                if let Some(value) = &*option.borrow_ref()? {
+                    self.hash_value(value, hasher)?;
+                }
+            }
+            Value::Result(result) => match &*result.borrow_ref()? {
+                Ok(value) => {
+                    hasher.write_u8(13);
+                    self.hash_value(value, hasher)?;
+                }
+                Err(value) => {
+                    hasher.write_u8(14);
+                    self.hash_value(value, hasher)?;
+                }
+            },
+            Value::TypedTuple(typed) => {
+                let typed = typed.borrow_ref()?;
+                (15u8, typed.rtti.hash).hash(hasher);
+                self.hash_slice(&typed.tuple, hasher)?;
+            }
+            Value::TupleVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                (16u8, variant.rtti.hash).hash(hasher);
+                self.hash_slice(&variant.tuple, hasher)?;
+            }
+            Value::TypedObject(typed) => {
+                let typed = typed.borrow_ref()?;
+                (17u8, typed.rtti.hash).hash(hasher);
+                self.hash_object(&typed.object, hasher)?;
+            }
+            Value::ObjectVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                (18u8, variant.rtti.hash).hash(hasher);
+                self.hash_object(&variant.object, hasher)?;
+            }
+            value => {
+                return Err(VmError::from(VmErrorKind::MissingProtocol {
+                    protocol: crate::HASH,
+                    actual: value.type_info()?,
+                }))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hash_slice(&mut self, values: &[Value], hasher: &mut XxHash64) -> Result<(), VmError> {
+        values.len().hash(hasher);
+
+        for value in values {
+            self.hash_value(value, hasher)?;
+        }
+
         Ok(())
     }
 
+    fn hash_object(&mut self, object: &Object, hasher: &mut XxHash64) -> Result<(), VmError> {
+        object.len().hash(hasher);
+
+        for (key, value) in sorted_fields(object) {
+            key.hash(hasher);
+            self.hash_value(value, hasher)?;
+        }
+
+        Ok(())
+    }
+
+    /// Call the given comparison protocol on `a` with `b` as its argument.
+    ///
+    /// Only script and native types implement comparison protocols, and they
+    /// are only called when both values are of the same type.
+    fn call_cmp_protocol(
+        &mut self,
+        protocol: Protocol,
+        a: &Value,
+        b: &Value,
+    ) -> Result<Option<Value>, VmError> {
+        match a {
+            Value::TypedTuple(..)
+            | Value::TupleVariant(..)
+            | Value::TypedObject(..)
+            | Value::ObjectVariant(..)
+            | Value::Any(..) => (),
+            _ => return Ok(None),
+        }
+
+        if a.type_of()? != b.type_of()? {
+            return Ok(None);
+        }
+
+        self.call_instance_fn_immediate(a, protocol, (b.clone(),))
+    }
+
+    fn slice_eq(&mut self, a: &[Value], b: &[Value]) -> Result<bool, VmError> {
+        if a.len() != b.len() {
+            return Ok(false);
+        }
+
+        for (a, b) in a.iter().zip(b.iter()) {
+            if !self.value_eq(a, b)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn object_eq(&mut self, a: &Object, b: &Object) -> Result<bool, VmError> {
+        if a.len() != b.len() {
+            return Ok(false);
+        }
+
+        for (key, a) in a.iter() {
+            let b = match b.get(key) {
+                Some(b) => b,
+                None => return Ok(false),
+            };
+
+            if !self.value_eq(a, b)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn slice_partial_cmp(&mut self, a: &[Value], b: &[Value]) -> Result<Option<Ordering>, VmError> {
+        for (a, b) in a.iter().zip(b.iter()) {
+            match self.value_partial_cmp(a, b)? {
+                Some(Ordering::Equal) => (),
+                ordering => return Ok(ordering),
+            }
+        }
+
+        Ok(a.len().partial_cmp(&b.len()))
+    }
+
+    fn object_partial_cmp(&mut self, a: &Object, b: &Object) -> Result<Option<Ordering>, VmError> {
+        let a = sorted_fields(a);
+        let b = sorted_fields(b);
+
+        for ((a_key, a), (b_key, b)) in a.iter().zip(b.iter()) {
+            if a_key != b_key {
+                return Ok(None);
+            }
+
+            match self.value_partial_cmp(a, b)? {
+                Some(Ordering::Equal) => (),
+                ordering => return Ok(ordering),
+            }
+        }
+
+        Ok(a.len().partial_cmp(&b.len()))
+    }
+
     /// Push a new call frame.
     ///
     /// This will cause the `args` number of elements on the stack to be
     /// associated and accessible to the new call frame.
     pub(crate) fn push_call_frame(&mut self, ip: usize, args: usize) -> Result<(), VmError> {
+        if let Some(limits) = &self.limits {
+            limits.set_depth(self.depth() + 1)?;
+        }
+
         let stack_top = self.stack.swap_stack_bottom(args)?;
 
         self.call_frames.push(CallFrame {
@@ -525,24 +1082,31 @@ impl Vm {
 
         self.stack.pop_stack_top(frame.stack_bottom)?;
         self.ip = frame.ip;
+
+        if let Some(limits) = &self.limits {
+            limits.set_depth(self.depth())?;
+        }
+
         Ok(false)
     }
 
-    /// Optimized equality implementation.
+    /// Equality implementation.
     #[inline]
     fn op_eq(&mut self) -> Result<(), VmError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(Value::value_ptr_eq(&a, &b)?);
+        let eq = self.value_eq(&a, &b)?;
+        self.stack.push(eq);
         Ok(())
     }
 
-    /// Optimized inequality implementation.
+    /// Inequality implementation.
     #[inline]
     fn op_neq(&mut self) -> Result<(), VmError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(!Value::value_ptr_eq(&a, &b)?);
+        let eq = self.value_eq(&a, &b)?;
+        self.stack.push(!eq);
         Ok(())
     }
 
@@ -681,7 +1245,7 @@ impl Vm {
             }
             InstOp::Mul => {
                 self.internal_num(
-                    crate::ADD,
+                    crate::MUL,
                     || VmError::from(VmErrorKind::Overflow),
                     i64::checked_mul,
                     std::ops::Mul::mul,
@@ -690,11 +1254,11 @@ impl Vm {
             }
             InstOp::Div => {
                 self.internal_num(
-                    crate::ADD,
+                    crate::DIV,
                     || VmError::from(VmErrorKind::DivideByZero),
                     i64::checked_div,
                     std::ops::Div::div,
-                    "+",
+                    "/",
                 )?;
             }
             InstOp::Rem => {
@@ -851,14 +1415,22 @@ impl Vm {
                     local_field.as_str()
                 }
                 Value::StaticString(string) => string.as_ref(),
+                Value::Range(range) => {
+                    let range = range.borrow_ref()?;
+
+                    if Self::try_slice_index_set(&target, &range, &value)? {
+                        return self.account(&target);
+                    }
+
+                    break;
+                }
                 _ => break,
             };
 
             match &target {
                 Value::Object(object) => {
-                    let mut object = object.borrow_mut()?;
-                    object.insert(field.to_owned(), value);
-                    return Ok(());
+                    object.borrow_mut()?.insert(field.to_owned(), value);
+                    return self.account(&target);
                 }
                 Value::TypedObject(typed_object) => {
                     let mut typed_object = typed_object.borrow_mut()?;
@@ -890,7 +1462,10 @@ impl Vm {
             }
         }
 
-        if !self.call_instance_fn(&target, crate::INDEX_SET, (&index, &value))? {
+        if self
+            .call_instance_fn_immediate(&target, crate::INDEX_SET, (&index, &value))?
+            .is_none()
+        {
             return Err(VmError::from(VmErrorKind::UnsupportedIndexSet {
                 target: target.type_info()?,
                 index: index.type_info()?,
@@ -898,7 +1473,7 @@ impl Vm {
             }));
         }
 
-        Ok(())
+        self.account(&target)
     }
 
     #[inline]
@@ -1026,6 +1601,114 @@ impl Vm {
         Ok(Some(value))
     }
 
+    /// Implementation of slicing a sequence-like value with a range.
+    fn try_slice_index_get(target: &Value, range: &Range) -> Result<Option<Value>, VmError> {
+        let value = match target {
+            Value::Vec(vec) => {
+                let vec = vec.borrow_ref()?;
+                let bounds = Self::slice_bounds(target, range, vec.len())?;
+                Value::vec(vec[bounds].to_vec())
+            }
+            Value::Bytes(bytes) => {
+                let bytes = bytes.borrow_ref()?;
+                let bounds = Self::slice_bounds(target, range, bytes.len())?;
+                Value::from(Bytes::from_vec(bytes[bounds].to_vec()))
+            }
+            Value::String(string) => {
+                let string = string.borrow_ref()?;
+                Value::from(Self::slice_str(target, range, string.as_str())?.to_owned())
+            }
+            Value::StaticString(string) => {
+                Value::from(Self::slice_str(target, range, string.as_ref())?.to_owned())
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+
+    /// Implementation of splicing a sequence-like value with a range, replacing
+    /// the elements selected by the range with the elements of `value`.
+    fn try_slice_index_set(target: &Value, range: &Range, value: &Value) -> Result<bool, VmError> {
+        // NB: the replacement is copied out before the target is borrowed, in
+        // case the target is spliced with itself.
+        match (target, value) {
+            (Value::Vec(vec), Value::Vec(replace)) => {
+                let replace = replace.borrow_ref()?.to_vec();
+                let mut vec = vec.borrow_mut()?;
+                let bounds = Self::slice_bounds(target, range, vec.len())?;
+                vec.splice(bounds, replace);
+            }
+            (Value::Bytes(bytes), Value::Bytes(replace)) => {
+                let replace = replace.borrow_ref()?.bytes.clone();
+                let mut bytes = bytes.borrow_mut()?;
+                let bounds = Self::slice_bounds(target, range, bytes.len())?;
+                bytes.bytes.splice(bounds, replace);
+            }
+            (Value::String(string), Value::String(replace)) => {
+                let replace = replace.borrow_ref()?.clone();
+                let mut string = string.borrow_mut()?;
+                let bounds = Self::slice_str_bounds(target, range, string.as_str())?;
+                string.replace_range(bounds, &replace);
+            }
+            (Value::String(string), Value::StaticString(replace)) => {
+                let mut string = string.borrow_mut()?;
+                let bounds = Self::slice_str_bounds(target, range, string.as_str())?;
+                string.replace_range(bounds, replace.as_ref());
+            }
+            (Value::Vec(..), actual) | (Value::Bytes(..), actual) | (Value::String(..), actual) => {
+                return Err(VmError::from(VmErrorKind::Expected {
+                    expected: target.type_info()?,
+                    actual: actual.type_info()?,
+                }));
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Calculate the bounds of slicing the given target with a range.
+    fn slice_bounds(
+        target: &Value,
+        range: &Range,
+        len: usize,
+    ) -> Result<ops::Range<usize>, VmError> {
+        match range.slice_bounds(len)? {
+            Some(bounds) => Ok(bounds),
+            None => Err(VmError::from(VmErrorKind::SliceOutOfBounds {
+                target: target.type_info()?,
+                range: format!("{:?}", range),
+                len,
+            })),
+        }
+    }
+
+    /// Slice a string with a range, making sure that both ends of the slice
+    /// are on character boundaries.
+    fn slice_str<'a>(target: &Value, range: &Range, string: &'a str) -> Result<&'a str, VmError> {
+        let bounds = Self::slice_str_bounds(target, range, string)?;
+        Ok(&string[bounds])
+    }
+
+    /// Calculate the bounds of slicing a string with a range, making sure that
+    /// both ends of the slice are on character boundaries.
+    fn slice_str_bounds(
+        target: &Value,
+        range: &Range,
+        string: &str,
+    ) -> Result<ops::Range<usize>, VmError> {
+        let bounds = Self::slice_bounds(target, range, string.len())?;
+
+        for &index in &[bounds.start, bounds.end] {
+            if !string.is_char_boundary(index) {
+                return Err(VmError::from(VmErrorKind::NotCharBoundary { index }));
+            }
+        }
+
+        Ok(bounds)
+    }
+
     /// Implementation of getting a mutable value out of a tuple-like value.
     fn try_tuple_like_index_get_mut(
         target: &Value,
@@ -1229,6 +1912,14 @@ impl Vm {
                         return Ok(());
                     }
                 }
+                Value::Range(range) => {
+                    let range = range.borrow_ref()?;
+
+                    if let Some(value) = Self::try_slice_index_get(&target, &range)? {
+                        self.stack.push(value);
+                        return Ok(());
+                    }
+                }
                 Value::Integer(index) => {
                     use std::convert::TryInto as _;
 
@@ -1249,6 +1940,8 @@ impl Vm {
                 }
                 _ => break,
             };
+
+            break;
         }
 
         if !self.call_instance_fn(&target, crate::INDEX_GET, (&index,))? {
@@ -1481,6 +2174,38 @@ impl Vm {
         Ok(())
     }
 
+    /// Pop the bounds of a range of the given kind from the stack.
+    fn pop_range(&mut self, range: InstRange) -> Result<Range, VmError> {
+        let end = if range.has_end() {
+            Some(self.stack.pop()?)
+        } else {
+            None
+        };
+
+        let start = if range.has_start() {
+            Some(self.stack.pop()?)
+        } else {
+            None
+        };
+
+        Ok(Range::new(start, end, range.limits()))
+    }
+
+    #[inline]
+    fn op_range(&mut self, range: InstRange) -> Result<(), VmError> {
+        let range = self.pop_range(range)?;
+        self.stack.push(range);
+        Ok(())
+    }
+
+    #[inline]
+    fn op_match_range(&mut self, range: InstRange) -> Result<(), VmError> {
+        let range = self.pop_range(range)?;
+        let value = self.stack.pop()?;
+        self.stack.push(range.contains(&value));
+        Ok(())
+    }
+
     /// Optimize operation to perform string concatenation.
     #[inline]
     fn op_string_concat(&mut self, len: usize, size_hint: usize) -> Result<(), VmError> {
@@ -1488,50 +2213,325 @@ impl Vm {
         let values = self.stack.drain_stack_top(len)?.collect::<Vec<_>>();
 
         for value in values {
-            match value {
-                Value::String(string) => {
-                    buf.push_str(&*string.borrow_ref()?);
+            self.display_value(value, &mut buf)?;
+        }
+
+        self.stack.push(buf);
+        Ok(())
+    }
+
+    /// Format a value according to a format specification, as used by
+    /// `{value:spec}` in template strings.
+    #[inline]
+    fn op_format(&mut self, spec: FormatSpec) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+        let mut buf = String::new();
+        self.format_value(value, &spec, &mut buf)?;
+        self.stack.push(buf);
+        Ok(())
+    }
+
+    /// Format a value according to the given specification and append it to
+    /// `buf`.
+    ///
+    /// Integers support all format types, and floats support display and
+    /// debug formatting with a precision. Other values are displayed using
+    /// the [STRING_DISPLAY][crate::STRING_DISPLAY] protocol, or debugged
+    /// using the [STRING_DEBUG][crate::STRING_DEBUG] protocol, and then
+    /// padded to the width of the specification.
+    ///
+    /// Errors if the width or precision of the specification is larger than
+    /// [FormatSpec::MAX_COUNT].
+    pub fn format_value(
+        &mut self,
+        value: Value,
+        spec: &FormatSpec,
+        buf: &mut String,
+    ) -> Result<(), VmError> {
+        if let Err(error) = spec.check() {
+            return Err(VmError::from(VmErrorKind::InvalidFormatSpec {
+                spec: *spec,
+                error,
+            }));
+        }
+
+        match (spec.format_type, value) {
+            (_, Value::Integer(n)) => spec.format_integer(n, buf),
+            (_, Value::Byte(b)) => spec.format_integer(b as i64, buf),
+            (FormatType::Display, Value::Float(f)) | (FormatType::Debug, Value::Float(f)) => {
+                spec.format_float(f, buf)
+            }
+            (FormatType::Display, value) => {
+                let mut s = String::new();
+                self.display_value(value, &mut s)?;
+                spec.format_str(&s, buf);
+            }
+            (FormatType::Debug, Value::String(s)) => spec.format_str(&s.borrow_ref()?, buf),
+            (FormatType::Debug, Value::StaticString(s)) => spec.format_str(s.as_str(), buf),
+            (FormatType::Debug, value) => {
+                let mut s = String::new();
+                self.debug_value(&value, &mut s)?;
+                spec.pad(&s, buf);
+            }
+            (_, value) => {
+                return Err(VmError::from(VmErrorKind::UnsupportedFormat {
+                    spec: *spec,
+                    actual: value.type_info()?,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Display a value and append it to `buf`, like `{value}` in a template
+    /// string.
+    fn display_value(&mut self, value: Value, buf: &mut String) -> Result<(), VmError> {
+        match value {
+            Value::String(string) => {
+                buf.push_str(&*string.borrow_ref()?);
+            }
+            Value::StaticString(string) => {
+                buf.push_str(string.as_ref());
+            }
+            Value::Integer(integer) => {
+                let mut buffer = itoa::Buffer::new();
+                buf.push_str(buffer.format(integer));
+            }
+            Value::Float(float) => {
+                let mut buffer = ryu::Buffer::new();
+                buf.push_str(buffer.format(float));
+            }
+            Value::Bool(b) => {
+                buf.push_str(if b { "true" } else { "false" });
+            }
+            Value::Char(c) => {
+                buf.push(c);
+            }
+            actual => {
+                if !self.call_format_protocol(&actual, crate::STRING_DISPLAY, buf)? {
+                    return Err(VmError::from(VmErrorKind::MissingProtocol {
+                        protocol: crate::STRING_DISPLAY,
+                        actual: actual.type_info()?,
+                    }));
                 }
-                Value::StaticString(string) => {
-                    buf.push_str(string.as_ref());
-                }
-                Value::Integer(integer) => {
-                    let mut buffer = itoa::Buffer::new();
-                    buf.push_str(buffer.format(integer));
-                }
-                Value::Float(float) => {
-                    let mut buffer = ryu::Buffer::new();
-                    buf.push_str(buffer.format(float));
-                }
-                actual => {
-                    let b = Shared::new(std::mem::take(&mut buf));
-
-                    if !self.call_instance_fn(
-                        &actual,
-                        crate::STRING_DISPLAY,
-                        (Value::String(b.clone()),),
-                    )? {
-                        return Err(VmError::from(VmErrorKind::MissingProtocol {
-                            protocol: crate::STRING_DISPLAY,
-                            actual: actual.type_info()?,
-                        }));
-                    }
+            }
+        }
 
-                    let value = fmt::Result::from_value(self.stack.pop()?)?;
+        Ok(())
+    }
 
-                    if let Err(fmt::Error) = value {
-                        return Err(VmError::from(VmErrorKind::FormatError));
-                    }
+    /// Append the debug representation of a value to `buf`, like `{value:?}`
+    /// in a template string.
+    ///
+    /// Script and native types can customize their representation through
+    /// the [STRING_DEBUG][crate::STRING_DEBUG] protocol.
+    pub(crate) fn debug_value(&mut self, value: &Value, buf: &mut String) -> Result<(), VmError> {
+        if let Value::TypedTuple(..)
+        | Value::TupleVariant(..)
+        | Value::TypedObject(..)
+        | Value::ObjectVariant(..)
+        | Value::Any(..) = value
+        {
+            if self.call_format_protocol(value, crate::STRING_DEBUG, buf)? {
+                return Ok(());
+            }
+        }
 
-                    buf = b.take()?;
+        match value {
+            Value::Unit => buf.push_str("()"),
+            Value::Integer(n) => {
+                let mut buffer = itoa::Buffer::new();
+                buf.push_str(buffer.format(*n));
+            }
+            Value::StaticString(s) => write!(buf, "{:?}", s.as_str())?,
+            Value::String(s) => write!(buf, "{:?}", s.borrow_ref()?.as_str())?,
+            Value::Bytes(b) => write!(buf, "b\"{}\"", b.borrow_ref()?.escape_ascii())?,
+            Value::Vec(vec) => self.debug_list(buf, "[", &vec.borrow_ref()?, "]")?,
+            Value::Tuple(tuple) => {
+                let tuple = tuple.borrow_ref()?;
+
+                if tuple.len() == 1 {
+                    buf.push('(');
+                    self.debug_value(&tuple[0], buf)?;
+                    buf.push_str(",)");
+                } else {
+                    self.debug_list(buf, "(", &tuple, ")")?;
+                }
+            }
+            Value::Object(object) => {
+                buf.push_str("#{");
+                self.debug_fields(buf, &*object.borrow_ref()?, true)?;
+                buf.push('}');
+            }
+            Value::Range(range) => {
+                let range = range.borrow_ref()?;
+
+                if let Some(start) = &range.start {
+                    self.debug_value(start, buf)?;
+                }
+
+                buf.push_str(match range.limits {
+                    RangeLimits::HalfOpen => "..",
+                    RangeLimits::Closed => "..=",
+                });
+
+                if let Some(end) = &range.end {
+                    self.debug_value(end, buf)?;
+                }
+            }
+            Value::Option(option) => match &*option.borrow_ref()? {
+                Some(value) => self.debug_list(buf, "Some(", std::slice::from_ref(value), ")")?,
+                None => buf.push_str("None"),
+            },
+            Value::Result(result) => match &*result.borrow_ref()? {
+                Ok(value) => self.debug_list(buf, "Ok(", std::slice::from_ref(value), ")")?,
+                Err(value) => self.debug_list(buf, "Err(", std::slice::from_ref(value), ")")?,
+            },
+            Value::GeneratorState(state) => match &*state.borrow_ref()? {
+                GeneratorState::Yielded(value) => {
+                    self.debug_list(buf, "Yielded(", std::slice::from_ref(value), ")")?
                 }
+                GeneratorState::Complete(value) => {
+                    self.debug_list(buf, "Complete(", std::slice::from_ref(value), ")")?
+                }
+            },
+            Value::TypedTuple(typed) => {
+                let typed = typed.borrow_ref()?;
+                self.debug_typed_tuple(buf, &typed.rtti.item, &typed.tuple)?;
             }
+            Value::TupleVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                self.debug_typed_tuple(buf, &variant.rtti.item, &variant.tuple)?;
+            }
+            Value::TypedObject(typed) => {
+                let typed = typed.borrow_ref()?;
+                self.debug_typed_object(buf, &typed.rtti.item, &typed.object)?;
+            }
+            Value::ObjectVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+                self.debug_typed_object(buf, &variant.rtti.item, &variant.object)?;
+            }
+            value => write!(buf, "{:?}", value)?,
         }
 
-        self.stack.push(buf);
         Ok(())
     }
 
+    fn debug_list(
+        &mut self,
+        buf: &mut String,
+        open: &str,
+        values: &[Value],
+        close: &str,
+    ) -> Result<(), VmError> {
+        buf.push_str(open);
+
+        let mut it = values.iter().peekable();
+
+        while let Some(value) = it.next() {
+            self.debug_value(value, buf)?;
+
+            if it.peek().is_some() {
+                buf.push_str(", ");
+            }
+        }
+
+        buf.push_str(close);
+        Ok(())
+    }
+
+    fn debug_fields(
+        &mut self,
+        buf: &mut String,
+        object: &Object,
+        quoted: bool,
+    ) -> Result<(), VmError> {
+        let mut it = sorted_fields(object).into_iter().peekable();
+
+        while let Some((key, value)) = it.next() {
+            if quoted {
+                write!(buf, "{:?}: ", key)?;
+            } else {
+                write!(buf, "{}: ", key)?;
+            }
+
+            self.debug_value(value, buf)?;
+
+            if it.peek().is_some() {
+                buf.push_str(", ");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn debug_typed_tuple(
+        &mut self,
+        buf: &mut String,
+        item: &Item,
+        tuple: &[Value],
+    ) -> Result<(), VmError> {
+        write!(buf, "{}", item)?;
+
+        if !tuple.is_empty() {
+            self.debug_list(buf, "(", tuple, ")")?;
+        }
+
+        Ok(())
+    }
+
+    fn debug_typed_object(
+        &mut self,
+        buf: &mut String,
+        item: &Item,
+        object: &Object,
+    ) -> Result<(), VmError> {
+        write!(buf, "{} {{", item)?;
+
+        if !object.is_empty() {
+            buf.push(' ');
+            self.debug_fields(buf, object, false)?;
+            buf.push(' ');
+        }
+
+        buf.push('}');
+        Ok(())
+    }
+
+    /// Call a formatting protocol like [STRING_DISPLAY][crate::STRING_DISPLAY]
+    /// on `value`, which appends to `buf`.
+    ///
+    /// Returns `false` if the value doesn't implement the protocol.
+    fn call_format_protocol(
+        &mut self,
+        value: &Value,
+        protocol: Protocol,
+        buf: &mut String,
+    ) -> Result<bool, VmError> {
+        let b = Shared::new(mem::take(buf));
+
+        let result =
+            self.call_instance_fn_immediate(value, protocol, (Value::String(b.clone()),))?;
+
+        *buf = b.take()?;
+
+        let result = match result {
+            Some(result) => result,
+            None => return Ok(false),
+        };
+
+        // NB: implementations in scripts are allowed to return unit instead
+        // of a `fmt::Result`.
+        if !matches!(result, Value::Unit) {
+            if let Err(fmt::Error) = fmt::Result::from_value(result)? {
+                return Err(VmError::from(VmErrorKind::FormatError));
+            }
+        }
+
+        Ok(true)
+    }
+
     #[inline]
     fn op_unwrap(&mut self) -> Result<(), VmError> {
         let value = self.stack.pop()?;
@@ -2010,6 +3010,7 @@ impl Vm {
 
                     let value = Value::typed_tuple(rtti.clone(), tuple);
                     self.stack.push(value);
+                    self.account_top()?;
                 }
                 UnitFn::TupleVariant {
                     hash,
@@ -2025,6 +3026,7 @@ impl Vm {
                     let tuple = self.stack.pop_sequence(args)?;
                     let value = Value::variant_tuple(rtti.clone(), tuple);
                     self.stack.push(value);
+                    self.account_top()?;
                 }
             },
             None => {
@@ -2034,6 +3036,7 @@ impl Vm {
                     .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
                 handler(&mut self.stack, args)?;
+                self.account_top()?;
             }
         }
 
@@ -2072,20 +3075,152 @@ impl Vm {
                 let handler = match self.context.lookup(hash) {
                     Some(handler) => handler,
                     None => {
+                        let instance = instance.type_info()?;
+
+                        if self.call_iterator_fn(inst_fn, args)? {
+                            return Ok(());
+                        }
+
+                        if self.call_cmp_fn(inst_fn, args)? {
+                            return Ok(());
+                        }
+
                         return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
-                            instance: instance.type_info()?,
+                            instance,
                             hash,
                         }));
                     }
                 };
 
+                // NB: the instance is accounted for again after the call,
+                // since native functions like `push` grow it.
+                let instance = self.limits.as_ref().map(|_| instance.clone());
+
                 handler(&mut self.stack, args)?;
+
+                if let Some(instance) = &instance {
+                    self.account(instance)?;
+                    self.account_top()?;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Call an [Iterator] function on an instance which doesn't have an
+    /// instance function with the given name itself, but which can be
+    /// converted into an iterator.
+    ///
+    /// This makes the iterator adapters available on anything which
+    /// implements the [INTO_ITER][crate::INTO_ITER] or [NEXT][crate::NEXT]
+    /// protocols.
+    fn call_iterator_fn<H>(&mut self, inst_fn: H, args: usize) -> Result<bool, VmError>
+    where
+        H: InstFnNameHash,
+    {
+        let hash = Hash::instance_function(Iterator::type_of(), inst_fn);
+
+        let handler = match self.context.lookup(hash) {
+            Some(handler) => handler.clone(),
+            None => return Ok(false),
+        };
+
+        let instance = self.stack.at_offset_from_top(args)?.clone();
+
+        let iterator = match self.iterator_from_value(instance)? {
+            Some(iterator) => iterator,
+            None => return Ok(false),
+        };
+
+        let mut values = self.stack.pop_sequence(args)?;
+        values[0] = iterator.to_value()?;
+        self.stack.extend(values);
+
+        handler(&mut self.stack, args)?;
+        Ok(true)
+    }
+
+    /// Call the `eq`, `partial_cmp` or `hash` function on an instance which
+    /// doesn't implement it itself, using the built-in implementation.
+    ///
+    /// `partial_cmp` returns `-1`, `0` or `1` wrapped in an option.
+    fn call_cmp_fn<H>(&mut self, inst_fn: H, args: usize) -> Result<bool, VmError>
+    where
+        H: InstFnNameHash,
+    {
+        let inst_fn = inst_fn.inst_fn_name_hash();
+
+        let value = if args == 2 && inst_fn == Hash::of(crate::EQ.name) {
+            let b = self.stack.pop()?;
+            let a = self.stack.pop()?;
+            Value::from(self.value_eq(&a, &b)?)
+        } else if args == 2 && inst_fn == Hash::of(crate::PARTIAL_CMP.name) {
+            let b = self.stack.pop()?;
+            let a = self.stack.pop()?;
+            let ordering = self.value_partial_cmp(&a, &b)?;
+            ordering.map(|o| o as i64).to_value()?
+        } else if args == 1 && inst_fn == Hash::of(crate::HASH.name) {
+            let value = self.stack.pop()?;
+            Value::from(self.value_hash(&value)?)
+        } else {
+            return Ok(false);
+        };
+
+        self.stack.push(value);
+        Ok(true)
+    }
+
+    /// Convert the given value into an [Iterator] through the
+    /// [NEXT][crate::NEXT] or [INTO_ITER][crate::INTO_ITER] protocols.
+    fn iterator_from_value(&mut self, value: Value) -> Result<Option<Iterator>, VmError> {
+        if let Some(next) = self.lookup_instance_fn(&value, crate::NEXT)? {
+            return Ok(Some(Iterator::from_protocol(value, next)));
+        }
+
+        if let Some(value) = self.call_instance_fn_immediate(&value, crate::INTO_ITER, ())? {
+            if let Value::Any(any) = &value {
+                if any.borrow_ref()?.is::<Iterator>() {
+                    return Ok(Some(value.into_iterator()?));
+                }
+            }
+
+            let next = self.lookup_instance_fn(&value, crate::NEXT)?;
+            return Ok(next.map(|next| Iterator::from_protocol(value, next)));
+        }
+
+        match value {
+            Value::Vec(..) | Value::Tuple(..) | Value::Object(..) | Value::Range(..) => {
+                Ok(Some(value.into_iterator()?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Look up an instance function as a function pointer.
+    fn lookup_instance_fn<H>(&self, target: &Value, hash: H) -> Result<Option<Function>, VmError>
+    where
+        H: IntoTypeHash,
+    {
+        let hash = Hash::instance_function(target.type_of()?, hash.into_type_hash());
+
+        if let Some(UnitFn::Offset { offset, call, args }) = self.unit.lookup(hash) {
+            return Ok(Some(Function::from_offset(
+                self.context.clone(),
+                self.unit.clone(),
+                offset,
+                call,
+                args,
+            )));
+        }
+
+        Ok(self
+            .context
+            .lookup(hash)
+            .cloned()
+            .map(Function::from_handler))
+    }
+
     fn op_call_fn(&mut self, args: usize) -> Result<Option<VmHalt>, VmError> {
         let function = self.stack.pop()?;
 
@@ -2114,11 +3249,26 @@ impl Vm {
 
     /// Evaluate a single instruction.
     pub(crate) fn run(&mut self) -> Result<VmHalt, VmError> {
+        let _guard = VmLimits::enter(self.limits.as_ref(), self.depth());
+        let _env = VmEnv::enter(&self.context, &self.unit);
+
         loop {
             if !budget::take() {
                 return Ok(VmHalt::Limited);
             }
 
+            if let Some(limits) = &self.limits {
+                if limits.is_interrupted() {
+                    return Ok(VmHalt::Interrupted);
+                }
+
+                if !limits.take_fuel() {
+                    return Ok(VmHalt::OutOfFuel);
+                }
+
+                limits.check_deadline()?;
+            }
+
             let inst = *self
                 .unit
                 .instruction_at(self.ip)
@@ -2149,6 +3299,7 @@ impl Vm {
                 }
                 Inst::IndexGet => {
                     self.op_index_get()?;
+                    self.account_top()?;
                 }
                 Inst::TupleIndexGet { index } => {
                     self.op_tuple_index_get(index)?;
@@ -2259,30 +3410,44 @@ impl Vm {
                 }
                 Inst::Vec { count } => {
                     self.op_vec(count)?;
+                    self.account_top()?;
                 }
                 Inst::Tuple { count } => {
                     self.op_tuple(count)?;
+                    self.account_top()?;
                 }
                 Inst::PushTuple => {
                     self.op_push_tuple()?;
                 }
                 Inst::Object { slot } => {
                     self.op_object(slot)?;
+                    self.account_top()?;
                 }
                 Inst::TypedObject { hash, slot } => {
                     self.op_typed_object(hash, slot)?;
+                    self.account_top()?;
                 }
                 Inst::ObjectVariant { hash, slot } => {
                     self.op_object_variant(hash, slot)?;
+                    self.account_top()?;
                 }
                 Inst::String { slot } => {
                     self.op_string(slot)?;
                 }
                 Inst::Bytes { slot } => {
                     self.op_bytes(slot)?;
+                    self.account_top()?;
                 }
                 Inst::StringConcat { len, size_hint } => {
                     self.op_string_concat(len, size_hint)?;
+                    self.account_top()?;
+                }
+                Inst::Format { spec } => {
+                    self.op_format(spec)?;
+                    self.account_top()?;
+                }
+                Inst::Range { range } => {
+                    self.op_range(range)?;
                 }
                 Inst::Is => {
                     self.op_is()?;
@@ -2317,6 +3482,9 @@ impl Vm {
                 Inst::EqStaticString { slot } => {
                     self.op_eq_static_string(slot)?;
                 }
+                Inst::MatchRange { range } => {
+                    self.op_match_range(range)?;
+                }
                 Inst::MatchSequence {
                     type_check,
                     len,
@@ -2342,6 +3510,7 @@ impl Vm {
                 }
                 Inst::Op { op } => {
                     self.op_op(op)?;
+                    self.account_top()?;
                 }
                 Inst::Assign { target, op } => {
                     self.op_assign(target, op)?;
@@ -2391,7 +3560,10 @@ impl Vm {
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if self
+            .call_instance_fn_immediate(&lhs, hash, (&rhs,))?
+            .is_none()
+        {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2399,8 +3571,8 @@ impl Vm {
             }));
         }
 
-        self.stack.pop()?;
-        Ok(())
+        // NB: assignments like `+=` on strings grow the target in place.
+        self.account(&lhs)
     }
 
     /// Internal impl of a numeric operation.
@@ -2501,7 +3673,10 @@ impl Vm {
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if self
+            .call_instance_fn_immediate(&lhs, hash, (&rhs,))?
+            .is_none()
+        {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2509,7 +3684,6 @@ impl Vm {
             }));
         }
 
-        self.stack.pop()?;
         Ok(())
     }
 
@@ -2574,7 +3748,10 @@ impl Vm {
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if self
+            .call_instance_fn_immediate(&lhs, hash, (&rhs,))?
+            .is_none()
+        {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2624,3 +3801,24 @@ impl CallFrame {
         self.stack_bottom
     }
 }
+
+/// Convert the value returned by a [PARTIAL_CMP][crate::PARTIAL_CMP]
+/// implementation into an ordering.
+fn ordering_from_value(value: Value) -> Result<Option<Ordering>, VmError> {
+    let value = match value {
+        Value::Option(option) => match option.borrow_ref()?.clone() {
+            Some(value) => value,
+            None => return Ok(None),
+        },
+        value => value,
+    };
+
+    Ok(Some(value.into_integer()?.cmp(&0)))
+}
+
+/// Get the fields of an object sorted by their names.
+fn sorted_fields(object: &Object) -> Vec<(&String, &Value)> {
+    let mut fields = object.iter().collect::<Vec<_>>();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    fields
+}
@@ -0,0 +1,70 @@
+use crate::{Context, Hash, Protocol, Type, Unit, Vm, VmError};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+thread_local!(static CURRENT: RefCell<Option<VmEnv>> = const { RefCell::new(None) });
+
+/// The context and unit of the virtual machine which is currently running on
+/// this thread.
+///
+/// This is used by native functions to call protocols which are implemented
+/// in the running unit, like when comparing values.
+#[derive(Clone)]
+pub(crate) struct VmEnv {
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+}
+
+impl VmEnv {
+    /// Mark the virtual machine with the given context and unit as running on
+    /// this thread until the returned guard is dropped.
+    pub(crate) fn enter(context: &Arc<Context>, unit: &Arc<Unit>) -> EnvGuard {
+        let env = VmEnv {
+            context: context.clone(),
+            unit: unit.clone(),
+        };
+
+        let previous = CURRENT.with(|current| current.replace(Some(env)));
+        EnvGuard { previous }
+    }
+
+    /// Construct a virtual machine in the environment of the virtual machine
+    /// which is currently running on this thread, inheriting its limits.
+    ///
+    /// If no virtual machine is running the constructed one is empty, so it
+    /// can only compare values without calling any protocols.
+    pub(crate) fn vm() -> Result<Vm, VmError> {
+        let env = CURRENT.with(|current| current.borrow().clone());
+
+        let mut vm = match env {
+            Some(env) => Vm::new(env.context, env.unit),
+            None => Vm::new(Arc::new(Context::default()), Arc::new(Unit::default())),
+        };
+
+        vm.inherit_limits()?;
+        Ok(vm)
+    }
+
+    /// Test if the given protocol is implemented for the given type by the
+    /// virtual machine which is currently running on this thread.
+    pub(crate) fn has_protocol(type_of: Type, protocol: Protocol) -> bool {
+        let hash = Hash::instance_function(type_of, protocol);
+
+        CURRENT.with(|current| match &*current.borrow() {
+            Some(env) => env.unit.lookup(hash).is_some() || env.context.lookup(hash).is_some(),
+            None => false,
+        })
+    }
+}
+
+/// Guard returned by [VmEnv::enter].
+pub(crate) struct EnvGuard {
+    previous: Option<VmEnv>,
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| current.replace(previous));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::panic::BoxedPanic;
 use crate::{
-    AccessError, Hash, Item, Panic, Protocol, StackError, TypeInfo, TypeOf, Unit, Value, VmHaltInfo,
+    AccessError, FormatSpec, FormatSpecError, Hash, Item, Panic, Protocol, StackError, TypeInfo,
+    TypeOf, Unit, Value, VmHaltInfo,
 };
 use std::fmt;
 use std::sync::Arc;
@@ -75,6 +76,17 @@ impl VmError {
         })
     }
 
+    /// Convert a halt because the virtual machine ran out of fuel into
+    /// [VmErrorKind::OutOfFuel], for executions which can't be resumed.
+    pub(crate) fn into_unresumable(self) -> Self {
+        match &*self.kind {
+            VmErrorKind::Halted {
+                halt: VmHaltInfo::OutOfFuel,
+            } => Self::from(VmErrorKind::OutOfFuel),
+            _ => self,
+        }
+    }
+
     /// Unpack an unwinded error, if it is present.
     pub fn as_unwound<'a>(&'a self) -> (&'a VmErrorKind, Option<(&'a Arc<Unit>, usize)>) {
         match &*self.kind {
@@ -158,6 +170,26 @@ pub enum VmErrorKind {
         /// The reason why the virtual machine stopped.
         halt: VmHaltInfo,
     },
+    /// The deadline of the virtual machine passed.
+    #[error("deadline exceeded")]
+    DeadlineExceeded,
+    /// A virtual machine which can't be resumed ran out of fuel, like one
+    /// called from a native function or a protocol.
+    #[error("out of fuel")]
+    OutOfFuel,
+    /// A call would exceed the maximum number of call frames of the virtual
+    /// machine.
+    #[error("call frame limit of {limit} exceeded")]
+    CallFrameLimitExceeded {
+        /// The maximum number of call frames.
+        limit: usize,
+    },
+    /// Values allocated by the virtual machine would exceed its memory limit.
+    #[error("memory limit of {limit} bytes exceeded")]
+    MemoryLimitExceeded {
+        /// The maximum number of bytes.
+        limit: usize,
+    },
     /// Error raised when external format function results in error.
     #[error("failed to format argument")]
     FormatError,
@@ -367,6 +399,88 @@ pub enum VmErrorKind {
         /// Index that we tried to access.
         index: VmIntegerRepr,
     },
+    /// Tried to slice a value with a range which is out of bounds.
+    #[error("range `{range}` is out of bounds for `{target}` of length `{len}`")]
+    SliceOutOfBounds {
+        /// The type which was sliced.
+        target: TypeInfo,
+        /// The range which was used to slice.
+        range: String,
+        /// The length of the sliced value.
+        len: usize,
+    },
+    /// Tried to slice a string at an index which is not on a character
+    /// boundary.
+    #[error("byte index `{index}` is not a char boundary in string")]
+    NotCharBoundary {
+        /// The offending byte index.
+        index: usize,
+    },
+    /// Tried to iterate over a range without a start.
+    #[error("cannot iterate over a range without a start")]
+    UnboundedRangeIter,
+    /// Tried to iterate over a range of values which can't be iterated over.
+    #[error("cannot iterate over a range of `{start}`")]
+    UnsupportedRangeIter {
+        /// The type of the start of the range.
+        start: TypeInfo,
+    },
+    /// Tried to convert a value which isn't supported into an iterator.
+    #[error("cannot convert `{actual}` into an iterator")]
+    UnsupportedIterator {
+        /// The type that couldn't be converted.
+        actual: TypeInfo,
+    },
+    /// Tried to use a value which isn't supported as a key.
+    #[error("`{actual}` can't be used as a key")]
+    UnsupportedKey {
+        /// The type that couldn't be used as a key.
+        actual: TypeInfo,
+    },
+    /// Tried to order keys which can't be compared, like in a `BTreeMap`.
+    #[error("keys of type `{actual}` can't be ordered")]
+    UnorderedKey {
+        /// The type of the keys.
+        actual: TypeInfo,
+    },
+    /// Tried to convert a value which can't be sent across threads into a
+    /// [SendValue][crate::SendValue].
+    #[error("`{actual}` can't be sent across threads")]
+    UnsupportedSendValue {
+        /// The type that couldn't be sent.
+        actual: TypeInfo,
+    },
+    /// Tried to look up a key which is missing in a map.
+    #[error("missing key `{key}` in `{target}`")]
+    MissingKey {
+        /// The type of the map.
+        target: TypeInfo,
+        /// The key which was missing.
+        key: String,
+    },
+    /// Tried to format a value with a format it doesn't support, like `{1.5:x}`.
+    #[error("`{actual}` can't be formatted with `{{:{spec}}}`")]
+    UnsupportedFormat {
+        /// The format specification which isn't supported.
+        spec: FormatSpec,
+        /// The type of the value being formatted.
+        actual: TypeInfo,
+    },
+    /// Tried to format a value with an invalid format specification, like
+    /// one with a width which is too large.
+    #[error("invalid format specification `{{:{spec}}}`: {error}")]
+    InvalidFormatSpec {
+        /// The invalid format specification.
+        spec: FormatSpec,
+        /// The error in the specification.
+        error: FormatSpecError,
+    },
+    /// Tried to iterate from the back of an iterator which doesn't support it.
+    #[error("`{name}` iterator cannot be iterated over from the back")]
+    NotDoubleEndedIterator {
+        /// The name of the iterator.
+        name: &'static str,
+    },
     /// When we try to access a field that is missing.
     #[error("missing field `{field}` on `{target}`")]
     MissingField {
@@ -476,6 +590,12 @@ impl VmErrorKind {
     }
 }
 
+impl From<fmt::Error> for VmErrorKind {
+    fn from(fmt::Error: fmt::Error) -> Self {
+        VmErrorKind::FormatError
+    }
+}
+
 /// A type-erased rust number.
 #[derive(Debug, Clone, Copy)]
 pub enum VmIntegerRepr {
@@ -1,5 +1,15 @@
 use crate::budget;
-use crate::{GeneratorState, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo};
+use crate::vm_env::VmEnv;
+use crate::{
+    Awaited, Context, GeneratorState, InterruptHandle, Unit, Value, Vm, VmError, VmErrorKind,
+    VmHalt, VmHaltInfo, VmLimits,
+};
+use futures_util::future::{self, Either};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Poll;
 
 /// The execution environment for a virtual machine.
 pub struct VmExecution {
@@ -28,6 +38,32 @@ impl VmExecution {
         }
     }
 
+    /// Get a handle which interrupts the execution from another thread.
+    ///
+    /// This is the handle of the limits of the execution, which are set up
+    /// if it doesn't have any yet.
+    pub fn interrupt_handle(&mut self) -> InterruptHandle {
+        let limits = self
+            .vms
+            .iter()
+            .find_map(|vm| vm.limits().cloned())
+            .unwrap_or_else(VmLimits::new);
+
+        for vm in &mut self.vms {
+            if vm.limits().is_none() {
+                vm.set_limits(limits.clone());
+            }
+        }
+
+        limits.interrupt_handle()
+    }
+
+    /// Get all virtual machines in the execution, starting with the one at
+    /// the bottom.
+    pub(crate) fn vms(&self) -> &[Vm] {
+        &self.vms
+    }
+
     /// Complete the current execution without support for async instructions.
     ///
     /// This will error if the execution is suspended through yielding.
@@ -62,7 +98,7 @@ impl VmExecution {
             match Self::run(vm)? {
                 VmHalt::Exited => (),
                 VmHalt::Awaited(awaited) => {
-                    awaited.into_vm(vm).await?;
+                    Self::wait(vm, awaited).await?;
                     continue;
                 }
                 VmHalt::VmCall(vm_call) => {
@@ -162,7 +198,7 @@ impl VmExecution {
         match budget::with(1, || Self::run(vm)).call()? {
             VmHalt::Exited => (),
             VmHalt::Awaited(awaited) => {
-                awaited.into_vm(vm).await?;
+                Self::wait(vm, awaited).await?;
                 return Ok(None);
             }
             VmHalt::VmCall(vm_call) => {
@@ -210,6 +246,39 @@ impl VmExecution {
         Ok(())
     }
 
+    /// Wait for the awaited value of the virtual machine, or until it's
+    /// interrupted.
+    ///
+    /// If it's interrupted, the awaited value is dropped which cancels it.
+    async fn wait(vm: &mut Vm, awaited: Awaited) -> Result<(), VmError> {
+        let limits = vm.limits().cloned();
+        let depth = vm.depth();
+        let context = vm.context().clone();
+        let unit = vm.unit().clone();
+
+        let awaited = WithEnv {
+            limits: limits.clone(),
+            depth,
+            context,
+            unit,
+            future: awaited.into_vm(vm),
+        };
+
+        let interrupted = match limits {
+            Some(limits) => limits.interrupt_handle().interrupted(),
+            None => return awaited.await,
+        };
+
+        futures_util::pin_mut!(awaited);
+
+        match future::select(awaited, interrupted).await {
+            Either::Left((result, _)) => result,
+            Either::Right(..) => Err(VmError::from(VmErrorKind::Halted {
+                halt: VmHaltInfo::Interrupted,
+            })),
+        }
+    }
+
     #[inline]
     fn run(vm: &mut Vm) -> Result<VmHalt, VmError> {
         match vm.run() {
@@ -218,3 +287,30 @@ impl VmExecution {
         }
     }
 }
+
+/// A future which is polled in the environment of the virtual machine awaiting
+/// it, so that virtual machines called into from native futures inherit its
+/// limits.
+#[pin_project]
+struct WithEnv<F> {
+    limits: Option<VmLimits>,
+    depth: usize,
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+    #[pin]
+    future: F,
+}
+
+impl<F> Future for WithEnv<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = VmLimits::enter(this.limits.as_ref(), *this.depth);
+        let _env = VmEnv::enter(this.context, this.unit);
+        this.future.poll(cx)
+    }
+}
@@ -8,6 +8,11 @@ pub enum VmHalt {
     Exited,
     /// The virtual machine exited because it ran out of execution quota.
     Limited,
+    /// The virtual machine ran out of fuel, see [VmLimits][crate::VmLimits].
+    OutOfFuel,
+    /// The virtual machine was interrupted, see
+    /// [InterruptHandle][crate::InterruptHandle].
+    Interrupted,
     /// The virtual machine yielded.
     Yielded,
     /// The virtual machine awaited on the given future.
@@ -22,6 +27,8 @@ impl VmHalt {
         match self {
             Self::Exited => VmHaltInfo::Exited,
             Self::Limited => VmHaltInfo::Limited,
+            Self::OutOfFuel => VmHaltInfo::OutOfFuel,
+            Self::Interrupted => VmHaltInfo::Interrupted,
             Self::Yielded => VmHaltInfo::Yielded,
             Self::Awaited(..) => VmHaltInfo::Awaited,
             Self::VmCall(..) => VmHaltInfo::VmCall,
@@ -36,6 +43,10 @@ pub enum VmHaltInfo {
     Exited,
     /// The virtual machine exited because it ran out of execution quota.
     Limited,
+    /// The virtual machine ran out of fuel.
+    OutOfFuel,
+    /// The virtual machine was interrupted.
+    Interrupted,
     /// The virtual machine yielded.
     Yielded,
     /// The virtual machine awaited on the given future.
@@ -49,6 +60,8 @@ impl fmt::Display for VmHaltInfo {
         match self {
             Self::Exited => write!(f, "exited"),
             Self::Limited => write!(f, "limited"),
+            Self::OutOfFuel => write!(f, "out of fuel"),
+            Self::Interrupted => write!(f, "interrupted"),
             Self::Yielded => write!(f, "yielded"),
             Self::Awaited => write!(f, "awaited"),
             Self::VmCall => write!(f, "calling into other vm"),
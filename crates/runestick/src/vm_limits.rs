@@ -0,0 +1,470 @@
+use crate::{AnyObj, InterruptHandle, Object, Shared, Value, VmError, VmErrorKind};
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// How many instructions are executed between each check of the deadline.
+const DEADLINE_INTERVAL: u32 = 1024;
+
+thread_local!(static CURRENT: RefCell<Option<VmLimits>> = const { RefCell::new(None) });
+
+/// Limits on the execution of a virtual machine, configured through
+/// [Vm::set_limits][crate::Vm::set_limits].
+///
+/// Limits are shared by cloning them, so a host can keep a handle to the
+/// limits of a virtual machine to top up its fuel. They also apply to any
+/// virtual machine which is called into from a limited one, like async
+/// functions, generators and closures called from native functions. Fuel is
+/// consumed by all of them, and call frames are counted across all of them.
+///
+/// * Fuel is consumed by one for each instruction executed. A
+///   [VmExecution][crate::VmExecution] which runs out of fuel halts with
+///   [VmHalt::OutOfFuel], and can be resumed after fuel has been added with
+///   [VmLimits::add_fuel]. Virtual machines which are run to completion
+///   can't be resumed, so they raise [VmErrorKind::OutOfFuel] instead. This
+///   includes [Vm::call][crate::Vm::call], generators, and the virtual
+///   machines called into from native functions and protocols, in which case
+///   the execution calling into them raises the error.
+/// * The deadline is checked periodically, and raises
+///   [VmErrorKind::DeadlineExceeded] once it has passed.
+/// * The maximum number of call frames raises
+///   [VmErrorKind::CallFrameLimitExceeded] if a call would exceed it.
+/// * The approximate number of bytes allocated by strings, byte arrays,
+///   vectors, tuples, objects and native types implementing [MemoryUsage]
+///   (like the collections in `std::collections`) is accounted for as they
+///   are constructed and grown, and released when they are dropped.
+///   Exceeding the maximum raises [VmErrorKind::MemoryLimitExceeded].
+/// * The [InterruptHandle] of the limits stops the virtual machines using
+///   them when it's interrupted, see [VmLimits::interrupt_handle].
+///
+/// [VmHalt::OutOfFuel]: crate::VmHalt::OutOfFuel
+#[derive(Debug, Clone)]
+pub struct VmLimits {
+    inner: Rc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// The remaining fuel, if limited.
+    fuel: Cell<Option<u64>>,
+    /// The deadline, if any.
+    deadline: Cell<Option<Instant>>,
+    /// Instructions left until the deadline is checked.
+    deadline_countdown: Cell<u32>,
+    /// The maximum number of call frames, if limited.
+    max_call_frames: Cell<Option<usize>>,
+    /// The number of call frames of the virtual machine which is currently
+    /// running, including the virtual machines calling into it.
+    depth: Cell<usize>,
+    /// The number of bytes currently allocated.
+    memory: Cell<usize>,
+    /// The highest number of bytes allocated at any one time.
+    peak_memory: Cell<usize>,
+    /// The maximum number of bytes allocated, if limited.
+    max_memory: Cell<Option<usize>>,
+    /// The handle used to interrupt the virtual machine.
+    interrupt: InterruptHandle,
+}
+
+impl VmLimits {
+    /// Construct a new set of limits, where nothing is limited.
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                fuel: Cell::new(None),
+                deadline: Cell::new(None),
+                deadline_countdown: Cell::new(DEADLINE_INTERVAL),
+                max_call_frames: Cell::new(None),
+                depth: Cell::new(0),
+                memory: Cell::new(0),
+                peak_memory: Cell::new(0),
+                max_memory: Cell::new(None),
+                interrupt: InterruptHandle::new(),
+            }),
+        }
+    }
+
+    /// Construct a copy of the limits which isn't shared with these ones, but
+    /// which uses the same interrupt handle.
+    ///
+    /// Memory usage isn't copied, since it belongs to the values which have
+    /// been accounted for with these limits.
+    pub(crate) fn detach(&self) -> Self {
+        let inner = &self.inner;
+
+        Self {
+            inner: Rc::new(Inner {
+                fuel: Cell::new(inner.fuel.get()),
+                deadline: Cell::new(inner.deadline.get()),
+                deadline_countdown: Cell::new(DEADLINE_INTERVAL),
+                max_call_frames: Cell::new(inner.max_call_frames.get()),
+                depth: Cell::new(0),
+                memory: Cell::new(0),
+                peak_memory: Cell::new(0),
+                max_memory: Cell::new(inner.max_memory.get()),
+                interrupt: inner.interrupt.clone(),
+            }),
+        }
+    }
+
+    /// Get the remaining fuel, or `None` if fuel is unlimited.
+    pub fn fuel(&self) -> Option<u64> {
+        self.inner.fuel.get()
+    }
+
+    /// Set the remaining fuel, or `None` to make it unlimited.
+    pub fn set_fuel(&self, fuel: Option<u64>) {
+        self.inner.fuel.set(fuel);
+    }
+
+    /// Add fuel to the remaining fuel. Does nothing if fuel is unlimited.
+    pub fn add_fuel(&self, fuel: u64) {
+        if let Some(current) = self.inner.fuel.get() {
+            self.inner.fuel.set(Some(current.saturating_add(fuel)));
+        }
+    }
+
+    /// Get the deadline, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.inner.deadline.get()
+    }
+
+    /// Set the deadline, or `None` to remove it.
+    pub fn set_deadline(&self, deadline: Option<Instant>) {
+        self.inner.deadline.set(deadline);
+        self.inner.deadline_countdown.set(DEADLINE_INTERVAL);
+    }
+
+    /// Set the deadline to the given duration from now.
+    pub fn set_timeout(&self, timeout: Duration) {
+        self.set_deadline(Some(Instant::now() + timeout));
+    }
+
+    /// Get the maximum number of call frames, or `None` if unlimited.
+    pub fn max_call_frames(&self) -> Option<usize> {
+        self.inner.max_call_frames.get()
+    }
+
+    /// Set the maximum number of call frames, or `None` to make it
+    /// unlimited.
+    pub fn set_max_call_frames(&self, max_call_frames: Option<usize>) {
+        self.inner.max_call_frames.set(max_call_frames);
+    }
+
+    /// Get the approximate number of bytes currently allocated by values.
+    pub fn memory_usage(&self) -> usize {
+        self.inner.memory.get()
+    }
+
+    /// Get the highest number of bytes allocated by values at any one time.
+    pub fn peak_memory_usage(&self) -> usize {
+        self.inner.peak_memory.get()
+    }
+
+    /// Get the maximum number of bytes allocated by values, or `None` if
+    /// unlimited.
+    pub fn max_memory(&self) -> Option<usize> {
+        self.inner.max_memory.get()
+    }
+
+    /// Set the maximum number of bytes allocated by values, or `None` to make
+    /// it unlimited.
+    pub fn set_max_memory(&self, max_memory: Option<usize>) {
+        self.inner.max_memory.set(max_memory);
+    }
+
+    /// Get a handle which interrupts the virtual machines using these limits,
+    /// and which can be sent to other threads.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.inner.interrupt.clone()
+    }
+
+    /// Test if the virtual machines using these limits have been interrupted.
+    #[inline]
+    pub(crate) fn is_interrupted(&self) -> bool {
+        self.inner.interrupt.is_interrupted()
+    }
+
+    /// Take fuel for a single instruction, returning `false` if the virtual
+    /// machine is out of fuel.
+    #[inline]
+    pub(crate) fn take_fuel(&self) -> bool {
+        match self.inner.fuel.get() {
+            None => true,
+            Some(0) => false,
+            Some(fuel) => {
+                self.inner.fuel.set(Some(fuel - 1));
+                true
+            }
+        }
+    }
+
+    /// Periodically check if the deadline has passed.
+    #[inline]
+    pub(crate) fn check_deadline(&self) -> Result<(), VmError> {
+        let deadline = match self.inner.deadline.get() {
+            Some(deadline) => deadline,
+            None => return Ok(()),
+        };
+
+        let countdown = self.inner.deadline_countdown.get();
+
+        if countdown > 0 {
+            self.inner.deadline_countdown.set(countdown - 1);
+            return Ok(());
+        }
+
+        self.inner.deadline_countdown.set(DEADLINE_INTERVAL);
+
+        if Instant::now() >= deadline {
+            return Err(VmError::from(VmErrorKind::DeadlineExceeded));
+        }
+
+        Ok(())
+    }
+
+    /// Check that the given number of call frames doesn't exceed the maximum.
+    pub(crate) fn check_depth(&self, depth: usize) -> Result<(), VmError> {
+        if let Some(limit) = self.inner.max_call_frames.get() {
+            if depth > limit {
+                return Err(VmError::from(VmErrorKind::CallFrameLimitExceeded { limit }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update the number of call frames of the running virtual machine, and
+    /// check that it doesn't exceed the maximum.
+    pub(crate) fn set_depth(&self, depth: usize) -> Result<(), VmError> {
+        self.check_depth(depth)?;
+        self.inner.depth.set(depth);
+        Ok(())
+    }
+
+    /// Account for the memory allocated by the given value.
+    ///
+    /// Values which have already been accounted for are measured again, in
+    /// case they've grown. Values which haven't are accounted for along with
+    /// the values they contain.
+    pub(crate) fn account(&self, value: &Value) -> Result<(), VmError> {
+        match value {
+            Value::String(string) => self.account_shared(string, String::capacity, |_| Ok(())),
+            Value::Bytes(bytes) => self.account_shared(bytes, |bytes| bytes.capacity(), |_| Ok(())),
+            Value::Vec(vec) => self.account_shared(
+                vec,
+                |vec| vec.capacity() * mem::size_of::<Value>(),
+                |vec| self.account_values(vec),
+            ),
+            Value::Tuple(tuple) => self.account_shared(
+                tuple,
+                |tuple| tuple.len() * mem::size_of::<Value>(),
+                |tuple| self.account_values(tuple),
+            ),
+            Value::Object(object) => {
+                self.account_shared(object, object_size, |object| self.account_object(object))
+            }
+            Value::TypedTuple(typed) => self.account_shared(
+                typed,
+                |typed| typed.tuple.len() * mem::size_of::<Value>(),
+                |typed| self.account_values(&typed.tuple),
+            ),
+            Value::TupleVariant(variant) => self.account_shared(
+                variant,
+                |variant| variant.tuple.len() * mem::size_of::<Value>(),
+                |variant| self.account_values(&variant.tuple),
+            ),
+            Value::TypedObject(typed) => self.account_shared(
+                typed,
+                |typed| object_size(&typed.object),
+                |typed| self.account_object(&typed.object),
+            ),
+            Value::ObjectVariant(variant) => self.account_shared(
+                variant,
+                |variant| object_size(&variant.object),
+                |variant| self.account_object(&variant.object),
+            ),
+            Value::Any(any) => self.account_shared(any, AnyObj::memory_size, |any| {
+                let mut values = Vec::new();
+                any.memory_values(&mut |value| values.push(value.clone()));
+                self.account_values(&values)
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that allocating the given number of bytes wouldn't exceed the
+    /// maximum, without accounting for them.
+    ///
+    /// This is used by native functions before making allocations whose size
+    /// is controlled by a script, since they are only accounted for once the
+    /// value has been constructed.
+    pub(crate) fn check_allocation(&self, bytes: usize) -> Result<(), VmError> {
+        if let Some(limit) = self.inner.max_memory.get() {
+            if self.inner.memory.get().saturating_add(bytes) > limit {
+                return Err(VmError::from(VmErrorKind::MemoryLimitExceeded { limit }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Release bytes which were accounted for by a value which was dropped.
+    pub(crate) fn release(&self, bytes: usize) {
+        self.inner
+            .memory
+            .set(self.inner.memory.get().saturating_sub(bytes));
+    }
+
+    /// Test if the two limits are the same.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn account_shared<T, M, C>(
+        &self,
+        shared: &Shared<T>,
+        measure: M,
+        children: C,
+    ) -> Result<(), VmError>
+    where
+        M: FnOnce(&T) -> usize,
+        C: FnOnce(&T) -> Result<(), VmError>,
+    {
+        // NB: values which are exclusively borrowed are being modified
+        // elsewhere, and are accounted for once they are used again.
+        let data = match shared.borrow_ref() {
+            Ok(data) => data,
+            Err(..) => return Ok(()),
+        };
+
+        let bytes = measure(&data);
+
+        match shared.replace_allocated(self, bytes) {
+            Some(previous) if bytes <= previous => {
+                self.release(previous - bytes);
+                Ok(())
+            }
+            Some(previous) => self.allocate(bytes - previous),
+            None => {
+                self.allocate(bytes)?;
+                children(&data)
+            }
+        }
+    }
+
+    fn account_values(&self, values: &[Value]) -> Result<(), VmError> {
+        for value in values {
+            self.account(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn account_object(&self, object: &Object) -> Result<(), VmError> {
+        for (_, value) in object.iter() {
+            self.account(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Allocate the given number of bytes, and check that they don't exceed
+    /// the maximum.
+    ///
+    /// The bytes are accounted for even if the maximum is exceeded, since the
+    /// value which allocated them will release them when it's dropped.
+    fn allocate(&self, bytes: usize) -> Result<(), VmError> {
+        let memory = self.inner.memory.get().saturating_add(bytes);
+        self.inner.memory.set(memory);
+
+        if memory > self.inner.peak_memory.get() {
+            self.inner.peak_memory.set(memory);
+        }
+
+        if let Some(limit) = self.inner.max_memory.get() {
+            if memory > limit {
+                return Err(VmError::from(VmErrorKind::MemoryLimitExceeded { limit }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the limits of the limited virtual machine which is currently
+    /// running on this thread, if any.
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Get the number of call frames of the running virtual machine.
+    pub(crate) fn depth(&self) -> usize {
+        self.inner.depth.get()
+    }
+
+    /// Mark the virtual machine with the given limits and number of call
+    /// frames as running on this thread until the returned guard is dropped.
+    pub(crate) fn enter(limits: Option<&Self>, depth: usize) -> LimitsGuard {
+        let previous_depth = limits.map(|limits| {
+            let previous = limits.inner.depth.get();
+            limits.inner.depth.set(depth);
+            previous
+        });
+
+        let previous = CURRENT.with(|current| current.replace(limits.cloned()));
+
+        LimitsGuard {
+            previous,
+            previous_depth,
+        }
+    }
+}
+
+impl Default for VmLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The approximate number of bytes allocated by an object, including its keys.
+fn object_size(object: &Object) -> usize {
+    let keys = object.iter().map(|(key, _)| key.capacity()).sum::<usize>();
+    object.len() * (mem::size_of::<String>() + mem::size_of::<Value>()) + keys
+}
+
+/// A native type which reports the memory it allocates, so that it's
+/// accounted against the memory limits of a virtual machine.
+///
+/// This is used by deriving [Any][crate::Any] with the `#[rune(memory)]`
+/// attribute.
+pub trait MemoryUsage {
+    /// The approximate number of bytes allocated by the value, not counting
+    /// the values it holds.
+    ///
+    /// This is called every time the value is modified, so it should be
+    /// cheap to compute.
+    fn memory_size(&self) -> usize;
+
+    /// Visit the values held by the value, so that they're accounted for
+    /// separately.
+    fn memory_values(&self, visit: &mut dyn FnMut(&Value));
+}
+
+/// Guard returned by [VmLimits::enter].
+pub(crate) struct LimitsGuard {
+    previous: Option<VmLimits>,
+    previous_depth: Option<usize>,
+}
+
+impl Drop for LimitsGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+
+        let limits = CURRENT.with(|current| current.replace(previous));
+
+        if let (Some(limits), Some(depth)) = (limits, self.previous_depth) {
+            limits.inner.depth.set(depth);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{SendValue, VmError, VmExecution};
+use pin_project::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An execution which can be sent to another thread, constructed with
+/// [Vm::send_execute][crate::Vm::send_execute].
+pub struct VmSendExecution {
+    execution: VmExecution,
+}
+
+// Safety: values are reference counted without synchronization, but the
+// execution only has access to values which were constructed from the `Send`
+// arguments it was called with, and to its context and unit which are `Send`
+// and `Sync`. Its limits are a copy which isn't shared with anything outside
+// of it. So all references to its values are moved along with it. The caller
+// of the unsafe `Vm::send_execute` guarantees that the native types and
+// futures it might construct are `Send`.
+unsafe impl Send for VmSendExecution {}
+
+impl VmSendExecution {
+    /// Construct an execution which can be sent to another thread.
+    pub(crate) fn new(execution: VmExecution) -> Self {
+        Self { execution }
+    }
+
+    /// Complete the execution with support for async instructions, and
+    /// convert its output into a [SendValue].
+    ///
+    /// This will error if the execution is suspended through yielding, or if
+    /// its output can't be converted into a send value.
+    pub fn async_complete(self) -> impl Future<Output = Result<SendValue, VmError>> + Send {
+        let mut execution = self.execution;
+
+        AssertSend {
+            future: async move {
+                let output = execution
+                    .async_complete()
+                    .await
+                    .map_err(VmError::into_unresumable)?;
+
+                output.to_sendable()
+            },
+        }
+    }
+}
+
+/// A future which owns a [VmSendExecution], and is therefore `Send`.
+#[pin_project]
+struct AssertSend<T> {
+    #[pin]
+    future: T,
+}
+
+// Safety: the future owns the execution and only produces a send value, and
+// anything it awaits is `Send` as guaranteed by the caller of
+// `Vm::send_execute`, see `VmSendExecution`.
+unsafe impl<T> Send for AssertSend<T> {}
+
+impl<T> Future for AssertSend<T>
+where
+    T: Future,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().future.poll(cx)
+    }
+}
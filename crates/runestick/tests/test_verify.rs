@@ -0,0 +1,188 @@
+use hashbrown::HashMap;
+use runestick::{Call, Context, Hash, Inst, Unit, UnitFn, VerifyError};
+
+/// Construct a unit with a single function `main` taking `args` arguments.
+fn unit(args: usize, instructions: Vec<Inst>) -> Unit {
+    let mut functions = HashMap::new();
+
+    functions.insert(
+        Hash::type_hash(&["main"]),
+        UnitFn::Offset {
+            offset: 0,
+            call: Call::Immediate,
+            args,
+        },
+    );
+
+    Unit::new(
+        instructions,
+        functions,
+        HashMap::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        HashMap::new(),
+        HashMap::new(),
+        None,
+    )
+}
+
+fn verify(unit: Unit) -> Vec<VerifyError> {
+    match unit.verify(&Context::new()) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.into_iter().collect(),
+    }
+}
+
+#[test]
+fn test_verify_ok() {
+    let errors = verify(unit(
+        1,
+        vec![
+            Inst::Copy { offset: 0 },
+            Inst::JumpIf { offset: 2 },
+            Inst::integer(1),
+            Inst::Jump { offset: 1 },
+            Inst::integer(2),
+            Inst::Clean { count: 1 },
+            Inst::Return,
+        ],
+    ));
+
+    assert_eq!(errors, vec![]);
+}
+
+#[test]
+fn test_verify_jump_out_of_bounds() {
+    let errors = verify(unit(0, vec![Inst::Jump { offset: 10 }, Inst::ReturnUnit]));
+    assert_eq!(
+        errors,
+        vec![VerifyError::JumpOutOfBounds { ip: 0, offset: 10 }]
+    );
+
+    let errors = verify(unit(0, vec![Inst::Jump { offset: -2 }, Inst::ReturnUnit]));
+    assert_eq!(
+        errors,
+        vec![VerifyError::JumpOutOfBounds { ip: 0, offset: -2 }]
+    );
+}
+
+#[test]
+fn test_verify_frame_offsets() {
+    let errors = verify(unit(1, vec![Inst::Copy { offset: 1 }, Inst::ReturnUnit]));
+
+    assert_eq!(
+        errors,
+        vec![VerifyError::FrameOffsetOutOfBounds {
+            ip: 0,
+            offset: 1,
+            depth: 1
+        }]
+    );
+
+    let errors = verify(unit(0, vec![Inst::Drop { offset: 0 }, Inst::ReturnUnit]));
+
+    assert_eq!(
+        errors,
+        vec![VerifyError::FrameOffsetOutOfBounds {
+            ip: 0,
+            offset: 0,
+            depth: 0
+        }]
+    );
+}
+
+#[test]
+fn test_verify_stack() {
+    let errors = verify(unit(0, vec![Inst::Pop, Inst::ReturnUnit]));
+
+    assert_eq!(
+        errors,
+        vec![VerifyError::StackUnderflow {
+            ip: 0,
+            needed: 1,
+            depth: 0
+        }]
+    );
+
+    let errors = verify(unit(1, vec![Inst::unit(), Inst::Return]));
+
+    assert_eq!(
+        errors,
+        vec![VerifyError::ReturnStackMismatch {
+            ip: 1,
+            depth: 2,
+            expected: 1
+        }]
+    );
+
+    let errors = verify(unit(
+        1,
+        vec![
+            Inst::Copy { offset: 0 },
+            Inst::JumpIf { offset: 1 },
+            Inst::unit(),
+            Inst::ReturnUnit,
+        ],
+    ));
+
+    assert_eq!(
+        errors,
+        vec![
+            VerifyError::ReturnStackMismatch {
+                ip: 3,
+                depth: 1,
+                expected: 0
+            },
+            VerifyError::InconsistentStack {
+                ip: 3,
+                expected: 1,
+                actual: 2
+            }
+        ]
+    );
+
+    let errors = verify(unit(0, vec![Inst::unit(), Inst::Pop]));
+    assert_eq!(errors, vec![VerifyError::MissingReturn { ip: 1 }]);
+}
+
+#[test]
+fn test_verify_static_slots() {
+    let errors = verify(unit(
+        0,
+        vec![
+            Inst::String { slot: 0 },
+            Inst::Bytes { slot: 1 },
+            Inst::Object { slot: 2 },
+            Inst::PopN { count: 3 },
+            Inst::ReturnUnit,
+        ],
+    ));
+
+    assert_eq!(
+        errors,
+        vec![
+            VerifyError::MissingStaticString { ip: 0, slot: 0 },
+            VerifyError::MissingStaticBytes { ip: 1, slot: 1 },
+            VerifyError::MissingStaticObjectKeys { ip: 2, slot: 2 },
+        ]
+    );
+}
+
+#[test]
+fn test_verify_functions() {
+    let hash = Hash::type_hash(&["missing"]);
+
+    let errors = verify(unit(0, vec![Inst::Call { hash, args: 0 }, Inst::Return]));
+    assert_eq!(errors, vec![VerifyError::MissingFunction { ip: 0, hash }]);
+
+    let errors = verify(unit(0, vec![]));
+
+    assert_eq!(
+        errors,
+        vec![VerifyError::FunctionOutOfBounds {
+            hash: Hash::type_hash(&["main"]),
+            offset: 0
+        }]
+    );
+}
@@ -66,6 +66,14 @@ async function tryActivate(context: vscode.ExtensionContext) {
 
     log.info(`command: ${command}`);
     client.start();
+
+    const debugCommand = command as string;
+
+    context.subscriptions.push(vscode.debug.registerDebugAdapterDescriptorFactory('rune', {
+        createDebugAdapterDescriptor(_session: vscode.DebugSession) {
+            return new vscode.DebugAdapterExecutable(debugCommand, ['--dap']);
+        }
+    }));
 }
 
 /**